@@ -0,0 +1,145 @@
+//! Benchmarks for document-model operations and project serialization, run headless against
+//! `tiny_neo_space::core` and `tiny_neo_space::project`
+//!
+//! Run with `cargo bench`.
+
+use std::collections::HashSet;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tiny_neo_space::core::cell::{Cell, CellAspect, CellGrid, GridSettings};
+use tiny_neo_space::core::color::Rgba;
+use tiny_neo_space::core::fill::{fill_region, FillTarget};
+use tiny_neo_space::core::history::{CellChange, Command};
+use tiny_neo_space::core::raster::{chunk_mips, rasterize, RasterOptions, ScaleFilter};
+use tiny_neo_space::core::selection::{compute_bounding_rect, filled_cells_in_rect, SelectionRect};
+use tiny_neo_space::project::format::{ProjectFile, ProjectMetadata};
+use tiny_neo_space::project::migrate::parse_project;
+use tiny_neo_space::project::text_format::encode_text_project;
+
+/// A solid `side` × `side` block of cells starting at the origin, with a repeating color pattern
+fn filled_grid(side: i32) -> CellGrid {
+    let mut cells = CellGrid::new();
+    for y in 0..side {
+        for x in 0..side {
+            let color = Rgba::new((x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8, 255);
+            cells.insert((x, y), Cell::with_color(color));
+        }
+    }
+    cells
+}
+
+fn selection_finalize(c: &mut Criterion) {
+    // 1000 × 1000 = 1M filled cells; the marquee covers a quarter of them
+    let cells = filled_grid(1000);
+    let rect = SelectionRect::from_points((250, 250), (749, 749));
+
+    c.bench_function("selection_finalize_1m", |b| {
+        b.iter(|| {
+            let picked = filled_cells_in_rect(black_box(&cells), black_box(&rect));
+            compute_bounding_rect(&picked)
+        })
+    });
+}
+
+fn chunk_rebuild(c: &mut Criterion) {
+    let cells = filled_grid(64);
+    let hidden = HashSet::new();
+
+    c.bench_function("chunk_rebuild_64_with_mips", |b| {
+        b.iter(|| chunk_mips(black_box(&cells), &hidden, (0, 0), 64, 4))
+    });
+}
+
+fn undo_apply(c: &mut Criterion) {
+    // One large stroke: repaint a 300 × 300 block that was previously empty
+    let changes: Vec<CellChange> = (0..300)
+        .flat_map(|y| (0..300).map(move |x| (x, y)))
+        .map(|coord| CellChange { coord, before: None, after: Some(Cell::with_color(Rgba::BLUE)) })
+        .collect();
+    let command = Command { changes };
+    let mut painted = CellGrid::new();
+    for ch in command.changes.iter() {
+        painted.insert(ch.coord, ch.after.unwrap());
+    }
+
+    c.bench_function("undo_apply_90k_changes", |b| {
+        b.iter_batched(
+            || painted.clone(),
+            |mut cells| {
+                command.revert(&mut cells);
+                cells
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn png_rasterize(c: &mut Criterion) {
+    let cells = filled_grid(256);
+    let rect = SelectionRect::from_points((0, 0), (255, 255));
+    let options = RasterOptions {
+        scale: 4,
+        filter: ScaleFilter::Nearest,
+        aspect: CellAspect::SQUARE,
+        background: Rgba::WHITE,
+        grid: Some(Rgba::new(0, 0, 0, 64)),
+    };
+
+    c.bench_function("rasterize_256_at_4x", |b| {
+        b.iter(|| rasterize(black_box(&cells), &rect, &options))
+    });
+
+    let smoothed = RasterOptions { scale: 2, filter: ScaleFilter::Scale2x, ..options };
+    c.bench_function("rasterize_256_scale2x_at_2x", |b| {
+        b.iter(|| rasterize(black_box(&cells), &rect, &smoothed))
+    });
+}
+
+fn flood_fill(c: &mut Criterion) {
+    // 512 × 512 single-color block with a one-cell frame of another color around it
+    let mut cells = CellGrid::new();
+    for y in -1..=512 {
+        for x in -1..=512 {
+            let inside = (0..512).contains(&x) && (0..512).contains(&y);
+            let color = if inside { Rgba::BLUE } else { Rgba::WHITE };
+            cells.insert((x, y), Cell::with_color(color));
+        }
+    }
+    let bounds = SelectionRect::from_points((-1, -1), (512, 512));
+    let target = FillTarget::at(&cells, (256, 256), 0.0);
+
+    c.bench_function("flood_fill_contiguous_262k", |b| {
+        b.iter(|| fill_region(black_box(&cells), (256, 256), target, false, &bounds))
+    });
+    c.bench_function("flood_fill_global_262k", |b| {
+        b.iter(|| fill_region(black_box(&cells), (256, 256), target, true, &bounds))
+    });
+}
+
+fn project_serialize(c: &mut Criterion) {
+    // The same 1M-cell fixture as selection_finalize, saved and reopened in both formats
+    let cells = filled_grid(1000);
+    let snapshot = || ProjectFile::from_document(&cells, CellAspect::SQUARE, GridSettings::DEFAULT, &[], &[], &[], &ProjectMetadata::default());
+    let file = snapshot();
+    let json = serde_json::to_string(&file).unwrap();
+    let text = encode_text_project(&file);
+
+    // Each pass takes around a second, so fewer samples than the default hundred
+    let mut group = c.benchmark_group("project_1m");
+    group.sample_size(10);
+    group.bench_function("save_json", |b| {
+        b.iter(|| serde_json::to_string(&snapshot()).unwrap())
+    });
+    group.bench_function("open_json", |b| {
+        b.iter(|| parse_project(black_box(&json)).unwrap())
+    });
+    group.bench_function("encode_text", |b| {
+        b.iter(|| encode_text_project(black_box(&file)))
+    });
+    group.bench_function("parse_text", |b| {
+        b.iter(|| parse_project(black_box(&text)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, selection_finalize, chunk_rebuild, undo_apply, png_rasterize, flood_fill, project_serialize);
+criterion_main!(benches);
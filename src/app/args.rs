@@ -0,0 +1,28 @@
+use crate::error::report;
+use crate::import::{can_import, extension};
+use crate::project::format::{PROJECT_EXTENSION, TEXT_PROJECT_EXTENSION};
+use crate::input::import::run_import;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+
+/// Command line of the editor: `tiny-neo-space [PATH]`, where PATH is a project to open
+/// or any file the import dialog understands (PNG, .p8, .piskel, ...)
+#[derive(Default)]
+pub struct StartupArgs {
+    pub open: Option<String>,
+}
+
+/// Load the startup file into the fresh document; the outcome goes to the status line
+pub fn open_startup_file(state: &mut ApplicationState, canvas: &mut CanvasRenderer, path: &str) {
+    // Projects open as the document; the import dialog would place them as a paste
+    if can_import(path) && ![PROJECT_EXTENSION, TEXT_PROJECT_EXTENSION].contains(&extension(path).as_str()) {
+        state.import.path = path.to_string();
+        run_import(state, canvas);
+        state.status_message = state.import.status.take();
+    } else {
+        state.status_message = Some(match crate::project::open_project(state, canvas, path) {
+            Ok(()) => format!("Opened {}", path),
+            Err(e) => report("Open", &e),
+        });
+    }
+}
@@ -2,62 +2,166 @@
 // Clean game loop using new modular architecture
 
 use macroquad::prelude::*;
-use crate::state::ApplicationState;
-use crate::rendering::{CanvasRenderer, GridRenderer, Hud, draw_cursor_based_on_mode, draw_selection_overlay, draw_selection_action_bar};
-use crate::input::{handle_input, handle_zoom, render_ui_buttons};
-use crate::ui::render_palette_window;
+use crate::state::{ApplicationState, Mode};
+use crate::rendering::{CanvasRenderer, GridRenderer, Hud, PreviewRenderer, draw_crosshair, draw_cursor_based_on_mode, draw_precision_cursor, PRECISION_ZOOM, draw_selection_overlay, draw_selection_action_bar, draw_tool_preview, draw_peer_cursors, draw_region_locks, draw_progress_overlay, draw_symmetry_guides, draw_diff_overlay, draw_cell_marker};
+use crate::input::diff::update_diff;
+use crate::input::frames::update_frame_playback;
+use crate::input::jobs::{poll_io_tasks, run_pending_job, update_backups};
+use crate::input::view_bounds::{lost_hint, update_view_bounds};
+use crate::input::{handle_input, handle_zoom, render_ui_buttons, update_camera_motion};
+use crate::ui::{render_menu_bar, render_palette_window, render_export_dialog, render_open_recent, render_file_picker, render_help_overlay, render_groups_gutter, render_confirm_dialog, render_stray_navigator, render_fill_options, render_transform_panel, render_paste_options, render_chat_overlay, render_import_dialog, render_canvas_settings, render_console, render_shift_dialog, render_harmony_panel, render_metadata_panel, render_saved_selections, render_timeline, render_go_to_cell};
+use crate::export::Mirror;
+use crate::ui::focus;
+use crate::ui::icons::ToolIcons;
+use crate::project::clipboard::load_clipboard;
+use crate::collab::update_session;
 
-pub async fn run() {
+pub mod args;
+
+pub use args::StartupArgs;
+
+pub async fn run(startup: StartupArgs) {
+    crate::logging::init();
+    log::info!("tiny-neo-space {} starting", env!("CARGO_PKG_VERSION"));
     let mut state = ApplicationState::new();
+    state.clipboard = load_clipboard();
     let mut hud = Hud::new();
+    let mut system_cursor_hidden = false;
     let mut grid_renderer = GridRenderer::new();
     let mut canvas_renderer = CanvasRenderer::new();
+    let preview_renderer = PreviewRenderer::new();
+    let mut mirror = Mirror::new();
+    let tool_icons = ToolIcons::new();
+
+    // File given on the command line, loaded once the window (and GPU) exist
+    if let Some(path) = &startup.open {
+        args::open_startup_file(&mut state, &mut canvas_renderer, path);
+    }
 
     loop {
         let dt = get_frame_time();
         hud.update(dt);
 
+        // Open queued last frame; its overlay was drawn then, so the stall isn't silent
+        run_pending_job(&mut state, &mut canvas_renderer);
+        poll_io_tasks(&mut state);
+        update_backups(&mut state);
+
         // White background
-        clear_background(WHITE);
+        clear_background(Color::from(state.theme.background));
 
         // LAYER 1: Grid (behind everything except canvas)
         grid_renderer.update_if_needed();
-        grid_renderer.draw(&state.camera);
+        grid_renderer.draw(&state.camera, state.grid, Color::from(state.theme.grid));
 
-        // LAYER 2: Canvas
+        // LAYER 2: Canvas (frame playback first, since it hides other frames' cells)
+        update_frame_playback(&mut state, &mut canvas_renderer);
+        update_diff(&mut state);
         canvas_renderer.update_if_screen_resized();
-        canvas_renderer.update(&state.cells);
+        canvas_renderer.update(&state.cells, &state.camera);
         canvas_renderer.draw(&state.cells, &state.camera);
 
         // LAYER 3: Selection overlay
+        draw_diff_overlay(&state);
         draw_selection_overlay(&state);
+        draw_tool_preview(&state);
+        draw_symmetry_guides(&state);
+        draw_cell_marker(&state);
+
+        // Shared session: apply what arrived, then show locks and everyone else's cursor
+        update_session(&mut state, &mut canvas_renderer);
+        draw_region_locks(&state);
+        draw_peer_cursors(&state);
 
-        // Check if mouse is over UI
-        let over_buttons = render_ui_buttons(&mut state);
+        // Check if mouse is over UI (keyboard focus first, so Tab and Enter reach the widgets)
+        focus::begin_frame(&state);
+        state.ui_regions.clear();
+        render_ui_buttons(&mut state, &mut canvas_renderer, &tool_icons);
+        render_fill_options(&mut state);
+        render_transform_panel(&mut state, &mut canvas_renderer);
+        render_paste_options(&mut state);
+        render_chat_overlay(&mut state);
         let over_palette = render_palette_window(&mut state);
-        let over_ui = over_buttons || over_palette;
+        let over_harmony = render_harmony_panel(&mut state);
+        let over_export = render_export_dialog(&mut state);
+        let over_import = render_import_dialog(&mut state, &mut canvas_renderer);
+        let over_settings = render_canvas_settings(&mut state);
+        let over_shift = render_shift_dialog(&mut state, &mut canvas_renderer);
+        let over_metadata = render_metadata_panel(&mut state);
+        let over_saved_selections = render_saved_selections(&mut state);
+        let over_timeline = render_timeline(&mut state);
+        let over_go_to = render_go_to_cell(&mut state);
+        let over_preview = preview_renderer.is_hovered(&state);
+        let over_recent = render_open_recent(&mut state, &mut canvas_renderer);
+        let over_picker = render_file_picker(&mut state);
+        let over_menu = render_menu_bar(&mut state, &mut canvas_renderer);
+        let over_groups = render_groups_gutter(&mut state, &mut canvas_renderer);
+        let over_strays = render_stray_navigator(&mut state, &mut canvas_renderer);
+        let over_help = render_help_overlay(&mut state);
+        let over_confirm = render_confirm_dialog(&mut state, &mut canvas_renderer);
+        let over_console = render_console(&state);
+        let over_regions = state.ui_regions.contains(Vec2::from(mouse_position()));
+        let over_ui = over_regions || over_palette || over_harmony || over_export || over_import || over_settings || over_shift || over_metadata || over_saved_selections || over_timeline || over_go_to || over_preview || over_recent || over_picker || over_menu || over_groups || over_strays || over_help || over_confirm || over_console;
 
         // Handle zoom (scroll wheel) - only if not over UI
         if !over_ui {
             handle_zoom(&mut state);
         }
 
+        // Pan inertia and edge auto-scroll (runs even over UI so drags keep scrolling)
+        update_camera_motion(&mut state, dt);
+
         // Handle user input (painting/erasing/panning) - only if not over UI
         if !over_ui {
             handle_input(&mut state, &mut canvas_renderer);
         }
 
+        // Pull the view back toward the drawing when the clamp is on
+        update_view_bounds(&mut state);
+
+        // Precision mode replaces the system cursor when zoomed in over the canvas
+        let precise = state.precision_cursor && !over_ui && state.camera.zoom >= PRECISION_ZOOM;
+        if precise != system_cursor_hidden {
+            show_mouse(!precise);
+            system_cursor_hidden = precise;
+        }
+
         // LAYER 4: Cursor (only if not over UI)
         if !over_ui {
             let screen_mouse_pos = Vec2::from(mouse_position());
-            draw_cursor_based_on_mode(&state.mode, &state.camera, screen_mouse_pos);
+            if state.show_crosshair {
+                draw_crosshair(&state.camera, screen_mouse_pos);
+            }
+            let cursor_mode = if state.space_pan_active { &Mode::Pan } else { &state.mode };
+            draw_cursor_based_on_mode(cursor_mode, &state.camera, screen_mouse_pos, state.spray_radius);
+            if precise {
+                draw_precision_cursor(&state.camera, &state.cells, screen_mouse_pos);
+            }
         }
 
         // LAYER 5: Selection action bar (on top of everything)
         draw_selection_action_bar(&mut state, &mut canvas_renderer);
 
         // LAYER 6: HUD (with camera info)
-        hud.draw(&state.camera);
+        let budget = state.show_budget.then(|| state.budget_report());
+        let session = state.collab.as_mut().map(|session| session.status_line());
+        if state.show_hud {
+            let lost = lost_hint(&state);
+            hud.draw(&state.camera, state.status_message.as_deref(), budget.as_ref(), session.as_deref(), lost.as_deref(), state.hud_layout);
+        }
+
+        // LAYER 7: 1:1 preview panel (always on top)
+        preview_renderer.draw(&mut state, &canvas_renderer);
+
+        // LAYER 8: Progress for a queued open and rebuild backlogs that outlast the watchdog
+        if let Some(job) = &state.pending_job {
+            draw_progress_overlay(&job.label(), None);
+        } else if let Some((done, total)) = canvas_renderer.rebuild_progress() {
+            draw_progress_overlay(&format!("Rebuilding {}/{} chunks", done, total), Some(done as f32 / total as f32));
+        }
+
+        // Live view mirroring (writes the drawing to disk for streaming tools)
+        mirror.update(&mut state, get_time());
 
         next_frame().await
     }
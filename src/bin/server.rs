@@ -0,0 +1,36 @@
+// Headless shared-canvas server; build with `cargo run --features server --bin tiny-neo-space-server`
+
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes each record to stderr as "seconds-since-epoch LEVEL message"
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64());
+            let _ = writeln!(std::io::stderr(), "{:.3} {:<5} {}", time, record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+fn main() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+    let result = tiny_neo_space::server::ServerConfig::from_args(std::env::args().skip(1))
+        .and_then(tiny_neo_space::server::run);
+    if let Err(e) = result {
+        log::error!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::collab::protocol::{encode_line, DEFAULT_PORT};
+
+/// How long to try each resolved address before moving on to the next
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(750);
+
+/// A non-blocking line-delimited JSON connection, polled once per frame
+/// Native only: the browser build has no raw sockets, so it hides the Session menu and
+/// never connects
+pub struct Connection {
+    stream: TcpStream,
+    /// Bytes received but not yet terminated by a newline
+    inbox: Vec<u8>,
+    /// Bytes queued but not yet accepted by the socket
+    outbox: Vec<u8>,
+    /// Longest line the peer may send before it is treated as misbehaving
+    max_line: usize,
+    /// Most unsent bytes allowed before the peer counts as gone
+    max_outbox: usize,
+}
+
+impl Connection {
+    /// Connect to "host" or "host:port", trying each resolved address for at most CONNECT_TIMEOUT
+    pub fn connect(address: &str) -> Result<Self, String> {
+        let address = if address.contains(':') { address.to_string() } else { format!("{}:{}", address, DEFAULT_PORT) };
+        let fail = |e: std::io::Error| format!("Could not connect to {}: {}", address, e);
+        let mut last_error = format!("Could not resolve {}", address);
+        for socket in address.to_socket_addrs().map_err(fail)? {
+            match TcpStream::connect_timeout(&socket, CONNECT_TIMEOUT) {
+                Ok(stream) => return Self::from_stream(stream),
+                Err(e) => last_error = fail(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Wrap an accepted or connected socket
+    pub fn from_stream(stream: TcpStream) -> Result<Self, String> {
+        stream.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let _ = stream.set_nodelay(true);
+        Ok(Self { stream, inbox: Vec::new(), outbox: Vec::new(), max_line: usize::MAX, max_outbox: usize::MAX })
+    }
+
+    /// Fail instead of buffering forever when the peer sends a line longer than `max_line`
+    /// bytes or stops reading with more than `max_outbox` bytes queued
+    pub fn with_limits(mut self, max_line: usize, max_outbox: usize) -> Self {
+        self.max_line = max_line;
+        self.max_outbox = max_outbox;
+        self
+    }
+
+    /// Queue one message and write as much as the socket takes; the rest goes out on later polls
+    pub fn send<T: Serialize>(&mut self, message: &T) -> Result<(), String> {
+        let line = encode_line(message)?;
+        self.outbox.extend_from_slice(line.as_bytes());
+        self.flush()
+    }
+
+    /// Write queued bytes until the socket would block
+    fn flush(&mut self) -> Result<(), String> {
+        let mut written = 0;
+        let result = loop {
+            if written == self.outbox.len() {
+                break Ok(());
+            }
+            match self.stream.write(&self.outbox[written..]) {
+                Ok(0) => break Err("Connection closed".to_string()),
+                Ok(n) => written += n,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e.to_string()),
+            }
+        };
+        self.outbox.drain(..written);
+        if self.outbox.len() > self.max_outbox {
+            return Err(format!("Peer fell {} bytes behind", self.outbox.len()));
+        }
+        result
+    }
+
+    /// Every complete message received since the last poll; Err once the peer has gone away
+    /// Lines that fail to parse are skipped so a newer peer can't break an older one
+    pub fn poll<T: DeserializeOwned>(&mut self) -> Result<Vec<T>, String> {
+        self.flush()?;
+        let mut buf = [0u8; 4096];
+        while self.inbox.len() <= self.max_line {
+            match self.stream.read(&mut buf) {
+                Ok(0) => return Err("Connection closed".to_string()),
+                Ok(n) => self.inbox.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+
+        let mut messages = Vec::new();
+        while let Some(end) = self.inbox.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.inbox.drain(..=end).collect();
+            if let Ok(message) = serde_json::from_slice(&line[..end]) {
+                messages.push(message);
+            }
+        }
+        if self.inbox.len() > self.max_line {
+            return Err(format!("Line longer than {} bytes", self.max_line));
+        }
+        Ok(messages)
+    }
+}
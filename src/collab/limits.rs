@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+/// What a participant may do on a shared canvas
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Watches and chats, can't paint
+    Viewer,
+    /// Paints within the rate limit
+    Editor,
+    /// Paints without a limit and manages roles
+    Admin,
+}
+
+impl Role {
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+/// Editors may paint `cells` cells per `seconds`; cells = 0 means unlimited
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub cells: u32,
+    pub seconds: f32,
+}
+
+impl RateLimit {
+    pub const UNLIMITED: RateLimit = RateLimit { cells: 0, seconds: 1.0 };
+}
+
+/// Token bucket for painting: full at `limit.cells`, refilling evenly over `limit.seconds`
+/// Time is passed in (seconds from any fixed start) so the server and the client's
+/// cooldown mirror share the same arithmetic
+#[derive(Debug, Clone, Copy)]
+pub struct PaintBudget {
+    pub limit: RateLimit,
+    tokens: f32,
+    last: f64,
+}
+
+impl PaintBudget {
+    pub fn new(limit: RateLimit, now: f64) -> Self {
+        Self { limit, tokens: limit.cells as f32, last: now }
+    }
+
+    fn refill(&mut self, now: f64) {
+        let rate = self.limit.cells as f32 / self.limit.seconds;
+        self.tokens = (self.tokens + (now - self.last).max(0.0) as f32 * rate).min(self.limit.cells as f32);
+        self.last = now;
+    }
+
+    /// Cells available right now
+    pub fn available(&mut self, now: f64) -> u32 {
+        if self.limit.cells == 0 {
+            return u32::MAX;
+        }
+        self.refill(now);
+        self.tokens as u32
+    }
+
+    /// Whether a stroke of `cells` can ever be paid for, i.e. fits in a full bucket
+    pub fn fits(&self, cells: usize) -> bool {
+        self.limit.cells == 0 || cells <= self.limit.cells as usize
+    }
+
+    /// Spend `cells` if they're all available; a stroke is never half-accepted
+    pub fn try_spend(&mut self, cells: usize, now: f64) -> bool {
+        if self.limit.cells == 0 {
+            return true;
+        }
+        self.refill(now);
+        if cells as f32 > self.tokens {
+            return false;
+        }
+        self.tokens -= cells as f32;
+        true
+    }
+
+    /// Seconds until `cells` cells will be available (0 when they already are, infinite when
+    /// they never will be; see `fits`)
+    pub fn wait_for(&mut self, cells: usize, now: f64) -> f32 {
+        if self.limit.cells == 0 {
+            return 0.0;
+        }
+        if !self.fits(cells) {
+            return f32::INFINITY;
+        }
+        self.refill(now);
+        let missing = cells as f32 - self.tokens;
+        (missing.max(0.0) * self.limit.seconds / self.limit.cells as f32).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit { cells: 10, seconds: 2.0 };
+
+    #[test]
+    fn refills_evenly_up_to_the_limit() {
+        let mut budget = PaintBudget::new(LIMIT, 0.0);
+        assert!(budget.try_spend(10, 0.0));
+        assert_eq!(budget.available(0.0), 0);
+        assert_eq!(budget.available(1.0), 5);
+        assert_eq!(budget.available(100.0), 10);
+    }
+
+    #[test]
+    fn strokes_are_all_or_nothing() {
+        let mut budget = PaintBudget::new(LIMIT, 0.0);
+        assert!(budget.try_spend(6, 0.0));
+        assert!(!budget.try_spend(5, 0.0));
+        assert_eq!(budget.available(0.0), 4);
+        assert!((budget.wait_for(5, 0.0) - 0.2).abs() < 1e-4);
+        assert!(budget.try_spend(5, 0.2));
+    }
+
+    #[test]
+    fn oversize_strokes_never_fit() {
+        let mut budget = PaintBudget::new(LIMIT, 0.0);
+        assert!(budget.fits(10));
+        assert!(!budget.fits(11));
+        assert!(!budget.try_spend(11, 1000.0));
+        assert_eq!(budget.wait_for(11, 1000.0), f32::INFINITY);
+    }
+
+    #[test]
+    fn unlimited_budget_takes_anything() {
+        let mut budget = PaintBudget::new(RateLimit::UNLIMITED, 0.0);
+        assert!(budget.fits(usize::MAX));
+        assert!(budget.try_spend(1_000_000, 0.0));
+        assert_eq!(budget.wait_for(1_000_000, 0.0), 0.0);
+    }
+}
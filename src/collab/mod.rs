@@ -0,0 +1,9 @@
+//! Networked collaboration: the line-delimited JSON wire protocol, the client connection
+//! and the session state (other participants' cursors, the chat log, and our role and paint limit)
+
+pub mod protocol;
+pub mod limits;
+pub mod client;
+pub mod session;
+
+pub use session::{join_session, leave_session, lock_selection, share_changes, unlock_selection, update_session, CollabSession};
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use crate::core::color::Rgba;
+use crate::core::selection::SelectionRect;
+use crate::collab::limits::{RateLimit, Role};
+
+/// Port the editor connects to when the address has none
+pub const DEFAULT_PORT: u16 = 7878;
+/// Longest chat line, in characters; the server refuses longer ones
+pub const MAX_CHAT_CHARS: usize = 500;
+/// Seconds of silence after which either side sends a Ping
+pub const PING_INTERVAL_SECS: f64 = 5.0;
+/// Seconds without hearing anything after which either side gives the connection up
+pub const IDLE_TIMEOUT_SECS: f64 = 20.0;
+
+/// Messages sent by the editor; each is one JSON object on its own line
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ClientMessage {
+    /// First message after connecting; a matching admin token grants the admin role, and the
+    /// `resume` token from an earlier Welcome takes over that connection's name and role
+    Hello {
+        name: String,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        resume: Option<String>,
+    },
+    /// Cursor position in world cell units, with the sender's current color and tool
+    Cursor { x: f32, y: f32, color: Rgba, tool: String },
+    Chat { text: String },
+    /// Cells written by a local command, in application order
+    Paint { changes: Vec<WireCell> },
+    /// Admin only: lock a rect (or just `cells` within it, for a group) so only `owners`
+    /// (and admins) can paint there
+    Lock {
+        rect: SelectionRect,
+        owners: Vec<String>,
+        #[serde(default)]
+        cells: Vec<(i32, i32)>,
+    },
+    /// Admin only: remove a lock
+    Unlock { id: u32 },
+    /// Sent after PING_INTERVAL_SECS of silence so the server knows the editor is still there
+    Ping,
+}
+
+/// A locked region of the shared canvas
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegionLock {
+    pub id: u32,
+    pub rect: SelectionRect,
+    /// Names allowed to paint inside besides admins
+    pub owners: Vec<String>,
+    /// The locked cells when a group was locked, sorted; empty locks the whole rect
+    #[serde(default)]
+    pub cells: Vec<(i32, i32)>,
+}
+
+
+/// One cell's new content; None empties it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WireCell {
+    pub x: i32,
+    pub y: i32,
+    pub color: Option<Rgba>,
+}
+
+/// Messages sent to the editor
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ServerMessage {
+    /// Reply to Hello with the id the server assigned to this client, and the token that lets
+    /// a reconnect replace this connection if it goes stale
+    Welcome {
+        id: u32,
+        #[serde(default)]
+        resume: String,
+    },
+    /// Reply to a Hello the server won't accept, e.g. a name someone connected already uses
+    Refused { reason: String },
+    /// This client's role and paint limit; sent after Welcome and whenever an admin changes it
+    Permissions { role: Role, limit: RateLimit },
+    /// A Paint was refused; `cells` holds the server's values so the client can undo it locally
+    Rejected { reason: String, cells: Vec<WireCell> },
+    PeerJoined { id: u32, name: String },
+    PeerLeft { id: u32 },
+    Cursor { id: u32, x: f32, y: f32, color: Rgba, tool: String },
+    Chat { name: String, text: String },
+    /// The whole shared canvas, sent once after Welcome
+    Snapshot { cells: Vec<WireCell> },
+    /// Cells another participant painted
+    Cells { changes: Vec<WireCell> },
+    /// Every current region lock; sent after Snapshot and whenever a lock changes
+    Locks { locks: Vec<RegionLock> },
+    /// Sent to every client each PING_INTERVAL_SECS so silent drops are noticed
+    Ping,
+}
+
+/// Serialize a message as one protocol line (newline included)
+pub fn encode_line<T: Serialize>(message: &T) -> Result<String, String> {
+    let mut line = serde_json::to_string(message).map_err(|e| e.to_string())?;
+    line.push('\n');
+    Ok(line)
+}
@@ -0,0 +1,517 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use macroquad::prelude::*;
+use crate::collab::client::Connection;
+use crate::collab::limits::{PaintBudget, RateLimit, Role};
+use crate::collab::protocol::{ClientMessage, RegionLock, ServerMessage, WireCell, IDLE_TIMEOUT_SECS, PING_INTERVAL_SECS};
+use crate::core::cell::Cell;
+use crate::core::color::Rgba;
+use crate::core::selection::SelectionRect;
+use crate::input::groups::{clear_groups, remove_cells_from_groups};
+use crate::rendering::CanvasRenderer;
+use crate::state::{ApplicationState, CellChange};
+
+/// Chat lines kept for the overlay
+pub const MAX_CHAT_LINES: usize = 50;
+/// Minimum seconds between cursor updates sent to the server
+const CURSOR_INTERVAL: f64 = 0.05;
+/// First reconnection delay after the connection drops; doubles per failed attempt
+const RETRY_DELAY: f64 = 1.0;
+const MAX_RETRY_DELAY: f64 = 15.0;
+/// Longest message accepted from the server (a snapshot of a large canvas is one line)
+const MAX_LINE_BYTES: usize = 1 << 30;
+/// Unsent bytes allowed before the connection counts as lost
+const MAX_OUTBOX_BYTES: usize = 64 << 20;
+
+/// Another participant as last reported by the server
+pub struct Peer {
+    pub name: String,
+    /// World cell position; None until their first cursor update
+    pub cursor: Option<Vec2>,
+    /// Their current paint color, used for the cursor and its label
+    pub color: Rgba,
+    pub tool: String,
+}
+
+pub struct ChatLine {
+    pub name: String,
+    pub text: String,
+}
+
+/// A shared session; it survives dropped connections, queueing local edits until it reconnects
+pub struct CollabSession {
+    /// None while offline
+    connection: Option<Connection>,
+    /// A connection attempt running off the frame
+    connecting: Option<Receiver<Result<Connection, String>>>,
+    /// Server address, for reconnecting
+    address: String,
+    /// From the server's Welcome; lets a reconnect take over our old connection before the
+    /// server notices it dropped
+    resume: Option<String>,
+    /// When the server was last heard from and when we last sent anything, for pings and
+    /// noticing a silent drop
+    last_heard: f64,
+    last_sent: f64,
+    /// Paint messages made while offline, oldest first; replayed on reconnect
+    offline_queue: Vec<Vec<WireCell>>,
+    /// When to try reconnecting next, and the delay after that
+    retry_at: f64,
+    retry_delay: f64,
+    /// Reconnected and waiting for the snapshot to rebase the queue onto
+    rejoining: bool,
+    /// Name shown to other participants
+    pub name: String,
+    /// Assigned by the server's Welcome
+    pub id: Option<u32>,
+    pub peers: HashMap<u32, Peer>,
+    /// Oldest first, capped at MAX_CHAT_LINES
+    pub chat: Vec<ChatLine>,
+    /// Granted by the server; editors until told otherwise
+    pub role: Role,
+    /// Mirror of the server's paint budget, for the cooldown indicator
+    pub budget: PaintBudget,
+    /// Region locks, drawn hatched
+    pub locks: Vec<RegionLock>,
+    last_cursor: Option<(Vec2, Rgba, String)>,
+    last_cursor_time: f64,
+}
+
+impl CollabSession {
+    /// Starts connecting in the background; the session is offline until that finishes
+    fn new(address: String, name: String) -> Self {
+        Self {
+            connection: None,
+            connecting: Some(connect_in_background(address.clone(), name.clone(), None)),
+            address,
+            resume: None,
+            last_heard: 0.0,
+            last_sent: 0.0,
+            offline_queue: Vec::new(),
+            retry_at: 0.0,
+            retry_delay: RETRY_DELAY,
+            rejoining: false,
+            name,
+            id: None,
+            peers: HashMap::new(),
+            chat: Vec::new(),
+            role: Role::Editor,
+            budget: PaintBudget::new(RateLimit::UNLIMITED, get_time()),
+            locks: Vec::new(),
+            last_cursor: None,
+            last_cursor_time: 0.0,
+        }
+    }
+
+    pub fn is_online(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Send now; a failed send drops the connection and starts reconnecting
+    fn send(&mut self, message: &ClientMessage) -> Result<(), String> {
+        let connection = self.connection.as_mut().ok_or("offline")?;
+        connection.send(message).inspect_err(|_| self.go_offline())?;
+        self.last_sent = get_time();
+        Ok(())
+    }
+
+    pub fn send_chat(&mut self, text: String) -> Result<(), String> {
+        self.send(&ClientMessage::Chat { text })
+    }
+
+    /// Send a paint, or queue it until the connection is back (and its snapshot has arrived,
+    /// since that snapshot would otherwise overwrite the edit locally)
+    fn send_paint(&mut self, changes: Vec<WireCell>) {
+        if !self.is_online() || self.rejoining {
+            self.offline_queue.push(changes);
+            return;
+        }
+        let message = ClientMessage::Paint { changes };
+        if self.send(&message).is_err() {
+            if let ClientMessage::Paint { changes } = message {
+                self.offline_queue.push(changes);
+            }
+        }
+    }
+
+    /// Forget the connection and everyone on it; the queue and our role are kept. Each drop
+    /// without a working connection in between doubles the wait before the next attempt
+    fn go_offline(&mut self) {
+        log::warn!("Lost the shared session at {}; retrying in {:.0}s", self.address, self.retry_delay);
+        self.connection = None;
+        self.peers.clear();
+        self.rejoining = false;
+        self.retry_at = get_time() + self.retry_delay;
+        self.retry_delay = (self.retry_delay * 2.0).min(MAX_RETRY_DELAY);
+    }
+
+    /// Pick up a finished connection attempt, or start one when the retry timer is due.
+    /// Err when the first join fails, since there is no session to come back to yet
+    fn try_reconnect(&mut self) -> Result<(), String> {
+        let now = get_time();
+        if let Some(attempt) = &self.connecting {
+            let error = match attempt.try_recv() {
+                Err(TryRecvError::Empty) => return Ok(()),
+                Ok(Ok(connection)) => {
+                    log::info!("Connected to {}", self.address);
+                    self.connecting = None;
+                    self.connection = Some(connection);
+                    // The first snapshot replaces the canvas; later ones rebase the queue
+                    self.rejoining = self.id.is_some();
+                    self.last_heard = now;
+                    self.last_sent = now;
+                    return Ok(());
+                }
+                Ok(Err(e)) => e,
+                Err(TryRecvError::Disconnected) => "the connection attempt stopped".to_string(),
+            };
+            self.connecting = None;
+            if self.id.is_none() {
+                return Err(error);
+            }
+            log::debug!("Reconnect failed: {}", error);
+            self.retry_at = now + self.retry_delay;
+            self.retry_delay = (self.retry_delay * 2.0).min(MAX_RETRY_DELAY);
+            return Ok(());
+        }
+        if !self.is_online() && now >= self.retry_at {
+            self.connecting = Some(connect_in_background(self.address.clone(), self.name.clone(), self.resume.clone()));
+        }
+        Ok(())
+    }
+
+    /// Ping after a quiet spell, and give the connection up once the server has been silent
+    /// for too long (it pings every PING_INTERVAL_SECS, so silence means the link is gone)
+    fn keep_alive(&mut self) -> Result<(), String> {
+        let now = get_time();
+        if !self.is_online() {
+            return Ok(());
+        }
+        if now - self.last_heard > IDLE_TIMEOUT_SECS {
+            self.go_offline();
+            return Err(format!("no word from the server for {:.0}s", IDLE_TIMEOUT_SECS));
+        }
+        if now - self.last_sent >= PING_INTERVAL_SECS {
+            let _ = self.send(&ClientMessage::Ping);
+        }
+        Ok(())
+    }
+
+    /// One-line summary for the HUD: sync state, role, then cells left or the cooldown
+    pub fn status_line(&mut self) -> String {
+        let now = get_time();
+        if !self.is_online() {
+            let queued = self.offline_queue.len();
+            return match (self.id, &self.connecting) {
+                (None, _) => format!("Shared: joining {}...", self.address),
+                (Some(_), Some(_)) => format!("Shared: offline, {} edit(s) queued, reconnecting...", queued),
+                (Some(_), None) => {
+                    format!("Shared: offline, {} edit(s) queued, retrying in {:.0}s", queued, (self.retry_at - now).max(0.0).ceil())
+                }
+            };
+        }
+        if self.rejoining {
+            return format!("Shared: syncing {} queued edit(s)", self.offline_queue.len());
+        }
+        let limit = match self.role {
+            Role::Viewer => "view only".to_string(),
+            _ if self.budget.limit.cells == 0 || self.role == Role::Admin => "unlimited".to_string(),
+            _ if self.budget.available(now) == 0 => format!("cooldown {:.1}s", self.budget.wait_for(1, now)),
+            _ => format!("{}/{} cells", self.budget.available(now), self.budget.limit.cells),
+        };
+        format!("Shared ({}): {}", self.role.label(), limit)
+    }
+
+    fn push_chat(&mut self, name: String, text: String) {
+        self.chat.push(ChatLine { name, text });
+        if self.chat.len() > MAX_CHAT_LINES {
+            self.chat.remove(0);
+        }
+    }
+}
+
+/// Admin token presented to the server, from TINY_NEO_SPACE_TOKEN
+fn local_token() -> Option<String> {
+    std::env::var("TINY_NEO_SPACE_TOKEN").ok().filter(|t| !t.is_empty())
+}
+
+/// Name announced to the session: the login name, or "guest"
+fn local_name() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_else(|_| "guest".to_string())
+}
+
+/// Send the cells a local command wrote (its `before` values, newest first, when undoing)
+/// Nothing is sent outside a shared session
+pub fn share_changes(state: &mut ApplicationState, changes: &[CellChange], undo: bool) {
+    let Some(session) = &mut state.collab else {
+        return;
+    };
+    let wire = |coord: (i32, i32), cell: Option<Cell>| WireCell { x: coord.0, y: coord.1, color: cell.filter(|c| c.is_filled).map(|c| c.color) };
+    let changes: Vec<WireCell> = if undo {
+        changes.iter().rev().map(|ch| wire(ch.coord, ch.before)).collect()
+    } else {
+        changes.iter().map(|ch| wire(ch.coord, ch.after)).collect()
+    };
+    // The server has the final say; the mirror only drives the cooldown indicator
+    if session.role != Role::Admin {
+        session.budget.try_spend(changes.len(), get_time());
+    }
+    session.send_paint(changes);
+}
+
+/// Admin: lock the selection's bounding rect, or just a selected group's cells
+/// Only admins can paint there until `/allow ID NAME` adds owners in chat
+pub fn lock_selection(state: &mut ApplicationState) {
+    let rect = state.selection.current.as_ref().map(|sel| sel.rect);
+    let group = state.selected_group_id.and_then(|id| state.groups.iter().find(|g| g.id == id));
+    let cells: Vec<(i32, i32)> = group.map(|g| g.cells.iter().copied().collect()).unwrap_or_default();
+    let Some(session) = &mut state.collab else {
+        return;
+    };
+    let result = match (session.role, rect) {
+        (Role::Admin, Some(rect)) => session.send(&ClientMessage::Lock { rect, owners: Vec::new(), cells }),
+        (Role::Admin, None) => Err("select a region to lock".to_string()),
+        _ => Err("only admins can lock regions".to_string()),
+    };
+    if let Err(e) = result {
+        state.status_message = Some(format!("Lock failed: {}", e));
+    }
+}
+
+/// Admin: remove every lock overlapping the selection (or under the cursor without one)
+pub fn unlock_selection(state: &mut ApplicationState) {
+    let rect = state.selection.current.as_ref().map(|sel| sel.rect).unwrap_or_else(|| {
+        let cell = state.camera.screen_to_cell(Vec2::from(mouse_position())).floor();
+        SelectionRect::from_points((cell.x as i32, cell.y as i32), (cell.x as i32, cell.y as i32))
+    });
+    let Some(session) = &mut state.collab else {
+        return;
+    };
+    if session.role != Role::Admin {
+        state.status_message = Some("Unlock failed: only admins can unlock regions".to_string());
+        return;
+    }
+    let overlapping: Vec<u32> = session.locks.iter().filter(|lock| rects_overlap(&lock.rect, &rect)).map(|lock| lock.id).collect();
+    for id in overlapping {
+        let _ = session.send(&ClientMessage::Unlock { id });
+    }
+}
+
+fn rects_overlap(a: &SelectionRect, b: &SelectionRect) -> bool {
+    a.min_x <= b.max_x && b.min_x <= a.max_x && a.min_y <= b.max_y && b.min_y <= a.max_y
+}
+
+/// Joining replaces the local drawing with the shared canvas; local history no longer applies
+fn load_snapshot(state: &mut ApplicationState, canvas: &mut CanvasRenderer, cells: Vec<WireCell>) {
+    state.selection.clear();
+    state.selection_preview = None;
+    clear_groups(state);
+    state.history.clear();
+    replace_cells(state, canvas, &cells);
+}
+
+/// After a reconnect: take the server's canvas (with whatever others painted meanwhile),
+/// then replay our queued edits on top and send them. History is kept, so undo still
+/// reverts our own steps
+fn rebase_on_snapshot(state: &mut ApplicationState, canvas: &mut CanvasRenderer, cells: Vec<WireCell>) {
+    replace_cells(state, canvas, &cells);
+    let Some(session) = &mut state.collab else {
+        return;
+    };
+    session.rejoining = false;
+    let queue = std::mem::take(&mut session.offline_queue);
+    let replayed = queue.len();
+    for changes in &queue {
+        apply_remote_cells(state, canvas, changes);
+    }
+    if let Some(session) = &mut state.collab {
+        for changes in queue {
+            session.send_paint(changes);
+        }
+    }
+    state.status_message = Some(format!("Reconnected; {} queued edit(s) synced", replayed));
+}
+
+fn replace_cells(state: &mut ApplicationState, canvas: &mut CanvasRenderer, cells: &[WireCell]) {
+    for &coord in state.cells.keys() {
+        canvas.mark_dirty(coord);
+    }
+    state.cells = Arc::default();
+    apply_remote_cells(state, canvas, cells);
+}
+
+/// Write cells painted by others; they bypass local undo, which only covers our own commands
+fn apply_remote_cells(state: &mut ApplicationState, canvas: &mut CanvasRenderer, changes: &[WireCell]) {
+    let mut cleared = Vec::new();
+    for change in changes {
+        let coord = (change.x, change.y);
+        match change.color {
+            Some(color) => {
+                Arc::make_mut(&mut state.cells).insert(coord, Cell::with_color(color));
+            }
+            None => {
+                Arc::make_mut(&mut state.cells).remove(&coord);
+                cleared.push(coord);
+            }
+        }
+        canvas.mark_dirty(coord);
+    }
+    state.history.touch();
+    if !cleared.is_empty() {
+        remove_cells_from_groups(state, &cleared);
+    }
+}
+
+fn connect_and_greet(address: &str, name: &str, resume: Option<String>) -> Result<Connection, String> {
+    let mut connection = Connection::connect(address)?.with_limits(MAX_LINE_BYTES, MAX_OUTBOX_BYTES);
+    connection.send(&ClientMessage::Hello { name: name.to_string(), token: local_token(), resume })?;
+    Ok(connection)
+}
+
+/// Resolving and connecting can take seconds, so it runs on a thread and the frame polls
+/// the result
+fn connect_in_background(address: String, name: String, resume: Option<String>) -> Receiver<Result<Connection, String>> {
+    let (sender, receiver) = channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(connect_and_greet(&address, &name, resume));
+    });
+    receiver
+}
+
+/// Connect to `state.collab_address`, replacing any current session. Desktop only: the
+/// browser build has no sockets (its menu leaves the Session entries out)
+pub fn join_session(state: &mut ApplicationState) {
+    if cfg!(target_arch = "wasm32") {
+        state.status_message = Some("Shared canvases need the desktop app".to_string());
+        return;
+    }
+    let address = state.collab_address.clone();
+    state.collab = Some(CollabSession::new(address.clone(), local_name()));
+    state.status_message = Some(format!("Joining {}...", address));
+}
+
+/// Disconnect; dropping the connection closes the socket
+pub fn leave_session(state: &mut ApplicationState) {
+    if state.collab.take().is_some() {
+        state.chat_open = false;
+        state.status_message = Some("Left the shared session".to_string());
+    }
+}
+
+/// Once per frame: reconnect if needed, apply everything the server sent and report our cursor
+pub fn update_session(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(session) = &mut state.collab else {
+        return;
+    };
+
+    if let Err(e) = session.try_reconnect() {
+        leave_session(state);
+        state.status_message = Some(format!("Join failed: {}", e));
+        return;
+    }
+    let Some(connection) = &mut session.connection else {
+        return;
+    };
+    let messages = match connection.poll::<ServerMessage>() {
+        Ok(messages) => messages,
+        Err(e) => {
+            session.go_offline();
+            state.status_message = Some(format!("Connection lost ({}); edits are queued until it returns", e));
+            return;
+        }
+    };
+    if !messages.is_empty() {
+        session.last_heard = get_time();
+    }
+    if let Err(e) = session.keep_alive() {
+        state.status_message = Some(format!("Connection lost ({}); edits are queued until it returns", e));
+        return;
+    }
+    let joining = session.id.is_none();
+    let mut remote_snapshot = Vec::new();
+    let mut remote_changes = Vec::new();
+    let mut rejected = None;
+    let mut refused = None;
+    for message in messages {
+        match message {
+            ServerMessage::Welcome { id, resume } => {
+                session.id = Some(id);
+                session.resume = Some(resume).filter(|token| !token.is_empty());
+                session.retry_delay = RETRY_DELAY;
+            }
+            ServerMessage::Refused { reason } => refused = Some(reason),
+            ServerMessage::Permissions { role, limit } => {
+                session.role = role;
+                session.budget = PaintBudget::new(limit, get_time());
+            }
+            ServerMessage::Rejected { reason, cells } => {
+                rejected = Some(reason);
+                remote_changes.extend(cells);
+            }
+            ServerMessage::PeerJoined { id, name } => {
+                session.push_chat(name.clone(), "joined".to_string());
+                session.peers.insert(id, Peer { name, cursor: None, color: Rgba::rgb(0, 0, 0), tool: String::new() });
+            }
+            ServerMessage::PeerLeft { id } => {
+                if let Some(peer) = session.peers.remove(&id) {
+                    session.push_chat(peer.name, "left".to_string());
+                }
+            }
+            ServerMessage::Cursor { id, x, y, color, tool } => {
+                if let Some(peer) = session.peers.get_mut(&id) {
+                    peer.cursor = Some(Vec2::new(x, y));
+                    peer.color = color;
+                    peer.tool = tool;
+                }
+            }
+            ServerMessage::Chat { name, text } => session.push_chat(name, text),
+            ServerMessage::Snapshot { cells } => remote_snapshot.push(cells),
+            ServerMessage::Cells { changes } => remote_changes.extend(changes),
+            ServerMessage::Locks { locks } => session.locks = locks,
+            ServerMessage::Ping => {}
+        }
+    }
+    if let Some(reason) = refused {
+        // Only a first join gives up; a refused reconnect (say, our old connection still
+        // holds the name) keeps the queue and tries again after the back-off
+        if session.id.is_none() {
+            leave_session(state);
+            state.status_message = Some(format!("Join refused: {}", reason));
+        } else {
+            session.go_offline();
+            state.status_message = Some(format!("Reconnect refused ({}); edits stay queued", reason));
+        }
+        return;
+    }
+    if joining && session.id.is_some() {
+        state.status_message = Some(format!("Joined {}", session.address));
+    }
+    let rejoining = session.rejoining;
+    for cells in remote_snapshot {
+        if rejoining {
+            rebase_on_snapshot(state, canvas, cells);
+        } else {
+            load_snapshot(state, canvas, cells);
+        }
+    }
+    apply_remote_cells(state, canvas, &remote_changes);
+    if let Some(reason) = rejected {
+        state.status_message = Some(format!("Not painted: {}", reason));
+    }
+    let Some(session) = &mut state.collab else {
+        return;
+    };
+
+    // Throttled, and only when something changed
+    let now = get_time();
+    let cursor = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let current = (cursor, state.current_color, format!("{:?}", state.mode));
+    if now - session.last_cursor_time >= CURSOR_INTERVAL && session.last_cursor.as_ref() != Some(&current) {
+        let message = ClientMessage::Cursor { x: cursor.x, y: cursor.y, color: current.1, tool: current.2.clone() };
+        if session.send(&message).is_ok() {
+            session.last_cursor = Some(current);
+            session.last_cursor_time = now;
+        }
+    }
+}
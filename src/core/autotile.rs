@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::selection::SelectionRect;
+
+/// Neighbor bits, clockwise from north
+const N: u8 = 1;
+const NE: u8 = 2;
+const E: u8 = 4;
+const SE: u8 = 8;
+const S: u8 = 16;
+const SW: u8 = 32;
+const W: u8 = 64;
+const NW: u8 = 128;
+
+/// Tile offsets matching the bits above
+const NEIGHBORS: [((i32, i32), u8); 8] = [
+    ((0, -1), N),
+    ((1, -1), NE),
+    ((1, 0), E),
+    ((1, 1), SE),
+    ((0, 1), S),
+    ((-1, 1), SW),
+    ((-1, 0), W),
+    ((-1, -1), NW),
+];
+
+/// Template layouts. Edges16 is a 4x4 sheet indexed by the N/E/S/W mask (N = 1, E = 2,
+/// S = 4, W = 8, read left to right, top to bottom); Blob47 is an 8x6 sheet holding the 47
+/// edge-and-corner variants in ascending order of their 8-neighbor mask (last slot unused)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileSet {
+    Edges16,
+    Blob47,
+}
+
+impl TileSet {
+    /// Sheet size in tiles
+    pub fn sheet(self) -> (i32, i32) {
+        match self {
+            TileSet::Edges16 => (4, 4),
+            TileSet::Blob47 => (8, 6),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TileSet::Edges16 => "16-tile",
+            TileSet::Blob47 => "47-tile",
+        }
+    }
+
+    /// Layout and tile size of a template sheet `width` x `height` cells, if it is one
+    pub fn detect(width: i32, height: i32) -> Option<(TileSet, i32)> {
+        [TileSet::Edges16, TileSet::Blob47].into_iter().find_map(|set| {
+            let (columns, rows) = set.sheet();
+            let size = width / columns;
+            (size > 0 && width == size * columns && height == size * rows).then_some((set, size))
+        })
+    }
+
+    /// Sheet slot of the variant for a tile whose terrain neighbors are `mask`
+    pub fn variant(self, mask: u8) -> usize {
+        match self {
+            TileSet::Edges16 => {
+                let bit = |flag: u8, value: usize| if mask & flag != 0 { value } else { 0 };
+                bit(N, 1) | bit(E, 2) | bit(S, 4) | bit(W, 8)
+            }
+            TileSet::Blob47 => {
+                let reduced = reduce_corners(mask);
+                (0..reduced).filter(|&m| reduce_corners(m) == m).count()
+            }
+        }
+    }
+}
+
+/// Drop corner bits whose two adjacent edges aren't both terrain; they don't change the
+/// tile's look, which is what folds 256 masks down to 47
+fn reduce_corners(mask: u8) -> u8 {
+    let mut reduced = mask;
+    for (corner, a, b) in [(NE, N, E), (SE, S, E), (SW, S, W), (NW, N, W)] {
+        if mask & a == 0 || mask & b == 0 {
+            reduced &= !corner;
+        }
+    }
+    reduced
+}
+
+/// Which of a tile's eight neighbors are terrain
+fn neighbor_mask(terrain: &HashSet<(i32, i32)>, tile: (i32, i32)) -> u8 {
+    NEIGHBORS
+        .iter()
+        .filter(|((dx, dy), _)| terrain.contains(&(tile.0 + dx, tile.1 + dy)))
+        .fold(0, |mask, (_, bit)| mask | bit)
+}
+
+/// Edge and corner variants cut from a template sheet, each stored as a stamp of cells
+/// relative to its tile's top-left corner
+#[derive(Clone, Debug)]
+pub struct AutotileTemplate {
+    pub set: TileSet,
+    /// Tile width and height in cells
+    pub size: i32,
+    pub stamps: Vec<CellGrid>,
+}
+
+impl AutotileTemplate {
+    /// Cut the sheet in `rect` into stamps; the rect must be 4x4 or 8x6 tiles of equal size
+    pub fn from_sheet(cells: &CellGrid, rect: &SelectionRect) -> Result<Self, String> {
+        let (set, size) = TileSet::detect(rect.width(), rect.height()).ok_or_else(|| {
+            format!("A {}x{} area isn't a template: use 4x4 tiles (16-tile) or 8x6 tiles (47-tile)", rect.width(), rect.height())
+        })?;
+        let (columns, rows) = set.sheet();
+        let stamps = (0..columns * rows)
+            .map(|slot| {
+                let (left, top) = (rect.min_x + slot % columns * size, rect.min_y + slot / columns * size);
+                (0..size)
+                    .flat_map(|dy| (0..size).map(move |dx| (dx, dy)))
+                    .filter_map(|(dx, dy)| cells.get(&(left + dx, top + dy)).filter(|c| c.is_filled).map(|&c| ((dx, dy), c)))
+                    .collect()
+            })
+            .collect();
+        Ok(Self { set, size, stamps })
+    }
+
+    /// Tile containing a cell
+    pub fn tile_at(&self, cell: (i32, i32)) -> (i32, i32) {
+        (cell.0.div_euclid(self.size), cell.1.div_euclid(self.size))
+    }
+
+    /// Whether `tile` currently holds one of the template's (non-empty) variants; terrain is
+    /// read off the canvas this way, so it survives undo and reloading
+    pub fn is_terrain(&self, cells: &CellGrid, tile: (i32, i32)) -> bool {
+        let (left, top) = (tile.0 * self.size, tile.1 * self.size);
+        let found: CellGrid = self
+            .offsets()
+            .filter_map(|(dx, dy)| cells.get(&(left + dx, top + dy)).filter(|c| c.is_filled).map(|&c| ((dx, dy), c)))
+            .collect();
+        !found.is_empty() && self.stamps.contains(&found)
+    }
+
+    /// Cell changes that add `tile` to the terrain (or take it out) and refresh the terrain
+    /// tiles around it; taking out a tile that isn't terrain changes nothing
+    pub fn repaint(&self, cells: &CellGrid, tile: (i32, i32), add: bool) -> Vec<((i32, i32), Option<Cell>)> {
+        // Variants of the 3x3 block around `tile` depend on the 5x5 block around it
+        let mut terrain: HashSet<(i32, i32)> = (-2..=2)
+            .flat_map(|dy| (-2..=2).map(move |dx| (tile.0 + dx, tile.1 + dy)))
+            .filter(|&t| self.is_terrain(cells, t))
+            .collect();
+        let changed = if add { terrain.insert(tile) } else { terrain.remove(&tile) };
+        if !changed && !add {
+            return Vec::new();
+        }
+
+        std::iter::once(tile)
+            .chain(NEIGHBORS.iter().map(|((dx, dy), _)| (tile.0 + dx, tile.1 + dy)).filter(|t| terrain.contains(t)))
+            .flat_map(|t| self.tile_cells(&terrain, t))
+            .collect()
+    }
+
+    /// Every cell of `tile`: the variant picked by its neighbors for terrain tiles, empty otherwise
+    fn tile_cells(&self, terrain: &HashSet<(i32, i32)>, tile: (i32, i32)) -> Vec<((i32, i32), Option<Cell>)> {
+        let stamp = terrain.contains(&tile).then(|| &self.stamps[self.set.variant(neighbor_mask(terrain, tile))]);
+        let (left, top) = (tile.0 * self.size, tile.1 * self.size);
+        self.offsets()
+            .map(|(dx, dy)| ((left + dx, top + dy), stamp.and_then(|s| s.get(&(dx, dy)).copied())))
+            .collect()
+    }
+
+    /// Cell offsets within a tile, row by row
+    fn offsets(&self) -> impl Iterator<Item = (i32, i32)> {
+        let size = self.size;
+        (0..size).flat_map(move |dy| (0..size).map(move |dx| (dx, dy)))
+    }
+}
@@ -0,0 +1,21 @@
+use crate::core::rng::Rng;
+
+/// Pick random cell offsets inside a disc of `radius` cells
+/// `density` is the fraction of the disc's area emitted per call (at least one cell)
+pub fn scatter_offsets(rng: &mut Rng, radius: i32, density: f32) -> Vec<(i32, i32)> {
+    let radius = radius.max(0);
+    let area = std::f32::consts::PI * (radius as f32 + 0.5).powi(2);
+    let count = ((area * density).round() as usize).max(1);
+    let r2 = radius * radius + radius;
+
+    let mut offsets = Vec::with_capacity(count);
+    while offsets.len() < count {
+        // Rejection sampling keeps the distribution uniform over the disc
+        let dx = rng.range_i32(-radius, radius);
+        let dy = rng.range_i32(-radius, radius);
+        if dx * dx + dy * dy <= r2 {
+            offsets.push((dx, dy));
+        }
+    }
+    offsets
+}
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+use crate::core::cell::{grid_bounds, CellGrid};
+
+/// Size limits worth checking against: GBA hardware sprite shapes (squares and the largest bands)
+pub const SIZE_PRESETS: [(i32, i32); 6] = [(8, 8), (16, 16), (32, 32), (64, 64), (64, 32), (32, 64)];
+/// Color limits: one 4bpp palette bank, or the full 8bpp palette
+pub const COLOR_PRESETS: [usize; 2] = [16, 256];
+
+/// Limits a drawing should stay within for a target (e.g. a GBA sprite)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpriteBudget {
+    pub max_colors: usize,
+    pub max_width: i32,
+    pub max_height: i32,
+}
+
+impl SpriteBudget {
+    /// 4bpp 64×64 sprite, the largest single GBA OBJ
+    pub const GBA_SPRITE: SpriteBudget = SpriteBudget { max_colors: 16, max_width: 64, max_height: 64 };
+
+    /// Next size from SIZE_PRESETS, keeping the color limit
+    pub fn next_size(self) -> Self {
+        let i = SIZE_PRESETS.iter().position(|&s| s == (self.max_width, self.max_height)).unwrap_or(0);
+        let (max_width, max_height) = SIZE_PRESETS[(i + 1) % SIZE_PRESETS.len()];
+        Self { max_width, max_height, ..self }
+    }
+
+    /// Next color limit from COLOR_PRESETS, keeping the size
+    pub fn next_colors(self) -> Self {
+        let i = COLOR_PRESETS.iter().position(|&c| c == self.max_colors).unwrap_or(0);
+        Self { max_colors: COLOR_PRESETS[(i + 1) % COLOR_PRESETS.len()], ..self }
+    }
+}
+
+/// How the drawing measures up against a budget
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BudgetReport {
+    pub colors: usize,
+    pub width: i32,
+    pub height: i32,
+    pub budget: SpriteBudget,
+}
+
+impl BudgetReport {
+    pub fn over_colors(&self) -> bool {
+        self.colors > self.budget.max_colors
+    }
+
+    pub fn over_size(&self) -> bool {
+        self.width > self.budget.max_width || self.height > self.budget.max_height
+    }
+}
+
+/// Count distinct colors and measure the drawing's bounding box
+pub fn check_budget(cells: &CellGrid, budget: SpriteBudget) -> BudgetReport {
+    let colors: HashSet<(u8, u8, u8, u8)> = cells
+        .values()
+        .filter(|cell| cell.is_filled)
+        .map(|cell| (cell.color.r, cell.color.g, cell.color.b, cell.color.a))
+        .collect();
+    let (width, height) = grid_bounds(cells).map(|rect| (rect.width(), rect.height())).unwrap_or((0, 0));
+    BudgetReport { colors: colors.len(), width, height, budget }
+}
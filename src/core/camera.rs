@@ -10,8 +10,26 @@ pub struct Camera {
     /// World cell coordinates at screen position (0, 0)
     pub origin: Vec2,
 
-    /// Zoom level where 1.0 = BASE_CELL_PIXELS per cell
+    /// Zoom level where 1.0 = `base_cell_pixels` per cell
     pub zoom: f32,
+
+    /// Screen pixels per cell at zoom 1.0 (the document's cell size setting)
+    pub base_cell_pixels: f32,
+
+    /// Cell width / height (1.0 = square cells, 2.0 = C64-style wide cells)
+    pub aspect: f32,
+
+    /// View rotation in radians (clockwise on screen), applied around screen (0, 0)
+    pub rotation: f32,
+
+    /// Mirror the view horizontally (non-destructive "flip view" check)
+    pub flip_x: bool,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Camera {
@@ -19,34 +37,78 @@ impl Camera {
         Self {
             origin: Vec2::ZERO,
             zoom: 1.0,
+            base_cell_pixels: BASE_CELL_PIXELS,
+            aspect: 1.0,
+            rotation: 0.0,
+            flip_x: false,
         }
     }
 
     /// Get current size in screen pixels of one world cell
     #[inline]
     pub fn pixel_scale(&self) -> f32 {
-        BASE_CELL_PIXELS * self.zoom
+        self.base_cell_pixels * self.zoom
+    }
+
+    /// Screen size of one cell; differs per axis for non-square cells
+    #[inline]
+    pub fn cell_size(&self) -> Vec2 {
+        let scale = self.pixel_scale();
+        Vec2::new(scale * self.aspect, scale)
     }
 
     /// Convert integer cell coordinates to screen pixels
     pub fn cell_to_screen(&self, cell: (i32, i32)) -> Vec2 {
         let cell_world = Vec2::new(cell.0 as f32, cell.1 as f32);
-        (cell_world - self.origin) * self.pixel_scale()
+        self.world_to_screen(cell_world)
+    }
+
+    /// Convert world cell coordinates (float) to screen pixels
+    pub fn world_to_screen(&self, world: Vec2) -> Vec2 {
+        let mut local = (world - self.origin) * self.cell_size();
+        if self.flip_x {
+            local.x = -local.x;
+        }
+        Vec2::from_angle(self.rotation).rotate(local)
     }
 
     /// Convert screen pixels to world cell coordinates (float)
     pub fn screen_to_cell(&self, screen: Vec2) -> Vec2 {
-        (screen / self.pixel_scale()) + self.origin
+        self.screen_delta_to_world(screen) + self.origin
+    }
+
+    /// Convert a screen-space movement to world cell units (no origin offset)
+    pub fn screen_delta_to_world(&self, delta: Vec2) -> Vec2 {
+        let mut local = Vec2::from_angle(-self.rotation).rotate(delta);
+        if self.flip_x {
+            local.x = -local.x;
+        }
+        local / self.cell_size()
+    }
+
+    /// World corner of the rect [min, max) that lands at the quad's local top-left on screen
+    /// (the max-x corner when the view is mirrored); textures are anchored there
+    pub fn quad_anchor(&self, min: Vec2, max: Vec2) -> Vec2 {
+        if self.flip_x {
+            Vec2::new(max.x, min.y)
+        } else {
+            min
+        }
     }
 
     /// Get the world-space rect of the visible canvas area
+    /// With rotation this is the bounding box of the rotated screen
     pub fn visible_world_rect(&self, screen_w: f32, screen_h: f32) -> (f32, f32, f32, f32) {
-        let scale = self.pixel_scale();
-        let world_min_x = self.origin.x;
-        let world_min_y = self.origin.y;
-        let world_max_x = self.origin.x + screen_w / scale;
-        let world_max_y = self.origin.y + screen_h / scale;
-        (world_min_x, world_min_y, world_max_x, world_max_y)
+        let corners = [
+            Vec2::ZERO,
+            Vec2::new(screen_w, 0.0),
+            Vec2::new(0.0, screen_h),
+            Vec2::new(screen_w, screen_h),
+        ]
+        .map(|c| self.screen_to_cell(c));
+        let min = corners.iter().fold(Vec2::splat(f32::MAX), |acc, c| acc.min(*c));
+        let max = corners.iter().fold(Vec2::splat(f32::MIN), |acc, c| acc.max(*c));
+        (min.x, min.y, max.x, max.y)
     }
 
     /// Pan the camera by a delta in world cell units
@@ -54,6 +116,11 @@ impl Camera {
         self.origin += delta_world;
     }
 
+    /// Pan so the world point lands at `screen` (e.g. the screen center)
+    pub fn center_on(&mut self, world: Vec2, screen: Vec2) {
+        self.origin += world - self.screen_to_cell(screen);
+    }
+
     /// Zoom around a point on screen (Figma-style zoom)
     pub fn zoom_around_cursor(&mut self, cursor_screen: Vec2, zoom_factor: f32) {
         // Get world position under cursor BEFORE zoom
@@ -69,4 +136,20 @@ impl Camera {
         // Adjust origin so the world point under cursor stays fixed
         self.origin += world_before - world_after;
     }
+
+    /// Rotate the view by `delta` radians around a screen point, keeping the world point under it fixed
+    pub fn rotate_around(&mut self, pivot_screen: Vec2, delta: f32) {
+        let world_before = self.screen_to_cell(pivot_screen);
+        self.rotation = (self.rotation + delta).rem_euclid(std::f32::consts::TAU);
+        let world_after = self.screen_to_cell(pivot_screen);
+        self.origin += world_before - world_after;
+    }
+
+    /// Toggle the horizontal mirror, keeping the world point under `pivot_screen` fixed
+    pub fn toggle_flip_around(&mut self, pivot_screen: Vec2) {
+        let world_before = self.screen_to_cell(pivot_screen);
+        self.flip_x = !self.flip_x;
+        let world_after = self.screen_to_cell(pivot_screen);
+        self.origin += world_before - world_after;
+    }
 }
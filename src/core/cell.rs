@@ -1,48 +1,173 @@
-use macroquad::prelude::*;
-use std::collections::HashMap;
+use crate::core::color::Rgba;
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Cell {
-    pub color: Color,
+    pub color: Rgba,
     pub is_filled: bool,
 }
 
 impl Cell {
-    #[allow(dead_code)]
     pub fn new() -> Self {
         Cell {
-            color: WHITE,
+            color: Rgba::WHITE,
             is_filled: false,
         }
     }
 
-    pub fn with_color(color: Color) -> Self {
+    pub fn with_color(color: Rgba) -> Self {
         Cell {
             color,
             is_filled: true,
         }
     }
+}
 
-    #[allow(dead_code)]
-    pub fn clear(&mut self) {
-        self.color = WHITE;
-        self.is_filled = false;
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 pub type CellGrid = HashMap<(i32, i32), Cell>;
 
-pub fn grid_position_to_cell_coords(pos: &Vec2, grid_size: f32) -> (i32, i32) {
-    (
-        (pos.x / grid_size).floor() as i32,
-        (pos.y / grid_size).floor() as i32,
-    )
+/// Bounding rect of all filled cells, or None for an empty grid
+pub fn grid_bounds(cells: &CellGrid) -> Option<crate::core::selection::SelectionRect> {
+    let mut filled = cells.iter().filter(|(_, c)| c.is_filled).map(|(&coord, _)| coord);
+    let first = filled.next()?;
+    let mut rect = crate::core::selection::SelectionRect::from_points(first, first);
+    for (x, y) in filled {
+        rect.min_x = rect.min_x.min(x);
+        rect.min_y = rect.min_y.min(y);
+        rect.max_x = rect.max_x.max(x);
+        rect.max_y = rect.max_y.max(y);
+    }
+    Some(rect)
+}
+
+/// Remove every cell inside `rect` and return what was there; walks the rect when it
+/// is smaller than the grid, otherwise sweeps the grid once
+pub fn clear_rect(cells: &mut CellGrid, rect: &crate::core::selection::SelectionRect) -> Vec<((i32, i32), Cell)> {
+    let area = rect.width() as i64 * rect.height() as i64;
+    if area <= cells.len() as i64 {
+        (rect.min_y..=rect.max_y)
+            .flat_map(|y| (rect.min_x..=rect.max_x).map(move |x| (x, y)))
+            .filter_map(|coord| cells.remove(&coord).map(|cell| (coord, cell)))
+            .collect()
+    } else {
+        cells.extract_if(|&(x, y), _| rect.contains(x, y)).collect()
+    }
 }
 
-#[allow(dead_code)]
-pub fn cell_coords_to_screen_position(coords: (i32, i32), grid_size: f32) -> Vec2 {
-    Vec2::new(
-        coords.0 as f32 * grid_size,
-        coords.1 as f32 * grid_size,
-    )
+/// Remove every cell at `coords` and return what was there; looks each one up when there
+/// are fewer of them than cells, otherwise sweeps the grid once
+pub fn clear_cells(cells: &mut CellGrid, coords: &HashSet<(i32, i32)>) -> Vec<((i32, i32), Cell)> {
+    if coords.len() <= cells.len() {
+        coords.iter().filter_map(|&coord| cells.remove(&coord).map(|cell| (coord, cell))).collect()
+    } else {
+        cells.extract_if(|coord, _| coords.contains(coord)).collect()
+    }
+}
+
+/// Remove every cell, returning the rect the drawing covered so only the chunks under it
+/// need redrawing
+pub fn clear_grid(cells: &mut CellGrid) -> Option<crate::core::selection::SelectionRect> {
+    let bounds = grid_bounds(cells);
+    cells.clear();
+    bounds
+}
+
+/// Cell shape as an integer width:height ratio, e.g. 2:1 for C64-style wide pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellAspect {
+    pub w: u32,
+    pub h: u32,
+}
+
+impl CellAspect {
+    pub const SQUARE: CellAspect = CellAspect { w: 1, h: 1 };
+    /// Presets cycled from the UI
+    pub const PRESETS: [CellAspect; 3] = [CellAspect::SQUARE, CellAspect { w: 2, h: 1 }, CellAspect { w: 1, h: 2 }];
+
+    /// Width divided by height, as used by the camera
+    pub fn ratio(self) -> f32 {
+        self.w as f32 / self.h as f32
+    }
+
+    pub fn next_preset(self) -> CellAspect {
+        let i = Self::PRESETS.iter().position(|&a| a == self).unwrap_or(0);
+        Self::PRESETS[(i + 1) % Self::PRESETS.len()]
+    }
+}
+
+/// Per-document canvas resolution: screen pixels per cell at 100% zoom, and how often
+/// a major grid line is drawn over the per-cell minor lines
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridSettings {
+    pub cell_pixels: u32,
+    /// Cells between major lines; 0 draws minor lines only
+    pub major_every: u32,
+}
+
+impl GridSettings {
+    pub const DEFAULT: GridSettings = GridSettings { cell_pixels: 24, major_every: 16 };
+    pub const MIN_CELL_PIXELS: u32 = 8;
+    pub const MAX_CELL_PIXELS: u32 = 64;
+    /// Major line spacings cycled from the settings panel
+    pub const MAJOR_OPTIONS: [u32; 5] = [0, 4, 8, 16, 32];
+
+    pub fn next_major(self) -> u32 {
+        let i = Self::MAJOR_OPTIONS.iter().position(|&m| m == self.major_every).unwrap_or(0);
+        Self::MAJOR_OPTIONS[(i + 1) % Self::MAJOR_OPTIONS.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::selection::SelectionRect;
+
+    fn grid(coords: &[(i32, i32)]) -> CellGrid {
+        coords.iter().map(|&coord| (coord, Cell::with_color(Rgba::WHITE))).collect()
+    }
+
+    fn sorted(removed: Vec<((i32, i32), Cell)>) -> Vec<(i32, i32)> {
+        let mut coords: Vec<_> = removed.into_iter().map(|(coord, _)| coord).collect();
+        coords.sort();
+        coords
+    }
+
+    #[test]
+    fn clear_rect_walks_small_rects_and_sweeps_large_ones() {
+        let cells = grid(&[(0, 0), (1, 1), (5, 5), (-3, 2)]);
+        // 4 cells of area against 4 cells: walked
+        let mut small = cells.clone();
+        assert_eq!(sorted(clear_rect(&mut small, &SelectionRect::from_points((0, 0), (1, 1)))), vec![(0, 0), (1, 1)]);
+        assert_eq!(small.len(), 2);
+        // Far more area than cells: swept
+        let mut large = cells.clone();
+        assert_eq!(sorted(clear_rect(&mut large, &SelectionRect::from_points((-100, -100), (2, 100)))), vec![(-3, 2), (0, 0), (1, 1)]);
+        assert_eq!(large.keys().collect::<Vec<_>>(), vec![&(5, 5)]);
+    }
+
+    #[test]
+    fn clear_cells_removes_only_the_given_cells() {
+        let cells = grid(&[(0, 0), (1, 1), (5, 5)]);
+        let mut few = cells.clone();
+        assert_eq!(sorted(clear_cells(&mut few, &HashSet::from([(1, 1), (9, 9)]))), vec![(1, 1)]);
+        assert_eq!(few.len(), 2);
+        let mut many = cells.clone();
+        let coords: HashSet<_> = (0..10).map(|i| (i, i)).collect();
+        assert_eq!(sorted(clear_cells(&mut many, &coords)), vec![(0, 0), (1, 1), (5, 5)]);
+        assert!(many.is_empty());
+    }
+
+    #[test]
+    fn clear_grid_reports_what_needs_redrawing() {
+        let mut cells = grid(&[(2, 3), (-1, 7)]);
+        assert_eq!(clear_grid(&mut cells), Some(SelectionRect::from_points((-1, 3), (2, 7))));
+        assert!(cells.is_empty());
+        assert_eq!(clear_grid(&mut cells), None);
+    }
 }
@@ -1,4 +1,6 @@
-#[derive(Copy, Clone, Debug, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Rgba {
     pub r: u8,
     pub g: u8,
@@ -11,23 +13,27 @@ impl Rgba {
         Self { r, g, b, a: 255 }
     }
 
-    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self { r, g, b, a }
     }
 
-    /// Convert to macroquad Color (f32 0.0-1.0)
-    pub fn to_mq_color(self) -> macroquad::color::Color {
-        macroquad::color::Color::from_rgba(self.r, self.g, self.b, self.a)
+    pub const WHITE: Rgba = Rgba::rgb(255, 255, 255);
+    pub const BLUE: Rgba = Rgba::rgb(0, 121, 241);
+
+    /// "#rrggbbaa" hex string
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
     }
 
-    /// Convert from macroquad Color
-    pub fn from_mq_color(c: macroquad::color::Color) -> Self {
-        Self {
-            r: (c.r * 255.0) as u8,
-            g: (c.g * 255.0) as u8,
-            b: (c.b * 255.0) as u8,
-            a: (c.a * 255.0) as u8,
+    /// Parse "#rrggbb" or "#rrggbbaa" (the leading '#' is optional)
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return None;
         }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        let a = if hex.len() == 8 { channel(6)? } else { 255 };
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?, a))
     }
 }
 
@@ -81,6 +87,53 @@ pub const GBA_PALETTE: [[Rgba; GBA_PALETTE_COLS]; GBA_PALETTE_ROWS] = [
     ],
 ];
 
+/// PICO-8's fixed 16-color palette
+pub const PICO8_PALETTE: [Rgba; 16] = [
+    Rgba::rgb(0x00, 0x00, 0x00),
+    Rgba::rgb(0x1D, 0x2B, 0x53),
+    Rgba::rgb(0x7E, 0x25, 0x53),
+    Rgba::rgb(0x00, 0x87, 0x51),
+    Rgba::rgb(0xAB, 0x52, 0x36),
+    Rgba::rgb(0x5F, 0x57, 0x4F),
+    Rgba::rgb(0xC2, 0xC3, 0xC7),
+    Rgba::rgb(0xFF, 0xF1, 0xE8),
+    Rgba::rgb(0xFF, 0x00, 0x4D),
+    Rgba::rgb(0xFF, 0xA3, 0x00),
+    Rgba::rgb(0xFF, 0xEC, 0x27),
+    Rgba::rgb(0x00, 0xE4, 0x36),
+    Rgba::rgb(0x29, 0xAD, 0xFF),
+    Rgba::rgb(0x83, 0x76, 0x9C),
+    Rgba::rgb(0xFF, 0x77, 0xA8),
+    Rgba::rgb(0xFF, 0xCC, 0xAA),
+];
+
+/// TIC-80's default palette (Sweetie 16), used when a cart doesn't define its own
+pub const SWEETIE16_PALETTE: [Rgba; 16] = [
+    Rgba::rgb(0x1a, 0x1c, 0x2c),
+    Rgba::rgb(0x5d, 0x27, 0x5d),
+    Rgba::rgb(0xb1, 0x3e, 0x53),
+    Rgba::rgb(0xef, 0x7d, 0x57),
+    Rgba::rgb(0xff, 0xcd, 0x75),
+    Rgba::rgb(0xa7, 0xf0, 0x70),
+    Rgba::rgb(0x38, 0xb7, 0x64),
+    Rgba::rgb(0x25, 0x71, 0x79),
+    Rgba::rgb(0x29, 0x36, 0x6f),
+    Rgba::rgb(0x3b, 0x5d, 0xc9),
+    Rgba::rgb(0x41, 0xa6, 0xf6),
+    Rgba::rgb(0x73, 0xef, 0xf7),
+    Rgba::rgb(0xf4, 0xf4, 0xf4),
+    Rgba::rgb(0x94, 0xb0, 0xc2),
+    Rgba::rgb(0x56, 0x6c, 0x86),
+    Rgba::rgb(0x33, 0x3c, 0x57),
+];
+
+/// Index of the palette entry closest to `color` (RGBA distance); 0 for an empty palette
+pub fn nearest_palette_index(palette: &[Rgba], color: Rgba) -> usize {
+    (0..palette.len())
+        .min_by(|&a, &b| palette[a].distance(color).total_cmp(&palette[b].distance(color)))
+        .unwrap_or(0)
+}
+
 /// Convert GBA 5-bit color component (0-31) to 8-bit (0-255)
 pub fn gba5_to_u8(c5: u8) -> u8 {
     ((c5 as u16 * 255) / 31) as u8
@@ -94,9 +147,134 @@ pub fn generate_gba_extended_palette() -> Vec<Rgba> {
     for &g in &steps {
         for &r in &steps {
             for &b in &steps {
-                colors.push(Rgba::rgba(gba5_to_u8(r), gba5_to_u8(g), gba5_to_u8(b), 255));
+                colors.push(Rgba::new(gba5_to_u8(r), gba5_to_u8(g), gba5_to_u8(b), 255));
             }
         }
     }
     colors
 }
+
+/// Snap an 8-bit channel to the nearest GBA 5-bit level
+pub fn quantize_gba(c: u8) -> u8 {
+    gba5_to_u8(((c as u16 * 31 + 127) / 255) as u8)
+}
+
+impl Rgba {
+    /// Hue in degrees [0, 360), saturation and value in [0, 1]
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let s = if max == 0.0 { 0.0 } else { delta / max };
+        (h, s, max)
+    }
+
+    pub fn from_hsv(h: f32, s: f32, v: f32, a: u8) -> Self {
+        let h = h.rem_euclid(360.0);
+        let c = v * s;
+        let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+        let m = v - c;
+        let (r, g, b) = match (h / 60.0) as i32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let to_u8 = |f: f32| ((f + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self::new(to_u8(r), to_u8(g), to_u8(b), a)
+    }
+}
+
+/// Rotate `hue` toward `target` by at most `amount` degrees along the shorter arc
+fn shift_hue_toward(hue: f32, target: f32, amount: f32) -> f32 {
+    let diff = (target - hue + 540.0).rem_euclid(360.0) - 180.0;
+    hue + diff.clamp(-amount, amount)
+}
+
+/// Generate an N-step shading ramp (dark to light) around `base`
+/// Shadows drift toward blue and gain saturation, highlights drift toward yellow
+/// and lose it; every step is snapped to the GBA 15-bit color space
+pub fn generate_hue_shifted_ramp(base: Rgba, steps: usize) -> Vec<Rgba> {
+    const SHADOW_HUE: f32 = 240.0;
+    const HIGHLIGHT_HUE: f32 = 60.0;
+    const MAX_HUE_SHIFT: f32 = 40.0;
+
+    let (h, s, v) = base.to_hsv();
+    let mut ramp = Vec::with_capacity(steps);
+    for i in 0..steps {
+        // -1.0 for the darkest step, +1.0 for the lightest
+        let t = if steps > 1 { i as f32 / (steps - 1) as f32 * 2.0 - 1.0 } else { 0.0 };
+
+        let target = if t < 0.0 { SHADOW_HUE } else { HIGHLIGHT_HUE };
+        let hue = shift_hue_toward(h, target, MAX_HUE_SHIFT * t.abs());
+        let sat = (s - 0.25 * t).clamp(0.0, 1.0);
+        let val = (v + 0.45 * t).clamp(0.08, 1.0);
+
+        let c = Rgba::from_hsv(hue, sat, val, base.a);
+        let c = Rgba::new(quantize_gba(c.r), quantize_gba(c.g), quantize_gba(c.b), c.a);
+        if ramp.last() != Some(&c) {
+            ramp.push(c);
+        }
+    }
+    ramp
+}
+
+/// Classic color-wheel relationships offered as suggestions next to the palette
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Harmony {
+    Complementary,
+    Analogous,
+    Triadic,
+    SplitComplementary,
+}
+
+impl Harmony {
+    pub const ALL: [Harmony; 4] = [Harmony::Complementary, Harmony::Analogous, Harmony::Triadic, Harmony::SplitComplementary];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Harmony::Complementary => "Complement",
+            Harmony::Analogous => "Analogous",
+            Harmony::Triadic => "Triadic",
+            Harmony::SplitComplementary => "Split",
+        }
+    }
+
+    /// Hue offsets in degrees from the base color
+    fn hue_offsets(self) -> &'static [f32] {
+        match self {
+            Harmony::Complementary => &[180.0],
+            Harmony::Analogous => &[-30.0, 30.0],
+            Harmony::Triadic => &[120.0, 240.0],
+            Harmony::SplitComplementary => &[150.0, 210.0],
+        }
+    }
+
+    /// Partners of `base` keeping its saturation, value and alpha, snapped to the GBA
+    /// 15-bit color space
+    pub fn colors(self, base: Rgba) -> Vec<Rgba> {
+        let (h, s, v) = base.to_hsv();
+        self.hue_offsets()
+            .iter()
+            .map(|offset| {
+                let c = Rgba::from_hsv(h + offset, s, v, base.a);
+                Rgba::new(quantize_gba(c.r), quantize_gba(c.g), quantize_gba(c.b), c.a)
+            })
+            .collect()
+    }
+}
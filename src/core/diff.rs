@@ -0,0 +1,50 @@
+use std::collections::HashSet;
+use crate::core::cell::CellGrid;
+
+/// How a cell differs between two versions of a drawing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellDiff {
+    /// Empty before, filled now
+    Added,
+    /// Filled before, empty now
+    Removed,
+    /// Filled in both with different colors
+    Changed,
+}
+
+/// Per-cell differences from `before` to `after`, sorted by row then column
+pub fn diff_cells(before: &CellGrid, after: &CellGrid) -> Vec<((i32, i32), CellDiff)> {
+    let color = |grid: &CellGrid, coord| grid.get(&coord).filter(|c| c.is_filled).map(|c| c.color);
+    let coords: HashSet<(i32, i32)> = before.keys().chain(after.keys()).copied().collect();
+    let mut diffs: Vec<((i32, i32), CellDiff)> = coords
+        .into_iter()
+        .filter_map(|coord| {
+            let kind = match (color(before, coord), color(after, coord)) {
+                (None, Some(_)) => CellDiff::Added,
+                (Some(_), None) => CellDiff::Removed,
+                (Some(a), Some(b)) if a != b => CellDiff::Changed,
+                _ => return None,
+            };
+            Some((coord, kind))
+        })
+        .collect();
+    diffs.sort_by_key(|&((x, y), _)| (y, x));
+    diffs
+}
+
+/// The cells of `coords` moved so the set's bounding box starts at (0, 0), for comparing
+/// frames drawn at different places on the canvas
+pub fn normalized(cells: &CellGrid, coords: &HashSet<(i32, i32)>) -> CellGrid {
+    let min_x = coords.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = coords.iter().map(|c| c.1).min().unwrap_or(0);
+    coords
+        .iter()
+        .filter_map(|&(x, y)| cells.get(&(x, y)).map(|&cell| ((x - min_x, y - min_y), cell)))
+        .collect()
+}
+
+/// Counts of (added, removed, changed) cells
+pub fn diff_counts(diffs: &[((i32, i32), CellDiff)]) -> (usize, usize, usize) {
+    let count = |kind| diffs.iter().filter(|(_, k)| *k == kind).count();
+    (count(CellDiff::Added), count(CellDiff::Removed), count(CellDiff::Changed))
+}
@@ -0,0 +1,125 @@
+use crate::core::color::Rgba;
+
+/// Classic 4×4 ordered-dither matrix (values 0..16)
+pub const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Threshold in (0, 1) for a cell; keyed on absolute coordinates so separate bands line up
+pub fn bayer_threshold(x: i32, y: i32) -> f32 {
+    (BAYER_4X4[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize] as f32 + 0.5) / 16.0
+}
+
+/// Ordered-dither transition from `from_color` at `from` to `to_color` at `to` (world cell
+/// units); cells before the start or past the end get the pure colors
+pub fn dither_gradient(
+    cells: impl IntoIterator<Item = (i32, i32)>,
+    from: (f32, f32),
+    to: (f32, f32),
+    from_color: Rgba,
+    to_color: Rgba,
+) -> Vec<((i32, i32), Rgba)> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_sq = (dx * dx + dy * dy).max(f32::EPSILON);
+    cells
+        .into_iter()
+        .map(|(x, y)| {
+            let (cx, cy) = (x as f32 + 0.5 - from.0, y as f32 + 0.5 - from.1);
+            let t = ((cx * dx + cy * dy) / length_sq).clamp(0.0, 1.0);
+            let color = if t > bayer_threshold(x, y) { to_color } else { from_color };
+            ((x, y), color)
+        })
+        .collect()
+}
+
+/// How a full-color image is fitted to a palette
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quantize {
+    /// Keep the image's own colors
+    Off,
+    /// Each cell snaps to its closest palette entry
+    Nearest,
+    /// Bayer-offset before snapping, giving a regular cross-hatch
+    Ordered,
+    /// Each cell's rounding error is pushed onto its unvisited neighbours
+    FloydSteinberg,
+}
+
+impl Quantize {
+    pub fn label(self) -> &'static str {
+        match self {
+            Quantize::Off => "Full color",
+            Quantize::Nearest => "Nearest",
+            Quantize::Ordered => "Ordered",
+            Quantize::FloydSteinberg => "Floyd-Steinberg",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Quantize::Off => Quantize::Nearest,
+            Quantize::Nearest => Quantize::Ordered,
+            Quantize::Ordered => Quantize::FloydSteinberg,
+            Quantize::FloydSteinberg => Quantize::Off,
+        }
+    }
+}
+
+/// Fit a row-major image of `width` columns to `palette`, one cell per pixel; `None` pixels
+/// stay empty and neither take nor pass on dithering error. Colors pass through unchanged
+/// with `Quantize::Off` or an empty palette
+pub fn quantize(pixels: &[Option<Rgba>], width: usize, palette: &[Rgba], mode: Quantize) -> Vec<Option<Rgba>> {
+    if mode == Quantize::Off || palette.is_empty() || width == 0 {
+        return pixels.to_vec();
+    }
+    let nearest = |rgb: [f32; 3]| {
+        let [r, g, b] = rgb.map(|c| c.round().clamp(0.0, 255.0) as u8);
+        palette[crate::core::color::nearest_palette_index(palette, Rgba::rgb(r, g, b))]
+    };
+    // Ordered offsets span roughly one palette step per channel
+    let spread = 255.0 / (palette.len() as f32).cbrt();
+    let mut error = vec![[0.0f32; 3]; pixels.len()];
+    let mut out = Vec::with_capacity(pixels.len());
+
+    for (i, pixel) in pixels.iter().enumerate() {
+        let Some(color) = pixel else {
+            out.push(None);
+            continue;
+        };
+        let (x, y) = (i % width, i / width);
+        let mut rgb = [color.r as f32, color.g as f32, color.b as f32];
+        let chosen = match mode {
+            Quantize::Ordered => {
+                let offset = (bayer_threshold(x as i32, y as i32) - 0.5) * spread;
+                nearest(rgb.map(|c| c + offset))
+            }
+            Quantize::FloydSteinberg => {
+                for (c, e) in rgb.iter_mut().zip(error[i]) {
+                    *c += e;
+                }
+                let chosen = nearest(rgb);
+                let residual = [rgb[0] - chosen.r as f32, rgb[1] - chosen.g as f32, rgb[2] - chosen.b as f32];
+                let rows = pixels.len().div_ceil(width);
+                for (dx, dy, weight) in [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)] {
+                    let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                    if nx < 0 || nx >= width as i64 || ny as usize >= rows {
+                        continue;
+                    }
+                    let n = ny as usize * width + nx as usize;
+                    if let Some(slot) = error.get_mut(n).filter(|_| pixels[n].is_some()) {
+                        for (s, r) in slot.iter_mut().zip(residual) {
+                            *s += r * weight / 16.0;
+                        }
+                    }
+                }
+                chosen
+            }
+            _ => nearest(rgb),
+        };
+        out.push(Some(chosen));
+    }
+    out
+}
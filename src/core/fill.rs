@@ -0,0 +1,74 @@
+use std::collections::{HashSet, VecDeque};
+use crate::core::cell::CellGrid;
+use crate::core::color::Rgba;
+use crate::core::selection::SelectionRect;
+
+/// Largest useful tolerance: the RGBA distance between transparent black and opaque white
+pub const MAX_FILL_TOLERANCE: f32 = 510.0;
+
+impl Rgba {
+    /// Euclidean distance over the four channels
+    pub fn distance(self, other: Rgba) -> f32 {
+        let d = |a: u8, b: u8| (a as f32 - b as f32).powi(2);
+        (d(self.r, other.r) + d(self.g, other.g) + d(self.b, other.b) + d(self.a, other.a)).sqrt()
+    }
+}
+
+/// What the fill replaces: empty cells, or cells whose color is within `tolerance` of `color`
+#[derive(Clone, Copy, Debug)]
+pub struct FillTarget {
+    pub color: Option<Rgba>,
+    pub tolerance: f32,
+}
+
+impl FillTarget {
+    /// Target whatever is at `start`
+    pub fn at(cells: &CellGrid, start: (i32, i32), tolerance: f32) -> Self {
+        let color = cells.get(&start).filter(|c| c.is_filled).map(|c| c.color);
+        Self { color, tolerance }
+    }
+
+    pub fn matches(&self, cells: &CellGrid, coord: (i32, i32)) -> bool {
+        let here = cells.get(&coord).filter(|c| c.is_filled).map(|c| c.color);
+        match (self.color, here) {
+            (None, None) => true,
+            (Some(target), Some(color)) => target.distance(color) <= self.tolerance,
+            _ => false,
+        }
+    }
+}
+
+/// Cells the fill would recolor, limited to `bounds` so empty space stays finite
+/// Contiguous mode spreads 4-connected from `start`; global mode takes every match in bounds
+pub fn fill_region(cells: &CellGrid, start: (i32, i32), target: FillTarget, global: bool, bounds: &SelectionRect) -> Vec<(i32, i32)> {
+    if !bounds.contains(start.0, start.1) || !target.matches(cells, start) {
+        return Vec::new();
+    }
+
+    if global {
+        // Only filled cells can match a color, so skip scanning empty space
+        return match target.color {
+            Some(_) => cells.keys().copied()
+                .filter(|&(x, y)| bounds.contains(x, y) && target.matches(cells, (x, y)))
+                .collect(),
+            None => (bounds.min_y..=bounds.max_y)
+                .flat_map(|y| (bounds.min_x..=bounds.max_x).map(move |x| (x, y)))
+                .filter(|&coord| target.matches(cells, coord))
+                .collect(),
+        };
+    }
+
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut region = Vec::new();
+    while let Some((x, y)) = queue.pop_front() {
+        region.push((x, y));
+        for n in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+            if bounds.contains(n.0, n.1) && !seen.contains(&n) && target.matches(cells, n) {
+                seen.insert(n);
+                queue.push_back(n);
+            }
+        }
+    }
+    region
+}
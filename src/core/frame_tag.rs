@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// How a tag's frames repeat, in playback and in exported GIFs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoopMode {
+    /// First to last, then from the first again
+    Forward,
+    /// First to last and back, without repeating the ends
+    PingPong,
+    /// First to last once, then hold the last frame
+    Once,
+}
+
+impl LoopMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            LoopMode::Forward => "Loop",
+            LoopMode::PingPong => "Ping-pong",
+            LoopMode::Once => "Once",
+        }
+    }
+
+    /// Name used in project files and sprite-sheet data
+    pub fn key(self) -> &'static str {
+        match self {
+            LoopMode::Forward => "forward",
+            LoopMode::PingPong => "ping_pong",
+            LoopMode::Once => "once",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            LoopMode::Forward => LoopMode::PingPong,
+            LoopMode::PingPong => LoopMode::Once,
+            LoopMode::Once => LoopMode::Forward,
+        }
+    }
+
+    /// Indices into `count` frames in the order one pass plays them; a ping-pong pass ends
+    /// just before the first frame comes round again
+    pub fn play_order(self, count: usize) -> Vec<usize> {
+        match self {
+            LoopMode::PingPong => (0..count).chain((1..count.saturating_sub(1)).rev()).collect(),
+            LoopMode::Forward | LoopMode::Once => (0..count).collect(),
+        }
+    }
+}
+
+/// A named run of animation frames ("walk", "idle"), by frame number, with its own loop mode
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FrameTag {
+    pub name: String,
+    /// First frame number in the tag
+    pub start: u32,
+    /// Last frame number in the tag, inclusive
+    pub end: u32,
+    pub loop_mode: LoopMode,
+}
+
+impl FrameTag {
+    pub fn contains(&self, frame: u32) -> bool {
+        (self.start..=self.end).contains(&frame)
+    }
+
+    /// "F2-F5"
+    pub fn range_label(&self) -> String {
+        format!("F{}-F{}", self.start, self.end)
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+use crate::core::cell::CellGrid;
+use crate::core::color::Rgba;
+use crate::core::dither::bayer_threshold;
+use crate::core::rng::Rng;
+use crate::core::selection::SelectionRect;
+
+/// Cells between value-noise lattice points
+const NOISE_SCALE: i32 = 6;
+
+/// Roughly one Voronoi seed per this many cells, up to MAX_SEEDS
+const CELLS_PER_SEED: i32 = 64;
+const MAX_SEEDS: i32 = 256;
+
+/// Procedural patterns written into a rect
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Generator {
+    /// One Game of Life step (B3/S23) over the rect's filled cells
+    LifeStep,
+    /// Random maze, walls in the primary color
+    Maze,
+    /// Smooth value noise, dithered from the primary to the secondary color
+    Noise,
+    /// Voronoi regions, each a random palette color
+    Voronoi,
+}
+
+impl Generator {
+    pub fn label(self) -> &'static str {
+        match self {
+            Generator::LifeStep => "Life Step",
+            Generator::Maze => "Maze",
+            Generator::Noise => "Noise Fill",
+            Generator::Voronoi => "Voronoi Regions",
+        }
+    }
+
+    /// New contents of every cell in `rect` (None = empty); cells outside it are only read
+    pub fn generate(self, cells: &CellGrid, rect: &SelectionRect, primary: Rgba, secondary: Rgba, palette: &[Rgba], rng: &mut Rng) -> Vec<((i32, i32), Option<Rgba>)> {
+        match self {
+            Generator::LifeStep => life_step(cells, rect, primary),
+            Generator::Maze => maze(rect, primary, rng),
+            Generator::Noise => noise(rect, primary, secondary, rng),
+            Generator::Voronoi => voronoi(rect, palette, rng),
+        }
+    }
+}
+
+fn rect_cells(rect: &SelectionRect) -> impl Iterator<Item = (i32, i32)> + '_ {
+    (rect.min_y..=rect.max_y).flat_map(move |y| (rect.min_x..=rect.max_x).map(move |x| (x, y)))
+}
+
+/// Survivors keep their color and births take the primary; neighbors outside the rect count
+/// but don't change
+fn life_step(cells: &CellGrid, rect: &SelectionRect, primary: Rgba) -> Vec<((i32, i32), Option<Rgba>)> {
+    let alive = |x: i32, y: i32| cells.get(&(x, y)).is_some_and(|c| c.is_filled);
+    rect_cells(rect)
+        .map(|(x, y)| {
+            let neighbors = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0) && alive(x + dx, y + dy))
+                .count();
+            let next = match (alive(x, y), neighbors) {
+                (true, 2 | 3) => cells.get(&(x, y)).map(|c| c.color),
+                (false, 3) => Some(primary),
+                _ => None,
+            };
+            ((x, y), next)
+        })
+        .collect()
+}
+
+/// Depth-first backtracker over the odd offsets of the rect; everything else starts as wall
+fn maze(rect: &SelectionRect, wall: Rgba, rng: &mut Rng) -> Vec<((i32, i32), Option<Rgba>)> {
+    let (rooms_x, rooms_y) = ((rect.width() - 1) / 2, (rect.height() - 1) / 2);
+    let mut open: HashSet<(i32, i32)> = HashSet::new();
+    if rooms_x > 0 && rooms_y > 0 {
+        let room = |(rx, ry): (i32, i32)| (rect.min_x + 1 + rx * 2, rect.min_y + 1 + ry * 2);
+        let start = (rng.range_i32(0, rooms_x - 1), rng.range_i32(0, rooms_y - 1));
+        let mut visited = HashSet::from([start]);
+        let mut stack = vec![start];
+        open.insert(room(start));
+        while let Some(&(rx, ry)) = stack.last() {
+            let unvisited: Vec<(i32, i32)> = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .map(|(dx, dy)| (rx + dx, ry + dy))
+                .filter(|&(nx, ny)| nx >= 0 && ny >= 0 && nx < rooms_x && ny < rooms_y && !visited.contains(&(nx, ny)))
+                .collect();
+            if unvisited.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let next = unvisited[rng.range_i32(0, unvisited.len() as i32 - 1) as usize];
+            let (from, to) = (room((rx, ry)), room(next));
+            open.insert(((from.0 + to.0) / 2, (from.1 + to.1) / 2));
+            open.insert(to);
+            visited.insert(next);
+            stack.push(next);
+        }
+    }
+    rect_cells(rect).map(|coord| (coord, (!open.contains(&coord)).then_some(wall))).collect()
+}
+
+/// Bilinear value noise on a random lattice, ordered-dithered between the two colors
+fn noise(rect: &SelectionRect, primary: Rgba, secondary: Rgba, rng: &mut Rng) -> Vec<((i32, i32), Option<Rgba>)> {
+    let columns = rect.width() / NOISE_SCALE + 2;
+    let rows = rect.height() / NOISE_SCALE + 2;
+    let lattice: Vec<f32> = (0..columns * rows).map(|_| (rng.next_u64() % 1024) as f32 / 1023.0).collect();
+    let at = |lx: i32, ly: i32| lattice[(ly * columns + lx) as usize];
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+
+    rect_cells(rect)
+        .map(|(x, y)| {
+            let (ox, oy) = (x - rect.min_x, y - rect.min_y);
+            let (lx, ly) = (ox / NOISE_SCALE, oy / NOISE_SCALE);
+            let tx = smooth((ox % NOISE_SCALE) as f32 / NOISE_SCALE as f32);
+            let ty = smooth((oy % NOISE_SCALE) as f32 / NOISE_SCALE as f32);
+            let top = at(lx, ly) + (at(lx + 1, ly) - at(lx, ly)) * tx;
+            let bottom = at(lx, ly + 1) + (at(lx + 1, ly + 1) - at(lx, ly + 1)) * tx;
+            let value = top + (bottom - top) * ty;
+            ((x, y), Some(if value > bayer_threshold(x, y) { secondary } else { primary }))
+        })
+        .collect()
+}
+
+/// Nearest-seed regions; seeds are scattered over the rect and each picks a palette color
+fn voronoi(rect: &SelectionRect, palette: &[Rgba], rng: &mut Rng) -> Vec<((i32, i32), Option<Rgba>)> {
+    if palette.is_empty() {
+        return Vec::new();
+    }
+    let count = (rect.width() * rect.height() / CELLS_PER_SEED).clamp(2, MAX_SEEDS);
+    let seeds: Vec<((i32, i32), Rgba)> = (0..count)
+        .map(|_| {
+            let seed = (rng.range_i32(rect.min_x, rect.max_x), rng.range_i32(rect.min_y, rect.max_y));
+            (seed, palette[rng.range_i32(0, palette.len() as i32 - 1) as usize])
+        })
+        .collect();
+    rect_cells(rect)
+        .map(|(x, y)| {
+            let nearest = seeds.iter().min_by_key(|((sx, sy), _)| (sx - x).pow(2) + (sy - y).pow(2)).map(|&(_, color)| color);
+            ((x, y), nearest)
+        })
+        .collect()
+}
@@ -5,4 +5,8 @@ pub struct Group {
     pub id: u32,
     pub name: String,
     pub cells: HashSet<(i32, i32)>,
+    /// Animation frame number when the group is tagged as a frame; frames play in this order
+    pub frame: Option<u32>,
+    /// How long the frame shows, in milliseconds; None follows the playback rate
+    pub duration_ms: Option<u32>,
 }
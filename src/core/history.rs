@@ -0,0 +1,115 @@
+use crate::core::cell::{Cell, CellGrid};
+
+/// Represents a change to a single cell for undo/redo
+#[derive(Clone, Debug)]
+pub struct CellChange {
+    pub coord: (i32, i32),
+    pub before: Option<Cell>,
+    pub after: Option<Cell>,
+}
+
+/// Represents a command that can be undone
+pub struct Command {
+    pub changes: Vec<CellChange>,
+}
+
+impl Command {
+    /// Move every cell by (dx, dy): all removals first, then all insertions, so cells whose
+    /// destination is another cell's origin never clobber each other (in either direction)
+    pub fn translate(cells: &CellGrid, dx: i32, dy: i32) -> Self {
+        let removals = cells.iter().map(|(&coord, &cell)| CellChange { coord, before: Some(cell), after: None });
+        let insertions = cells.iter().map(|(&(x, y), &cell)| CellChange { coord: (x + dx, y + dy), before: None, after: Some(cell) });
+        Self { changes: removals.chain(insertions).collect() }
+    }
+
+    /// Write every change's `after` value, oldest first (the original application order)
+    pub fn apply(&self, cells: &mut CellGrid) {
+        for ch in self.changes.iter() {
+            match ch.after {
+                Some(cell) => {
+                    cells.insert(ch.coord, cell);
+                }
+                None => {
+                    cells.remove(&ch.coord);
+                }
+            }
+        }
+    }
+
+    /// Restore every touched cell to its `before` value
+    /// Runs in reverse order so a cell touched several times ends at its earliest 'before'
+    pub fn revert(&self, cells: &mut CellGrid) {
+        for ch in self.changes.iter().rev() {
+            match ch.before {
+                Some(cell) => {
+                    cells.insert(ch.coord, cell);
+                }
+                None => {
+                    cells.remove(&ch.coord);
+                }
+            }
+        }
+    }
+}
+
+/// History stack for undo/redo functionality
+pub struct History {
+    pub stack: Vec<Command>,
+    /// Undone commands, newest last; cleared whenever a new command is pushed
+    pub redo_stack: Vec<Command>,
+    pub max: usize,
+    /// Bumped by every push, undo, redo and `touch`, so callers can tell the document changed
+    pub revision: u64,
+}
+
+impl History {
+    pub fn new(max: usize) -> Self {
+        Self {
+            stack: Vec::new(),
+            redo_stack: Vec::new(),
+            max,
+            revision: 0,
+        }
+    }
+
+    /// Forget every command when the document is replaced; the revision still moves on so
+    /// nothing computed for the old document is mistaken for current
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.redo_stack.clear();
+        self.revision += 1;
+    }
+
+    /// Note a change made outside history, such as cells painted by others
+    pub fn touch(&mut self) {
+        self.revision += 1;
+    }
+
+    pub fn push(&mut self, cmd: Command) {
+        self.stack.push(cmd);
+        self.redo_stack.clear();
+        self.revision += 1;
+        if self.stack.len() > self.max {
+            self.stack.remove(0);
+        }
+    }
+
+    /// Revert the newest command on `cells` and keep it for redo
+    /// Returns the command so callers can refresh the cells it touched
+    pub fn undo(&mut self, cells: &mut CellGrid) -> Option<&Command> {
+        let cmd = self.stack.pop()?;
+        cmd.revert(cells);
+        self.redo_stack.push(cmd);
+        self.revision += 1;
+        self.redo_stack.last()
+    }
+
+    /// Re-apply the most recently undone command on `cells`
+    pub fn redo(&mut self, cells: &mut CellGrid) -> Option<&Command> {
+        let cmd = self.redo_stack.pop()?;
+        cmd.apply(cells);
+        self.stack.push(cmd);
+        self.revision += 1;
+        self.stack.last()
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{HashMap, HashSet};
+use crate::core::cell::Cell;
+use crate::core::color::Rgba;
+
+/// Feather widths for pasted and moved content, in cells (0 = hard edges)
+pub const FEATHER_OPTIONS: [u8; 5] = [0, 1, 2, 3, 4];
+
+/// How pasted cells combine with cells already on the canvas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasteMerge {
+    /// Pasted cells overwrite whatever is there
+    Replace,
+    /// Pasted cells only fill empty cells (paste a background behind a sprite)
+    Underlay,
+    /// Pasted and existing colors are mixed half and half
+    Blend,
+}
+
+impl PasteMerge {
+    pub const ALL: [PasteMerge; 3] = [PasteMerge::Replace, PasteMerge::Underlay, PasteMerge::Blend];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PasteMerge::Replace => "Replace",
+            PasteMerge::Underlay => "Underlay",
+            PasteMerge::Blend => "Blend",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            PasteMerge::Replace => PasteMerge::Underlay,
+            PasteMerge::Underlay => PasteMerge::Blend,
+            PasteMerge::Blend => PasteMerge::Replace,
+        }
+    }
+
+    /// The cell that ends up on the canvas, or None to leave the existing one alone
+    pub fn merge(self, existing: Option<Cell>, incoming: Cell) -> Option<Cell> {
+        let existing = existing.filter(|c| c.is_filled);
+        match (self, existing) {
+            (PasteMerge::Underlay, Some(_)) => None,
+            (PasteMerge::Blend, Some(below)) => Some(Cell::with_color(mix(below.color, incoming.color))),
+            _ => Some(incoming),
+        }
+    }
+
+    /// `merge`, then faded into what is already there when the cell sits on a feathered edge
+    pub fn merge_feathered(self, existing: Option<Cell>, incoming: Cell, weight: Option<f32>) -> Option<Cell> {
+        let merged = self.merge(existing, incoming)?;
+        Some(match weight {
+            Some(weight) => feather_cell(existing, merged, weight),
+            None => merged,
+        })
+    }
+}
+
+/// Next entry of `FEATHER_OPTIONS` after `width`, wrapping around
+pub fn next_feather(width: u8) -> u8 {
+    let i = FEATHER_OPTIONS.iter().position(|&w| w == width).unwrap_or(0);
+    FEATHER_OPTIONS[(i + 1) % FEATHER_OPTIONS.len()]
+}
+
+/// Opacity of each cell within `width` cells of the shape's outline: cells touching the outside
+/// get 1 / (width + 1), rising by the same step per cell inward. Cells deeper in are left out
+pub fn feather_weights(coords: &HashSet<(i32, i32)>, width: u8) -> HashMap<(i32, i32), f32> {
+    let mut weights = HashMap::new();
+    if width == 0 {
+        return weights;
+    }
+    let neighbours = |(x, y): (i32, i32)| [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)];
+    let step = 1.0 / (width as f32 + 1.0);
+
+    let mut frontier: Vec<(i32, i32)> = coords
+        .iter()
+        .copied()
+        .filter(|&c| neighbours(c).iter().any(|n| !coords.contains(n)))
+        .collect();
+    for depth in 1..=width {
+        for &c in &frontier {
+            weights.insert(c, depth as f32 * step);
+        }
+        frontier = frontier
+            .iter()
+            .flat_map(|&c| neighbours(c))
+            .filter(|n| coords.contains(n) && !weights.contains_key(n))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+    }
+    weights
+}
+
+/// `incoming` at `weight` opacity over `existing`: mixed toward the cell below,
+/// or made translucent over an empty one
+pub fn feather_cell(existing: Option<Cell>, incoming: Cell, weight: f32) -> Cell {
+    let fade = |below: u8, above: u8| (below as f32 + (above as f32 - below as f32) * weight).round() as u8;
+    let (a, b) = (existing.filter(|c| c.is_filled).map(|c| c.color), incoming.color);
+    Cell::with_color(match a {
+        Some(a) => Rgba::new(fade(a.r, b.r), fade(a.g, b.g), fade(a.b, b.b), fade(a.a, b.a)),
+        None => Rgba::new(b.r, b.g, b.b, fade(0, b.a)),
+    })
+}
+
+fn mix(a: Rgba, b: Rgba) -> Rgba {
+    let avg = |x: u8, y: u8| (x as u16 + y as u16).div_ceil(2) as u8;
+    Rgba::new(avg(a.r, b.r), avg(a.g, b.g), avg(a.b, b.b), avg(a.a, b.a))
+}
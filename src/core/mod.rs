@@ -1,11 +1,24 @@
-pub mod constants;
 pub mod cell;
 pub mod camera;
 pub mod color;
 pub mod selection;
+pub mod rng;
+pub mod brush;
+pub mod raster;
+pub mod history;
+pub mod budget;
+pub mod group;
+pub mod strays;
+pub mod shapes;
+pub mod fill;
+pub mod dither;
+pub mod transform;
+pub mod merge;
+pub mod symmetry;
+pub mod diff;
+pub mod autotile;
+pub mod generate;
+pub mod frame_tag;
 
-pub use constants::*;
 pub use cell::*;
-pub use camera::*;
-pub use color::*;
 pub use selection::*;
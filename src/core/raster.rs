@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+use crate::core::cell::{CellAspect, CellGrid};
+use crate::core::color::Rgba;
+use crate::core::selection::SelectionRect;
+use crate::error::FileError;
+
+/// Most pixels `rasterize` will allocate (256 megapixels, 1 GiB of RGBA)
+pub const MAX_RASTER_PIXELS: usize = 1 << 28;
+
+/// Plain RGBA8 image built from the cell grid, independent of any GPU context
+pub struct RasterImage {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major, top row first, 4 bytes per pixel
+    pub pixels: Vec<u8>,
+}
+
+/// Pixel-art upscaler run on the one-pixel-per-cell image before the integer `scale`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScaleFilter {
+    /// Plain blocks, no smoothing
+    Nearest,
+    /// EPX/Scale2x: doubles the size, rounding diagonal steps
+    Scale2x,
+    /// Scale3x: triples the size, rounding diagonal steps
+    Scale3x,
+}
+
+impl ScaleFilter {
+    pub fn label(self) -> &'static str {
+        match self {
+            ScaleFilter::Nearest => "Nearest",
+            ScaleFilter::Scale2x => "Scale2x",
+            ScaleFilter::Scale3x => "Scale3x",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ScaleFilter::Nearest => ScaleFilter::Scale2x,
+            ScaleFilter::Scale2x => ScaleFilter::Scale3x,
+            ScaleFilter::Scale3x => ScaleFilter::Nearest,
+        }
+    }
+
+    /// Size multiplier the filter adds on top of the export scale
+    pub fn factor(self) -> u32 {
+        match self {
+            ScaleFilter::Nearest => 1,
+            ScaleFilter::Scale2x => 2,
+            ScaleFilter::Scale3x => 3,
+        }
+    }
+
+    fn apply(self, image: &RasterImage) -> RasterImage {
+        match self {
+            ScaleFilter::Nearest => upscale_nearest(image, 1, 1),
+            ScaleFilter::Scale2x => scale2x(image),
+            ScaleFilter::Scale3x => scale3x(image),
+        }
+    }
+}
+
+/// How cells are turned into pixels
+#[derive(Clone, Copy, Debug)]
+pub struct RasterOptions {
+    /// Pixels per cell (integer upscale)
+    pub scale: u32,
+    /// Smoothing upscaler applied before `scale`; multiplies the output size by its factor
+    pub filter: ScaleFilter,
+    /// Cell shape; each cell becomes (scale * w) × (scale * h) pixels
+    pub aspect: CellAspect,
+    /// Fill for empty cells (use an alpha of 0 for transparency)
+    pub background: Rgba,
+    /// Draw 1px grid lines along cell edges in this color
+    pub grid: Option<Rgba>,
+}
+
+impl RasterOptions {
+    /// Size of the image `rasterize` makes of `rect`, None if it can't be built: a side past
+    /// u32 or more than MAX_RASTER_PIXELS in all
+    pub fn output_size(&self, rect: &SelectionRect) -> Option<(u32, u32)> {
+        let side = |cells: i64, aspect: u32| {
+            let per_cell = (self.scale.max(1) as usize).checked_mul(aspect.max(1) as usize)?.checked_mul(self.filter.factor() as usize)?;
+            u32::try_from((cells as usize).checked_mul(per_cell)?).ok()
+        };
+        let width = side(rect.max_x as i64 - rect.min_x as i64 + 1, self.aspect.w)?;
+        let height = side(rect.max_y as i64 - rect.min_y as i64 + 1, self.aspect.h)?;
+        (width as usize).checked_mul(height as usize).filter(|&pixels| pixels <= MAX_RASTER_PIXELS)?;
+        Some((width, height))
+    }
+}
+
+/// Rasterize the cells inside `rect` according to `options`; fails rather than allocating
+/// more than MAX_RASTER_PIXELS
+pub fn rasterize(cells: &CellGrid, rect: &SelectionRect, options: &RasterOptions) -> Result<RasterImage, FileError> {
+    if options.output_size(rect).is_none() {
+        return Err(FileError::Unsupported(format!(
+            "A {}x{} cell image at this scale would exceed {} megapixels",
+            rect.max_x as i64 - rect.min_x as i64 + 1,
+            rect.max_y as i64 - rect.min_y as i64 + 1,
+            MAX_RASTER_PIXELS >> 20
+        )));
+    }
+    let scale = options.scale.max(1);
+    let (aw, ah) = (options.aspect.w.max(1), options.aspect.h.max(1));
+    let mut image = match options.filter {
+        ScaleFilter::Nearest => fill_cells(cells, rect, options.background, scale * aw, scale * ah),
+        filter => upscale_nearest(&filter.apply(&fill_cells(cells, rect, options.background, aw, ah)), scale, scale),
+    };
+
+    if let Some(grid) = options.grid {
+        let factor = options.filter.factor();
+        draw_grid(&mut image, factor * scale * aw, factor * scale * ah, grid);
+    }
+    Ok(image)
+}
+
+/// One `sx` × `sy` block of pixels per cell, `background` where cells are empty
+fn fill_cells(cells: &CellGrid, rect: &SelectionRect, background: Rgba, sx: u32, sy: u32) -> RasterImage {
+    let cols = rect.width() as usize;
+    let rows = rect.height() as usize;
+    let (sx, sy) = (sx as usize, sy as usize);
+    let width = cols * sx;
+    let height = rows * sy;
+
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for _ in 0..width * height {
+        pixels.extend_from_slice(&[background.r, background.g, background.b, background.a]);
+    }
+
+    for row in 0..rows {
+        for col in 0..cols {
+            let coord = (rect.min_x + col as i32, rect.min_y + row as i32);
+            let color = match cells.get(&coord) {
+                Some(cell) if cell.is_filled => cell.color,
+                _ => continue,
+            };
+            for py in row * sy..(row + 1) * sy {
+                let start = (py * width + col * sx) * 4;
+                for px in pixels[start..start + sx * 4].chunks_exact_mut(4) {
+                    px.copy_from_slice(&[color.r, color.g, color.b, color.a]);
+                }
+            }
+        }
+    }
+
+    RasterImage { width: width as u32, height: height as u32, pixels }
+}
+
+/// 1px lines along the edges of every `sx` × `sy` cell block and around the image
+fn draw_grid(image: &mut RasterImage, sx: u32, sy: u32, grid: Rgba) {
+    let (width, height) = (image.width, image.height);
+    let grid_px = [grid.r, grid.g, grid.b, grid.a];
+    for y in 0..height {
+        for x in 0..width {
+            let on_edge = x % sx == 0 || y % sy == 0 || x == width - 1 || y == height - 1;
+            if on_edge {
+                let i = (y as usize * width as usize + x as usize) * 4;
+                image.pixels[i..i + 4].copy_from_slice(&grid_px);
+            }
+        }
+    }
+}
+
+impl RasterImage {
+    /// Pixel at (x, y), clamped to the nearest edge pixel when outside
+    fn pixel(&self, x: i32, y: i32) -> [u8; 4] {
+        let x = x.clamp(0, self.width as i32 - 1) as u32;
+        let y = y.clamp(0, self.height as i32 - 1) as u32;
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+}
+
+/// Build a `factor`× larger image where each source pixel becomes a block from `block`
+/// (row-major, `factor * factor` entries)
+fn expand(image: &RasterImage, factor: u32, block: impl Fn(i32, i32) -> Vec<[u8; 4]>) -> RasterImage {
+    let factor = factor as usize;
+    let (width, height) = (image.width as usize * factor, image.height as usize * factor);
+    let mut pixels = vec![0u8; width * height * 4];
+    for y in 0..image.height as usize {
+        for x in 0..image.width as usize {
+            for (k, px) in block(x as i32, y as i32).into_iter().enumerate() {
+                let (bx, by) = (k % factor, k / factor);
+                let i = ((y * factor + by) * width + x * factor + bx) * 4;
+                pixels[i..i + 4].copy_from_slice(&px);
+            }
+        }
+    }
+    RasterImage { width: width as u32, height: height as u32, pixels }
+}
+
+/// Repeat every pixel into an `sx` × `sy` block
+fn upscale_nearest(image: &RasterImage, sx: u32, sy: u32) -> RasterImage {
+    let (sx, sy) = (sx as usize, sy as usize);
+    let (width, height) = (image.width as usize * sx, image.height as usize * sy);
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            pixels.extend_from_slice(&image.pixel((x / sx) as i32, (y / sy) as i32));
+        }
+    }
+    RasterImage { width: width as u32, height: height as u32, pixels }
+}
+
+/// Scale2x (AdvMAME2x): each pixel becomes 2×2, taking an edge neighbour's color in the
+/// corners where two neighbours agree, which rounds off staircase diagonals
+fn scale2x(image: &RasterImage) -> RasterImage {
+    expand(image, 2, |x, y| {
+        let e = image.pixel(x, y);
+        let (b, d, f, h) = (image.pixel(x, y - 1), image.pixel(x - 1, y), image.pixel(x + 1, y), image.pixel(x, y + 1));
+        if b == h || d == f {
+            return vec![e; 4];
+        }
+        vec![
+            if d == b { d } else { e },
+            if b == f { f } else { e },
+            if d == h { d } else { e },
+            if h == f { f } else { e },
+        ]
+    })
+}
+
+/// Scale3x (AdvMAME3x): the 3×3 counterpart of `scale2x`, also looking at diagonal neighbours
+fn scale3x(image: &RasterImage) -> RasterImage {
+    expand(image, 3, |x, y| {
+        let p = |dx: i32, dy: i32| image.pixel(x + dx, y + dy);
+        let (a, b, c) = (p(-1, -1), p(0, -1), p(1, -1));
+        let (d, e, f) = (p(-1, 0), p(0, 0), p(1, 0));
+        let (g, h, i) = (p(-1, 1), p(0, 1), p(1, 1));
+        if b == h || d == f {
+            return vec![e; 9];
+        }
+        vec![
+            if d == b { d } else { e },
+            if (d == b && e != c) || (b == f && e != a) { b } else { e },
+            if b == f { f } else { e },
+            if (d == b && e != g) || (d == h && e != a) { d } else { e },
+            e,
+            if (b == f && e != i) || (h == f && e != c) { f } else { e },
+            if d == h { d } else { e },
+            if (d == h && e != i) || (h == f && e != g) { h } else { e },
+            if h == f { f } else { e },
+        ]
+    })
+}
+
+/// Slot images for one canvas chunk at `levels` resolutions, each halving the last
+/// Level 0 has one pixel per cell; every image carries a 1px gutter repeating its edge
+/// pixels so nearest or linear sampling at the chunk border stays inside the chunk.
+/// Cells in `hidden` are drawn as empty
+pub fn chunk_mips(cells: &CellGrid, hidden: &HashSet<(i32, i32)>, min: (i32, i32), size: i32, levels: usize) -> Vec<RasterImage> {
+    // Premultiplied RGBA per cell, so averaging never darkens edges against empty cells
+    let mut texels = vec![[0.0f32; 4]; (size * size) as usize];
+    for local_y in 0..size {
+        for local_x in 0..size {
+            let coord = (min.0 + local_x, min.1 + local_y);
+            if let Some(cell) = cells.get(&coord) {
+                if cell.is_filled && !hidden.contains(&coord) {
+                    let c = cell.color;
+                    let a = c.a as f32 / 255.0;
+                    texels[(local_y * size + local_x) as usize] =
+                        [c.r as f32 * a, c.g as f32 * a, c.b as f32 * a, a];
+                }
+            }
+        }
+    }
+
+    let mut images = Vec::with_capacity(levels);
+    for level in 0..levels {
+        let side = size >> level;
+        if level > 0 {
+            texels = downsample(&texels, side as usize * 2);
+        }
+
+        let slot = (side + 2) as u32;
+        let mut pixels = vec![0u8; (slot * slot * 4) as usize];
+        for slot_y in 0..slot as i32 {
+            for slot_x in 0..slot as i32 {
+                let local_x = (slot_x - 1).clamp(0, side - 1);
+                let local_y = (slot_y - 1).clamp(0, side - 1);
+                let [r, g, b, a] = texels[(local_y * side + local_x) as usize];
+                if a > 0.0 {
+                    let i = ((slot_y as u32 * slot + slot_x as u32) * 4) as usize;
+                    pixels[i..i + 4].copy_from_slice(&[
+                        (r / a).round() as u8,
+                        (g / a).round() as u8,
+                        (b / a).round() as u8,
+                        (a * 255.0).round() as u8,
+                    ]);
+                }
+            }
+        }
+        images.push(RasterImage { width: slot, height: slot, pixels });
+    }
+    images
+}
+
+/// Halve a square premultiplied texel block with a 2×2 box filter
+fn downsample(src: &[[f32; 4]], side: usize) -> Vec<[f32; 4]> {
+    let half = side / 2;
+    let mut dst = vec![[0.0f32; 4]; half * half];
+    for y in 0..half {
+        for x in 0..half {
+            let mut sum = [0.0f32; 4];
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let texel = src[(y * 2 + dy) * side + x * 2 + dx];
+                for (acc, v) in sum.iter_mut().zip(texel) {
+                    *acc += v;
+                }
+            }
+            dst[y * half + x] = sum.map(|v| v / 4.0);
+        }
+    }
+    dst
+}
@@ -1,8 +1,8 @@
 use crate::core::cell::CellGrid;
-use macroquad::prelude::RenderTarget;
 use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SelectionRect {
     pub min_x: i32,
     pub min_y: i32,
@@ -48,7 +48,6 @@ pub enum SelectionKind {
 pub struct Selection {
     pub rect: SelectionRect,
     pub kind: SelectionKind,
-    pub preview: Option<RenderTarget>,
 }
 
 /// Main selection state tracking
@@ -122,40 +121,6 @@ impl SelectionState {
         }
     }
 
-    pub fn finalize_drag(&mut self, cells: &CellGrid) -> bool {
-        self.active_drag = false;
-
-        if let (Some(start), Some(end)) = (self.drag_start, self.drag_end) {
-            let rect = SelectionRect::from_points(start, end);
-
-            let selected_cells: HashSet<(i32, i32)> = cells
-                .iter()
-                .filter_map(|(coord, cell)| {
-                    if cell.is_filled && rect.contains(coord.0, coord.1) {
-                        Some(*coord)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            if selected_cells.is_empty() {
-                self.current = None;
-                return false;
-            }
-
-            self.current = Some(Selection {
-                rect,
-                kind: SelectionKind::Cells(selected_cells),
-                preview: None,
-            });
-            return true;
-        }
-
-        self.current = None;
-        false
-    }
-
     pub fn contains_point(&self, x: i32, y: i32) -> bool {
         if let Some(sel) = &self.current {
             sel.rect.contains(x, y)
@@ -164,56 +129,21 @@ impl SelectionState {
         }
     }
 
-    pub fn start_move(&mut self, mouse_world: (f32, f32)) {
-        if self.current.is_some() {
-            self.is_moving = true;
-            self.move_offset_x = 0.0;
-            self.move_offset_y = 0.0;
-            self.last_move_mouse = Some(mouse_world);
-        }
-    }
-
     pub fn update_move(&mut self, delta_x: f32, delta_y: f32) {
         if self.is_moving {
             self.move_offset_x += delta_x;
             self.move_offset_y += delta_y;
         }
     }
+}
 
-    pub fn finalize_move(&mut self) -> Option<(i32, i32)> {
-        if !self.is_moving {
-            return None;
-        }
-
-        self.is_moving = false;
-
-        let offset_x = self.move_offset_x.round() as i32;
-        let offset_y = self.move_offset_y.round() as i32;
-
-        if offset_x == 0 && offset_y == 0 {
-            self.move_offset_x = 0.0;
-            self.move_offset_y = 0.0;
-            return None;
-        }
-
-        if let Some(sel) = &mut self.current {
-            if let SelectionKind::Cells(coords) = &mut sel.kind {
-                // Update cell coordinates
-                let updated: HashSet<(i32, i32)> = coords.iter().map(|(x, y)| (x + offset_x, y + offset_y)).collect();
-                *coords = updated;
-
-                // Update rect
-                sel.rect.min_x += offset_x;
-                sel.rect.max_x += offset_x;
-                sel.rect.min_y += offset_y;
-                sel.rect.max_y += offset_y;
-            }
-        }
-
-        self.move_offset_x = 0.0;
-        self.move_offset_y = 0.0;
-        Some((offset_x, offset_y))
-    }
+/// Coordinates of all filled cells inside `rect` (the tight marquee pick)
+pub fn filled_cells_in_rect(cells: &CellGrid, rect: &SelectionRect) -> HashSet<(i32, i32)> {
+    cells
+        .iter()
+        .filter(|(&(x, y), cell)| cell.is_filled && rect.contains(x, y))
+        .map(|(&coord, _)| coord)
+        .collect()
 }
 
 pub fn compute_bounding_rect(cells: &HashSet<(i32, i32)>) -> Option<SelectionRect> {
@@ -0,0 +1,151 @@
+/// Bresenham line algorithm - returns all grid cells between two points
+pub fn line_cells(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let mut cells = Vec::new();
+    let (mut x0, mut y0) = from;
+    let (x1, y1) = to;
+
+    let dx = (x1 - x0).abs();
+    let dy = (y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx - dy;
+
+    loop {
+        cells.push((x0, y0));
+
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+
+        let e2 = 2 * err;
+        if e2 > -dy {
+            err -= dy;
+            x0 += sx;
+        }
+        if e2 < dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+
+    cells
+}
+
+/// Cells of the axis-aligned rectangle with corners `a` and `b`: its border, or every cell
+/// inside it as well when `filled`
+pub fn rect_cells(a: (i32, i32), b: (i32, i32), filled: bool) -> Vec<(i32, i32)> {
+    let (min_x, max_x) = (a.0.min(b.0), a.0.max(b.0));
+    let (min_y, max_y) = (a.1.min(b.1), a.1.max(b.1));
+    let mut cells = Vec::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if filled || x == min_x || x == max_x || y == min_y || y == max_y {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+/// Cells along a chain of points, optionally joining the last point back to the first
+pub fn polyline_cells(points: &[(i32, i32)], closed: bool) -> Vec<(i32, i32)> {
+    let mut cells = Vec::new();
+    for pair in points.windows(2) {
+        cells.extend(line_cells(pair[0], pair[1]));
+    }
+    if closed && points.len() > 2 {
+        cells.extend(line_cells(points[points.len() - 1], points[0]));
+    }
+    if points.len() == 1 {
+        cells.push(points[0]);
+    }
+    cells
+}
+
+/// Cells whose centers fall inside the polygon (even-odd rule, vertices at cell centers)
+pub fn polygon_fill_cells(points: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let min_y = points.iter().map(|p| p.1).min().unwrap_or(0);
+    let max_y = points.iter().map(|p| p.1).max().unwrap_or(0);
+
+    let mut cells = Vec::new();
+    let mut crossings: Vec<f32> = Vec::new();
+    for y in min_y..=max_y {
+        // Vertices and cell centers share integer coordinates; the half-open edge test
+        // counts a vertex shared by two edges only once
+        let sy = y as f32;
+        crossings.clear();
+        for i in 0..points.len() {
+            let (ax, ay) = points[i];
+            let (bx, by) = points[(i + 1) % points.len()];
+            let (ay, by) = (ay as f32, by as f32);
+            if (ay <= sy && by > sy) || (by <= sy && ay > sy) {
+                let t = (sy - ay) / (by - ay);
+                crossings.push(ax as f32 + t * (bx - ax) as f32);
+            }
+        }
+        crossings.sort_by(|a, b| a.total_cmp(b));
+        for span in crossings.chunks_exact(2) {
+            let start = span[0].ceil() as i32;
+            let end = span[1].floor() as i32;
+            cells.extend((start..=end).map(|x| (x, y)));
+        }
+    }
+    cells
+}
+
+/// Quadratic or cubic Bezier curve in world cell units (cell centers sit at +0.5)
+/// `points` are [start, control 1, control 2, end]; a quadratic curve ignores control 2
+#[derive(Clone, Copy, Debug)]
+pub struct Curve {
+    pub points: [(f32, f32); 4],
+    pub cubic: bool,
+    /// Controls follow the endpoints (at 1/3 and 2/3) until the user drags one
+    pub controls_moved: bool,
+}
+
+impl Curve {
+    pub fn new(start: (f32, f32), end: (f32, f32), cubic: bool) -> Self {
+        let mut curve = Self { points: [start, start, start, end], cubic, controls_moved: false };
+        curve.place_default_controls();
+        curve
+    }
+
+    /// Spread the controls along the straight line between the endpoints
+    pub fn place_default_controls(&mut self) {
+        let [start, _, _, end] = self.points;
+        let lerp = |t: f32| (start.0 + (end.0 - start.0) * t, start.1 + (end.1 - start.1) * t);
+        if self.cubic {
+            self.points[1] = lerp(1.0 / 3.0);
+            self.points[2] = lerp(2.0 / 3.0);
+        } else {
+            self.points[1] = lerp(0.5);
+            self.points[2] = lerp(0.5);
+        }
+    }
+
+    /// Point on the curve at t in [0, 1]
+    pub fn sample(&self, t: f32) -> (f32, f32) {
+        let [p0, p1, p2, p3] = self.points;
+        let u = 1.0 - t;
+        if self.cubic {
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            (a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0, a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1)
+        } else {
+            let (a, b, c) = (u * u, 2.0 * u * t, t * t);
+            (a * p0.0 + b * p1.0 + c * p3.0, a * p0.1 + b * p1.1 + c * p3.1)
+        }
+    }
+
+    /// Rasterize as `steps` straight segments between evenly spaced samples
+    pub fn cells(&self, steps: u32) -> Vec<(i32, i32)> {
+        let steps = steps.max(1);
+        let to_cell = |(x, y): (f32, f32)| (x.floor() as i32, y.floor() as i32);
+        let samples: Vec<(i32, i32)> = (0..=steps).map(|i| to_cell(self.sample(i as f32 / steps as f32))).collect();
+        let mut cells = polyline_cells(&samples, false);
+        cells.dedup();
+        cells
+    }
+}
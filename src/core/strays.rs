@@ -0,0 +1,93 @@
+use std::collections::{HashSet, VecDeque};
+use crate::core::cell::CellGrid;
+use crate::core::selection::{compute_bounding_rect, SelectionRect};
+
+/// Small clumps at least this many cells away from the main drawing count as strays too
+pub const OUTLIER_DISTANCE: i32 = 32;
+/// Largest clump that can be an outlier (bigger ones are probably deliberate)
+pub const OUTLIER_MAX_CELLS: usize = 4;
+
+/// Found strays and which one the navigator is showing
+pub struct StrayNavigator {
+    pub cells: Vec<(i32, i32)>,
+    pub index: usize,
+}
+
+impl StrayNavigator {
+    pub fn current(&self) -> Option<(i32, i32)> {
+        self.cells.get(self.index).copied()
+    }
+
+    pub fn step(&mut self, forward: bool) {
+        let len = self.cells.len();
+        if len > 0 {
+            self.index = if forward { (self.index + 1) % len } else { (self.index + len - 1) % len };
+        }
+    }
+
+    /// Drop a cell from the list (after it was deleted), keeping the index in range
+    pub fn remove(&mut self, coord: (i32, i32)) {
+        self.cells.retain(|&c| c != coord);
+        if self.index >= self.cells.len() {
+            self.index = 0;
+        }
+    }
+}
+
+/// Filled cells that are probably accidents: cells with no filled neighbor (8-connected),
+/// plus small clumps far from the largest connected shape. Farthest first
+pub fn find_strays(cells: &CellGrid) -> Vec<(i32, i32)> {
+    let filled: HashSet<(i32, i32)> = cells.iter().filter(|(_, c)| c.is_filled).map(|(&coord, _)| coord).collect();
+    let components = connected_components(&filled);
+    let Some(main) = components.iter().max_by_key(|c| c.len()) else {
+        return Vec::new();
+    };
+    let main_rect = compute_bounding_rect(main);
+
+    let distance = |(x, y): (i32, i32)| match &main_rect {
+        Some(r) => distance_to_rect(r, x, y),
+        None => 0,
+    };
+
+    let mut strays: Vec<(i32, i32)> = components
+        .iter()
+        .filter(|c| !std::ptr::eq(*c, main) || c.len() == 1)
+        .filter(|c| c.len() == 1 || (c.len() <= OUTLIER_MAX_CELLS && c.iter().all(|&p| distance(p) >= OUTLIER_DISTANCE)))
+        .flat_map(|c| c.iter().copied())
+        .collect();
+    strays.sort_by_key(|&p| (std::cmp::Reverse(distance(p)), p.1, p.0));
+    strays
+}
+
+/// Chebyshev distance from a cell to a rect (0 inside)
+fn distance_to_rect(rect: &SelectionRect, x: i32, y: i32) -> i32 {
+    let dx = (rect.min_x - x).max(x - rect.max_x).max(0);
+    let dy = (rect.min_y - y).max(y - rect.max_y).max(0);
+    dx.max(dy)
+}
+
+/// 8-connected groups of cells
+fn connected_components(filled: &HashSet<(i32, i32)>) -> Vec<HashSet<(i32, i32)>> {
+    let mut seen: HashSet<(i32, i32)> = HashSet::new();
+    let mut components = Vec::new();
+    for &start in filled {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut component = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((x, y)) = queue.pop_front() {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let n = (x + dx, y + dy);
+                    if filled.contains(&n) && seen.insert(n) {
+                        component.insert(n);
+                        queue.push_back(n);
+                    }
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
@@ -0,0 +1,111 @@
+/// Radial segment counts offered by the menu (1 = no rotational copies)
+pub const SEGMENT_OPTIONS: [u32; 8] = [1, 2, 3, 4, 6, 8, 12, 16];
+
+/// Upper bound on copies of one cell, so combined axes and segments stay cheap
+const MAX_IMAGES: usize = 64;
+
+/// A point relative to the pivot, in cell units
+type Point = (f32, f32);
+
+/// Mirror axes and rotational copies around a movable pivot, applied to freehand strokes
+/// Axes combine: left/right plus top/bottom gives four copies, adding a diagonal gives eight,
+/// and any axis together with `segments` gives a kaleidoscope
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Symmetry {
+    /// Mirror across the vertical line through `center`
+    pub vertical: bool,
+    /// Mirror across the horizontal line through `center`
+    pub horizontal: bool,
+    /// Mirror across the 45° line running top-left to bottom-right
+    pub diagonal: bool,
+    /// Mirror across the 45° line running bottom-left to top-right
+    pub anti_diagonal: bool,
+    /// Rotational copies around `center` (1 = none)
+    pub segments: u32,
+    /// Pivot in world cell units, kept on cell edges or centers (multiples of 0.5)
+    pub center: (f32, f32),
+}
+
+impl Symmetry {
+    pub const OFF: Symmetry = Symmetry {
+        vertical: false,
+        horizontal: false,
+        diagonal: false,
+        anti_diagonal: false,
+        segments: 1,
+        center: (0.0, 0.0),
+    };
+
+    pub fn is_active(&self) -> bool {
+        self.vertical || self.horizontal || self.diagonal || self.anti_diagonal || self.segments > 1
+    }
+
+    /// Next entry of `SEGMENT_OPTIONS`, wrapping around
+    pub fn next_segments(&self) -> u32 {
+        let i = SEGMENT_OPTIONS.iter().position(|&s| s == self.segments).unwrap_or(0);
+        SEGMENT_OPTIONS[(i + 1) % SEGMENT_OPTIONS.len()]
+    }
+
+    /// Move the pivot to `world`, snapped to the nearest cell edge or center
+    pub fn set_center(&mut self, world: (f32, f32)) {
+        let snap = |v: f32| (v * 2.0).round() / 2.0;
+        self.center = (snap(world.0), snap(world.1));
+    }
+
+    /// Angles in radians (world space, y down) of the mirror lines, for drawing guides
+    pub fn axis_angles(&self) -> Vec<f32> {
+        use std::f32::consts::FRAC_PI_4;
+        [(self.horizontal, 0.0), (self.diagonal, FRAC_PI_4), (self.vertical, 2.0 * FRAC_PI_4), (self.anti_diagonal, 3.0 * FRAC_PI_4)]
+            .into_iter()
+            .filter_map(|(on, angle)| on.then_some(angle))
+            .collect()
+    }
+
+    /// `cell` and all its mirrored/rotated copies (deduplicated, `cell` first)
+    pub fn images(&self, cell: (i32, i32)) -> Vec<(i32, i32)> {
+        if !self.is_active() {
+            return vec![cell];
+        }
+
+        // Work on cell centers relative to the pivot, closing the set under every generator
+        let (cx, cy) = self.center;
+        let start = (cell.0 as f32 + 0.5 - cx, cell.1 as f32 + 0.5 - cy);
+        let (sin, cos) = (std::f32::consts::TAU / self.segments.max(1) as f32).sin_cos();
+        let mut generators: Vec<fn(Point) -> Point> = Vec::new();
+        if self.vertical {
+            generators.push(|(x, y)| (-x, y));
+        }
+        if self.horizontal {
+            generators.push(|(x, y)| (x, -y));
+        }
+        if self.diagonal {
+            generators.push(|(x, y)| (y, x));
+        }
+        if self.anti_diagonal {
+            generators.push(|(x, y)| (-y, -x));
+        }
+        let rotate = |(x, y): Point| (x * cos - y * sin, x * sin + y * cos);
+
+        let mut points = vec![start];
+        let mut i = 0;
+        while i < points.len() && points.len() < MAX_IMAGES {
+            let p = points[i];
+            let rotated = (self.segments > 1).then(|| rotate(p));
+            for q in generators.iter().map(|g| g(p)).chain(rotated) {
+                if !points.iter().any(|r| (r.0 - q.0).abs() < 1e-3 && (r.1 - q.1).abs() < 1e-3) {
+                    points.push(q);
+                }
+            }
+            i += 1;
+        }
+
+        let mut cells: Vec<(i32, i32)> = Vec::with_capacity(points.len());
+        for (x, y) in points {
+            let c = ((x + cx - 0.5).round() as i32, (y + cy - 0.5).round() as i32);
+            if !cells.contains(&c) {
+                cells.push(c);
+            }
+        }
+        cells
+    }
+}
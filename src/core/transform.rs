@@ -0,0 +1,106 @@
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::selection::{compute_bounding_rect, SelectionRect};
+use std::collections::HashSet;
+
+/// Pending transform of the selection's contents, previewed until applied
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelectionTransform {
+    /// Clockwise rotation in degrees
+    pub angle: f32,
+    /// Horizontal skew: x moves by `shear_x` cells per cell of y (italics)
+    pub shear_x: f32,
+    /// Vertical skew: y moves by `shear_y` cells per cell of x
+    pub shear_y: f32,
+    /// Horizontal stretch; 1 keeps the width
+    pub scale_x: f32,
+    /// Vertical stretch; 1 keeps the height
+    pub scale_y: f32,
+}
+
+/// Skew range offered by the handles and panel
+pub const MAX_SHEAR: f32 = 2.0;
+
+/// Largest stretch the scale handles allow on either axis
+pub const MAX_SCALE: f32 = 8.0;
+
+impl SelectionTransform {
+    pub const IDENTITY: Self = Self { angle: 0.0, shear_x: 0.0, shear_y: 0.0, scale_x: 1.0, scale_y: 1.0 };
+
+    /// Row-major 2×2 matrix mapping source offsets (from the pivot) to destination offsets
+    /// Scale is applied first, then shear, then rotation
+    pub fn matrix(&self) -> [[f32; 2]; 2] {
+        let m = self.shear_rotation();
+        [[m[0][0] * self.scale_x, m[0][1] * self.scale_y], [m[1][0] * self.scale_x, m[1][1] * self.scale_y]]
+    }
+
+    /// `matrix` without the scale: shear, then rotation
+    pub fn shear_rotation(&self) -> [[f32; 2]; 2] {
+        // y points down on screen, so this positive angle turns clockwise
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let rotation = [[cos, -sin], [sin, cos]];
+        let shear = [[1.0, self.shear_x], [self.shear_y, 1.0]];
+        let mut m = [[0.0; 2]; 2];
+        for (row, out) in m.iter_mut().enumerate() {
+            for (col, value) in out.iter_mut().enumerate() {
+                *value = rotation[row][0] * shear[0][col] + rotation[row][1] * shear[1][col];
+            }
+        }
+        m
+    }
+}
+
+/// Center of a cell rect in world units, the pivot transforms turn around
+pub fn rect_center(rect: &SelectionRect) -> (f32, f32) {
+    ((rect.min_x + rect.max_x + 1) as f32 / 2.0, (rect.min_y + rect.max_y + 1) as f32 / 2.0)
+}
+
+/// Inverse of a 2×2 matrix, None when it squashes everything onto a line
+pub fn invert(m: [[f32; 2]; 2]) -> Option<[[f32; 2]; 2]> {
+    let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    if det.abs() < 1e-6 {
+        return None;
+    }
+    Some([[m[1][1] / det, -m[0][1] / det], [-m[1][0] / det, m[0][0] / det]])
+}
+
+/// Transform `source` cells about `pivot` with nearest-neighbor resampling
+/// Each destination cell center is mapped back through the inverse matrix, so the result
+/// has no holes however the cells are turned
+pub fn resample_nearest(source: &CellGrid, pivot: (f32, f32), m: [[f32; 2]; 2]) -> Vec<((i32, i32), Cell)> {
+    let coords: HashSet<(i32, i32)> = source.keys().copied().collect();
+    let Some(rect) = compute_bounding_rect(&coords) else {
+        return Vec::new();
+    };
+    let Some(inv) = invert(m) else {
+        return Vec::new();
+    };
+    let apply = |m: &[[f32; 2]; 2], (x, y): (f32, f32)| (m[0][0] * x + m[0][1] * y, m[1][0] * x + m[1][1] * y);
+
+    // Destination bounds: the transformed corners of the source rect
+    let corners = [
+        (rect.min_x as f32, rect.min_y as f32),
+        ((rect.max_x + 1) as f32, rect.min_y as f32),
+        (rect.min_x as f32, (rect.max_y + 1) as f32),
+        ((rect.max_x + 1) as f32, (rect.max_y + 1) as f32),
+    ]
+    .map(|(x, y)| {
+        let (dx, dy) = apply(&m, (x - pivot.0, y - pivot.1));
+        (dx + pivot.0, dy + pivot.1)
+    });
+    let min_x = corners.iter().map(|c| c.0).fold(f32::MAX, f32::min).floor() as i32;
+    let max_x = corners.iter().map(|c| c.0).fold(f32::MIN, f32::max).ceil() as i32;
+    let min_y = corners.iter().map(|c| c.1).fold(f32::MAX, f32::min).floor() as i32;
+    let max_y = corners.iter().map(|c| c.1).fold(f32::MIN, f32::max).ceil() as i32;
+
+    let mut out = Vec::new();
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let (sx, sy) = apply(&inv, (x as f32 + 0.5 - pivot.0, y as f32 + 0.5 - pivot.1));
+            let src = ((sx + pivot.0).floor() as i32, (sy + pivot.1).floor() as i32);
+            if let Some(cell) = source.get(&src) {
+                out.push(((x, y), *cell));
+            }
+        }
+    }
+    out
+}
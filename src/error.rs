@@ -0,0 +1,58 @@
+use std::fmt;
+use std::io;
+
+/// Why a save, open, import or export failed
+#[derive(Debug)]
+pub enum FileError {
+    /// The file system refused a read
+    Read { path: String, source: io::Error },
+    /// The file system refused a write (or the encoder feeding it failed)
+    Write { path: String, source: io::Error },
+    /// The file was read but its contents weren't understood
+    Format { path: String, message: String },
+    /// The request can't be carried out whatever the files hold, e.g. an unknown file type
+    Unsupported(String),
+}
+
+impl FileError {
+    pub fn read(path: &str, source: io::Error) -> Self {
+        FileError::Read { path: path.to_string(), source }
+    }
+
+    pub fn write(path: &str, source: impl Into<io::Error>) -> Self {
+        FileError::Write { path: path.to_string(), source: source.into() }
+    }
+
+    pub fn format(path: &str, message: impl Into<String>) -> Self {
+        FileError::Format { path: path.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Read { path, source } => write!(f, "Could not read {}: {}", path, source),
+            FileError::Write { path, source } => write!(f, "Could not write {}: {}", path, source),
+            FileError::Format { path, message } => write!(f, "{}: {}", path, message),
+            FileError::Unsupported(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileError::Read { source, .. } | FileError::Write { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Record a failed `operation` ("Save", "Import", ...) in the error log and return the
+/// status line to show for it
+pub fn report(operation: &str, error: &FileError) -> String {
+    let message = format!("{} failed: {}", operation, error);
+    crate::project::error_log::append_error_log(&message);
+    log::error!("{}", message);
+    message
+}
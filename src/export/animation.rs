@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+use std::time::Duration;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use serde_json::json;
+use crate::core::cell::{grid_bounds, CellGrid};
+use crate::core::frame_tag::LoopMode;
+use crate::core::group::Group;
+use crate::core::raster::{rasterize, RasterImage, RasterOptions, MAX_RASTER_PIXELS};
+use crate::core::selection::SelectionRect;
+use crate::error::FileError;
+use crate::export::png::write_png;
+
+/// A frame tag as frame indices into a sprite sheet
+pub struct SheetTag {
+    pub name: String,
+    /// First and last frame of the tag, 0-based, inclusive
+    pub from: usize,
+    pub to: usize,
+    pub loop_mode: LoopMode,
+}
+
+/// How an animation is written
+pub enum AnimationFormat {
+    /// The frames played through once per loop in `loop_mode`
+    Gif { loop_mode: LoopMode },
+    /// Every frame left to right in one PNG, with a JSON file beside it giving each frame's
+    /// rect and the tags' frame ranges, as game engines' sprite-sheet importers expect
+    SpriteSheet { tags: Vec<SheetTag>, data_path: String },
+}
+
+/// Everything an animation export needs, copied off the app state so it can run in the
+/// background
+pub struct AnimationExport {
+    /// Cells showing during each frame, in frame order
+    pub frames: Vec<CellGrid>,
+    /// How long each frame shows, in milliseconds
+    pub durations: Vec<u32>,
+    pub options: RasterOptions,
+    pub format: AnimationFormat,
+}
+
+/// The cells on screen while `frame` plays: its own plus every cell outside the tagged frames
+pub fn frame_cells(cells: &CellGrid, tagged: &[&Group], frame: &Group) -> CellGrid {
+    let hidden: HashSet<(i32, i32)> = tagged.iter().filter(|g| g.id != frame.id).flat_map(|g| g.cells.iter().copied()).collect();
+    cells
+        .iter()
+        .filter(|(coord, _)| frame.cells.contains(coord) || !hidden.contains(coord))
+        .map(|(&coord, &cell)| (coord, cell))
+        .collect()
+}
+
+impl AnimationExport {
+    /// Rasterize every frame over the bounds they share and write them to `path`
+    pub fn write(&self, path: &str) -> Result<(), FileError> {
+        let rect = self
+            .frames
+            .iter()
+            .filter_map(grid_bounds)
+            .reduce(|a, b| SelectionRect::from_points((a.min_x.min(b.min_x), a.min_y.min(b.min_y)), (a.max_x.max(b.max_x), a.max_y.max(b.max_y))))
+            .ok_or_else(|| FileError::Unsupported("The frames are empty".to_string()))?;
+        let images: Vec<RasterImage> = self.frames.iter().map(|cells| rasterize(cells, &rect, &self.options)).collect::<Result<_, _>>()?;
+        match &self.format {
+            AnimationFormat::Gif { loop_mode } => write_gif(&images, &self.durations, *loop_mode, path),
+            AnimationFormat::SpriteSheet { tags, data_path } => {
+                write_png(&sheet_image(&images)?, path)?;
+                let data = sheet_data(&images, &self.durations, tags, path);
+                let text = serde_json::to_string_pretty(&data).map_err(|e| FileError::write(data_path, e))?;
+                std::fs::write(data_path, text).map_err(|e| FileError::write(data_path, e))
+            }
+        }
+    }
+}
+
+fn write_gif(images: &[RasterImage], durations: &[u32], loop_mode: LoopMode, path: &str) -> Result<(), FileError> {
+    let to_io = |e: image::ImageError| match e {
+        image::ImageError::IoError(e) => e,
+        e => std::io::Error::other(e),
+    };
+    let file = std::fs::File::create(path).map_err(|e| FileError::write(path, e))?;
+    let mut encoder = GifEncoder::new(std::io::BufWriter::new(file));
+    // Without a repeat count a GIF plays once
+    if loop_mode != LoopMode::Once {
+        encoder.set_repeat(Repeat::Infinite).map_err(|e| FileError::write(path, to_io(e)))?;
+    }
+    let frames = loop_mode.play_order(images.len()).into_iter().map(|i| {
+        let image = &images[i];
+        let buffer = RgbaImage::from_raw(image.width, image.height, image.pixels.clone()).expect("raster size matches its pixels");
+        // GIF delays are whole hundredths of a second
+        Frame::from_parts(buffer, 0, 0, Delay::from_saturating_duration(Duration::from_millis(durations[i] as u64)))
+    });
+    encoder.encode_frames(frames).map_err(|e| FileError::write(path, to_io(e)))
+}
+
+/// Frames side by side, top-aligned; they all share one size
+fn sheet_image(images: &[RasterImage]) -> Result<RasterImage, FileError> {
+    let (width, height) = (images[0].width, images[0].height);
+    let sheet_width = (width as usize)
+        .checked_mul(images.len())
+        .filter(|&w| w.saturating_mul(height as usize) <= MAX_RASTER_PIXELS)
+        .ok_or_else(|| FileError::Unsupported(format!("A sheet of {} frames would exceed {} megapixels", images.len(), MAX_RASTER_PIXELS >> 20)))?;
+    let mut sheet = RgbaImage::new(sheet_width as u32, height);
+    for (i, image) in images.iter().enumerate() {
+        let frame = RgbaImage::from_raw(image.width, image.height, image.pixels.clone()).expect("raster size matches its pixels");
+        image::imageops::replace(&mut sheet, &frame, (i as u32 * width) as i64, 0);
+    }
+    Ok(RasterImage { width: sheet.width(), height: sheet.height(), pixels: sheet.into_raw() })
+}
+
+fn sheet_data(images: &[RasterImage], durations: &[u32], tags: &[SheetTag], path: &str) -> serde_json::Value {
+    let image = std::path::Path::new(path).file_name().map_or(path.to_string(), |name| name.to_string_lossy().into_owned());
+    let frames: Vec<_> = images
+        .iter()
+        .zip(durations)
+        .enumerate()
+        .map(|(i, (frame, duration_ms))| json!({ "x": i as u32 * frame.width, "y": 0, "w": frame.width, "h": frame.height, "duration_ms": duration_ms }))
+        .collect();
+    let tags: Vec<_> = tags
+        .iter()
+        .map(|tag| json!({ "name": tag.name, "from": tag.from, "to": tag.to, "loop": tag.loop_mode.key() }))
+        .collect();
+    json!({ "image": image, "frames": frames, "tags": tags })
+}
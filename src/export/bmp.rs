@@ -0,0 +1,103 @@
+use crate::core::raster::RasterImage;
+
+const FILE_HEADER_SIZE: u32 = 14;
+/// BITMAPINFOHEADER, readable by every BMP loader
+const INFO_HEADER_SIZE: u32 = 40;
+/// BITMAPV4HEADER, needed to declare an alpha channel
+const V4_HEADER_SIZE: u32 = 108;
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+/// 72 DPI in pixels per meter
+const PIXELS_PER_METER: u32 = 2835;
+
+/// Encode as an uncompressed BMP: 24-bit when every pixel is opaque (what old
+/// toolchains expect), otherwise 32-bit BGRA with a V4 header so alpha survives
+pub fn encode_bmp(image: &RasterImage) -> Vec<u8> {
+    let opaque = image.pixels.chunks_exact(4).all(|px| px[3] == 255);
+    let (bits, header_size) = if opaque { (24, INFO_HEADER_SIZE) } else { (32, V4_HEADER_SIZE) };
+    // Rows are padded to 4 bytes
+    let row_size = (image.width * bits / 8).next_multiple_of(4);
+    let data_offset = FILE_HEADER_SIZE + header_size;
+    let data_size = row_size * image.height;
+
+    let mut out = Vec::with_capacity((data_offset + data_size) as usize);
+    out.extend_from_slice(b"BM");
+    out.extend_from_slice(&(data_offset + data_size).to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&data_offset.to_le_bytes());
+
+    out.extend_from_slice(&header_size.to_le_bytes());
+    out.extend_from_slice(&(image.width as i32).to_le_bytes());
+    // Positive height: rows stored bottom-up
+    out.extend_from_slice(&(image.height as i32).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&(bits as u16).to_le_bytes());
+    out.extend_from_slice(&(if opaque { BI_RGB } else { BI_BITFIELDS }).to_le_bytes());
+    out.extend_from_slice(&data_size.to_le_bytes());
+    out.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+    out.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    if !opaque {
+        // Red, green, blue and alpha masks, then the "sRGB" color space tag; the
+        // endpoints and gamma that follow are unused for sRGB
+        for mask in [0x00ff_0000u32, 0x0000_ff00, 0x0000_00ff, 0xff00_0000] {
+            out.extend_from_slice(&mask.to_le_bytes());
+        }
+        out.extend_from_slice(b"BGRs");
+        out.resize(out.len() + 48, 0);
+    }
+
+    let stride = image.width as usize * 4;
+    for row in image.pixels.chunks_exact(stride.max(1)).rev() {
+        let start = out.len();
+        for px in row.chunks_exact(4) {
+            out.extend_from_slice(&[px[2], px[1], px[0]]);
+            if !opaque {
+                out.push(px[3]);
+            }
+        }
+        out.resize(start + row_size as usize, 0);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+
+    /// An odd width, so 24-bit rows need padding
+    fn sample_image(alpha: impl Fn(u32, u32) -> u8) -> RasterImage {
+        let (width, height) = (7, 5);
+        let mut pixels = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                pixels.extend_from_slice(&[(x * 30) as u8, (y * 50) as u8, (x * y * 9) as u8, alpha(x, y)]);
+            }
+        }
+        RasterImage { width, height, pixels }
+    }
+
+    fn decode(bytes: &[u8]) -> image::RgbaImage {
+        image::load_from_memory_with_format(bytes, ImageFormat::Bmp).unwrap().to_rgba8()
+    }
+
+    #[test]
+    fn opaque_images_round_trip_as_24_bit() {
+        let image = sample_image(|_, _| 255);
+        let bytes = encode_bmp(&image);
+        assert_eq!(u16::from_le_bytes([bytes[28], bytes[29]]), 24);
+        let decoded = decode(&bytes);
+        assert_eq!((decoded.width(), decoded.height()), (image.width, image.height));
+        assert_eq!(decoded.into_raw(), image.pixels);
+    }
+
+    #[test]
+    fn alpha_survives_as_32_bit() {
+        let image = sample_image(|x, y| (x * 36 + y) as u8);
+        let bytes = encode_bmp(&image);
+        assert_eq!(u16::from_le_bytes([bytes[28], bytes[29]]), 32);
+        assert_eq!(decode(&bytes).into_raw(), image.pixels);
+    }
+}
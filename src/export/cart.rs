@@ -0,0 +1,108 @@
+use crate::core::cell::CellGrid;
+use crate::core::color::{nearest_palette_index, Rgba, PICO8_PALETTE};
+use crate::core::selection::SelectionRect;
+use crate::error::FileError;
+use crate::import::pico8::{is_section_header, GFX_SECTION, SHEET_SIZE};
+use crate::import::tic80::{cart_palette, SHEET_TILES};
+
+const PICO8_HEADER: &str = "pico-8 cartridge // http://www.pico-8.com\nversion 41\n";
+
+/// Palette indices for the 128x128 sheet taken from `rect`'s top-left; empty cells are 0
+/// Returns the sheet and whether part of `rect` didn't fit
+fn sheet_indices(cells: &CellGrid, rect: &SelectionRect, palette: &[Rgba]) -> (Vec<u8>, bool) {
+    let size = SHEET_SIZE as usize;
+    let mut sheet = vec![0u8; size * size];
+    for y in 0..SHEET_SIZE.min(rect.height()) {
+        for x in 0..SHEET_SIZE.min(rect.width()) {
+            if let Some(cell) = cells.get(&(rect.min_x + x, rect.min_y + y)).filter(|c| c.is_filled) {
+                sheet[y as usize * size + x as usize] = nearest_palette_index(palette, cell.color) as u8;
+            }
+        }
+    }
+    (sheet, rect.width() > SHEET_SIZE || rect.height() > SHEET_SIZE)
+}
+
+/// Write `rect` into the __gfx__ section of the .p8 cart at `path`, keeping its code and
+/// other sections (a new cart is created if the file doesn't exist)
+/// Returns true if the drawing was cropped to 128x128
+pub fn export_p8(cells: &CellGrid, rect: &SelectionRect, path: &str) -> Result<bool, FileError> {
+    let (sheet, cropped) = sheet_indices(cells, rect, &PICO8_PALETTE);
+    let mut gfx = String::from(GFX_SECTION);
+    gfx.push('\n');
+    for row in sheet.chunks(SHEET_SIZE as usize) {
+        gfx.extend(row.iter().map(|&i| char::from_digit(i as u32, 16).unwrap_or('0')));
+        gfx.push('\n');
+    }
+
+    let existing = read_existing(path)?.unwrap_or_else(|| PICO8_HEADER.to_string());
+    let mut out = String::new();
+    let mut lines = existing.lines().peekable();
+    let mut replaced = false;
+    while let Some(line) = lines.next() {
+        if line.trim() == GFX_SECTION {
+            out.push_str(&gfx);
+            replaced = true;
+            while lines.peek().is_some_and(|next| !is_section_header(next)) {
+                lines.next();
+            }
+        } else {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    if !replaced {
+        out.push_str(&gfx);
+    }
+    std::fs::write(path, out).map_err(|e| FileError::write(path, e))?;
+    Ok(cropped)
+}
+
+/// Write `rect` into the TILES block of the TIC-80 .lua cart at `path` using the cart's
+/// palette, keeping everything else; all-zero tiles are omitted as TIC-80 does
+/// Returns true if the drawing was cropped to 128x128
+pub fn export_tic(cells: &CellGrid, rect: &SelectionRect, path: &str) -> Result<bool, FileError> {
+    let existing = read_existing(path)?.unwrap_or_default();
+    let (sheet, cropped) = sheet_indices(cells, rect, &cart_palette(&existing));
+
+    let mut block = String::from("-- <TILES>\n");
+    let size = SHEET_SIZE as usize;
+    for tile in 0..(SHEET_TILES * SHEET_TILES) as usize {
+        let (tx, ty) = (tile % SHEET_TILES as usize * 8, tile / SHEET_TILES as usize * 8);
+        let digits: String = (0..64)
+            .map(|i| sheet[(ty + i / 8) * size + tx + i % 8])
+            .map(|index| char::from_digit(index as u32, 16).unwrap_or('0'))
+            .collect();
+        if digits.chars().any(|c| c != '0') {
+            block.push_str(&format!("-- {:03}:{}\n", tile, digits));
+        }
+    }
+    block.push_str("-- </TILES>\n");
+
+    let mut out = String::new();
+    let mut lines = existing.lines();
+    let mut replaced = false;
+    while let Some(line) = lines.next() {
+        if line.trim() == "-- <TILES>" {
+            out.push_str(&block);
+            replaced = true;
+            lines.by_ref().find(|next| next.trim() == "-- </TILES>");
+        } else {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    if !replaced {
+        out.push('\n');
+        out.push_str(&block);
+    }
+    std::fs::write(path, out).map_err(|e| FileError::write(path, e))?;
+    Ok(cropped)
+}
+
+fn read_existing(path: &str) -> Result<Option<String>, FileError> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(FileError::read(path, e)),
+    }
+}
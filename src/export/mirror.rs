@@ -0,0 +1,65 @@
+use crate::state::ApplicationState;
+use crate::core::cell::grid_bounds;
+use crate::core::color::Rgba;
+use crate::core::raster::{rasterize, RasterOptions, ScaleFilter};
+use crate::error::{report, FileError};
+use crate::export::png::write_png;
+
+/// How often the mirror image is refreshed, in seconds
+const MIRROR_INTERVAL: f64 = 0.5;
+/// Output pixels per cell (capture tools scale poorly from 1×)
+const MIRROR_SCALE: u32 = 4;
+/// Default output path, overridable via the TINY_NEO_SPACE_MIRROR env var
+const DEFAULT_MIRROR_PATH: &str = "tiny-neo-space-mirror.png";
+
+/// Live view mirroring: periodically writes the drawing to a PNG that OBS
+/// (Image Source) or an image viewer on a second monitor can watch
+pub struct Mirror {
+    path: String,
+    last_write: f64,
+    last_pixels: Vec<u8>,
+}
+
+impl Mirror {
+    pub fn new() -> Self {
+        Self {
+            path: std::env::var("TINY_NEO_SPACE_MIRROR").unwrap_or_else(|_| DEFAULT_MIRROR_PATH.to_string()),
+            last_write: f64::MIN,
+            last_pixels: Vec::new(),
+        }
+    }
+
+    /// Rewrite the mirror file if enabled, due, and the image changed
+    pub fn update(&mut self, state: &mut ApplicationState, now: f64) {
+        if !state.mirror_enabled || now - self.last_write < MIRROR_INTERVAL {
+            return;
+        }
+        self.last_write = now;
+
+        let rect = match grid_bounds(&state.cells) {
+            Some(rect) => rect,
+            None => return,
+        };
+        let options = RasterOptions { scale: MIRROR_SCALE, filter: ScaleFilter::Nearest, aspect: state.cell_aspect, background: Rgba::WHITE, grid: None };
+        let image = match rasterize(&state.cells, &rect, &options) {
+            Ok(image) if image.pixels == self.last_pixels => return,
+            result => result,
+        };
+
+        // Write to a temp file and rename so readers never see a half-written image
+        let tmp_path = format!("{}.tmp", self.path);
+        let result = image.and_then(|image| {
+            write_png(&image, &tmp_path)
+                .and_then(|_| std::fs::rename(&tmp_path, &self.path).map_err(|e| FileError::write(&self.path, e)))
+                .map(|_| image.pixels)
+        });
+
+        match result {
+            Ok(pixels) => self.last_pixels = pixels,
+            Err(e) => {
+                state.status_message = Some(report("Mirror", &e));
+                state.mirror_enabled = false;
+            }
+        }
+    }
+}
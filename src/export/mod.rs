@@ -0,0 +1,9 @@
+pub mod bmp;
+pub mod mirror;
+pub mod png;
+pub mod print_size;
+pub mod qoi;
+pub mod cart;
+pub mod animation;
+
+pub use mirror::Mirror;
@@ -0,0 +1,227 @@
+use crate::core::cell::{CellAspect, CellGrid};
+use crate::core::color::Rgba;
+use crate::core::raster::{rasterize, RasterImage, RasterOptions, ScaleFilter};
+use crate::core::selection::SelectionRect;
+use crate::error::FileError;
+use crate::export::bmp::encode_bmp;
+use crate::export::qoi::encode_qoi;
+use crate::project::format::ProjectMetadata;
+
+pub const MIN_EXPORT_SCALE: u32 = 1;
+pub const MAX_EXPORT_SCALE: u32 = 16;
+const GRID_LINE_COLOR: Rgba = Rgba::rgb(180, 190, 215);
+const DEFAULT_EXPORT_PATH: &str = "tiny-neo-space-export.png";
+
+/// What empty cells become in the exported image
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportBackground {
+    Transparent,
+    White,
+    /// Uses the secondary color
+    Custom,
+}
+
+impl ExportBackground {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportBackground::Transparent => "Transparent",
+            ExportBackground::White => "White",
+            ExportBackground::Custom => "Secondary",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ExportBackground::Transparent => ExportBackground::White,
+            ExportBackground::White => ExportBackground::Custom,
+            ExportBackground::Custom => ExportBackground::Transparent,
+        }
+    }
+}
+
+/// File format written by the export dialog
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Png,
+    Bmp,
+    Qoi,
+}
+
+impl ExportFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Png => "PNG",
+            ExportFormat::Bmp => "BMP",
+            ExportFormat::Qoi => "QOI",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Png => "png",
+            ExportFormat::Bmp => "bmp",
+            ExportFormat::Qoi => "qoi",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ExportFormat::Png => ExportFormat::Bmp,
+            ExportFormat::Bmp => ExportFormat::Qoi,
+            ExportFormat::Qoi => ExportFormat::Png,
+        }
+    }
+}
+
+/// Options chosen in the export dialog
+#[derive(Clone, Debug)]
+pub struct ExportSettings {
+    pub background: ExportBackground,
+    pub format: ExportFormat,
+    pub scale: u32,
+    /// Pixel-art smoothing upscaler applied before `scale`
+    pub filter: ScaleFilter,
+    pub grid_lines: bool,
+    /// Dots per inch used for the print-size readout
+    pub dpi: u32,
+    /// Index into PRINT_PRESETS of the last applied preset
+    pub preset: usize,
+    pub path: String,
+    /// Outcome of the last export, shown in the dialog
+    pub status: Option<String>,
+}
+
+impl ExportSettings {
+    pub fn new() -> Self {
+        Self {
+            background: ExportBackground::Transparent,
+            format: ExportFormat::Png,
+            scale: 1,
+            filter: ScaleFilter::Nearest,
+            grid_lines: false,
+            dpi: 300,
+            preset: 0,
+            path: DEFAULT_EXPORT_PATH.to_string(),
+            status: None,
+        }
+    }
+
+    /// Switch format, giving the output path the matching extension
+    pub fn set_format(&mut self, format: ExportFormat) {
+        self.format = format;
+        self.path = format!("{}.{}", self.path_stem(), format.extension());
+    }
+
+    /// Output path for region exports, next to the main export
+    pub fn region_path(&self) -> String {
+        format!("{}-region.{}", self.path_stem(), self.format.extension())
+    }
+
+    /// Output path for selection exports, next to the main export
+    pub fn selection_path(&self) -> String {
+        format!("{}-selection.{}", self.path_stem(), self.format.extension())
+    }
+
+    /// PNG path for a single group's export, next to the main export and named after the
+    /// group: "art/export.png" + "Hero Idle" -> "art/export-hero-idle.png"
+    pub fn group_path(&self, name: &str) -> String {
+        let slug: String = name
+            .trim()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '-' })
+            .flat_map(char::to_lowercase)
+            .collect();
+        let slug = slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+        let slug = if slug.is_empty() { "group".to_string() } else { slug };
+        format!("{}-{}.png", self.path_stem(), slug)
+    }
+
+    /// Output path for animated GIFs, next to the main export
+    pub fn gif_path(&self) -> String {
+        format!("{}.gif", self.path_stem())
+    }
+
+    /// Output paths for the sprite sheet image and its frame/tag data
+    pub fn sprite_sheet_paths(&self) -> (String, String) {
+        (format!("{}-sheet.png", self.path_stem()), format!("{}-sheet.json", self.path_stem()))
+    }
+
+    /// The output path without its image extension
+    fn path_stem(&self) -> &str {
+        [ExportFormat::Png, ExportFormat::Bmp, ExportFormat::Qoi]
+            .iter()
+            .find_map(|format| self.path.strip_suffix(&format!(".{}", format.extension())))
+            .unwrap_or(&self.path)
+    }
+
+    pub fn raster_options(&self, custom_background: Rgba, aspect: CellAspect) -> RasterOptions {
+        let background = match self.background {
+            ExportBackground::Transparent => Rgba::new(0, 0, 0, 0),
+            ExportBackground::White => Rgba::WHITE,
+            ExportBackground::Custom => custom_background,
+        };
+        RasterOptions {
+            scale: self.scale.clamp(MIN_EXPORT_SCALE, MAX_EXPORT_SCALE),
+            filter: self.filter,
+            aspect,
+            background,
+            grid: self.grid_lines.then_some(GRID_LINE_COLOR),
+        }
+    }
+}
+
+/// Write an already rasterized image as PNG
+pub fn write_png(image: &RasterImage, path: &str) -> Result<(), FileError> {
+    image::save_buffer_with_format(
+        path,
+        &image.pixels,
+        image.width,
+        image.height,
+        image::ColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+    .map_err(|e| match e {
+        image::ImageError::IoError(e) => FileError::write(path, e),
+        e => FileError::write(path, std::io::Error::other(e)),
+    })
+}
+
+/// Write an image as PNG with (keyword, text) chunks; non-Latin text goes in UTF-8 iTXt chunks
+pub fn write_png_with_text(image: &RasterImage, text: &[(&str, String)], path: &str) -> Result<(), FileError> {
+    let to_io = |e: png::EncodingError| match e {
+        png::EncodingError::IoError(e) => e,
+        e => std::io::Error::other(e),
+    };
+    let file = std::fs::File::create(path).map_err(|e| FileError::write(path, e))?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    for (keyword, value) in text {
+        let added = if value.is_ascii() {
+            encoder.add_text_chunk(keyword.to_string(), value.clone())
+        } else {
+            encoder.add_itxt_chunk(keyword.to_string(), value.clone())
+        };
+        added.map_err(|e| FileError::write(path, to_io(e)))?;
+    }
+    let mut writer = encoder.write_header().map_err(|e| FileError::write(path, to_io(e)))?;
+    writer.write_image_data(&image.pixels).map_err(|e| FileError::write(path, to_io(e)))?;
+    writer.finish().map_err(|e| FileError::write(path, to_io(e)))
+}
+
+/// Write an already rasterized image in `format`; `text` (title, author...) is embedded in PNGs
+/// and dropped for formats without text chunks
+pub fn write_image(image: &RasterImage, format: ExportFormat, text: &[(&str, String)], path: &str) -> Result<(), FileError> {
+    match format {
+        ExportFormat::Png => write_png_with_text(image, text, path),
+        ExportFormat::Bmp => std::fs::write(path, encode_bmp(image)).map_err(|e| FileError::write(path, e)),
+        ExportFormat::Qoi => std::fs::write(path, encode_qoi(image)).map_err(|e| FileError::write(path, e)),
+    }
+}
+
+/// Rasterize `rect` with the export settings and write it to `path` in the chosen format,
+/// carrying the document metadata where the format allows
+pub fn export_image(cells: &CellGrid, rect: &SelectionRect, settings: &ExportSettings, custom_background: Rgba, aspect: CellAspect, metadata: &ProjectMetadata, path: &str) -> Result<(), FileError> {
+    let image = rasterize(cells, rect, &settings.raster_options(custom_background, aspect))?;
+    write_image(&image, settings.format, &metadata.text_chunks(), path)
+}
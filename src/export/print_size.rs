@@ -0,0 +1,39 @@
+/// DPI choices offered in the export dialog
+pub const DPI_OPTIONS: [u32; 4] = [72, 150, 300, 600];
+
+const CM_PER_INCH: f32 = 2.54;
+
+/// A common physical output size, in inches
+#[derive(Clone, Copy, Debug)]
+pub struct PrintPreset {
+    pub name: &'static str,
+    pub width_in: f32,
+    pub height_in: f32,
+}
+
+pub const PRINT_PRESETS: [PrintPreset; 5] = [
+    PrintPreset { name: "2x2in sticker", width_in: 2.0, height_in: 2.0 },
+    PrintPreset { name: "3x3in sticker", width_in: 3.0, height_in: 3.0 },
+    PrintPreset { name: "4x6in print", width_in: 4.0, height_in: 6.0 },
+    PrintPreset { name: "A6 card", width_in: 4.13, height_in: 5.83 },
+    PrintPreset { name: "A4 poster", width_in: 8.27, height_in: 11.69 },
+];
+
+/// Physical size in inches of an image of `pixels` at `dpi`
+pub fn pixels_to_inches(pixels: u32, dpi: u32) -> f32 {
+    pixels as f32 / dpi.max(1) as f32
+}
+
+pub fn inches_to_cm(inches: f32) -> f32 {
+    inches * CM_PER_INCH
+}
+
+/// Largest integer scale at which `cells_w` × `cells_h` still fits the preset at `dpi`
+/// (either orientation), or None if even 1× is too big
+pub fn fit_scale(cells_w: u32, cells_h: u32, dpi: u32, preset: &PrintPreset) -> Option<u32> {
+    let max_w = preset.width_in * dpi as f32;
+    let max_h = preset.height_in * dpi as f32;
+    let fit = |w: f32, h: f32| ((w / cells_w.max(1) as f32).min(h / cells_h.max(1) as f32)).floor() as u32;
+    let best = fit(max_w, max_h).max(fit(max_h, max_w));
+    (best >= 1).then_some(best)
+}
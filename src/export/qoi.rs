@@ -0,0 +1,114 @@
+use crate::core::raster::RasterImage;
+
+const MAGIC: &[u8; 4] = b"qoif";
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RUN: u8 = 0xc0;
+const OP_RGB: u8 = 0xfe;
+const OP_RGBA: u8 = 0xff;
+/// Longest run one OP_RUN can hold (62 and 63 would collide with OP_RGB/OP_RGBA)
+const MAX_RUN: u8 = 62;
+const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// Encode as QOI ("Quite OK Image", qoiformat.org): lossless RGBA, sRGB
+pub fn encode_qoi(image: &RasterImage) -> Vec<u8> {
+    let mut out = Vec::with_capacity(14 + image.pixels.len() / 2 + END_MARKER.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&image.width.to_be_bytes());
+    out.extend_from_slice(&image.height.to_be_bytes());
+    // 4 channels, sRGB with linear alpha
+    out.extend_from_slice(&[4, 0]);
+
+    let mut seen = [[0u8; 4]; 64];
+    let mut prev = [0u8, 0, 0, 255];
+    let mut run = 0u8;
+    for chunk in image.pixels.chunks_exact(4) {
+        let px = [chunk[0], chunk[1], chunk[2], chunk[3]];
+        if px == prev {
+            run += 1;
+            if run == MAX_RUN {
+                out.push(OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            out.push(OP_RUN | (run - 1));
+            run = 0;
+        }
+
+        let index = hash(px);
+        if seen[index] == px {
+            out.push(OP_INDEX | index as u8);
+        } else {
+            seen[index] = px;
+            if px[3] == prev[3] {
+                let dr = px[0].wrapping_sub(prev[0]) as i8;
+                let dg = px[1].wrapping_sub(prev[1]) as i8;
+                let db = px[2].wrapping_sub(prev[2]) as i8;
+                let (dr_dg, db_dg) = (dr.wrapping_sub(dg), db.wrapping_sub(dg));
+                if (-2..=1).contains(&dr) && (-2..=1).contains(&dg) && (-2..=1).contains(&db) {
+                    out.push(OP_DIFF | ((dr + 2) as u8) << 4 | ((dg + 2) as u8) << 2 | (db + 2) as u8);
+                } else if (-32..=31).contains(&dg) && (-8..=7).contains(&dr_dg) && (-8..=7).contains(&db_dg) {
+                    out.push(OP_LUMA | (dg + 32) as u8);
+                    out.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+                } else {
+                    out.extend_from_slice(&[OP_RGB, px[0], px[1], px[2]]);
+                }
+            } else {
+                out.extend_from_slice(&[OP_RGBA, px[0], px[1], px[2], px[3]]);
+            }
+        }
+        prev = px;
+    }
+    if run > 0 {
+        out.push(OP_RUN | (run - 1));
+    }
+    out.extend_from_slice(&END_MARKER);
+    out
+}
+
+fn hash([r, g, b, a]: [u8; 4]) -> usize {
+    (r as usize * 3 + g as usize * 5 + b as usize * 7 + a as usize * 11) % 64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+
+    /// Runs, small and large color steps, repeats of earlier colors and alpha changes,
+    /// so every chunk type gets written
+    fn sample_image() -> RasterImage {
+        let (width, height) = (37, 23);
+        let mut pixels = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = match (x / 8 + y / 6) % 4 {
+                    0 => [10, 20, 30, 255],
+                    1 => [(x * 3) as u8, (y * 2) as u8, 100, 255],
+                    2 => [(x * 40) as u8, (y * 90) as u8, (x * y) as u8, 255],
+                    _ => [200, 100, 50, (x * 7) as u8],
+                };
+                pixels.extend_from_slice(&pixel);
+            }
+        }
+        RasterImage { width, height, pixels }
+    }
+
+    #[test]
+    fn decodes_to_the_same_pixels() {
+        let image = sample_image();
+        let decoded = image::load_from_memory_with_format(&encode_qoi(&image), ImageFormat::Qoi).unwrap().to_rgba8();
+        assert_eq!((decoded.width(), decoded.height()), (image.width, image.height));
+        assert_eq!(decoded.into_raw(), image.pixels);
+    }
+
+    #[test]
+    fn long_runs_split_without_colliding_with_other_ops() {
+        let image = RasterImage { width: 200, height: 1, pixels: [9, 8, 7, 255].repeat(200) };
+        let decoded = image::load_from_memory_with_format(&encode_qoi(&image), ImageFormat::Qoi).unwrap().to_rgba8();
+        assert_eq!(decoded.into_raw(), image.pixels);
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::Path;
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::color::{gba5_to_u8, Rgba};
+
+const TILE_SIZE: i32 = 8;
+/// 8x8 pixels at 4 bits each
+const TILE_BYTES: usize = 32;
+/// Palette files tried next to the tile data, in order
+const PALETTE_EXTENSIONS: [&str; 3] = ["pal", "gbapal", "palette"];
+
+/// Expand a 15-bit BGR555 color (as stored in GBA palette RAM) to 8 bits per channel
+pub fn bgr555_to_rgba(value: u16) -> Rgba {
+    let channel = |shift: u16| gba5_to_u8(((value >> shift) & 31) as u8);
+    Rgba::rgb(channel(0), channel(5), channel(10))
+}
+
+/// Read a raw palette: little-endian BGR555 entries; only the first 16 (bank 0) are used
+pub fn decode_palette(bytes: &[u8]) -> Result<Vec<Rgba>, String> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return Err(format!("Palette is {} bytes; expected pairs of bytes", bytes.len()));
+    }
+    Ok(bytes.chunks_exact(2).take(16).map(|pair| bgr555_to_rgba(u16::from_le_bytes([pair[0], pair[1]]))).collect())
+}
+
+/// The palette beside `tiles` (same name, .pal/.gbapal/.palette), if there is one
+pub fn find_palette(tiles: &Path) -> Result<Option<Vec<Rgba>>, String> {
+    for extension in PALETTE_EXTENSIONS {
+        let candidate = tiles.with_extension(extension);
+        if let Ok(bytes) = std::fs::read(&candidate) {
+            return decode_palette(&bytes).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// Decode 4bpp tiles laid out `tiles_per_row` across. Each byte holds two pixels, left one
+/// in the low nibble; index 0 is transparent. Without a palette, indices become a gray ramp
+pub fn decode_4bpp(bytes: &[u8], palette: Option<&[Rgba]>, tiles_per_row: u32) -> Result<CellGrid, String> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(TILE_BYTES) {
+        return Err(format!("Tile data is {} bytes; expected a multiple of {}", bytes.len(), TILE_BYTES));
+    }
+    let color = |index: u8| match palette {
+        Some(palette) => palette.get(index as usize).copied(),
+        None => Some(Rgba::rgb(index * 17, index * 17, index * 17)),
+    };
+
+    let tiles_per_row = tiles_per_row.max(1) as i32;
+    let mut cells = CellGrid::new();
+    for (tile, data) in bytes.chunks_exact(TILE_BYTES).enumerate() {
+        let origin_x = (tile as i32 % tiles_per_row) * TILE_SIZE;
+        let origin_y = (tile as i32 / tiles_per_row) * TILE_SIZE;
+        for (i, byte) in data.iter().enumerate() {
+            let (x, y) = ((i as i32 % 4) * 2, i as i32 / 4);
+            for (dx, index) in [(0, byte & 0x0F), (1, byte >> 4)] {
+                if index == 0 {
+                    continue;
+                }
+                if let Some(rgba) = color(index) {
+                    cells.insert((origin_x + x + dx, origin_y + y), Cell::with_color(rgba));
+                }
+            }
+        }
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bgr555_puts_red_in_the_low_bits() {
+        assert_eq!(bgr555_to_rgba(0x001F), Rgba::rgb(255, 0, 0));
+        assert_eq!(bgr555_to_rgba(0x7C00), Rgba::rgb(0, 0, 255));
+        assert_eq!(bgr555_to_rgba(0x7FFF), Rgba::rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn palettes_keep_bank_zero() {
+        let bytes: Vec<u8> = (0..32u16).flat_map(|i| i.to_le_bytes()).collect();
+        assert_eq!(decode_palette(&bytes).unwrap().len(), 16);
+        assert!(decode_palette(&[0x1F]).is_err());
+        assert!(decode_palette(&[]).is_err());
+    }
+
+    #[test]
+    fn low_nibble_is_the_left_pixel() {
+        let mut bytes = [0u8; 2 * TILE_BYTES];
+        bytes[0] = 0x21;
+        bytes[TILE_BYTES + 31] = 0xF0;
+        let cells = decode_4bpp(&bytes, None, 1).unwrap();
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[&(0, 0)].color, Rgba::rgb(17, 17, 17));
+        assert_eq!(cells[&(1, 0)].color, Rgba::rgb(34, 34, 34));
+        // The second tile sits below the first, its last byte's high nibble at the bottom right
+        assert_eq!(cells[&(7, 15)].color, Rgba::rgb(255, 255, 255));
+
+        let palette = [Rgba::rgb(0, 0, 0), Rgba::rgb(1, 2, 3)];
+        let cells = decode_4bpp(&bytes, Some(&palette), 2).unwrap();
+        assert_eq!(cells.len(), 1, "indices past the palette are skipped");
+        assert_eq!(cells[&(0, 0)].color, Rgba::rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn truncated_tiles_are_errors() {
+        assert!(decode_4bpp(&[0u8; TILE_BYTES - 1], None, 1).is_err());
+        assert!(decode_4bpp(&[0u8; TILE_BYTES + 5], None, 1).is_err());
+        assert!(decode_4bpp(&[], None, 1).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Importers for other editors' and consoles' formats; each decodes a file into cells whose
+//! top-left is (0, 0), which the import dialog places on the canvas
+
+pub mod gba;
+pub mod pico8;
+pub mod piskel;
+pub mod png;
+pub mod sequence;
+pub mod svg;
+pub mod tic80;
+
+use crate::core::cell::CellGrid;
+use crate::core::color::Rgba;
+use crate::core::dither::Quantize;
+use crate::error::FileError;
+use crate::project::format::{PROJECT_EXTENSION, TEXT_PROJECT_EXTENSION};
+use crate::project::preset::PRESET_EXTENSION;
+
+/// Empty columns between animation frames laid out side by side
+pub const FRAME_GAP: i32 = 1;
+
+/// A decoded file
+pub struct Imported {
+    pub cells: CellGrid,
+    /// Named cell sets to turn into groups, one per animation frame, in playback order
+    pub groups: Vec<(String, Vec<(i32, i32)>)>,
+}
+
+impl From<CellGrid> for Imported {
+    fn from(cells: CellGrid) -> Self {
+        Self { cells, groups: Vec::new() }
+    }
+}
+
+/// Options chosen in the import dialog
+#[derive(Clone, Debug)]
+pub struct ImportSettings {
+    /// File to import; the extension picks the format
+    pub path: String,
+    /// Tile formats: how many 8x8 tiles per row on the canvas
+    pub tiles_per_row: u32,
+    /// Full-color images: how they are fitted to the active palette
+    pub quantize: Quantize,
+    /// Projects: bring their groups along, prefixed with the project's name
+    pub project_groups: bool,
+    /// SVG: cells per SVG user unit
+    pub svg_scale: f32,
+    /// PNG: read every numbered file beside the chosen one as the frames of an animation
+    pub png_sequence: bool,
+    /// Outcome of the last import, shown in the dialog
+    pub status: Option<String>,
+}
+
+pub const MIN_TILES_PER_ROW: u32 = 1;
+pub const MAX_TILES_PER_ROW: u32 = 64;
+
+impl ImportSettings {
+    pub fn new() -> Self {
+        Self { path: String::new(), tiles_per_row: 16, quantize: Quantize::Off, project_groups: true, svg_scale: 1.0, png_sequence: false, status: None }
+    }
+}
+
+/// Whether the import dialog reads this path's file type (workspace presets and projects included)
+pub fn can_import(path: &str) -> bool {
+    matches!(extension(path).as_str(), "4bpp" | "bin" | "p8" | "lua" | "png" | "gif" | "piskel" | "svg" | PRESET_EXTENSION | PROJECT_EXTENSION | TEXT_PROJECT_EXTENSION)
+}
+
+/// Whether `export_file` can write the dialog's file type
+pub fn can_export(settings: &ImportSettings) -> bool {
+    matches!(extension(&settings.path).as_str(), "p8" | "lua" | PRESET_EXTENSION)
+}
+
+pub fn extension(path: &str) -> String {
+    std::path::Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase()
+}
+
+/// Decode `settings.path` by extension; `palette` is what full-color images are quantized to
+pub fn decode_file(settings: &ImportSettings, palette: &[Rgba]) -> Result<Imported, FileError> {
+    let path = std::path::Path::new(&settings.path);
+    let extension = extension(&settings.path);
+    let read = || std::fs::read(path).map_err(|e| FileError::read(&settings.path, e));
+    let decoded = match extension.as_str() {
+        "4bpp" | "bin" => {
+            let palette = gba::find_palette(path).map_err(|e| FileError::format(&settings.path, e))?;
+            gba::decode_4bpp(&read()?, palette.as_deref(), settings.tiles_per_row).map(Imported::from)
+        }
+        "p8" => pico8::decode_p8(&String::from_utf8_lossy(&read()?)).map(Imported::from),
+        "lua" => tic80::decode_tic(&String::from_utf8_lossy(&read()?)).map(Imported::from),
+        "png" if settings.png_sequence => sequence::decode_png_sequence(path, palette, settings.quantize),
+        "png" => png::decode_png(&read()?, palette, settings.quantize).map(Imported::from),
+        "gif" => sequence::decode_gif(&read()?, palette, settings.quantize),
+        "piskel" => piskel::decode_piskel(&String::from_utf8_lossy(&read()?)),
+        "svg" => {
+            let palette = (settings.quantize != Quantize::Off).then_some(palette);
+            svg::decode_svg(&String::from_utf8_lossy(&read()?), settings.svg_scale, palette).map(Imported::from)
+        }
+        "tic" => return Err(FileError::Unsupported("Binary .tic carts aren't supported; save the cart as .lua in TIC-80".to_string())),
+        "" => return Err(FileError::Unsupported("Type the path of a file to import".to_string())),
+        other => return Err(FileError::Unsupported(format!("Unsupported file type .{}", other))),
+    };
+    decoded.map_err(|e| FileError::format(&settings.path, e))
+}
@@ -0,0 +1,54 @@
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::color::PICO8_PALETTE;
+
+/// The sprite sheet section of a .p8 cart: 128 lines of 128 hex digits, one per pixel
+pub const GFX_SECTION: &str = "__gfx__";
+pub const SHEET_SIZE: i32 = 128;
+
+/// Lines of a .p8 section, up to the next `__name__` header
+pub fn section_lines<'a>(text: &'a str, header: &str) -> Option<impl Iterator<Item = &'a str>> {
+    let mut lines = text.lines();
+    lines.by_ref().find(|line| line.trim() == header)?;
+    Some(lines.take_while(|line| !is_section_header(line)))
+}
+
+pub fn is_section_header(line: &str) -> bool {
+    let line = line.trim();
+    line.len() > 4 && line.starts_with("__") && line.ends_with("__")
+}
+
+/// Decode the __gfx__ sprite sheet; color 0 (black) is transparent, as in PICO-8's spr()
+pub fn decode_p8(text: &str) -> Result<CellGrid, String> {
+    let lines = section_lines(text, GFX_SECTION).ok_or("This cart has no __gfx__ section")?;
+    let mut cells = CellGrid::new();
+    for (y, line) in lines.take(SHEET_SIZE as usize).enumerate() {
+        for (x, digit) in line.trim().chars().take(SHEET_SIZE as usize).enumerate() {
+            let index = digit.to_digit(16).ok_or_else(|| format!("Bad pixel '{}' on gfx line {}", digit, y + 1))?;
+            if index != 0 {
+                cells.insert((x as i32, y as i32), Cell::with_color(PICO8_PALETTE[index as usize]));
+            }
+        }
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gfx_digits_are_palette_indices() {
+        let cells = decode_p8("pico-8 cartridge\n__gfx__\n0100\n000f\n__label__\n1111\n").unwrap();
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[&(1, 0)].color, PICO8_PALETTE[1]);
+        assert_eq!(cells[&(3, 1)].color, PICO8_PALETTE[15]);
+    }
+
+    #[test]
+    fn missing_or_malformed_gfx_are_errors() {
+        assert!(decode_p8("pico-8 cartridge\n__lua__\n").is_err());
+        assert!(decode_p8("__gfx__\n01g0\n").is_err());
+        // A sheet cut short just has fewer rows
+        assert_eq!(decode_p8("__gfx__\n01").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::{ImageFormat, RgbaImage};
+use serde_json::Value;
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::color::Rgba;
+use crate::import::png::MAX_IMAGE_SIDE;
+use crate::import::sequence::{check_frame_count, check_pixels};
+use crate::import::{Imported, FRAME_GAP};
+
+/// One layer's frames, each a width x height RGBA image
+struct Layer {
+    opacity: f32,
+    frames: Vec<RgbaImage>,
+}
+
+/// Decode a .piskel project: every frame with its layers flattened (bottom layer first,
+/// honoring layer opacity), laid out left to right; each frame of an animation becomes a group
+pub fn decode_piskel(text: &str) -> Result<Imported, String> {
+    let root: Value = serde_json::from_str(text).map_err(|e| format!("Not a Piskel file: {}", e))?;
+    let piskel = root.get("piskel").ok_or("Not a Piskel file (no \"piskel\" object)")?;
+    let size = |key: &str| piskel.get(key).and_then(Value::as_u64).filter(|&v| v > 0).map(|v| v as u32);
+    let (width, height) = size("width").zip(size("height")).ok_or("Piskel file has no width/height")?;
+    if width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+        return Err(format!("Image is {}x{}; the limit is {}x{}", width, height, MAX_IMAGE_SIDE, MAX_IMAGE_SIDE));
+    }
+
+    let layers = piskel
+        .get("layers")
+        .and_then(Value::as_array)
+        .ok_or("Piskel file has no layers")?
+        .iter()
+        .map(|layer| match layer {
+            // Layers are stored as JSON strings inside the JSON
+            Value::String(inner) => serde_json::from_str(inner).map_err(|e| format!("Bad layer: {}", e)),
+            other => Ok(other.clone()),
+        })
+        .collect::<Result<Vec<Value>, String>>()?;
+    // Every layer holds a full image per frame, so all of them count against the limits
+    let mut decoded_frames = 0;
+    for layer in &layers {
+        let count = frame_count(layer);
+        check_frame_count(count)?;
+        decoded_frames += count as u64;
+        check_pixels(decoded_frames, width, height)?;
+    }
+    let layers = layers.iter().map(|layer| decode_layer(layer, width, height)).collect::<Result<Vec<_>, String>>()?;
+
+    let frame_count = layers.iter().map(|layer| layer.frames.len()).max().unwrap_or(0);
+    let mut imported = Imported { cells: CellGrid::new(), groups: Vec::new() };
+    for frame in 0..frame_count {
+        let offset_x = frame as i32 * (width as i32 + FRAME_GAP);
+        let mut coords = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let color = layers
+                    .iter()
+                    .filter_map(|layer| Some((layer.frames.get(frame)?.get_pixel(x, y).0, layer.opacity)))
+                    .fold(None, |below: Option<[f32; 4]>, (px, opacity)| Some(over(below, px, opacity)));
+                if let Some([r, g, b, a]) = color.filter(|c| c[3] > 0.0) {
+                    let coord = (offset_x + x as i32, y as i32);
+                    let to_u8 = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+                    imported.cells.insert(coord, Cell::with_color(Rgba::new(to_u8(r), to_u8(g), to_u8(b), to_u8(a))));
+                    coords.push(coord);
+                }
+            }
+        }
+        if frame_count > 1 && !coords.is_empty() {
+            imported.groups.push((format!("Frame {}", frame + 1), coords));
+        }
+    }
+    Ok(imported)
+}
+
+/// Source-over of an 8-bit pixel (scaled by layer opacity) onto a straight-alpha color in 0..1
+fn over(below: Option<[f32; 4]>, px: [u8; 4], opacity: f32) -> [f32; 4] {
+    let [dr, dg, db, da] = below.unwrap_or([0.0; 4]);
+    let sa = px[3] as f32 / 255.0 * opacity;
+    let a = sa + da * (1.0 - sa);
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+    let mix = |s: u8, d: f32| (s as f32 / 255.0 * sa + d * da * (1.0 - sa)) / a;
+    [mix(px[0], dr), mix(px[1], dg), mix(px[2], db), a]
+}
+
+fn frame_count(layer: &Value) -> usize {
+    layer.get("frameCount").and_then(Value::as_u64).map_or(1, |count| usize::try_from(count).unwrap_or(usize::MAX))
+}
+
+/// A layer's frames from its PNG chunks; model version 2 places frames by `layout[column][row]`,
+/// version 1 has a single horizontal strip
+fn decode_layer(layer: &Value, width: u32, height: u32) -> Result<Layer, String> {
+    let opacity = layer.get("opacity").and_then(Value::as_f64).unwrap_or(1.0) as f32;
+    let frame_count = frame_count(layer);
+    let mut frames = vec![RgbaImage::new(width, height); frame_count];
+
+    let chunks: Vec<(Value, &str)> = match layer.get("chunks").and_then(Value::as_array) {
+        Some(chunks) => chunks
+            .iter()
+            .map(|chunk| {
+                let png = chunk.get("base64PNG").and_then(Value::as_str).ok_or("Layer chunk has no PNG")?;
+                Ok((chunk.get("layout").cloned().unwrap_or(Value::Null), png))
+            })
+            .collect::<Result<_, String>>()?,
+        None => {
+            let png = layer.get("base64PNG").and_then(Value::as_str).ok_or("Layer has no PNG data")?;
+            let strip = (0..frame_count).map(|i| Value::from(vec![i])).collect::<Vec<_>>();
+            vec![(Value::from(strip), png)]
+        }
+    };
+
+    for (layout, png) in chunks {
+        let sheet = decode_png(png)?;
+        let columns = layout.as_array().cloned().unwrap_or_default();
+        for (column, rows) in columns.iter().enumerate() {
+            for (row, index) in rows.as_array().into_iter().flatten().enumerate() {
+                let Some(frame) = index.as_u64().and_then(|i| frames.get_mut(i as usize)) else {
+                    continue;
+                };
+                // Wide math so a bogus layout can't wrap around into the sheet
+                let (sx, sy) = (column as u64 * width as u64, row as u64 * height as u64);
+                if sx + width as u64 <= sheet.width() as u64 && sy + height as u64 <= sheet.height() as u64 {
+                    *frame = image::imageops::crop_imm(&sheet, sx as u32, sy as u32, width, height).to_image();
+                }
+            }
+        }
+    }
+    Ok(Layer { opacity, frames })
+}
+
+fn decode_png(data_url: &str) -> Result<RgbaImage, String> {
+    let encoded = data_url.split_once("base64,").map_or(data_url, |(_, data)| data);
+    let png = BASE64.decode(encoded).map_err(|e| format!("Bad layer image: {}", e))?;
+    let image = image::load_from_memory_with_format(&png, ImageFormat::Png).map_err(|e| format!("Bad layer image: {}", e))?;
+    Ok(image.to_rgba8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A model-version-2 piskel of one layer whose frames sit side by side in `sheet`
+    fn piskel(width: u32, height: u32, frame_count: usize, sheet: &RgbaImage) -> String {
+        let mut png = Vec::new();
+        sheet.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+        let layout: Vec<Vec<usize>> = (0..frame_count.min(8)).map(|i| vec![i]).collect();
+        let layer = serde_json::json!({
+            "name": "Layer 1",
+            "opacity": 1.0,
+            "frameCount": frame_count,
+            "chunks": [{ "layout": layout, "base64PNG": format!("data:image/png;base64,{}", BASE64.encode(png)) }],
+        });
+        serde_json::json!({
+            "modelVersion": 2,
+            "piskel": { "width": width, "height": height, "layers": [layer.to_string()] },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn frames_become_groups_side_by_side() {
+        let mut sheet = RgbaImage::new(4, 2);
+        sheet.put_pixel(0, 0, image::Rgba([255, 0, 0, 255]));
+        sheet.put_pixel(3, 1, image::Rgba([0, 0, 255, 255]));
+        let imported = decode_piskel(&piskel(2, 2, 2, &sheet)).unwrap();
+        assert_eq!(imported.cells.len(), 2);
+        assert_eq!(imported.cells[&(0, 0)].color, Rgba::new(255, 0, 0, 255));
+        assert_eq!(imported.cells[&(2 + FRAME_GAP + 1, 1)].color, Rgba::new(0, 0, 255, 255));
+        assert_eq!(imported.groups.len(), 2);
+    }
+
+    #[test]
+    fn oversize_documents_are_refused_before_allocating() {
+        let sheet = RgbaImage::new(1, 1);
+        assert!(decode_piskel(&piskel(MAX_IMAGE_SIDE + 1, 1, 1, &sheet)).is_err());
+        assert!(decode_piskel(&piskel(1, 1, usize::MAX, &sheet)).is_err());
+        assert!(decode_piskel(&piskel(512, 512, 64, &sheet)).is_err());
+    }
+
+    #[test]
+    fn truncated_files_are_errors() {
+        let text = piskel(2, 2, 2, &RgbaImage::new(4, 2));
+        assert!(decode_piskel(&text[..text.len() / 2]).is_err());
+        assert!(decode_piskel(&text.replace("base64,", "base64,!")).is_err());
+    }
+}
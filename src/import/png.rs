@@ -0,0 +1,83 @@
+use image::{ImageFormat, RgbaImage};
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::color::Rgba;
+use crate::core::dither::{quantize, Quantize};
+
+/// Largest image side accepted; each pixel becomes a cell
+pub const MAX_IMAGE_SIDE: u32 = 512;
+/// Pixels at or below this alpha are left empty when quantizing to an opaque palette
+const QUANTIZE_ALPHA_CUTOFF: u8 = 127;
+
+/// Decode a PNG one pixel per cell, optionally fitted to `palette`
+pub fn decode_png(bytes: &[u8], palette: &[Rgba], mode: Quantize) -> Result<CellGrid, String> {
+    let image = image::load_from_memory_with_format(bytes, ImageFormat::Png)
+        .map_err(|e| format!("Not a PNG image: {}", e))?
+        .to_rgba8();
+    image_cells(&image, palette, mode)
+}
+
+/// One cell per pixel of a decoded image (animation frames included), optionally fitted to
+/// `palette`
+pub fn image_cells(image: &RgbaImage, palette: &[Rgba], mode: Quantize) -> Result<CellGrid, String> {
+    let (width, height) = image.dimensions();
+    if width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+        return Err(format!("Image is {}x{}; the limit is {}x{}", width, height, MAX_IMAGE_SIDE, MAX_IMAGE_SIDE));
+    }
+
+    let cutoff = if mode == Quantize::Off { 0 } else { QUANTIZE_ALPHA_CUTOFF };
+    let pixels: Vec<Option<Rgba>> = image
+        .pixels()
+        .map(|p| (p[3] > cutoff).then_some(Rgba::new(p[0], p[1], p[2], p[3])))
+        .collect();
+
+    Ok(quantize(&pixels, width as usize, palette, mode)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, color)| {
+            let coord = ((i % width as usize) as i32, (i / width as usize) as i32);
+            Some((coord, Cell::with_color(color?)))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn png(image: &RgbaImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn pixels_become_cells() {
+        let mut image = RgbaImage::new(2, 2);
+        image.put_pixel(1, 0, image::Rgba([10, 20, 30, 40]));
+        let cells = decode_png(&png(&image), &[], Quantize::Off).unwrap();
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[&(1, 0)].color, Rgba::new(10, 20, 30, 40));
+    }
+
+    #[test]
+    fn quantizing_snaps_to_the_palette_and_drops_faint_pixels() {
+        let mut image = RgbaImage::new(3, 1);
+        image.put_pixel(0, 0, image::Rgba([250, 10, 10, 255]));
+        image.put_pixel(1, 0, image::Rgba([10, 10, 240, 200]));
+        image.put_pixel(2, 0, image::Rgba([255, 0, 0, QUANTIZE_ALPHA_CUTOFF]));
+        let palette = [Rgba::rgb(255, 0, 0), Rgba::rgb(0, 0, 255)];
+        let cells = decode_png(&png(&image), &palette, Quantize::Nearest).unwrap();
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[&(0, 0)].color, palette[0]);
+        assert_eq!(cells[&(1, 0)].color, palette[1]);
+    }
+
+    #[test]
+    fn oversize_and_truncated_images_are_errors() {
+        let wide = png(&RgbaImage::new(MAX_IMAGE_SIDE + 1, 1));
+        assert!(decode_png(&wide, &[], Quantize::Off).is_err());
+        let bytes = png(&RgbaImage::new(4, 4));
+        assert!(decode_png(&bytes[..bytes.len() / 2], &[], Quantize::Off).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use image::codecs::gif::GifDecoder;
+use image::AnimationDecoder;
+use crate::core::cell::CellGrid;
+use crate::core::color::Rgba;
+use crate::core::dither::Quantize;
+use crate::import::png::{decode_png, image_cells};
+use crate::import::{Imported, FRAME_GAP};
+
+/// Most frames one animation may bring in
+const MAX_SEQUENCE_FRAMES: usize = 256;
+/// Most pixels over all frames together; each becomes a cell
+const MAX_SEQUENCE_PIXELS: u64 = 1 << 22;
+
+/// Decode an animated GIF, one frame per group; frames come composited (disposal applied),
+/// so each is the full picture shown at that point
+pub fn decode_gif(bytes: &[u8], palette: &[Rgba], mode: Quantize) -> Result<Imported, String> {
+    let decoder = GifDecoder::new(Cursor::new(bytes)).map_err(|e| format!("Not a GIF image: {}", e))?;
+    let mut frames = Vec::new();
+    let mut width = 0;
+    for frame in decoder.into_frames() {
+        let frame = frame.map_err(|e| format!("Bad GIF frame {}: {}", frames.len() + 1, e))?;
+        check_frame_count(frames.len() + 1)?;
+        let image = frame.buffer();
+        check_pixels(frames.len() as u64 + 1, image.width(), image.height())?;
+        width = width.max(image.width() as i32);
+        frames.push(image_cells(image, palette, mode)?);
+    }
+    Ok(lay_out_frames(frames, width))
+}
+
+/// Decode a numbered PNG sequence: `path` names any one file (e.g. walk_03.png), and every
+/// PNG beside it with the same name apart from the number is a frame, in numeric order
+pub fn decode_png_sequence(path: &Path, palette: &[Rgba], mode: Quantize) -> Result<Imported, String> {
+    let paths = sequence_paths(path)?;
+    check_frame_count(paths.len())?;
+    let mut frames = Vec::new();
+    let mut width = 0;
+    for (i, frame_path) in paths.iter().enumerate() {
+        let bytes = std::fs::read(frame_path).map_err(|e| format!("Can't read {}: {}", frame_path.display(), e))?;
+        let cells = decode_png(&bytes, palette, mode).map_err(|e| format!("{}: {}", frame_path.display(), e))?;
+        let size = cells.keys().fold((0, 0), |(w, h), &(x, y)| (w.max(x + 1), h.max(y + 1)));
+        check_pixels(i as u64 + 1, size.0 as u32, size.1 as u32)?;
+        width = width.max(size.0);
+        frames.push(cells);
+    }
+    Ok(lay_out_frames(frames, width))
+}
+
+/// Files of the sequence `path` belongs to, sorted by frame number
+fn sequence_paths(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let prefix = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+    if prefix.len() == stem.len() {
+        return Err(format!("{} has no frame number at the end of its name", path.display()));
+    }
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Can't list {}: {}", dir.display(), e))?;
+
+    let mut numbered: Vec<(u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let file = entry.ok()?.path();
+            let is_png = file.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("png"));
+            let digits = file.file_stem()?.to_str()?.strip_prefix(prefix)?;
+            let number = digits.bytes().all(|b| b.is_ascii_digit()).then(|| digits.parse().ok()).flatten()?;
+            is_png.then_some((number, file))
+        })
+        .collect();
+    numbered.sort();
+    Ok(numbered.into_iter().map(|(_, file)| file).collect())
+}
+
+pub fn check_frame_count(count: usize) -> Result<(), String> {
+    if count > MAX_SEQUENCE_FRAMES {
+        return Err(format!("More than {} frames", MAX_SEQUENCE_FRAMES));
+    }
+    Ok(())
+}
+
+pub fn check_pixels(frames: u64, width: u32, height: u32) -> Result<(), String> {
+    if frames * width as u64 * height as u64 > MAX_SEQUENCE_PIXELS {
+        return Err(format!("{} frames of {}x{} is too large to import", frames, width, height));
+    }
+    Ok(())
+}
+
+/// Frames left to right, `width` apart plus the gap; with more than one, each is a group in
+/// playback order
+fn lay_out_frames(frames: Vec<CellGrid>, width: i32) -> Imported {
+    let animated = frames.len() > 1;
+    let mut imported = Imported { cells: CellGrid::new(), groups: Vec::new() };
+    for (frame, cells) in frames.into_iter().enumerate() {
+        let offset_x = frame as i32 * (width + FRAME_GAP);
+        let mut coords: Vec<(i32, i32)> = cells.keys().map(|&(x, y)| (x + offset_x, y)).collect();
+        coords.sort();
+        imported.cells.extend(cells.into_iter().map(|((x, y), cell)| ((x + offset_x, y), cell)));
+        if animated && !coords.is_empty() {
+            imported.groups.push((format!("Frame {}", frame + 1), coords));
+        }
+    }
+    imported
+}
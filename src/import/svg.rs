@@ -0,0 +1,441 @@
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::color::{nearest_palette_index, Rgba};
+use crate::core::shapes::{polyline_cells, Curve};
+use crate::import::png::MAX_IMAGE_SIDE;
+
+/// Choices for the SVG scale row of the import dialog, in cells per SVG user unit
+pub const SVG_SCALES: [f32; 7] = [0.125, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// Curves and arcs are flattened into segments about this many cells long
+const FLATTEN_STEP: f32 = 2.0;
+
+/// Beyond this many cells from the origin an f32 coordinate can't tell neighbouring cells apart
+const MAX_COORDINATE: f32 = (1 << 24) as f32;
+
+/// A flattened subpath in SVG user units, and whether it closes back on itself
+type Polyline = (Vec<(f32, f32)>, bool);
+
+/// Rasterize the outlines of an SVG's `<path>`, `<polyline>`, `<polygon>` and `<line>`
+/// elements as one-cell strokes, `scale` cells per user unit. Each shape takes its stroke
+/// color (or its fill, when it has no stroke), snapped to `palette` when one is given;
+/// transforms and other elements are ignored
+pub fn decode_svg(text: &str, scale: f32, palette: Option<&[Rgba]>) -> Result<CellGrid, String> {
+    let mut shapes: Vec<(Rgba, Vec<Polyline>)> = Vec::new();
+    for (name, tag) in elements(text) {
+        let polylines = match name {
+            "path" => parse_path(attribute(tag, "d").unwrap_or_default(), scale)?,
+            "polyline" | "polygon" => {
+                let numbers = parse_numbers(attribute(tag, "points").unwrap_or_default(), false)?;
+                vec![(numbers.chunks_exact(2).map(|p| (p[0], p[1])).collect(), name == "polygon")]
+            }
+            "line" => {
+                let coord = |key: &str| attribute(tag, key).and_then(|v| v.trim().parse::<f32>().ok()).unwrap_or(0.0);
+                vec![(vec![(coord("x1"), coord("y1")), (coord("x2"), coord("y2"))], false)]
+            }
+            _ => continue,
+        };
+        let color = shape_color(tag);
+        let color = match palette.filter(|p| !p.is_empty()) {
+            Some(palette) => palette[nearest_palette_index(palette, color)],
+            None => color,
+        };
+        shapes.push((color, polylines));
+    }
+
+    check_extent(&shapes, scale)?;
+    let to_cell = |(x, y): (f32, f32)| ((x * scale).floor() as i32, (y * scale).floor() as i32);
+    let strokes: Vec<(Rgba, Vec<(i32, i32)>)> = shapes
+        .iter()
+        .flat_map(|(color, polylines)| {
+            polylines.iter().filter(|(points, _)| !points.is_empty()).map(move |(points, closed)| {
+                let mut cells: Vec<(i32, i32)> = points.iter().copied().map(to_cell).collect();
+                cells.dedup();
+                (*color, polyline_cells(&cells, *closed))
+            })
+        })
+        .collect();
+
+    // Importers place the top-left at (0, 0)
+    let min_x = strokes.iter().flat_map(|(_, cells)| cells.iter().map(|c| c.0)).min().ok_or("This SVG has no paths, polylines, polygons or lines")?;
+    let min_y = strokes.iter().flat_map(|(_, cells)| cells.iter().map(|c| c.1)).min().unwrap_or(0);
+    let mut grid = CellGrid::new();
+    for (color, cells) in strokes {
+        for (x, y) in cells {
+            grid.insert((x - min_x, y - min_y), Cell::with_color(color));
+        }
+    }
+    Ok(grid)
+}
+
+/// Refuse drawings that would rasterize to more than MAX_IMAGE_SIDE cells across, before
+/// any cells are made; a single far-off point would otherwise stretch a line over billions
+fn check_extent(shapes: &[(Rgba, Vec<Polyline>)], scale: f32) -> Result<(), String> {
+    let points = shapes.iter().flat_map(|(_, polylines)| polylines.iter().flat_map(|(points, _)| points.iter()));
+    let (mut min, mut max) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+    for &(x, y) in points {
+        let (x, y) = (x * scale, y * scale);
+        if !(x.abs() < MAX_COORDINATE && y.abs() < MAX_COORDINATE) {
+            return Err(format!("Coordinate ({}, {}) is out of range", x, y));
+        }
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    let (width, height) = (max.0.floor() - min.0.floor() + 1.0, max.1.floor() - min.1.floor() + 1.0);
+    if width > MAX_IMAGE_SIDE as f32 || height > MAX_IMAGE_SIDE as f32 {
+        return Err(format!("Drawing is {}x{} cells; the limit is {}x{}", width, height, MAX_IMAGE_SIDE, MAX_IMAGE_SIDE));
+    }
+    Ok(())
+}
+
+/// Start tags in document order as (element name, tag text)
+fn elements(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    text.split('<').skip(1).filter_map(|chunk| {
+        let tag = &chunk[..chunk.find('>')?];
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').next()?;
+        Some((name, tag))
+    })
+}
+
+/// Value of `name="..."` (or single-quoted) within a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(at) = rest.find(name) {
+        let before = rest[..at].chars().next_back();
+        let after = rest[at + name.len()..].trim_start();
+        rest = &rest[at + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(after) = after.strip_prefix('=') else { continue };
+        let after = after.trim_start();
+        let quote = after.chars().next().filter(|&q| q == '"' || q == '\'')?;
+        let value = &after[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+/// `stroke`, then `fill`, as an attribute or in `style`; black when neither is a hex color
+fn shape_color(tag: &str) -> Rgba {
+    let style = attribute(tag, "style").unwrap_or_default();
+    let from_style = |key: &str| {
+        style.split(';').find_map(|rule| {
+            let (k, v) = rule.split_once(':')?;
+            (k.trim() == key).then(|| v.trim())
+        })
+    };
+    ["stroke", "fill"]
+        .iter()
+        .filter_map(|key| from_style(key).or_else(|| attribute(tag, key)))
+        .find_map(parse_color)
+        .unwrap_or(Rgba::rgb(0, 0, 0))
+}
+
+/// `#rgb` or `#rrggbb`
+fn parse_color(value: &str) -> Option<Rgba> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() == 3 && hex.is_ascii() {
+        let doubled: String = hex.chars().flat_map(|c| [c, c]).collect();
+        return Rgba::from_hex(&doubled);
+    }
+    (hex.len() == 6).then(|| Rgba::from_hex(hex)).flatten()
+}
+
+/// Numbers separated by commas and/or whitespace, where a sign or a second decimal point
+/// also starts a new number ("10-5.5.5" is 10, -5.5, .5). With `arc`, the 4th and 5th of
+/// every seven are flags, a single 0 or 1 that needs no separator ("5 5 0 011 1")
+fn parse_numbers(text: &str, arc: bool) -> Result<Vec<f32>, String> {
+    let mut numbers = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() || c == ',' {
+            chars.next();
+            continue;
+        }
+        if arc && matches!(numbers.len() % 7, 3 | 4) {
+            let flag = match c {
+                '0' => 0.0,
+                '1' => 1.0,
+                _ => return Err(format!("Arc flag must be 0 or 1, not '{}'", c)),
+            };
+            numbers.push(flag);
+            chars.next();
+            continue;
+        }
+        let mut end = start;
+        let (mut seen_dot, mut seen_exponent, mut first) = (false, false, true);
+        while let Some(&(i, c)) = chars.peek() {
+            let accept = match c {
+                '0'..='9' => true,
+                '+' | '-' => first || text[..i].ends_with(['e', 'E']),
+                '.' if !seen_dot && !seen_exponent => {
+                    seen_dot = true;
+                    true
+                }
+                'e' | 'E' if !seen_exponent && !first => {
+                    seen_exponent = true;
+                    true
+                }
+                _ => false,
+            };
+            if !accept {
+                break;
+            }
+            first = false;
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        if end == start {
+            return Err(format!("Unexpected '{}' in SVG coordinates", c));
+        }
+        numbers.push(text[start..end].parse::<f32>().map_err(|_| format!("Bad number '{}' in SVG coordinates", &text[start..end]))?);
+    }
+    Ok(numbers)
+}
+
+/// Flatten path data into polylines; `scale` only sets how finely curves are sampled
+fn parse_path(d: &str, scale: f32) -> Result<Vec<Polyline>, String> {
+    let mut polylines: Vec<Polyline> = Vec::new();
+    let mut current: Vec<(f32, f32)> = Vec::new();
+    let (mut pen, mut start) = ((0.0f32, 0.0f32), (0.0f32, 0.0f32));
+    // Reflected control point for S/T, and which command produced it
+    let mut last_control: Option<((f32, f32), char)> = None;
+
+    // Every letter but the exponent marker starts a command
+    let bounds: Vec<usize> = d
+        .match_indices(|c: char| c.is_ascii_alphabetic() && !matches!(c, 'e' | 'E'))
+        .map(|(i, _)| i)
+        .chain(std::iter::once(d.len()))
+        .collect();
+    for window in bounds.windows(2) {
+        let command = d[window[0]..].chars().next().unwrap_or('M');
+        let args = parse_numbers(&d[window[0] + 1..window[1]], matches!(command, 'A' | 'a'))?;
+        let relative = command.is_ascii_lowercase();
+        let offset = |p: (f32, f32), origin: (f32, f32)| if relative { (p.0 + origin.0, p.1 + origin.1) } else { p };
+        let arity = match command.to_ascii_uppercase() {
+            'M' | 'L' | 'T' => 2,
+            'H' | 'V' => 1,
+            'C' => 6,
+            'S' | 'Q' => 4,
+            'A' => 7,
+            'Z' => 0,
+            other => return Err(format!("Unsupported path command '{}'", other)),
+        };
+
+        if arity == 0 {
+            if !current.is_empty() {
+                polylines.push((std::mem::take(&mut current), true));
+            }
+            pen = start;
+            last_control = None;
+            continue;
+        }
+        if args.len() % arity != 0 || args.is_empty() {
+            return Err(format!("Path command '{}' needs its coordinates in groups of {}", command, arity));
+        }
+
+        for (index, a) in args.chunks_exact(arity).enumerate() {
+            let upper = command.to_ascii_uppercase();
+            let mut control = None;
+            if current.is_empty() {
+                current.push(pen);
+            }
+            match upper {
+                // Pairs after the first moveto are implicit linetos
+                'M' if index == 0 => {
+                    if current.len() > 1 {
+                        polylines.push((std::mem::take(&mut current), false));
+                    }
+                    pen = offset((a[0], a[1]), pen);
+                    start = pen;
+                    current = vec![pen];
+                }
+                'M' | 'L' => {
+                    pen = offset((a[0], a[1]), pen);
+                    current.push(pen);
+                }
+                'H' => {
+                    pen = (if relative { pen.0 + a[0] } else { a[0] }, pen.1);
+                    current.push(pen);
+                }
+                'V' => {
+                    pen = (pen.0, if relative { pen.1 + a[0] } else { a[0] });
+                    current.push(pen);
+                }
+                'C' | 'S' => {
+                    let (c1, c2, end) = if upper == 'C' {
+                        (offset((a[0], a[1]), pen), offset((a[2], a[3]), pen), offset((a[4], a[5]), pen))
+                    } else {
+                        (reflect(pen, last_control, 'C'), offset((a[0], a[1]), pen), offset((a[2], a[3]), pen))
+                    };
+                    flatten(&mut current, Curve { points: [pen, c1, c2, end], cubic: true, controls_moved: true }, scale);
+                    control = Some((c2, 'C'));
+                    pen = end;
+                }
+                'Q' | 'T' => {
+                    let (c, end) = if upper == 'Q' {
+                        (offset((a[0], a[1]), pen), offset((a[2], a[3]), pen))
+                    } else {
+                        (reflect(pen, last_control, 'Q'), offset((a[0], a[1]), pen))
+                    };
+                    flatten(&mut current, Curve { points: [pen, c, c, end], cubic: false, controls_moved: true }, scale);
+                    control = Some((c, 'Q'));
+                    pen = end;
+                }
+                _ => {
+                    let end = offset((a[5], a[6]), pen);
+                    arc(&mut current, pen, end, &a[..5], scale);
+                    pen = end;
+                }
+            }
+            last_control = control;
+        }
+    }
+    if current.len() > 1 {
+        polylines.push((current, false));
+    }
+    Ok(polylines)
+}
+
+/// Control point implied by S or T: the previous one mirrored through the pen, or the pen
+/// itself when the previous segment wasn't the same kind of curve
+fn reflect(pen: (f32, f32), last: Option<((f32, f32), char)>, kind: char) -> (f32, f32) {
+    match last {
+        Some((c, k)) if k == kind => (2.0 * pen.0 - c.0, 2.0 * pen.1 - c.1),
+        _ => pen,
+    }
+}
+
+/// Append points along a curve, skipping its start (already in the polyline)
+fn flatten(points: &mut Vec<(f32, f32)>, curve: Curve, scale: f32) {
+    let [p0, p1, p2, p3] = curve.points;
+    let hull = [(p0, p1), (p1, p2), (p2, p3)].iter().map(|&(a, b)| distance(a, b)).sum::<f32>() * scale;
+    let steps = ((hull / FLATTEN_STEP).ceil() as u32).clamp(1, 256);
+    points.extend((1..=steps).map(|i| curve.sample(i as f32 / steps as f32)));
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+/// Append points along an elliptical arc, converted from SVG's endpoint form to a center
+/// and angle sweep (SVG spec, appendix B.2.4). `params` are the arc command's radii,
+/// rotation, large-arc flag and sweep flag
+fn arc(points: &mut Vec<(f32, f32)>, from: (f32, f32), to: (f32, f32), params: &[f32], scale: f32) {
+    let (mut rx, mut ry) = (params[0].abs(), params[1].abs());
+    let (rotation, large, sweep) = (params[2], params[3] != 0.0, params[4] != 0.0);
+    if rx == 0.0 || ry == 0.0 || from == to {
+        points.push(to);
+        return;
+    }
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let (hx, hy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let (x1, y1) = (cos * hx + sin * hy, -sin * hx + cos * hy);
+
+    // Radii too small to reach the endpoint are scaled up until they just do
+    let fit = (x1 / rx).powi(2) + (y1 / ry).powi(2);
+    if fit > 1.0 {
+        rx *= fit.sqrt();
+        ry *= fit.sqrt();
+    }
+    let numerator = (rx * ry).powi(2) - (rx * y1).powi(2) - (ry * x1).powi(2);
+    let denominator = (rx * y1).powi(2) + (ry * x1).powi(2);
+    let mut factor = (numerator / denominator).max(0.0).sqrt();
+    if large == sweep {
+        factor = -factor;
+    }
+    let (cx1, cy1) = (factor * rx * y1 / ry, -factor * ry * x1 / rx);
+    let center = (cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0, sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0);
+
+    let angle = |ux: f32, uy: f32| uy.atan2(ux);
+    let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut delta = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - start;
+    let tau = std::f32::consts::TAU;
+    if sweep && delta < 0.0 {
+        delta += tau;
+    } else if !sweep && delta > 0.0 {
+        delta -= tau;
+    }
+
+    let length = delta.abs() * rx.max(ry) * scale;
+    let steps = ((length / FLATTEN_STEP).ceil() as u32).clamp(1, 256);
+    points.extend((1..=steps).map(|i| {
+        let theta = start + delta * i as f32 / steps as f32;
+        let (px, py) = (rx * theta.cos(), ry * theta.sin());
+        (cos * px - sin * py + center.0, sin * px + cos * py + center.1)
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn svg(body: &str) -> String {
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\">{}</svg>", body)
+    }
+
+    fn path(d: &str) -> Vec<Polyline> {
+        parse_path(d, 1.0).unwrap()
+    }
+
+    #[test]
+    fn numbers_split_on_signs_and_second_dots() {
+        assert_eq!(parse_numbers("10-5.5.5", false).unwrap(), vec![10.0, -5.5, 0.5]);
+        assert_eq!(parse_numbers("1e2,-3E-1 4", false).unwrap(), vec![100.0, -0.3, 4.0]);
+    }
+
+    #[test]
+    fn arc_flags_are_single_digits() {
+        assert_eq!(parse_numbers("5 5 0 011 1", true).unwrap(), vec![5.0, 5.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        assert_eq!(parse_numbers("5,5,30,1,0,2,3 5 5 0 10-1-1", true).unwrap(), vec![5.0, 5.0, 30.0, 1.0, 0.0, 2.0, 3.0, 5.0, 5.0, 0.0, 1.0, 0.0, -1.0, -1.0]);
+        assert!(parse_numbers("5 5 0 2 1 1 1", true).is_err());
+        assert_eq!(path("M0 0a5 5 0 011 1"), path("M0 0a5 5 0 0 1 1 1"));
+    }
+
+    #[test]
+    fn relative_commands_follow_the_pen() {
+        assert_eq!(path("m10 10 l5 0 l0 5 h-5 z"), vec![(vec![(10.0, 10.0), (15.0, 10.0), (15.0, 15.0), (10.0, 15.0)], true)]);
+        assert_eq!(path("M10 10 v5 H0 V0"), vec![(vec![(10.0, 10.0), (10.0, 15.0), (0.0, 15.0), (0.0, 0.0)], false)]);
+    }
+
+    #[test]
+    fn pairs_after_moveto_are_linetos() {
+        assert_eq!(path("M0 0 10 0 10 10"), vec![(vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)], false)]);
+        assert_eq!(path("m1 1 2 0 0 2"), vec![(vec![(1.0, 1.0), (3.0, 1.0), (3.0, 3.0)], false)]);
+        assert_eq!(path("M0 0 L1 0 M5 5 6 5").len(), 2);
+    }
+
+    #[test]
+    fn smooth_curves_reflect_the_previous_control_point() {
+        assert_eq!(path("M0 0 C0 10 10 10 10 0 S20 -10 20 0"), path("M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0"));
+        assert_eq!(path("M0 0 Q5 10 10 0 T20 0"), path("M0 0 Q5 10 10 0 Q15 -10 20 0"));
+        assert_eq!(path("m0 0 q5 10 10 0 t10 0"), path("M0 0 Q5 10 10 0 Q15 -10 20 0"));
+        // After a line there is nothing to reflect, so the pen is the control point
+        assert_eq!(path("M0 0 L10 0 S20 10 20 0"), path("M0 0 L10 0 C10 0 20 10 20 0"));
+        assert_eq!(path("M0 0 L10 0 T20 0"), path("M0 0 L10 0 Q10 0 20 0"));
+    }
+
+    #[test]
+    fn arcs_follow_their_sweep_flag() {
+        for (sweep, side) in [(1.0, -1.0), (0.0, 1.0)] {
+            let d = format!("M0 0 A5 5 0 0 {} 10 0", sweep);
+            let points = &path(&d)[0].0;
+            assert!(distance(points[points.len() - 1], (10.0, 0.0)) < 1e-3);
+            for &(x, y) in points {
+                assert!((distance((x, y), (5.0, 0.0)) - 5.0).abs() < 1e-3, "{:?} is off the circle", (x, y));
+                assert!(y * side >= -1e-3, "{} went the wrong way", d);
+            }
+        }
+    }
+
+    #[test]
+    fn oversize_drawings_are_refused() {
+        assert!(decode_svg(&svg(r#"<line x1="0" y1="0" x2="511" y2="0"/>"#), 1.0, None).is_ok());
+        assert!(decode_svg(&svg(r#"<line x1="0" y1="0" x2="512" y2="0"/>"#), 1.0, None).is_err());
+        assert!(decode_svg(&svg(r#"<line x1="0" y1="0" x2="1e9" y2="1e9"/>"#), 1.0, None).is_err());
+        assert!(decode_svg(&svg(r#"<path d="M0 0 L300 0"/>"#), 2.0, None).is_err());
+        // Far from the origin but small is still out of f32's reach
+        assert!(decode_svg(&svg(r#"<polyline points="3e38 0 3e38 1"/>"#), 8.0, None).is_err());
+        assert!(decode_svg(&svg(r#"<line x1="NaN" y1="0" x2="1" y2="0"/>"#), 1.0, None).is_err());
+    }
+}
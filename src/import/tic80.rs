@@ -0,0 +1,104 @@
+use crate::core::cell::{Cell, CellGrid};
+use crate::core::color::{Rgba, SWEETIE16_PALETTE};
+
+const TILE_SIZE: i32 = 8;
+/// Tiles per row of the 128x128 sheet
+pub const SHEET_TILES: i32 = 16;
+
+/// Lines of a `-- <NAME>` ... `-- </NAME>` block in a TIC-80 text cart, without the `-- ` prefix
+pub fn block_lines<'a>(text: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let open = format!("-- <{}>", name);
+    let close = format!("-- </{}>", name);
+    let mut lines = text.lines();
+    lines.by_ref().find(|line| line.trim() == open)?;
+    Some(
+        lines
+            .take_while(|line| line.trim() != close)
+            .map(|line| line.trim().trim_start_matches("--").trim())
+            .collect(),
+    )
+}
+
+/// The cart's palette block (48 hex bytes), or Sweetie 16 when it is missing or malformed
+pub fn cart_palette(text: &str) -> Vec<Rgba> {
+    let parsed = block_lines(text, "PALETTE")
+        .and_then(|lines| lines.first().map(|line| line.trim_start_matches("000:").to_string()))
+        .map(|hex| (0..16).filter_map(|i| hex.get(i * 6..i * 6 + 6).and_then(Rgba::from_hex)).collect::<Vec<_>>());
+    match parsed {
+        Some(palette) if palette.len() == 16 => palette,
+        _ => SWEETIE16_PALETTE.to_vec(),
+    }
+}
+
+/// Decode the TILES (top sheet) and SPRITES (below it) blocks of a .lua cart; each line is
+/// `NNN:` plus 64 hex digits, one per pixel of tile NNN. Color 0 is transparent
+pub fn decode_tic(text: &str) -> Result<CellGrid, String> {
+    let palette = cart_palette(text);
+    let mut cells = CellGrid::new();
+    let mut found = false;
+    for (block, sheet_y) in [("TILES", 0), ("SPRITES", SHEET_TILES * TILE_SIZE)] {
+        let Some(lines) = block_lines(text, block) else {
+            continue;
+        };
+        found = true;
+        for line in lines {
+            let (index, pixels) = line.split_once(':').ok_or_else(|| format!("Bad {} line '{}'", block, line))?;
+            let tile: i32 = index.trim().parse().map_err(|_| format!("Bad tile number '{}'", index))?;
+            let origin = ((tile % SHEET_TILES) * TILE_SIZE, sheet_y + (tile / SHEET_TILES) * TILE_SIZE);
+            for (i, digit) in pixels.trim().chars().take(64).enumerate() {
+                let color = digit.to_digit(16).ok_or_else(|| format!("Bad pixel '{}' in tile {}", digit, tile))?;
+                if color != 0 {
+                    let (x, y) = (i as i32 % TILE_SIZE, i as i32 / TILE_SIZE);
+                    cells.insert((origin.0 + x, origin.1 + y), Cell::with_color(palette[color as usize]));
+                }
+            }
+        }
+    }
+    if !found {
+        return Err("This cart has no TILES or SPRITES block".to_string());
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cart(palette: &str) -> String {
+        format!("-- <PALETTE>\n-- 000:{}\n-- </PALETTE>\n", palette)
+    }
+
+    #[test]
+    fn reads_the_palette_block() {
+        let palette = cart_palette(&cart(&"ff0000".repeat(16)));
+        assert_eq!(palette.len(), 16);
+        assert_eq!(palette[15], Rgba::from_hex("ff0000").unwrap());
+    }
+
+    #[test]
+    fn malformed_palettes_fall_back_to_sweetie_16() {
+        // Multi-byte characters put byte offsets off char boundaries
+        assert_eq!(cart_palette(&cart(&"é".repeat(48))), SWEETIE16_PALETTE.to_vec());
+        assert_eq!(cart_palette(&cart("ff0000")), SWEETIE16_PALETTE.to_vec());
+    }
+
+    #[test]
+    fn tiles_fill_the_top_sheet_and_sprites_the_bottom() {
+        let pixels = format!("2{}", "0".repeat(62)) + "3";
+        let text = format!("-- <TILES>\n-- 001:{0}\n-- </TILES>\n-- <SPRITES>\n-- 000:{0}\n-- </SPRITES>\n", pixels);
+        let cells = decode_tic(&text).unwrap();
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells[&(8, 0)].color, SWEETIE16_PALETTE[2]);
+        assert_eq!(cells[&(15, 7)].color, SWEETIE16_PALETTE[3]);
+        assert_eq!(cells[&(0, 128)].color, SWEETIE16_PALETTE[2]);
+    }
+
+    #[test]
+    fn malformed_carts_are_errors() {
+        assert!(decode_tic("-- <CODE>\n-- </CODE>\n").is_err());
+        assert!(decode_tic("-- <TILES>\n-- 001 12\n-- </TILES>\n").is_err());
+        assert!(decode_tic("-- <TILES>\n-- 001:1x\n-- </TILES>\n").is_err());
+        // A truncated tile line just leaves the rest of the tile empty
+        assert_eq!(decode_tic("-- <TILES>\n-- 000:11").unwrap().len(), 2);
+    }
+}
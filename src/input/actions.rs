@@ -0,0 +1,855 @@
+use macroquad::prelude::*;
+use crate::state::{ApplicationState, Mode};
+use crate::rendering::CanvasRenderer;
+use super::dispatcher::{redo_last, undo_last};
+use super::jobs::start_save;
+use super::diff::{take_diff_snapshot, toggle_frame_diff, toggle_saved_file_diff, toggle_snapshot_diff, DiffSource, DiffView};
+use super::autotile::set_autotile_template;
+use super::generate::start_generator;
+use crate::core::generate::Generator;
+use super::symmetry::{center_symmetry_on_view, cycle_symmetry_segments, toggle_symmetry_axis};
+use crate::project::format::{PROJECT_EXTENSION, TEXT_PROJECT_EXTENSION};
+use crate::project::is_text_project;
+use crate::ui::file_picker::FilePicker;
+use crate::ui::focus;
+
+/// Every user-facing command, shared by the keymap and the menu bar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    NewDocument,
+    OpenProject,
+    OpenRecent,
+    RestoreBackup,
+    SaveProject,
+    SaveProjectAs,
+    ToggleTextFormat,
+    ToggleSaveHistory,
+    ToggleActionLog,
+    CycleBackupInterval,
+    CycleBackupCount,
+    ExportPng,
+    QuickExport,
+    ExportGif,
+    ExportGroups,
+    ExportSpriteSheet,
+    Import,
+    DocumentInfo,
+    Undo,
+    Redo,
+    RecenterDocument,
+    ShiftDocument,
+    ToggleDocumentBounds,
+    TrimToBounds,
+    FindStrays,
+    Copy,
+    Cut,
+    Paste,
+    DeleteSelection,
+    FillSelection,
+    FillSelectionRect,
+    GroupSelection,
+    ExtractSelection,
+    ToggleQuickMask,
+    ToggleAlphaLock,
+    SaveSelection,
+    ToggleSavedSelections,
+    CycleFeather,
+    PlayFrames,
+    ToggleTimeline,
+    TransformSelection,
+    Deselect,
+    ToolPaint,
+    ToolErase,
+    ToolSelect,
+    ToolPan,
+    ToolSpray,
+    ToolLine,
+    ToolRectangle,
+    ToggleRectangleFill,
+    ToolPen,
+    TogglePenFill,
+    ToolCurve,
+    ToolFill,
+    ToggleFillGlobal,
+    ToolDither,
+    ToolAutotile,
+    SetAutotileTemplate,
+    GenerateLife,
+    GenerateMaze,
+    GenerateNoise,
+    GenerateVoronoi,
+    ToggleCurveCubic,
+    CurveStepsDown,
+    CurveStepsUp,
+    SwapColors,
+    BrushSmaller,
+    BrushLarger,
+    DensityDown,
+    DensityUp,
+    SymmetryVertical,
+    SymmetryHorizontal,
+    SymmetryDiagonal,
+    SymmetryAntiDiagonal,
+    CycleSymmetrySegments,
+    CenterSymmetry,
+    TogglePalette,
+    ToggleHarmony,
+    ZoomIn,
+    ZoomOut,
+    ResetView,
+    ReturnToDrawing,
+    GoToCell,
+    RotateClockwise,
+    RotateCounterClockwise,
+    FlipView,
+    CycleCellAspect,
+    TogglePreview,
+    ToggleMirror,
+    ToggleCrosshair,
+    TogglePrecisionCursor,
+    ToggleViewClamp,
+    SwapMouseButtons,
+    InvertWheelZoom,
+    CycleZoomStep,
+    CanvasSettings,
+    CycleTheme,
+    ToggleHud,
+    ToggleHudCompact,
+    CycleHudCorner,
+    ToggleBudget,
+    CycleBudgetSize,
+    CycleBudgetColors,
+    TakeDiffSnapshot,
+    DiffSnapshot,
+    DiffSavedFile,
+    DiffFrames,
+    JoinSession,
+    LeaveSession,
+    OpenChat,
+    LockRegion,
+    UnlockRegion,
+    ToggleHelp,
+    ToggleConsole,
+    About,
+}
+
+/// A key plus the exact modifier state it needs (Ctrl also matches Cmd)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl KeyBinding {
+    pub const fn plain(key: KeyCode) -> Self {
+        Self { key, ctrl: false, shift: false }
+    }
+
+    pub const fn shift(key: KeyCode) -> Self {
+        Self { key, ctrl: false, shift: true }
+    }
+
+    pub const fn ctrl(key: KeyCode) -> Self {
+        Self { key, ctrl: true, shift: false }
+    }
+
+    pub const fn ctrl_shift(key: KeyCode) -> Self {
+        Self { key, ctrl: true, shift: true }
+    }
+
+    /// Pressed this frame with exactly these modifiers, so Ctrl+E never also fires E
+    pub fn is_pressed(&self) -> bool {
+        is_key_pressed(self.key) && ctrl_or_cmd() == self.ctrl && shift_down() == self.shift
+    }
+
+    /// Human-readable form, e.g. "Ctrl+Shift+O"
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.ctrl {
+            label.push_str("Ctrl+");
+        }
+        if self.shift {
+            label.push_str("Shift+");
+        }
+        label.push_str(&key_name(self.key));
+        label
+    }
+}
+
+/// The default keymap (workspace presets can replace it); the first binding of an action is
+/// the one menus display
+pub const KEYMAP: &[(KeyBinding, Action)] = &[
+    (KeyBinding::ctrl(KeyCode::N), Action::NewDocument),
+    (KeyBinding::ctrl(KeyCode::O), Action::OpenProject),
+    (KeyBinding::ctrl_shift(KeyCode::O), Action::OpenRecent),
+    (KeyBinding::ctrl(KeyCode::S), Action::SaveProject),
+    (KeyBinding::ctrl_shift(KeyCode::S), Action::SaveProjectAs),
+    (KeyBinding::ctrl(KeyCode::E), Action::ExportPng),
+    (KeyBinding::ctrl_shift(KeyCode::E), Action::QuickExport),
+    (KeyBinding::ctrl(KeyCode::I), Action::Import),
+    (KeyBinding::ctrl(KeyCode::Z), Action::Undo),
+    (KeyBinding::ctrl_shift(KeyCode::Z), Action::Redo),
+    (KeyBinding::ctrl(KeyCode::Y), Action::Redo),
+    (KeyBinding::ctrl(KeyCode::C), Action::Copy),
+    (KeyBinding::ctrl(KeyCode::X), Action::Cut),
+    (KeyBinding::ctrl(KeyCode::V), Action::Paste),
+    (KeyBinding::plain(KeyCode::Delete), Action::DeleteSelection),
+    (KeyBinding::plain(KeyCode::Backspace), Action::DeleteSelection),
+    (KeyBinding::ctrl(KeyCode::D), Action::Deselect),
+    (KeyBinding::ctrl(KeyCode::G), Action::GroupSelection),
+    (KeyBinding::ctrl_shift(KeyCode::G), Action::PlayFrames),
+    (KeyBinding::ctrl(KeyCode::T), Action::TransformSelection),
+    (KeyBinding::plain(KeyCode::B), Action::ToolPaint),
+    (KeyBinding::plain(KeyCode::E), Action::ToolErase),
+    (KeyBinding::plain(KeyCode::V), Action::ToolSelect),
+    (KeyBinding::plain(KeyCode::H), Action::ToolPan),
+    (KeyBinding::plain(KeyCode::A), Action::ToolSpray),
+    (KeyBinding::plain(KeyCode::L), Action::ToolLine),
+    (KeyBinding::plain(KeyCode::S), Action::ToolRectangle),
+    (KeyBinding::plain(KeyCode::N), Action::ToolPen),
+    (KeyBinding::plain(KeyCode::C), Action::ToolCurve),
+    (KeyBinding::plain(KeyCode::G), Action::ToolFill),
+    (KeyBinding::plain(KeyCode::D), Action::ToolDither),
+    (KeyBinding::plain(KeyCode::U), Action::ToolAutotile),
+    (KeyBinding::plain(KeyCode::Comma), Action::CurveStepsDown),
+    (KeyBinding::plain(KeyCode::Period), Action::CurveStepsUp),
+    (KeyBinding::plain(KeyCode::X), Action::SwapColors),
+    (KeyBinding::plain(KeyCode::LeftBracket), Action::BrushSmaller),
+    (KeyBinding::plain(KeyCode::RightBracket), Action::BrushLarger),
+    (KeyBinding::shift(KeyCode::LeftBracket), Action::DensityDown),
+    (KeyBinding::shift(KeyCode::RightBracket), Action::DensityUp),
+    (KeyBinding::ctrl(KeyCode::Equal), Action::ZoomIn),
+    (KeyBinding::ctrl(KeyCode::Minus), Action::ZoomOut),
+    (KeyBinding::ctrl(KeyCode::Key0), Action::ResetView),
+    (KeyBinding::plain(KeyCode::F), Action::ReturnToDrawing),
+    (KeyBinding::ctrl(KeyCode::J), Action::GoToCell),
+    (KeyBinding::plain(KeyCode::R), Action::RotateClockwise),
+    (KeyBinding::shift(KeyCode::R), Action::RotateCounterClockwise),
+    (KeyBinding::plain(KeyCode::M), Action::FlipView),
+    (KeyBinding::plain(KeyCode::F6), Action::CycleCellAspect),
+    (KeyBinding::plain(KeyCode::P), Action::TogglePreview),
+    (KeyBinding::plain(KeyCode::F9), Action::ToggleMirror),
+    (KeyBinding::plain(KeyCode::F1), Action::ToggleHelp),
+    (KeyBinding::plain(KeyCode::F2), Action::ToggleHud),
+    (KeyBinding::plain(KeyCode::GraveAccent), Action::ToggleConsole),
+    (KeyBinding::plain(KeyCode::T), Action::OpenChat),
+    (KeyBinding::plain(KeyCode::Q), Action::ToggleQuickMask),
+    (KeyBinding::shift(KeyCode::A), Action::ToggleAlphaLock),
+];
+
+/// Bindings that are held rather than pressed, so they live outside KEYMAP; listed for the help overlay
+pub const HELD_BINDINGS: &[(&str, &str)] = &[
+    ("Space (hold)", "Pan over the current tool"),
+    ("Middle drag", "Pan"),
+    ("Wheel", "Zoom around cursor"),
+    ("Alt+Wheel", "Rotate view"),
+    ("Shift+drag (Erase)", "Erase a rectangle"),
+    ("Right click swatch", "Set secondary color"),
+    ("Tab / Shift+Tab", "Focus next / previous button"),
+    ("Arrows, Enter", "Move focus, press the focused button"),
+];
+
+impl Action {
+    /// Every action, in declaration order
+    pub const ALL: &'static [Action] = &[
+        Action::NewDocument,
+        Action::OpenProject,
+        Action::OpenRecent,
+        Action::RestoreBackup,
+        Action::SaveProject,
+        Action::SaveProjectAs,
+        Action::ToggleTextFormat,
+        Action::ToggleSaveHistory,
+        Action::ToggleActionLog,
+        Action::CycleBackupInterval,
+        Action::CycleBackupCount,
+        Action::ExportPng,
+        Action::QuickExport,
+        Action::ExportGif,
+        Action::ExportGroups,
+        Action::ExportSpriteSheet,
+        Action::Import,
+        Action::DocumentInfo,
+        Action::Undo,
+        Action::Redo,
+        Action::RecenterDocument,
+        Action::ShiftDocument,
+        Action::ToggleDocumentBounds,
+        Action::TrimToBounds,
+        Action::FindStrays,
+        Action::Copy,
+        Action::Cut,
+        Action::Paste,
+        Action::DeleteSelection,
+        Action::FillSelection,
+        Action::FillSelectionRect,
+        Action::GroupSelection,
+        Action::ExtractSelection,
+        Action::ToggleQuickMask,
+        Action::ToggleAlphaLock,
+        Action::SaveSelection,
+        Action::ToggleSavedSelections,
+        Action::CycleFeather,
+        Action::PlayFrames,
+        Action::ToggleTimeline,
+        Action::TransformSelection,
+        Action::Deselect,
+        Action::ToolPaint,
+        Action::ToolErase,
+        Action::ToolSelect,
+        Action::ToolPan,
+        Action::ToolSpray,
+        Action::ToolLine,
+        Action::ToolRectangle,
+        Action::ToggleRectangleFill,
+        Action::ToolPen,
+        Action::TogglePenFill,
+        Action::ToolCurve,
+        Action::ToolFill,
+        Action::ToggleFillGlobal,
+        Action::ToolDither,
+        Action::ToolAutotile,
+        Action::SetAutotileTemplate,
+        Action::GenerateLife,
+        Action::GenerateMaze,
+        Action::GenerateNoise,
+        Action::GenerateVoronoi,
+        Action::ToggleCurveCubic,
+        Action::CurveStepsDown,
+        Action::CurveStepsUp,
+        Action::SwapColors,
+        Action::BrushSmaller,
+        Action::BrushLarger,
+        Action::DensityDown,
+        Action::DensityUp,
+        Action::SymmetryVertical,
+        Action::SymmetryHorizontal,
+        Action::SymmetryDiagonal,
+        Action::SymmetryAntiDiagonal,
+        Action::CycleSymmetrySegments,
+        Action::CenterSymmetry,
+        Action::TogglePalette,
+        Action::ToggleHarmony,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ResetView,
+        Action::ReturnToDrawing,
+        Action::GoToCell,
+        Action::RotateClockwise,
+        Action::RotateCounterClockwise,
+        Action::FlipView,
+        Action::CycleCellAspect,
+        Action::TogglePreview,
+        Action::ToggleMirror,
+        Action::ToggleCrosshair,
+        Action::TogglePrecisionCursor,
+        Action::ToggleViewClamp,
+        Action::SwapMouseButtons,
+        Action::InvertWheelZoom,
+        Action::CycleZoomStep,
+        Action::CanvasSettings,
+        Action::CycleTheme,
+        Action::ToggleHud,
+        Action::ToggleHudCompact,
+        Action::CycleHudCorner,
+        Action::ToggleBudget,
+        Action::CycleBudgetSize,
+        Action::CycleBudgetColors,
+        Action::TakeDiffSnapshot,
+        Action::DiffSnapshot,
+        Action::DiffSavedFile,
+        Action::DiffFrames,
+        Action::JoinSession,
+        Action::LeaveSession,
+        Action::OpenChat,
+        Action::LockRegion,
+        Action::UnlockRegion,
+        Action::ToggleHelp,
+        Action::ToggleConsole,
+        Action::About,
+    ];
+
+    /// Stable identifier used in preset files (the variant name)
+    pub fn name(self) -> String {
+        format!("{:?}", self)
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::ALL.iter().copied().find(|action| action.name() == name)
+    }
+
+    /// Menu label
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::NewDocument => "New",
+            Action::OpenProject => "Open...",
+            Action::OpenRecent => "Open Recent...",
+            Action::RestoreBackup => "Restore Backup...",
+            Action::SaveProject => "Save",
+            Action::SaveProjectAs => "Save As...",
+            Action::ToggleTextFormat => "Plain-Text Project (.tnst)",
+            Action::ToggleSaveHistory => "Save Undo History",
+            Action::ToggleActionLog => "Log Actions",
+            Action::CycleBackupInterval => "Cycle Backup Interval",
+            Action::CycleBackupCount => "Cycle Backups Kept",
+            Action::ExportPng => "Export PNG...",
+            Action::QuickExport => "Export Now",
+            Action::ExportGif => "Export Animated GIF",
+            Action::ExportGroups => "Export Groups as PNGs",
+            Action::ExportSpriteSheet => "Export Sprite Sheet",
+            Action::DocumentInfo => "Document Info...",
+            Action::Import => "Import...",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::RecenterDocument => "Re-center at Origin",
+            Action::ShiftDocument => "Shift Everything...",
+            Action::ToggleDocumentBounds => "Fixed Document Bounds",
+            Action::TrimToBounds => "Trim to Bounds...",
+            Action::FindStrays => "Find Stray Cells",
+            Action::Copy => "Copy",
+            Action::Cut => "Cut",
+            Action::Paste => "Paste...",
+            Action::DeleteSelection => "Delete",
+            Action::FillSelection => "Fill selection",
+            Action::FillSelectionRect => "Fill rect",
+            Action::GroupSelection => "Group",
+            Action::ExtractSelection => "Extract to New Document",
+            Action::ToggleQuickMask => "Quick Mask",
+            Action::ToggleAlphaLock => "Lock Transparency",
+            Action::SaveSelection => "Save Selection",
+            Action::ToggleSavedSelections => "Saved Selections...",
+            Action::CycleFeather => "Cycle Edge Feather",
+            Action::PlayFrames => "Play Tagged Frames",
+            Action::ToggleTimeline => "Timeline...",
+            Action::TransformSelection => "Rotate / Shear...",
+            Action::Deselect => "Deselect",
+            Action::ToolPaint => "Paint Tool",
+            Action::ToolErase => "Erase Tool",
+            Action::ToolSelect => "Select Tool",
+            Action::ToolPan => "Pan Tool",
+            Action::ToolSpray => "Spray Tool",
+            Action::ToolLine => "Line Tool",
+            Action::ToolRectangle => "Rectangle Tool",
+            Action::ToggleRectangleFill => "Fill Rectangles",
+            Action::ToolPen => "Pen Tool",
+            Action::TogglePenFill => "Fill Closed Pen Shapes",
+            Action::ToolCurve => "Curve Tool",
+            Action::ToolFill => "Fill Tool",
+            Action::ToggleFillGlobal => "Global Fill",
+            Action::ToolDither => "Dither Gradient Tool",
+            Action::ToolAutotile => "Autotile Tool",
+            Action::SetAutotileTemplate => "Set Autotile Template",
+            Action::GenerateLife => Generator::LifeStep.label(),
+            Action::GenerateMaze => Generator::Maze.label(),
+            Action::GenerateNoise => Generator::Noise.label(),
+            Action::GenerateVoronoi => Generator::Voronoi.label(),
+            Action::ToggleCurveCubic => "Cubic Curves",
+            Action::CurveStepsDown => "Fewer Curve Steps",
+            Action::CurveStepsUp => "More Curve Steps",
+            Action::SwapColors => "Swap Colors",
+            Action::BrushSmaller => "Smaller Spray",
+            Action::BrushLarger => "Larger Spray",
+            Action::DensityDown => "Less Spray Density",
+            Action::DensityUp => "More Spray Density",
+            Action::SymmetryVertical => "Symmetry: Left/Right",
+            Action::SymmetryHorizontal => "Symmetry: Top/Bottom",
+            Action::SymmetryDiagonal => "Symmetry: Diagonal \\",
+            Action::SymmetryAntiDiagonal => "Symmetry: Diagonal /",
+            Action::CycleSymmetrySegments => "Cycle Radial Segments",
+            Action::CenterSymmetry => "Symmetry Pivot to View Center",
+            Action::TogglePalette => "Palette",
+            Action::ToggleHarmony => "Color Harmonies",
+            Action::ZoomIn => "Zoom In",
+            Action::ZoomOut => "Zoom Out",
+            Action::ResetView => "Reset View",
+            Action::ReturnToDrawing => "Return to Drawing",
+            Action::GoToCell => "Go to Cell...",
+            Action::RotateClockwise => "Rotate 90° CW",
+            Action::RotateCounterClockwise => "Rotate 90° CCW",
+            Action::FlipView => "Flip View",
+            Action::CycleCellAspect => "Cycle Cell Aspect",
+            Action::TogglePreview => "Preview Panel",
+            Action::ToggleMirror => "Live Mirror",
+            Action::ToggleCrosshair => "Crosshair Guides",
+            Action::TogglePrecisionCursor => "Precision Cursor",
+            Action::ToggleViewClamp => "Keep View Near Drawing",
+            Action::SwapMouseButtons => "Paint with Right Button",
+            Action::InvertWheelZoom => "Invert Wheel Zoom",
+            Action::CycleZoomStep => "Cycle Wheel Zoom Step",
+            Action::CanvasSettings => "Canvas Settings...",
+            Action::CycleTheme => "Next Theme",
+            Action::ToggleHud => "HUD",
+            Action::ToggleHudCompact => "Compact HUD",
+            Action::CycleHudCorner => "Move HUD to Next Corner",
+            Action::ToggleBudget => "Sprite Budget",
+            Action::CycleBudgetSize => "Cycle Budget Size",
+            Action::CycleBudgetColors => "Cycle Budget Colors",
+            Action::TakeDiffSnapshot => "Take Diff Snapshot",
+            Action::DiffSnapshot => "Diff Against Snapshot",
+            Action::DiffSavedFile => "Diff Against Saved File",
+            Action::DiffFrames => "Diff Frames",
+            Action::JoinSession => "Join Shared Canvas",
+            Action::LeaveSession => "Leave Shared Canvas",
+            Action::OpenChat => "Chat",
+            Action::LockRegion => "Lock Selected Region",
+            Action::UnlockRegion => "Unlock Region",
+            Action::ToggleHelp => "Keyboard Shortcuts",
+            Action::ToggleConsole => "Log Console",
+            Action::About => "About",
+        }
+    }
+
+    /// First key binding for this action in `keymap`, if any
+    pub fn binding(self, keymap: &[(KeyBinding, Action)]) -> Option<KeyBinding> {
+        keymap.iter().find(|(_, action)| *action == self).map(|(binding, _)| *binding)
+    }
+
+    /// On/off state for toggles and tools (None for plain commands), shown as a check mark
+    pub fn checked(self, state: &ApplicationState) -> Option<bool> {
+        match self {
+            Action::ToolPaint => Some(state.mode == Mode::Paint),
+            Action::ToolErase => Some(state.mode == Mode::Erase),
+            Action::ToolSelect => Some(state.mode == Mode::Select),
+            Action::ToolPan => Some(state.mode == Mode::Pan),
+            Action::ToolSpray => Some(state.mode == Mode::Spray),
+            Action::ToolLine => Some(state.mode == Mode::Line),
+            Action::ToolRectangle => Some(state.mode == Mode::Rectangle),
+            Action::ToggleRectangleFill => Some(state.rectangle_fill),
+            Action::ToolPen => Some(state.mode == Mode::Pen),
+            Action::TogglePenFill => Some(state.pen_fill),
+            Action::ToolCurve => Some(state.mode == Mode::Curve),
+            Action::ToolFill => Some(state.mode == Mode::Fill),
+            Action::ToolDither => Some(state.mode == Mode::Dither),
+            Action::ToolAutotile => Some(state.mode == Mode::Autotile),
+            Action::ToggleFillGlobal => Some(state.fill_global),
+            Action::ToggleCurveCubic => Some(state.curve_cubic),
+            Action::TogglePalette => Some(state.show_palette),
+            Action::ToggleHarmony => Some(state.show_harmony),
+            Action::ToggleQuickMask => Some(state.quick_mask.is_some()),
+            Action::ToggleAlphaLock => Some(state.alpha_lock),
+            Action::ToggleSavedSelections => Some(state.show_saved_selections),
+            Action::DiffSnapshot => Some(matches!(state.diff, Some(DiffView { source: DiffSource::Snapshot, .. }))),
+            Action::DiffSavedFile => Some(matches!(state.diff, Some(DiffView { source: DiffSource::SavedFile(_), .. }))),
+            Action::DiffFrames => Some(matches!(state.diff, Some(DiffView { source: DiffSource::Frames(..), .. }))),
+            Action::SymmetryVertical => Some(state.symmetry.vertical),
+            Action::SymmetryHorizontal => Some(state.symmetry.horizontal),
+            Action::SymmetryDiagonal => Some(state.symmetry.diagonal),
+            Action::SymmetryAntiDiagonal => Some(state.symmetry.anti_diagonal),
+            Action::FlipView => Some(state.camera.flip_x),
+            Action::TogglePreview => Some(state.show_preview),
+            Action::ToggleMirror => Some(state.mirror_enabled),
+            Action::ToggleCrosshair => Some(state.show_crosshair),
+            Action::TogglePrecisionCursor => Some(state.precision_cursor),
+            Action::ToggleViewClamp => Some(state.clamp_view),
+            Action::SwapMouseButtons => Some(state.mouse.swap_buttons),
+            Action::InvertWheelZoom => Some(state.mouse.invert_zoom),
+            Action::ToggleHud => Some(state.show_hud),
+            Action::ToggleHudCompact => Some(state.hud_layout.compact),
+            Action::ToggleBudget => Some(state.show_budget),
+            Action::ToggleHelp => Some(state.show_help),
+            Action::PlayFrames => Some(state.frame_playback.is_some()),
+            Action::ToggleTimeline => Some(state.show_timeline),
+            Action::ToggleConsole => Some(state.show_console),
+            Action::ToggleSaveHistory => Some(state.save_history),
+            Action::ToggleTextFormat => Some(is_text_project(&state.project_path)),
+            Action::ToggleActionLog => Some(state.log_actions),
+            Action::ToggleDocumentBounds => Some(state.document_bounds.is_some()),
+            _ => None,
+        }
+    }
+}
+
+/// Run every action whose key binding was pressed this frame
+pub fn dispatch_hotkeys(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if state.is_typing() {
+        return;
+    }
+    // While a widget has keyboard focus, Tab, Enter and the arrows move or activate it instead
+    let focused = focus::is_active();
+    let pressed: Vec<Action> = state
+        .keymap
+        .iter()
+        .filter(|(binding, _)| binding.is_pressed() && !(focused && focus::NAVIGATION_KEYS.contains(&binding.key)))
+        .map(|&(_, action)| action)
+        .collect();
+    for action in pressed {
+        perform(action, state, canvas);
+    }
+}
+
+/// Execute an action, whether it came from a hotkey or the menu
+pub fn perform(action: Action, state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let screen_center = Vec2::new(screen_width(), screen_height()) / 2.0;
+
+    match action {
+        Action::NewDocument => {
+            crate::project::new_document(state, canvas);
+            state.status_message = Some("New drawing".to_string());
+        }
+        Action::OpenProject => {
+            state.file_picker = Some(FilePicker::new(false, &state.project_path));
+        }
+        Action::OpenRecent => {
+            state.restoring_backup_of = None;
+            state.open_recent = Some(crate::project::recent::load_recent_projects());
+        }
+        Action::RestoreBackup => {
+            let backups = crate::project::backup::list_backups(&state.project_path)
+                .into_iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect();
+            state.open_recent = Some(crate::project::recent::load_project_cards(backups));
+            state.restoring_backup_of = Some(state.project_path.clone());
+        }
+        Action::CycleBackupInterval => {
+            state.backup = state.backup.next_interval();
+            state.status_message = Some(match state.backup.interval_minutes {
+                0 => "Automatic backups off".to_string(),
+                minutes => format!("Backing up every {} min, keeping {}", minutes, state.backup.keep),
+            });
+        }
+        Action::CycleBackupCount => {
+            state.backup = state.backup.next_keep();
+            state.status_message = Some(format!("Keeping the last {} backups per project", state.backup.keep));
+        }
+        Action::SaveProject => {
+            start_save(state, state.project_path.clone());
+        }
+        Action::SaveProjectAs => {
+            state.file_picker = Some(FilePicker::new(true, &state.project_path));
+        }
+        Action::ToggleTextFormat => {
+            // Same name with the other extension, saved right away so the switch sticks
+            let extension = if is_text_project(&state.project_path) { PROJECT_EXTENSION } else { TEXT_PROJECT_EXTENSION };
+            state.project_path = std::path::Path::new(&state.project_path).with_extension(extension).to_string_lossy().into_owned();
+            start_save(state, state.project_path.clone());
+        }
+        Action::ToggleSaveHistory => state.save_history = !state.save_history,
+        Action::ToggleActionLog => {
+            state.log_actions = !state.log_actions;
+            if state.log_actions {
+                let path = crate::project::action_log::action_log_path();
+                state.status_message = Some(format!("Logging actions to {}", path.display()));
+            }
+        }
+        Action::ExportPng => state.show_export_dialog = !state.show_export_dialog,
+        Action::QuickExport => crate::input::jobs::start_quick_export(state),
+        Action::ExportGif => crate::input::jobs::start_gif_export(state),
+        Action::ExportGroups => crate::input::jobs::start_all_groups_export(state),
+        Action::ExportSpriteSheet => crate::input::jobs::start_sprite_sheet_export(state),
+        Action::DocumentInfo => {
+            state.show_metadata = !state.show_metadata;
+            state.metadata_edit = None;
+        }
+        Action::Import => {
+            state.show_import_dialog = !state.show_import_dialog;
+            state.import.status = None;
+        }
+        Action::Undo => undo_last(state, canvas),
+        Action::Redo => redo_last(state, canvas),
+        Action::RecenterDocument => crate::input::transform::recenter_document(state, canvas),
+        Action::ShiftDocument => state.shift_canvas = if state.shift_canvas.is_some() { None } else { Some((0, 0)) },
+        Action::ToggleDocumentBounds => crate::input::transform::toggle_document_bounds(state),
+        Action::TrimToBounds => crate::input::transform::trim_to_bounds(state),
+        Action::FindStrays => crate::ui::stray_navigator::open_stray_navigator(state),
+        Action::Copy => crate::input::clipboard::copy_selection(state),
+        Action::Cut => crate::input::clipboard::cut_selection(state, canvas),
+        Action::Paste => crate::input::clipboard::begin_paste(state),
+        Action::DeleteSelection => crate::input::selection::delete_selection(state, canvas),
+        Action::FillSelection => crate::input::selection::fill_selection(state, canvas, false),
+        Action::FillSelectionRect => crate::input::selection::fill_selection(state, canvas, true),
+        Action::GroupSelection => crate::input::groups::create_group_from_selection(state),
+        Action::ExtractSelection => crate::input::transform::extract_selection(state),
+        Action::ToggleQuickMask => crate::input::quick_mask::toggle_quick_mask(state),
+        Action::ToggleAlphaLock => {
+            state.alpha_lock = !state.alpha_lock;
+            state.status_message = Some(if state.alpha_lock { "Transparency locked: painting only recolors filled cells" } else { "Transparency unlocked" }.to_string());
+        }
+        Action::SaveSelection => crate::input::saved_selections::save_selection(state),
+        Action::ToggleSavedSelections => {
+            state.show_saved_selections = !state.show_saved_selections;
+            state.saved_selection_rename = None;
+        }
+        Action::CycleFeather => {
+            state.feather = crate::core::merge::next_feather(state.feather);
+            state.status_message = Some(match state.feather {
+                0 => "Paste and move edges: hard".to_string(),
+                width => format!("Paste and move edges: {}-cell feather", width),
+            });
+        }
+        Action::PlayFrames => crate::input::frames::toggle_frame_playback(state, canvas),
+        Action::ToggleTimeline => {
+            state.show_timeline = !state.show_timeline;
+            state.frame_tag_rename = None;
+        }
+        Action::TransformSelection => crate::input::transform::begin_selection_transform(state),
+        Action::Deselect => {
+            if !state.selection.is_moving {
+                state.selection.current = None;
+                state.selected_group_id = None;
+            }
+        }
+        Action::ToolPaint => state.mode = Mode::Paint,
+        Action::ToolErase => state.mode = Mode::Erase,
+        Action::ToolSelect => state.mode = Mode::Select,
+        Action::ToolPan => state.mode = Mode::Pan,
+        Action::ToolSpray => state.mode = Mode::Spray,
+        Action::ToolLine => state.mode = Mode::Line,
+        Action::ToolRectangle => state.mode = Mode::Rectangle,
+        Action::ToggleRectangleFill => state.rectangle_fill = !state.rectangle_fill,
+        Action::ToolPen => state.mode = Mode::Pen,
+        Action::TogglePenFill => state.pen_fill = !state.pen_fill,
+        Action::ToolCurve => state.mode = Mode::Curve,
+        Action::ToolFill => state.mode = Mode::Fill,
+        Action::ToolDither => state.mode = Mode::Dither,
+        Action::ToolAutotile => state.mode = Mode::Autotile,
+        Action::SetAutotileTemplate => set_autotile_template(state),
+        Action::GenerateLife => start_generator(state, canvas, Generator::LifeStep),
+        Action::GenerateMaze => start_generator(state, canvas, Generator::Maze),
+        Action::GenerateNoise => start_generator(state, canvas, Generator::Noise),
+        Action::GenerateVoronoi => start_generator(state, canvas, Generator::Voronoi),
+        Action::ToggleFillGlobal => state.fill_global = !state.fill_global,
+        Action::ToggleCurveCubic => crate::input::curve::toggle_curve_kind(state),
+        Action::CurveStepsDown => {
+            state.curve_steps = (state.curve_steps / 2).max(crate::input::curve::MIN_CURVE_STEPS);
+            state.status_message = Some(format!("Curve steps: {}", state.curve_steps));
+        }
+        Action::CurveStepsUp => {
+            state.curve_steps = (state.curve_steps * 2).min(crate::input::curve::MAX_CURVE_STEPS);
+            state.status_message = Some(format!("Curve steps: {}", state.curve_steps));
+        }
+        Action::SwapColors => std::mem::swap(&mut state.current_color, &mut state.secondary_color),
+        Action::BrushSmaller => state.spray_radius = (state.spray_radius - 1).max(0),
+        Action::BrushLarger => state.spray_radius = (state.spray_radius + 1).min(32),
+        Action::DensityDown => state.spray_density = (state.spray_density - 0.01).max(0.01),
+        Action::DensityUp => state.spray_density = (state.spray_density + 0.01).min(1.0),
+        Action::SymmetryVertical => toggle_symmetry_axis(state, |s| &mut s.vertical),
+        Action::SymmetryHorizontal => toggle_symmetry_axis(state, |s| &mut s.horizontal),
+        Action::SymmetryDiagonal => toggle_symmetry_axis(state, |s| &mut s.diagonal),
+        Action::SymmetryAntiDiagonal => toggle_symmetry_axis(state, |s| &mut s.anti_diagonal),
+        Action::CycleSymmetrySegments => cycle_symmetry_segments(state),
+        Action::CenterSymmetry => center_symmetry_on_view(state),
+        Action::TogglePalette => state.show_palette = !state.show_palette,
+        Action::ToggleHarmony => state.show_harmony = !state.show_harmony,
+        Action::ZoomIn => state.camera.zoom_around_cursor(screen_center, 1.25),
+        Action::ZoomOut => state.camera.zoom_around_cursor(screen_center, 1.0 / 1.25),
+        Action::ResetView => {
+            let aspect = state.camera.aspect;
+            state.camera = crate::core::camera::Camera::new();
+            state.camera.aspect = aspect;
+        }
+        Action::ReturnToDrawing => super::view_bounds::return_to_drawing(state),
+        Action::GoToCell => state.go_to_cell = Some(String::new()),
+        // Rotate the view in 90° steps around the screen center
+        Action::RotateClockwise => state.camera.rotate_around(screen_center, std::f32::consts::FRAC_PI_2),
+        Action::RotateCounterClockwise => state.camera.rotate_around(screen_center, -std::f32::consts::FRAC_PI_2),
+        // Flip view horizontally (mirror check); cells are untouched
+        Action::FlipView => state.camera.toggle_flip_around(screen_center),
+        // Cycle the document cell aspect ratio (1:1, 2:1, 1:2)
+        Action::CycleCellAspect => {
+            let next = state.cell_aspect.next_preset();
+            state.set_cell_aspect(next);
+        }
+        Action::TogglePreview => state.show_preview = !state.show_preview,
+        Action::ToggleMirror => state.mirror_enabled = !state.mirror_enabled,
+        Action::ToggleCrosshair => state.show_crosshair = !state.show_crosshair,
+        Action::TogglePrecisionCursor => state.precision_cursor = !state.precision_cursor,
+        Action::ToggleViewClamp => state.clamp_view = !state.clamp_view,
+        Action::SwapMouseButtons => state.mouse.swap_buttons = !state.mouse.swap_buttons,
+        Action::InvertWheelZoom => state.mouse.invert_zoom = !state.mouse.invert_zoom,
+        Action::CycleZoomStep => {
+            state.mouse.zoom_step = state.mouse.next_zoom_step();
+            state.status_message = Some(format!("Wheel zoom step: {:.0}%", (state.mouse.zoom_step - 1.0) * 100.0));
+        }
+        Action::CanvasSettings => state.show_canvas_settings = !state.show_canvas_settings,
+        Action::CycleTheme => {
+            state.theme = state.theme.next();
+            state.status_message = Some(format!("Theme: {}", state.theme.name));
+        }
+        Action::ToggleHud => state.show_hud = !state.show_hud,
+        Action::ToggleHudCompact => {
+            state.hud_layout.compact = !state.hud_layout.compact;
+            state.show_hud = true;
+        }
+        Action::CycleHudCorner => {
+            state.hud_layout.corner = state.hud_layout.corner.next();
+            state.show_hud = true;
+            state.status_message = Some(format!("HUD: {}", state.hud_layout.corner.label()));
+        }
+        Action::ToggleBudget => state.show_budget = !state.show_budget,
+        Action::CycleBudgetSize => {
+            state.budget = state.budget.next_size();
+            state.show_budget = true;
+        }
+        Action::CycleBudgetColors => {
+            state.budget = state.budget.next_colors();
+            state.show_budget = true;
+        }
+        Action::TakeDiffSnapshot => take_diff_snapshot(state),
+        Action::DiffSnapshot => toggle_snapshot_diff(state),
+        Action::DiffSavedFile => toggle_saved_file_diff(state),
+        Action::DiffFrames => toggle_frame_diff(state),
+        Action::JoinSession => crate::collab::join_session(state),
+        Action::LeaveSession => crate::collab::leave_session(state),
+        Action::OpenChat => {
+            if state.collab.is_some() {
+                state.chat_open = true;
+                state.chat_input.clear();
+            } else {
+                state.status_message = Some("Join a shared canvas to chat".to_string());
+            }
+        }
+        Action::LockRegion => crate::collab::lock_selection(state),
+        Action::UnlockRegion => crate::collab::unlock_selection(state),
+        Action::ToggleHelp | Action::About => state.show_help = !state.show_help,
+        Action::ToggleConsole => state.show_console = !state.show_console,
+    }
+}
+
+/// Helper to check if Ctrl (Windows/Linux) or Cmd (Mac) is pressed
+pub fn ctrl_or_cmd() -> bool {
+    is_key_down(KeyCode::LeftControl)
+        || is_key_down(KeyCode::RightControl)
+        || is_key_down(KeyCode::LeftSuper)
+        || is_key_down(KeyCode::RightSuper)
+}
+
+/// Helper to check if either Shift key is held
+pub fn shift_down() -> bool {
+    is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
+}
+
+/// Keys a preset may bind, looked up by their `KeyCode` variant name
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H, KeyCode::I,
+    KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+    KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5, KeyCode::Key6,
+    KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8,
+    KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::LeftBracket, KeyCode::RightBracket, KeyCode::Equal, KeyCode::Minus, KeyCode::Comma, KeyCode::Period,
+    KeyCode::Slash, KeyCode::Semicolon, KeyCode::Apostrophe, KeyCode::Backslash, KeyCode::GraveAccent,
+    KeyCode::Delete, KeyCode::Backspace, KeyCode::Insert, KeyCode::Home, KeyCode::End, KeyCode::PageUp,
+    KeyCode::PageDown, KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+];
+
+/// Key for a `KeyCode` variant name such as "F2" or "LeftBracket"
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.iter().copied().find(|key| format!("{:?}", key) == name)
+}
+
+/// Display name of a key for menus and the help overlay
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::LeftBracket => "[".to_string(),
+        KeyCode::RightBracket => "]".to_string(),
+        KeyCode::Equal => "=".to_string(),
+        KeyCode::Minus => "-".to_string(),
+        KeyCode::Comma => ",".to_string(),
+        KeyCode::Period => ".".to_string(),
+        KeyCode::Key0 => "0".to_string(),
+        other => format!("{:?}", other),
+    }
+}
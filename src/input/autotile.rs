@@ -0,0 +1,67 @@
+use macroquad::prelude::*;
+use crate::core::autotile::AutotileTemplate;
+use crate::core::shapes::line_cells;
+use crate::rendering::CanvasRenderer;
+use crate::state::{ApplicationState, Mode};
+use super::tools::{commit_stroke, set_cell};
+
+/// Cut the selected area into the autotile template and switch to the autotile tool
+pub fn set_autotile_template(state: &mut ApplicationState) {
+    let Some(sel) = &state.selection.current else {
+        state.status_message = Some("Select a 16- or 47-tile template sheet first".to_string());
+        return;
+    };
+    match AutotileTemplate::from_sheet(&state.cells, &sel.rect) {
+        Ok(template) => {
+            state.status_message = Some(format!("Autotile template: {}, {}x{} cells per tile", template.set.label(), template.size, template.size));
+            state.autotile = Some(template);
+            state.mode = Mode::Autotile;
+        }
+        Err(e) => state.status_message = Some(e),
+    }
+}
+
+/// Autotile tool: drag to paint terrain tiles, right-drag to remove them. Each tile gets the
+/// template variant that matches its terrain neighbors, and the neighbors are updated to match;
+/// tiles sit on a grid aligned to cell (0, 0). The whole stroke is one undo step
+pub fn handle_autotile_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(template) = state.autotile.take() else {
+        if is_mouse_button_pressed(state.mouse.primary()) {
+            state.status_message = Some("Select a template sheet and use Set Autotile Template first".to_string());
+        }
+        return;
+    };
+
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let tile = template.tile_at((world.x.floor() as i32, world.y.floor() as i32));
+
+    let (primary, secondary) = (state.mouse.primary(), state.mouse.secondary());
+    if is_mouse_button_pressed(primary) || is_mouse_button_pressed(secondary) {
+        let add = is_mouse_button_pressed(primary);
+        state.autotile_stroke = Some((tile, add));
+        paint_tile(state, &template, tile, add, canvas);
+    } else if let Some((last, add)) = state.autotile_stroke {
+        let button = if add { primary } else { secondary };
+        if is_mouse_button_down(button) {
+            if tile != last {
+                for t in line_cells(last, tile).into_iter().skip(1) {
+                    paint_tile(state, &template, t, add, canvas);
+                }
+                state.autotile_stroke = Some((tile, add));
+            }
+        } else {
+            state.autotile_stroke = None;
+            commit_stroke(state);
+        }
+    }
+
+    state.autotile = Some(template);
+}
+
+fn paint_tile(state: &mut ApplicationState, template: &AutotileTemplate, tile: (i32, i32), add: bool, canvas: &mut CanvasRenderer) {
+    for (coords, cell) in template.repaint(&state.cells, tile, add) {
+        if let Some(change) = set_cell(state, coords, cell, canvas) {
+            state.stroke_changes.push(change);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use macroquad::prelude::*;
+use crate::state::{ApplicationState, Mode};
+
+/// Exponential decay rate of the pan glide (per second)
+const PAN_FRICTION: f32 = 6.0;
+/// Glide stops once it drops below this speed (screen pixels per second)
+const PAN_MIN_SPEED: f32 = 20.0;
+/// Width of the screen border that triggers auto-scroll while dragging
+const EDGE_MARGIN: f32 = 24.0;
+/// Auto-scroll speed at the very edge of the screen (pixels per second)
+const EDGE_SCROLL_SPEED: f32 = 600.0;
+
+/// Per-frame camera motion that is not tied to a single input event:
+/// momentum after a pan release, and auto-scroll when dragging near the screen edge
+pub fn update_camera_motion(state: &mut ApplicationState, dt: f32) {
+    apply_pan_inertia(state, dt);
+    auto_scroll_at_edges(state, dt);
+}
+
+/// Let the view glide to a stop after the pan drag is released
+fn apply_pan_inertia(state: &mut ApplicationState, dt: f32) {
+    if state.pan_drag_start_screen.is_some() || state.pan_velocity == Vec2::ZERO {
+        return;
+    }
+
+    if state.pan_velocity.length() < PAN_MIN_SPEED {
+        state.pan_velocity = Vec2::ZERO;
+        return;
+    }
+
+    let delta_world = state.camera.screen_delta_to_world(state.pan_velocity * dt);
+    state.camera.origin -= delta_world;
+    state.pan_velocity *= (-PAN_FRICTION * dt).exp();
+}
+
+/// True while a paint stroke, selection drag/move or region drag is in progress
+fn drag_in_progress(state: &ApplicationState) -> bool {
+    if state.space_pan_active || !is_mouse_button_down(state.mouse.primary()) {
+        return false;
+    }
+    match state.mode {
+        Mode::Paint => state.last_painted_cell.is_some(),
+        Mode::Erase => state.last_painted_cell.is_some() || state.erase_rect_drag.is_some(),
+        Mode::Spray => !state.stroke_changes.is_empty(),
+        Mode::Select => state.selection.active_drag || state.selection.is_moving,
+        Mode::ExportRegion => state.export_region_drag.is_some(),
+        Mode::Curve => state.curve_drag.is_some(),
+        Mode::Line => state.line_drag.is_some(),
+        Mode::Rectangle => state.rectangle_drag.is_some(),
+        Mode::Dither => state.dither_drag.is_some(),
+        Mode::Autotile => state.autotile_stroke.is_some(),
+        Mode::Pan | Mode::Pen | Mode::Fill => false,
+    }
+}
+
+/// Scroll the camera toward the screen edge the cursor is pushing against;
+/// the active tool keeps working because it re-reads the world cursor each frame
+fn auto_scroll_at_edges(state: &mut ApplicationState, dt: f32) {
+    if !drag_in_progress(state) {
+        return;
+    }
+
+    let mouse = Vec2::from(mouse_position());
+    let size = Vec2::new(screen_width(), screen_height());
+
+    // How far into the margin the cursor is on each side, 0..1 (beyond the window clamps to 1)
+    let push = |near: f32, far: f32| -> f32 {
+        if near < EDGE_MARGIN {
+            -((EDGE_MARGIN - near) / EDGE_MARGIN).min(1.0)
+        } else if far < EDGE_MARGIN {
+            ((EDGE_MARGIN - far) / EDGE_MARGIN).min(1.0)
+        } else {
+            0.0
+        }
+    };
+    let direction = Vec2::new(push(mouse.x, size.x - mouse.x), push(mouse.y, size.y - mouse.y));
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let delta_world = state.camera.screen_delta_to_world(direction * EDGE_SCROLL_SPEED * dt);
+    state.camera.origin += delta_world;
+}
@@ -1,92 +1,117 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use macroquad::prelude::*;
 use crate::state::{ApplicationState, CellChange};
+use crate::input::dispatcher::apply_changes_and_record;
+use crate::input::groups::{create_group, set_group_frame};
+use crate::input::frames::set_frame_duration;
 use crate::rendering::CanvasRenderer;
+use crate::project::clipboard::save_clipboard;
 use crate::core::selection::{SelectionKind, Selection, compute_bounding_rect};
+use crate::core::merge::feather_weights;
 
 /// Copy the current selection to the clipboard
 /// Stores cells with relative coordinates (offset from rect.min_x, rect.min_y)
 pub fn copy_selection(state: &mut ApplicationState) {
     if let Some(sel) = &state.selection.current {
-        if let SelectionKind::Cells(set) = &sel.kind {
-            let rect = sel.rect;
-            let mut cells = HashMap::new();
+        let SelectionKind::Cells(set) = &sel.kind;
+        let rect = sel.rect;
+        let mut cells = HashMap::new();
 
-            // Copy cells with relative coordinates
-            for &(x, y) in set.iter() {
-                if let Some(cell) = state.cells.get(&(x, y)).cloned() {
-                    let rel_x = x - rect.min_x;
-                    let rel_y = y - rect.min_y;
-                    cells.insert((rel_x, rel_y), cell);
-                }
+        // Copy cells with relative coordinates
+        for &(x, y) in set.iter() {
+            if let Some(cell) = state.cells.get(&(x, y)).cloned() {
+                let rel_x = x - rect.min_x;
+                let rel_y = y - rect.min_y;
+                cells.insert((rel_x, rel_y), cell);
             }
-
-            // Update clipboard
-            state.clipboard.width = rect.max_x - rect.min_x + 1;
-            state.clipboard.height = rect.max_y - rect.min_y + 1;
-            state.clipboard.cells = cells;
-            state.clipboard.has_data = true;
         }
+
+        // Update clipboard
+        state.clipboard.width = rect.max_x - rect.min_x + 1;
+        state.clipboard.height = rect.max_y - rect.min_y + 1;
+        state.clipboard.cells = cells;
+        state.clipboard.has_data = true;
+        state.clipboard.groups.clear();
+        save_clipboard(&state.clipboard);
     }
 }
 
 /// Cut the current selection (copy then delete)
-/// For now, manually handles deletion - will integrate with history in Phase 3
+/// Deletion goes through delete_selection so groups and a shared session see it too
 pub fn cut_selection(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
-    // First copy to clipboard
     copy_selection(state);
+    crate::input::selection::delete_selection(state, canvas);
+}
 
-    // Then delete the cells
-    if let Some(sel) = &state.selection.current {
-        if let SelectionKind::Cells(set) = &sel.kind {
-            // Delete each cell from the grid
-            for &(x, y) in set.iter() {
-                state.cells.remove(&(x, y));
-                canvas.mark_dirty((x, y));
-            }
+/// Start a pending paste: the clipboard follows the cursor until a click places it
+pub fn begin_paste(state: &mut ApplicationState) {
+    if state.clipboard.has_data {
+        state.pending_paste = true;
+    }
+}
 
-            // Clear the selection
-            state.selection.current = None;
-        }
+/// Cell under the cursor, where the clipboard's top-left lands
+pub fn paste_anchor(state: &ApplicationState) -> (i32, i32) {
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    (world.x.floor() as i32, world.y.floor() as i32)
+}
+
+/// Pending paste input: click places, Tab cycles the merge mode, Esc cancels
+pub fn handle_pending_paste(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        state.pending_paste = false;
+        paste_clipboard_at_cursor(state, canvas);
+    }
+    if is_key_pressed(KeyCode::Tab) {
+        state.paste_merge = state.paste_merge.next();
+    }
+    if is_key_pressed(KeyCode::Escape) {
+        state.pending_paste = false;
     }
 }
 
-/// Paste clipboard contents at the cursor position
-/// Creates a new selection at the pasted location
-/// For now, manually handles insertion - will integrate with history in Phase 3
+/// Paste clipboard contents at the cursor position, combined per `paste_merge` with edges faded
+/// per `feather`
+/// Creates a new selection at the pasted location; one undo command
 pub fn paste_clipboard_at_cursor(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
     if !state.clipboard.has_data {
         return;
     }
 
-    // Get cursor position in world coordinates
-    let mouse = Vec2::from(mouse_position());
-    let world = state.camera.screen_to_cell(mouse);
-    let anchor = (world.x.floor() as i32, world.y.floor() as i32);
+    let anchor = paste_anchor(state);
 
     // Place clipboard cells offset from anchor
     let mut placed_coords = Vec::new();
+    let mut changes = Vec::new();
+    let shape: HashSet<(i32, i32)> = state.clipboard.cells.keys().copied().collect();
+    let weights = feather_weights(&shape, state.feather);
 
     for (rel_coord, cell) in state.clipboard.cells.iter() {
-        let dest_x = anchor.0 + rel_coord.0;
-        let dest_y = anchor.1 + rel_coord.1;
-        let dest = (dest_x, dest_y);
-
-        // Insert cell into grid
-        state.cells.insert(dest, *cell);
-        canvas.mark_dirty(dest);
+        let dest = (anchor.0 + rel_coord.0, anchor.1 + rel_coord.1);
         placed_coords.push(dest);
+        let weight = weights.get(rel_coord).copied();
+        if let Some(merged) = state.paste_merge.merge_feathered(state.cells.get(&dest).copied(), *cell, weight) {
+            changes.push(CellChange { coord: dest, before: None, after: Some(merged) });
+        }
+    }
+    apply_changes_and_record(state, canvas, "Paste", changes);
+
+    // Groups riding along (from an imported project); frame tags go after the existing frames
+    let first_frame = state.groups.iter().filter_map(|g| g.frame).max().unwrap_or(0);
+    for group in state.clipboard.groups.clone() {
+        let cells = group.cells.iter().map(|&(x, y)| (anchor.0 + x, anchor.1 + y)).collect();
+        let id = create_group(state, group.name, cells);
+        set_group_frame(state, id, group.frame.map(|frame| first_frame + frame));
+        set_frame_duration(state, id, group.duration_ms);
     }
 
     // Create selection at pasted location
-    use std::collections::HashSet;
     let set: HashSet<(i32, i32)> = placed_coords.into_iter().collect();
 
     if let Some(rect) = compute_bounding_rect(&set) {
         state.selection.current = Some(Selection {
             rect,
             kind: SelectionKind::Cells(set),
-            preview: None,
         });
     }
 }
@@ -0,0 +1,83 @@
+use macroquad::prelude::*;
+use crate::core::shapes::Curve;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::focus;
+use super::tools::paint_cells;
+
+/// Screen distance within which a click grabs a curve handle
+const HANDLE_GRAB_PIXELS: f32 = 10.0;
+pub const MIN_CURVE_STEPS: u32 = 2;
+pub const MAX_CURVE_STEPS: u32 = 128;
+
+/// Curve tool: drag from start to end, then drag the endpoint and control handles
+/// Clicking away from the handles (or Enter) commits the curve; Esc discards it
+pub fn handle_curve_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let screen_mouse = Vec2::from(mouse_position());
+    let world = state.camera.screen_to_cell(screen_mouse);
+    let cell_center = (world.x.floor() + 0.5, world.y.floor() + 0.5);
+
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        state.curve_drag = state.curve.as_ref().and_then(|curve| grabbed_handle(state, curve, screen_mouse));
+        if state.curve_drag.is_none() {
+            commit_curve(state, canvas);
+            state.curve = Some(Curve::new(cell_center, cell_center, state.curve_cubic));
+            state.curve_drag = Some(3);
+        }
+    }
+
+    if is_mouse_button_down(state.mouse.primary()) {
+        if let (Some(curve), Some(index)) = (&mut state.curve, state.curve_drag) {
+            if index == 0 || index == 3 {
+                // Endpoints snap to cell centers
+                curve.points[index] = cell_center;
+                if !curve.controls_moved {
+                    curve.place_default_controls();
+                }
+            } else {
+                curve.points[index] = (world.x, world.y);
+                curve.controls_moved = true;
+            }
+        }
+    }
+
+    if is_mouse_button_released(state.mouse.primary()) {
+        state.curve_drag = None;
+    }
+
+    if focus::enter_pressed() {
+        commit_curve(state, canvas);
+    }
+    if is_key_pressed(KeyCode::Escape) {
+        state.curve = None;
+        state.curve_drag = None;
+    }
+}
+
+/// Paint the pending curve (if any) as one undo step
+pub fn commit_curve(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if let Some(curve) = state.curve.take() {
+        let cells = curve.cells(state.curve_steps);
+        paint_cells(state, cells, canvas);
+    }
+}
+
+/// Switch between quadratic and cubic, for new curves and the one being edited
+pub fn toggle_curve_kind(state: &mut ApplicationState) {
+    state.curve_cubic = !state.curve_cubic;
+    if let Some(curve) = &mut state.curve {
+        curve.cubic = state.curve_cubic;
+        if !curve.controls_moved {
+            curve.place_default_controls();
+        }
+    }
+}
+
+/// Index of the handle under the mouse; controls win over endpoints when stacked
+fn grabbed_handle(state: &ApplicationState, curve: &Curve, screen_mouse: Vec2) -> Option<usize> {
+    let handles: &[usize] = if curve.cubic { &[1, 2, 0, 3] } else { &[1, 0, 3] };
+    handles.iter().copied().find(|&i| {
+        let (x, y) = curve.points[i];
+        state.camera.world_to_screen(Vec2::new(x, y)).distance(screen_mouse) <= HANDLE_GRAB_PIXELS
+    })
+}
@@ -0,0 +1,125 @@
+use crate::core::cell::CellGrid;
+use crate::core::diff::{diff_cells, diff_counts, normalized, CellDiff};
+use crate::error::report;
+use crate::input::frames::tagged_frames;
+use crate::project::read_project;
+use crate::state::ApplicationState;
+
+/// What the drawing is compared against in the diff view
+pub enum DiffSource {
+    /// Cells captured with "Take Diff Snapshot"
+    Snapshot,
+    /// The project file at the current path, as read when the diff was turned on
+    SavedFile(CellGrid),
+    /// Two tagged frames by group id, earlier first; differences are drawn over the later one
+    Frames(u32, u32),
+}
+
+/// Highlighted differences: added cells green, removed red, changed yellow
+pub struct DiffView {
+    pub source: DiffSource,
+    pub cells: Vec<((i32, i32), CellDiff)>,
+    /// History revision the cells were computed at; edits trigger a recompute
+    revision: u64,
+}
+
+/// Remember the current cells as the "before" side of snapshot diffs
+pub fn take_diff_snapshot(state: &mut ApplicationState) {
+    state.diff_snapshot = Some(CellGrid::clone(&state.cells));
+    state.status_message = Some(format!("Diff snapshot taken ({} cells)", state.cells.len()));
+    if matches!(state.diff, Some(DiffView { source: DiffSource::Snapshot, .. })) {
+        show_diff(state, DiffSource::Snapshot);
+    }
+}
+
+/// Compare the drawing with the last snapshot, or hide that diff
+pub fn toggle_snapshot_diff(state: &mut ApplicationState) {
+    if matches!(state.diff, Some(DiffView { source: DiffSource::Snapshot, .. })) {
+        state.diff = None;
+    } else if state.diff_snapshot.is_none() {
+        state.status_message = Some("Take a diff snapshot first".to_string());
+    } else {
+        show_diff(state, DiffSource::Snapshot);
+    }
+}
+
+/// Compare the drawing with its saved project file, or hide that diff
+pub fn toggle_saved_file_diff(state: &mut ApplicationState) {
+    if matches!(state.diff, Some(DiffView { source: DiffSource::SavedFile(_), .. })) {
+        state.diff = None;
+        return;
+    }
+    let path = state.project_path.clone();
+    match read_project(&path) {
+        Ok(document) => show_diff(state, DiffSource::SavedFile(document.cells)),
+        Err(e) => state.status_message = Some(report("Diff", &e)),
+    }
+}
+
+/// Compare the selected frame with the one before it (or the first two frames when no later
+/// frame is selected), or hide that diff
+pub fn toggle_frame_diff(state: &mut ApplicationState) {
+    if matches!(state.diff, Some(DiffView { source: DiffSource::Frames(..), .. })) {
+        state.diff = None;
+        return;
+    }
+    let frames: Vec<u32> = tagged_frames(&state.groups).iter().map(|g| g.id).collect();
+    if frames.len() < 2 {
+        state.status_message = Some("Tag at least two groups as frames (right-click a group)".to_string());
+        return;
+    }
+    let later = match state.selected_group_id.and_then(|id| frames.iter().position(|&f| f == id)) {
+        Some(i) if i > 0 => i,
+        _ => 1,
+    };
+    show_diff(state, DiffSource::Frames(frames[later - 1], frames[later]));
+}
+
+/// Recompute the diff after edits; drops a frame diff whose groups are gone
+pub fn update_diff(state: &mut ApplicationState) {
+    let Some(view) = &state.diff else {
+        return;
+    };
+    if view.revision == state.history.revision {
+        return;
+    }
+    match compute(state, &view.source) {
+        Some(cells) => {
+            if let Some(view) = &mut state.diff {
+                view.cells = cells;
+                view.revision = state.history.revision;
+            }
+        }
+        None => state.diff = None,
+    }
+}
+
+fn show_diff(state: &mut ApplicationState, source: DiffSource) {
+    let Some(cells) = compute(state, &source) else {
+        return;
+    };
+    let (added, removed, changed) = diff_counts(&cells);
+    let against = match &source {
+        DiffSource::Snapshot => "snapshot".to_string(),
+        DiffSource::SavedFile(_) => state.project_path.clone(),
+        DiffSource::Frames(..) => "previous frame".to_string(),
+    };
+    state.status_message = Some(format!("Diff vs {}: {} added, {} removed, {} changed", against, added, removed, changed));
+    state.diff = Some(DiffView { source, cells, revision: state.history.revision });
+}
+
+/// Differences for `source`, in canvas coordinates; None if its frames no longer exist
+fn compute(state: &ApplicationState, source: &DiffSource) -> Option<Vec<((i32, i32), CellDiff)>> {
+    match source {
+        DiffSource::Snapshot => Some(diff_cells(state.diff_snapshot.as_ref()?, &state.cells)),
+        DiffSource::SavedFile(saved) => Some(diff_cells(saved, &state.cells)),
+        &DiffSource::Frames(earlier, later) => {
+            let group = |id: u32| state.groups.iter().find(|g| g.id == id);
+            let (earlier, later) = (group(earlier)?, group(later)?);
+            let min_x = later.cells.iter().map(|c| c.0).min()?;
+            let min_y = later.cells.iter().map(|c| c.1).min()?;
+            let diffs = diff_cells(&normalized(&state.cells, &earlier.cells), &normalized(&state.cells, &later.cells));
+            Some(diffs.into_iter().map(|((x, y), kind)| ((x + min_x, y + min_y), kind)).collect())
+        }
+    }
+}
@@ -1,8 +1,25 @@
+use std::sync::Arc;
 use macroquad::prelude::*;
 use crate::state::{Mode, ApplicationState};
 use crate::rendering::CanvasRenderer;
-use super::tools::perform_drawing;
-use super::selection::handle_select_tool;
+use super::tools::{commit_stroke, finish_erase_rect, perform_drawing, perform_erase_rect, perform_spray};
+use super::selection::{finish_interrupted_drag, handle_select_tool};
+use super::export_region::{finish_region_drag, handle_export_region_tool};
+use super::actions::dispatch_hotkeys;
+use super::pen::handle_pen_tool;
+use super::transform::handle_transform_handles;
+use super::clipboard::handle_pending_paste;
+use super::curve::handle_curve_tool;
+use super::fill::handle_fill_tool;
+use super::dither::{finish_dither_drag, handle_dither_tool};
+use super::line::{finish_line_drag, handle_line_tool};
+use super::rectangle::{finish_rectangle_drag, handle_rectangle_tool};
+use super::symmetry::handle_symmetry_drag;
+use super::autotile::handle_autotile_tool;
+use super::generate::handle_generator_region;
+use crate::project::action_log::append_action_log;
+use crate::collab::share_changes;
+use crate::collab::limits::Role;
 
 /// Central input dispatcher that handles all user input based on current application state
 pub fn handle_input(
@@ -10,60 +27,73 @@ pub fn handle_input(
     canvas_renderer: &mut CanvasRenderer,
 ) {
     // Handle temporary pan mode with middle mouse button
-    if is_mouse_button_pressed(MouseButton::Middle) {
-        if !state.temp_pan_active {
-            state.temp_pan_previous_mode = Some(state.mode.clone());
-            state.mode = Mode::Pan;
-            state.temp_pan_active = true;
-        }
+    if is_mouse_button_pressed(MouseButton::Middle) && !state.temp_pan_active {
+        state.temp_pan_previous_mode = Some(state.mode.clone());
+        state.mode = Mode::Pan;
+        state.temp_pan_active = true;
     }
 
-    if is_mouse_button_released(MouseButton::Middle) {
-        if state.temp_pan_active {
-            if let Some(previous_mode) = state.temp_pan_previous_mode.take() {
-                state.mode = previous_mode;
-            }
-            state.temp_pan_active = false;
-            // Clear pan drag state when exiting temp pan
-            state.pan_drag_start_screen = None;
-            state.pan_drag_start_origin = None;
+    if is_mouse_button_released(MouseButton::Middle) && state.temp_pan_active {
+        if let Some(previous_mode) = state.temp_pan_previous_mode.take() {
+            state.mode = previous_mode;
         }
+        state.temp_pan_active = false;
+        // Clear pan drag state when exiting temp pan
+        state.pan_drag_start_screen = None;
+        state.pan_drag_start_origin = None;
+        state.pan_last_mouse = None;
     }
 
-    // Clipboard operations (check before mode hotkeys to avoid conflicts)
-    if ctrl_or_cmd() && is_key_pressed(KeyCode::C) {
-        crate::input::clipboard::copy_selection(state);
+    // Hold Space to pan over the current tool; the tool's stroke state is left untouched
+    // so a stroke paused mid-drag resumes (as the same undo command) when Space is released
+    if is_key_pressed(KeyCode::Space) && !state.temp_pan_active && !state.space_pan_active {
+        begin_space_pan(state);
     }
-
-    if ctrl_or_cmd() && is_key_pressed(KeyCode::X) {
-        crate::input::clipboard::cut_selection(state, canvas_renderer);
+    if is_key_released(KeyCode::Space) && state.space_pan_active {
+        end_space_pan(state);
     }
-
-    if ctrl_or_cmd() && is_key_pressed(KeyCode::V) {
-        crate::input::clipboard::paste_clipboard_at_cursor(state, canvas_renderer);
+    if state.space_pan_active {
+        // Letting go of the button while panning ends the paused stroke
+        if state.space_pan_paused_stroke && is_mouse_button_released(state.mouse.primary()) {
+            state.space_pan_paused_stroke = false;
+            finish_paused_stroke(state, canvas_renderer);
+        }
+        handle_pan_tool(state, Vec2::from(mouse_position()));
+        return;
     }
 
-    if ctrl_or_cmd() && is_key_pressed(KeyCode::Z) {
-        undo_last(state, canvas_renderer);
-    }
+    // Hotkeys from the shared keymap (check before mode dispatch)
+    dispatch_hotkeys(state, canvas_renderer);
 
-    // Hotkeys for mode switching (check before mode dispatch)
-    if is_key_pressed(KeyCode::B) {
-        state.mode = Mode::Paint;
+    // A pending selection transform owns the canvas until applied or cancelled
+    if state.selection_transform.is_some() {
+        handle_transform_handles(state, canvas_renderer);
+        return;
     }
-    if is_key_pressed(KeyCode::E) {
-        state.mode = Mode::Erase;
+
+    // A pending paste places on click, whatever the tool
+    if state.pending_paste {
+        handle_pending_paste(state, canvas_renderer);
+        return;
     }
-    if !ctrl_or_cmd() && is_key_pressed(KeyCode::V) {
-        state.mode = Mode::Select;
+
+    // Viewers of a shared canvas can look around but not edit
+    if state.collab.as_ref().is_some_and(|session| session.role == Role::Viewer) && state.mode != Mode::Pan {
+        if is_mouse_button_pressed(state.mouse.primary()) {
+            state.status_message = Some("View only: an admin can make you an editor".to_string());
+        }
+        return;
     }
-    if is_key_pressed(KeyCode::H) || is_key_pressed(KeyCode::Space) {
-        state.mode = Mode::Pan;
+
+    // A generator started without a selection waits for its rect, whatever the tool
+    if state.pending_generator.is_some() {
+        handle_generator_region(state, canvas_renderer);
+        return;
     }
 
-    // Delete selection hotkey
-    if is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace) {
-        crate::input::selection::delete_selection(state, canvas_renderer);
+    // The symmetry pivot can be dragged with any tool
+    if handle_symmetry_drag(state) {
+        return;
     }
 
     // Existing mode-based dispatch
@@ -72,16 +102,41 @@ pub fn handle_input(
 
     match state.mode {
         Mode::Paint => perform_drawing(state, &world_mouse_pos, false, canvas_renderer),
-        Mode::Erase => perform_drawing(state, &world_mouse_pos, true, canvas_renderer),
+        Mode::Erase => {
+            if !perform_erase_rect(state, &world_mouse_pos, canvas_renderer) {
+                perform_drawing(state, &world_mouse_pos, true, canvas_renderer);
+            }
+        }
         Mode::Pan => handle_pan_tool(state, screen_mouse_pos),
         Mode::Select => handle_select_tool(state, canvas_renderer),
+        Mode::Spray => perform_spray(state, &world_mouse_pos, canvas_renderer),
+        Mode::ExportRegion => handle_export_region_tool(state),
+        Mode::Line => handle_line_tool(state, canvas_renderer),
+        Mode::Rectangle => handle_rectangle_tool(state, canvas_renderer),
+        Mode::Pen => handle_pen_tool(state, canvas_renderer),
+        Mode::Curve => handle_curve_tool(state, canvas_renderer),
+        Mode::Fill => handle_fill_tool(state, canvas_renderer),
+        Mode::Dither => handle_dither_tool(state, canvas_renderer),
+        Mode::Autotile => handle_autotile_tool(state, canvas_renderer),
+    }
+}
+
+/// Push a committed command onto the history, appending it to the action log when enabled
+/// and sending it to the shared session if one is joined
+/// `kind` names what produced it (a tool or menu action) for the log
+pub fn record_command(state: &mut ApplicationState, kind: &str, cmd: crate::state::Command) {
+    if state.log_actions {
+        append_action_log(kind, cmd.changes.len());
     }
+    share_changes(state, &cmd.changes, false);
+    state.history.push(cmd);
 }
 
 /// Apply changes to cells and record them in history for undo
 pub fn apply_changes_and_record(
     state: &mut ApplicationState,
     canvas: &mut CanvasRenderer,
+    kind: &str,
     mut changes: Vec<crate::state::CellChange>,
 ) {
     // Fill in 'before' values if not set
@@ -92,59 +147,110 @@ pub fn apply_changes_and_record(
     }
 
     // Apply changes
-    for ch in changes.iter() {
-        match ch.after {
-            Some(cell) => {
-                state.cells.insert(ch.coord, cell);
-            }
-            None => {
-                state.cells.remove(&ch.coord);
-            }
-        }
-        canvas.mark_dirty(ch.coord);
-    }
+    let cmd = crate::state::Command { changes };
+    cmd.apply(Arc::make_mut(&mut state.cells));
+    canvas.mark_cells_dirty(cmd.changes.iter().map(|ch| ch.coord));
 
     // Record in history
-    state.history.push(crate::state::Command { changes });
+    record_command(state, kind, cmd);
 }
 
 /// Undo the last command in history
 pub fn undo_last(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
-    if let Some(cmd) = state.history.pop() {
-        for ch in cmd.changes {
-            match ch.before {
-                Some(cell) => {
-                    state.cells.insert(ch.coord, cell);
-                }
-                None => {
-                    state.cells.remove(&ch.coord);
-                }
-            }
-            canvas.mark_dirty(ch.coord);
+    let Some(cmd) = state.history.undo(Arc::make_mut(&mut state.cells)) else {
+        return;
+    };
+    let changes = cmd.changes.clone();
+    canvas.mark_cells_dirty(changes.iter().map(|ch| ch.coord));
+    if state.log_actions {
+        append_action_log("Undo", changes.len());
+    }
+    share_changes(state, &changes, true);
+}
+
+/// Redo the last undone command
+pub fn redo_last(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(cmd) = state.history.redo(Arc::make_mut(&mut state.cells)) else {
+        return;
+    };
+    let changes = cmd.changes.clone();
+    canvas.mark_cells_dirty(changes.iter().map(|ch| ch.coord));
+    if state.log_actions {
+        append_action_log("Redo", changes.len());
+    }
+    share_changes(state, &changes, false);
+}
+
+/// Start a Space quick-pan; if the button is already down, the pan drag starts right away
+fn begin_space_pan(state: &mut ApplicationState) {
+    state.space_pan_active = true;
+    state.space_pan_paused_stroke = is_mouse_button_down(state.mouse.primary());
+    if state.space_pan_paused_stroke {
+        let screen_mouse = Vec2::from(mouse_position());
+        state.pan_drag_start_screen = Some(screen_mouse);
+        state.pan_drag_start_origin = Some(state.camera.origin);
+        state.pan_last_mouse = Some(screen_mouse);
+        state.pan_velocity = Vec2::ZERO;
+    }
+}
+
+/// End a Space quick-pan and re-anchor a paused stroke at the cursor's new world position
+fn end_space_pan(state: &mut ApplicationState) {
+    state.space_pan_active = false;
+    state.pan_drag_start_screen = None;
+    state.pan_drag_start_origin = None;
+    state.pan_last_mouse = None;
+
+    if std::mem::take(&mut state.space_pan_paused_stroke) && is_mouse_button_down(state.mouse.primary()) {
+        let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+        let cell_coords = (world.x.floor() as i32, world.y.floor() as i32);
+        // Continue from here rather than drawing a line (or jumping the selection) across the pan
+        if state.last_painted_cell.is_some() {
+            state.last_painted_cell = Some(cell_coords);
+        }
+        if state.selection.is_moving {
+            state.selection.last_move_mouse = Some((world.x, world.y));
         }
     }
 }
 
-/// Helper to check if Ctrl (Windows/Linux) or Cmd (Mac) is pressed
-fn ctrl_or_cmd() -> bool {
-    is_key_down(KeyCode::LeftControl)
-        || is_key_down(KeyCode::RightControl)
-        || is_key_down(KeyCode::LeftSuper)
-        || is_key_down(KeyCode::RightSuper)
+/// Finish the tool operation that was paused by Space when its button is released mid-pan
+fn finish_paused_stroke(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    match state.mode {
+        Mode::Paint | Mode::Erase | Mode::Spray => {
+            state.last_painted_cell = None;
+            commit_stroke(state);
+            finish_erase_rect(state, canvas);
+        }
+        Mode::Select => finish_interrupted_drag(state, canvas),
+        Mode::ExportRegion => finish_region_drag(state),
+        Mode::Curve => state.curve_drag = None,
+        Mode::Line => finish_line_drag(state, canvas),
+        Mode::Rectangle => finish_rectangle_drag(state, canvas),
+        Mode::Dither => finish_dither_drag(state, canvas),
+        Mode::Autotile => {
+            state.autotile_stroke = None;
+            commit_stroke(state);
+        }
+        Mode::Pan | Mode::Pen | Mode::Fill => {}
+    }
 }
 
 /// Handle pan tool interaction
 fn handle_pan_tool(state: &mut ApplicationState, screen_mouse: Vec2) {
-    // Use middle mouse button if in temp pan mode, otherwise left button
+    // Use middle mouse button if in temp pan mode, otherwise the primary button
     let pan_button = if state.temp_pan_active {
         MouseButton::Middle
     } else {
-        MouseButton::Left
+        state.mouse.primary()
     };
 
     if is_mouse_button_pressed(pan_button) {
         state.pan_drag_start_screen = Some(screen_mouse);
         state.pan_drag_start_origin = Some(state.camera.origin);
+        state.pan_last_mouse = Some(screen_mouse);
+        // Grabbing the canvas stops any glide in progress
+        state.pan_velocity = Vec2::ZERO;
     }
 
     if is_mouse_button_down(pan_button) {
@@ -152,14 +258,23 @@ fn handle_pan_tool(state: &mut ApplicationState, screen_mouse: Vec2) {
             (state.pan_drag_start_screen, state.pan_drag_start_origin)
         {
             let delta_screen = screen_mouse - start_screen;
-            let delta_world = delta_screen / state.camera.pixel_scale();
+            let delta_world = state.camera.screen_delta_to_world(delta_screen);
             state.camera.origin = start_origin - delta_world;
         }
+
+        // Smoothed release velocity, so a pause before letting go stops the glide
+        if let Some(last) = state.pan_last_mouse {
+            let dt = get_frame_time().max(1e-4);
+            let frame_velocity = (screen_mouse - last) / dt;
+            state.pan_velocity = state.pan_velocity.lerp(frame_velocity, 0.5);
+        }
+        state.pan_last_mouse = Some(screen_mouse);
     }
 
     if is_mouse_button_released(pan_button) {
         state.pan_drag_start_screen = None;
         state.pan_drag_start_origin = None;
+        state.pan_last_mouse = None;
     }
 }
 
@@ -167,9 +282,17 @@ fn handle_pan_tool(state: &mut ApplicationState, screen_mouse: Vec2) {
 pub fn handle_zoom(state: &mut ApplicationState) {
     let (_scroll_x, scroll_y) = mouse_wheel();
 
+    // Alt + wheel: free view rotation around the cursor
+    if scroll_y != 0.0 && (is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt)) {
+        let cursor_screen = Vec2::from(mouse_position());
+        let step = 5f32.to_radians() * scroll_y.signum();
+        state.camera.rotate_around(cursor_screen, step);
+        return;
+    }
+
     if scroll_y != 0.0 {
         let cursor_screen = Vec2::from(mouse_position());
-        let zoom_factor = if scroll_y > 0.0 { 1.1 } else { 1.0 / 1.1 };
+        let zoom_factor = state.mouse.zoom_factor(scroll_y);
         state.camera.zoom_around_cursor(cursor_screen, zoom_factor);
     }
 }
@@ -0,0 +1,48 @@
+use macroquad::prelude::*;
+use crate::core::dither::dither_gradient;
+use crate::core::selection::SelectionKind;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use super::tools::paint_colored_cells;
+
+/// Dither tool: drag across the selection to lay a Bayer-dithered band from the primary
+/// color (drag start) to the secondary color (drag end). One undo step; Esc cancels the drag
+pub fn handle_dither_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let point = (world.x, world.y);
+
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        if state.selection.current.is_none() {
+            state.status_message = Some("Select the area to dither into first".to_string());
+            return;
+        }
+        state.dither_drag = Some((point, point));
+    }
+
+    if is_mouse_button_down(state.mouse.primary()) {
+        if let Some((start, _)) = state.dither_drag {
+            state.dither_drag = Some((start, point));
+        }
+    }
+
+    if is_mouse_button_released(state.mouse.primary()) {
+        finish_dither_drag(state, canvas);
+    }
+
+    if is_key_pressed(KeyCode::Escape) {
+        state.dither_drag = None;
+    }
+}
+
+/// Apply the dragged gradient to the selection's cells
+pub fn finish_dither_drag(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some((from, to)) = state.dither_drag.take() else {
+        return;
+    };
+    let Some(sel) = &state.selection.current else {
+        return;
+    };
+    let SelectionKind::Cells(set) = &sel.kind;
+    let cells = dither_gradient(set.iter().copied(), from, to, state.current_color, state.secondary_color);
+    paint_colored_cells(state, cells, canvas);
+}
@@ -0,0 +1,46 @@
+use macroquad::prelude::*;
+use crate::state::{ApplicationState, Mode};
+use crate::core::selection::SelectionRect;
+use crate::input::jobs::start_export;
+
+/// Drag a rectangle on the canvas and export exactly that cell region,
+/// empty cells included, independent of the selection system
+pub fn handle_export_region_tool(state: &mut ApplicationState) {
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let cell_coords = (world.x.floor() as i32, world.y.floor() as i32);
+
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        state.export_region_drag = Some((cell_coords, cell_coords));
+    }
+
+    if is_mouse_button_down(state.mouse.primary()) {
+        if let Some((start, _)) = state.export_region_drag {
+            state.export_region_drag = Some((start, cell_coords));
+        }
+    }
+
+    if is_mouse_button_released(state.mouse.primary()) {
+        finish_region_drag(state);
+    }
+
+    if is_key_pressed(KeyCode::Escape) {
+        state.export_region_drag = None;
+        finish(state);
+    }
+}
+
+/// Export the dragged region (if any) and leave region mode
+pub fn finish_region_drag(state: &mut ApplicationState) {
+    if let Some((start, end)) = state.export_region_drag.take() {
+        let rect = SelectionRect::from_points(start, end);
+        let path = state.export.region_path();
+        start_export(state, rect, path, true);
+        finish(state);
+    }
+}
+
+/// Leave region mode and reopen the dialog so the result is visible
+fn finish(state: &mut ApplicationState) {
+    state.mode = state.export_region_previous_mode.take().unwrap_or(Mode::Paint);
+    state.show_export_dialog = true;
+}
@@ -0,0 +1,29 @@
+use macroquad::prelude::*;
+use crate::core::cell::grid_bounds;
+use crate::core::fill::{fill_region, FillTarget};
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use super::tools::paint_cells;
+
+/// Fill tool: click to flood the region under the cursor with the current color
+/// Empty space is bounded by the document bounds, or else the drawing's bounding box
+pub fn handle_fill_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if !is_mouse_button_pressed(state.mouse.primary()) {
+        return;
+    }
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let start = (world.x.floor() as i32, world.y.floor() as i32);
+
+    let Some(bounds) = state.document_bounds.or_else(|| grid_bounds(&state.cells)) else {
+        state.status_message = Some("Nothing to fill: the drawing is empty".to_string());
+        return;
+    };
+    if !bounds.contains(start.0, start.1) {
+        state.status_message = Some("Click inside the drawing to fill".to_string());
+        return;
+    }
+
+    let target = FillTarget::at(&state.cells, start, state.fill_tolerance);
+    let region = fill_region(&state.cells, start, target, state.fill_global, &bounds);
+    paint_cells(state, region, canvas);
+}
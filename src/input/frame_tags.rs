@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+use crate::core::frame_tag::{FrameTag, LoopMode};
+use crate::state::ApplicationState;
+
+/// Tag every current frame under a fresh name, make it the active tag and open the list so it
+/// can be renamed and narrowed
+pub fn new_frame_tag(state: &mut ApplicationState) {
+    let numbers: Vec<u32> = state.groups.iter().filter_map(|g| g.frame).collect();
+    let start = numbers.iter().copied().min().unwrap_or(1);
+    let end = numbers.iter().copied().max().unwrap_or(1);
+    let taken: HashSet<&str> = state.frame_tags.iter().map(|t| t.name.as_str()).collect();
+    let name = (1..).map(|n| format!("Tag {}", n)).find(|name| !taken.contains(name.as_str())).unwrap_or_default();
+    state.status_message = Some(format!("Tagged frames {}-{} as {}", start, end, name));
+    state.frame_tags.push(FrameTag { name, start, end, loop_mode: LoopMode::Forward });
+    state.active_frame_tag = Some(state.frame_tags.len() - 1);
+    state.show_timeline = true;
+}
+
+/// Make the tag at `index` the one playback and GIF export follow; picking the active tag
+/// again goes back to every frame
+pub fn toggle_active_frame_tag(state: &mut ApplicationState, index: usize) {
+    if index >= state.frame_tags.len() {
+        return;
+    }
+    state.active_frame_tag = if state.active_frame_tag == Some(index) { None } else { Some(index) };
+    restart_playback(state);
+    state.status_message = Some(match crate::input::frames::active_tag(state) {
+        Some(tag) => format!("Playing and exporting {} ({})", tag.name, tag.range_label()),
+        None => "Playing and exporting every frame".to_string(),
+    });
+}
+
+/// Move the first (`end` false) or last frame of the tag at `index` by `delta`, keeping
+/// start <= end and the range within the frames in use
+pub fn nudge_frame_tag(state: &mut ApplicationState, index: usize, end: bool, delta: i32) {
+    let last = state.groups.iter().filter_map(|g| g.frame).max().unwrap_or(1);
+    let Some(tag) = state.frame_tags.get_mut(index) else {
+        return;
+    };
+    if end {
+        tag.end = tag.end.saturating_add_signed(delta).clamp(tag.start, last.max(tag.start));
+    } else {
+        tag.start = tag.start.saturating_add_signed(delta).clamp(1, tag.end);
+    }
+    restart_playback(state);
+}
+
+/// Step the tag at `index` to the next loop mode
+pub fn cycle_frame_tag_loop(state: &mut ApplicationState, index: usize) {
+    if let Some(tag) = state.frame_tags.get_mut(index) {
+        tag.loop_mode = tag.loop_mode.next();
+        restart_playback(state);
+    }
+}
+
+/// Forget the tag at `index`
+pub fn delete_frame_tag(state: &mut ApplicationState, index: usize) {
+    if index >= state.frame_tags.len() {
+        return;
+    }
+    let removed = state.frame_tags.remove(index);
+    state.frame_tag_rename = None;
+    state.active_frame_tag = match state.active_frame_tag {
+        Some(active) if active == index => None,
+        Some(active) if active > index => Some(active - 1),
+        active => active,
+    };
+    restart_playback(state);
+    state.status_message = Some(format!("Deleted frame tag {}", removed.name));
+}
+
+/// Play from the start of the changed range, if playing
+fn restart_playback(state: &mut ApplicationState) {
+    if let Some(playback) = &mut state.frame_playback {
+        playback.started = macroquad::prelude::get_time();
+        playback.shown = None;
+    }
+}
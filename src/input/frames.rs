@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+use macroquad::prelude::*;
+use crate::core::frame_tag::{FrameTag, LoopMode};
+use crate::core::group::Group;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+
+pub const DEFAULT_FRAME_FPS: f32 = 8.0;
+/// Shortest and longest duration a frame can be given, in milliseconds
+pub const MIN_FRAME_DURATION_MS: u32 = 10;
+pub const MAX_FRAME_DURATION_MS: u32 = 10_000;
+/// Step of the timeline's -/+ duration buttons
+pub const FRAME_DURATION_STEP_MS: u32 = 10;
+
+/// Frame playback in progress: tagged groups are shown one at a time on the canvas
+pub struct FramePlayback {
+    pub started: f64,
+    /// Group id of the frame currently shown
+    pub shown: Option<u32>,
+}
+
+/// Tag a group as the frame after the last tagged one, or untag it if it is already a frame
+pub fn toggle_frame_tag(state: &mut ApplicationState, id: u32) {
+    let next = state.groups.iter().filter_map(|g| g.frame).max().unwrap_or(0) + 1;
+    if let Some(g) = state.groups.iter_mut().find(|g| g.id == id) {
+        g.frame = match g.frame {
+            Some(_) => None,
+            None => Some(next),
+        };
+    }
+}
+
+/// How long `frame` shows: its own duration, or one tick of the playback rate
+pub fn frame_duration_ms(frame: &Group, fps: f32) -> u32 {
+    frame.duration_ms.unwrap_or_else(|| default_duration_ms(fps))
+}
+
+/// Duration of frames without their own, from the playback rate
+pub fn default_duration_ms(fps: f32) -> u32 {
+    (1000.0 / fps).round() as u32
+}
+
+/// Give the frame group `id` its own duration, or None to follow the playback rate
+pub fn set_frame_duration(state: &mut ApplicationState, id: u32, duration_ms: Option<u32>) {
+    if let Some(g) = state.groups.iter_mut().find(|g| g.id == id) {
+        g.duration_ms = duration_ms.map(|ms| ms.clamp(MIN_FRAME_DURATION_MS, MAX_FRAME_DURATION_MS));
+    }
+}
+
+/// Lengthen (`steps` > 0) or shorten the frame group `id` by whole duration steps; landing on
+/// the playback rate's duration goes back to following the rate
+pub fn nudge_frame_duration(state: &mut ApplicationState, id: u32, steps: i32) {
+    let default = default_duration_ms(state.frame_fps);
+    let Some(current) = state.groups.iter().find(|g| g.id == id).map(|g| frame_duration_ms(g, state.frame_fps)) else {
+        return;
+    };
+    let next = current.saturating_add_signed(steps * FRAME_DURATION_STEP_MS as i32).clamp(MIN_FRAME_DURATION_MS, MAX_FRAME_DURATION_MS);
+    set_frame_duration(state, id, (next != default).then_some(next));
+}
+
+/// Tagged groups in playback order
+pub fn tagged_frames(groups: &[Group]) -> Vec<&Group> {
+    let mut frames: Vec<&Group> = groups.iter().filter(|g| g.frame.is_some()).collect();
+    frames.sort_by_key(|g| g.frame);
+    frames
+}
+
+/// The frame tag playback and GIF export follow, if one is picked
+pub fn active_tag(state: &ApplicationState) -> Option<&FrameTag> {
+    state.active_frame_tag.and_then(|i| state.frame_tags.get(i))
+}
+
+/// Frames of `tag` in playback order, or every tagged frame without one
+pub fn tag_frames<'a>(groups: &'a [Group], tag: Option<&FrameTag>) -> Vec<&'a Group> {
+    let mut frames = tagged_frames(groups);
+    if let Some(tag) = tag {
+        frames.retain(|g| g.frame.is_some_and(|f| tag.contains(f)));
+    }
+    frames
+}
+
+/// Start playing the active tag's frames (every tagged frame without one), or stop and show
+/// everything again
+pub fn toggle_frame_playback(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if state.frame_playback.take().is_some() {
+        canvas.set_hidden(HashSet::new());
+        return;
+    }
+    let tag = active_tag(state);
+    let count = tag_frames(&state.groups, tag).len();
+    if count < 2 {
+        state.status_message = Some(match tag {
+            Some(tag) => format!("{} covers fewer than two frames ({})", tag.name, tag.range_label()),
+            None => "Tag at least two groups as frames (right-click a group)".to_string(),
+        });
+        return;
+    }
+    let playing = match tag {
+        Some(tag) => format!("Playing {} ({} frames, {}) at {} fps", tag.name, count, tag.loop_mode.label(), state.frame_fps),
+        None => format!("Playing {} frames at {} fps", count, state.frame_fps),
+    };
+    state.frame_playback = Some(FramePlayback { started: get_time(), shown: None });
+    state.status_message = Some(playing);
+}
+
+/// Advance playback: hide every tagged group except the current frame's, stepping through
+/// the active tag in its loop mode, each frame held for its duration
+/// Stops by itself once fewer than two frames are left (groups ungrouped or cleared)
+pub fn update_frame_playback(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(playback) = &state.frame_playback else {
+        return;
+    };
+    let tag = active_tag(state);
+    let frames = tag_frames(&state.groups, tag);
+    if frames.len() < 2 {
+        state.frame_playback = None;
+        canvas.set_hidden(HashSet::new());
+        return;
+    }
+    let loop_mode = tag.map_or(LoopMode::Forward, |tag| tag.loop_mode);
+    let order = loop_mode.play_order(frames.len());
+    let durations: Vec<u64> = order.iter().map(|&i| frame_duration_ms(frames[i], state.frame_fps) as u64).collect();
+    let total: u64 = durations.iter().sum();
+    let elapsed = ((get_time() - playback.started) * 1000.0) as u64;
+    let mut time = match loop_mode {
+        LoopMode::Once => elapsed.min(total - 1),
+        LoopMode::Forward | LoopMode::PingPong => elapsed % total,
+    };
+    // The step whose span holds `time`
+    let step = durations
+        .iter()
+        .position(|&duration| {
+            let inside = time < duration;
+            time = time.saturating_sub(duration);
+            inside
+        })
+        .unwrap_or(order.len() - 1);
+    let current = frames[order[step]];
+    if playback.shown == Some(current.id) {
+        return;
+    }
+    let hidden: HashSet<(i32, i32)> = tagged_frames(&state.groups)
+        .iter()
+        .flat_map(|g| g.cells.iter())
+        .filter(|c| !current.cells.contains(c))
+        .copied()
+        .collect();
+    let shown = current.id;
+    canvas.set_hidden(hidden);
+    if let Some(playback) = &mut state.frame_playback {
+        playback.shown = Some(shown);
+    }
+}
@@ -0,0 +1,77 @@
+use macroquad::prelude::*;
+use crate::core::cell::Cell;
+use crate::core::generate::Generator;
+use crate::core::selection::SelectionRect;
+use crate::rendering::CanvasRenderer;
+use crate::state::{ApplicationState, CellChange};
+use super::dispatcher::apply_changes_and_record;
+
+/// Largest area a generator writes in one go
+const MAX_GENERATOR_CELLS: i64 = 1 << 20;
+
+/// Run `generator` over the selection's rect, or, with nothing selected, wait for a rect to
+/// be dragged on the canvas
+pub fn start_generator(state: &mut ApplicationState, canvas: &mut CanvasRenderer, generator: Generator) {
+    match state.selection.current.as_ref().map(|sel| sel.rect) {
+        Some(rect) if !state.selection.is_moving => apply_generator(state, canvas, generator, rect),
+        Some(_) => {}
+        None => {
+            state.pending_generator = Some(generator);
+            state.status_message = Some(format!("Drag a rect to fill with {}; Esc cancels", generator.label()));
+        }
+    }
+}
+
+/// Drag out the rect for a pending generator; it runs on release. Owns the canvas until then
+pub fn handle_generator_region(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(generator) = state.pending_generator else {
+        return;
+    };
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let cell_coords = (world.x.floor() as i32, world.y.floor() as i32);
+
+    if is_key_pressed(KeyCode::Escape) {
+        state.pending_generator = None;
+        state.generator_drag = None;
+        state.status_message = Some(format!("{} cancelled", generator.label()));
+        return;
+    }
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        state.generator_drag = Some((cell_coords, cell_coords));
+    } else if let Some((start, _)) = state.generator_drag {
+        if is_mouse_button_down(state.mouse.primary()) {
+            state.generator_drag = Some((start, cell_coords));
+        } else {
+            state.pending_generator = None;
+            state.generator_drag = None;
+            apply_generator(state, canvas, generator, SelectionRect::from_points(start, cell_coords));
+        }
+    }
+}
+
+/// Write the generator's output into `rect` as one undoable command
+fn apply_generator(state: &mut ApplicationState, canvas: &mut CanvasRenderer, generator: Generator, rect: SelectionRect) {
+    if rect.width() as i64 * rect.height() as i64 > MAX_GENERATOR_CELLS {
+        state.status_message = Some(format!("{}x{} is too large to generate into", rect.width(), rect.height()));
+        return;
+    }
+    let palette = state.active_palette();
+    let output = generator.generate(&state.cells, &rect, state.current_color, state.secondary_color, &palette, &mut state.generator_rng);
+    let changes: Vec<CellChange> = output
+        .into_iter()
+        .filter_map(|(coord, color)| {
+            let before = state.cells.get(&coord).copied();
+            let current = before.filter(|c| c.is_filled).map(|c| c.color);
+            (current != color).then_some(CellChange { coord, before, after: color.map(Cell::with_color) })
+        })
+        .collect();
+
+    let count = changes.len();
+    if count > 0 {
+        apply_changes_and_record(state, canvas, generator.label(), changes);
+    }
+    state.status_message = Some(match count {
+        0 => format!("{}: nothing changed", generator.label()),
+        n => format!("{}: {} cells changed", generator.label(), n),
+    });
+}
@@ -1,6 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use macroquad::prelude::*;
+use std::sync::Arc;
 use crate::state::{ApplicationState, CellChange};
+use crate::core::cell::clear_cells;
 use crate::rendering::CanvasRenderer;
 use crate::core::group::Group;
 
@@ -11,27 +13,46 @@ pub fn create_group_from_selection(state: &mut ApplicationState) {
     };
     if cells.is_empty() { return; }
 
+    let name = format!("Group {}", state.next_group_id);
+    let id = create_group(state, name, cells);
+    state.selected_group_id = Some(id);
+}
+
+/// Add a group over `cells` and return its id
+pub fn create_group(state: &mut ApplicationState, name: String, cells: HashSet<(i32,i32)>) -> u32 {
     let id = state.next_group_id; state.next_group_id += 1;
-    let name = format!("Group {}", id);
-    state.groups.push(Group { id, name, cells: cells.clone() });
     for &c in &cells { state.group_index.insert(c, id); }
-    state.selected_group_id = Some(id);
+    state.groups.push(Group { id, name, cells, frame: None, duration_ms: None });
+    id
 }
 
 pub fn select_group(state: &mut ApplicationState, id: u32) {
     if let Some(g) = state.groups.iter().find(|g| g.id == id) {
         use crate::core::selection::{Selection, SelectionKind, compute_bounding_rect};
         if let Some(rect) = compute_bounding_rect(&g.cells) {
-            state.selection.current = Some(Selection { rect, kind: SelectionKind::Cells(g.cells.clone()), preview: None });
+            state.selection.current = Some(Selection { rect, kind: SelectionKind::Cells(g.cells.clone()) });
             state.selected_group_id = Some(id);
         }
     }
 }
 
+// Forget every group (the cells they named belong to a replaced document)
+pub fn clear_groups(state: &mut ApplicationState) {
+    state.groups.clear();
+    state.group_index.clear();
+    state.selected_group_id = None;
+    state.group_renaming_id = None;
+    state.group_context_target = None;
+}
+
 pub fn rename_group(state: &mut ApplicationState, id: u32, new_name: String) {
     if let Some(g) = state.groups.iter_mut().find(|g| g.id == id) { g.name = new_name; }
 }
 
+pub fn set_group_frame(state: &mut ApplicationState, id: u32, frame: Option<u32>) {
+    if let Some(g) = state.groups.iter_mut().find(|g| g.id == id) { g.frame = frame; }
+}
+
 pub fn ungroup(state: &mut ApplicationState, id: u32) {
     if let Some(pos) = state.groups.iter().position(|g| g.id == id) {
         for &c in state.groups[pos].cells.iter() { state.group_index.remove(&c); }
@@ -42,30 +63,35 @@ pub fn ungroup(state: &mut ApplicationState, id: u32) {
 
 pub fn delete_group_and_cells(state: &mut ApplicationState, canvas: &mut CanvasRenderer, id: u32) {
     if let Some(pos) = state.groups.iter().position(|g| g.id == id) {
-        let mut changes: Vec<CellChange> = Vec::new();
-        for &c in state.groups[pos].cells.iter() {
-            if state.cells.get(&c).is_some() { changes.push(CellChange { coord: c, before: None, after: None }); }
-            state.group_index.remove(&c);
-        }
-        state.groups.remove(pos);
-        if !changes.is_empty() {
-            crate::input::dispatcher::apply_changes_and_record(state, canvas, changes);
+        let group = state.groups.remove(pos);
+        for c in group.cells.iter() { state.group_index.remove(c); }
+        let removed = clear_cells(Arc::make_mut(&mut state.cells), &group.cells);
+        if !removed.is_empty() {
+            canvas.mark_cells_dirty(removed.iter().map(|&(coord, _)| coord));
+            let changes = removed.into_iter().map(|(coord, cell)| CellChange { coord, before: Some(cell), after: None }).collect();
+            crate::input::dispatcher::record_command(state, "Delete Group", crate::state::Command { changes });
         }
         if state.selected_group_id == Some(id) { state.selected_group_id = None; state.selection.current = None; }
     }
 }
 
+/// A cell's coordinates before and after a move
+pub type CellMove = ((i32, i32), (i32, i32));
+
 // Update membership when cells move (old -> new pairs)
-pub fn update_membership_on_move(state: &mut ApplicationState, moved: &[( (i32,i32), (i32,i32) )]) {
+// Two passes, since a cell's destination may be another moved cell's origin
+pub fn update_membership_on_move(state: &mut ApplicationState, moved: &[CellMove]) {
+    let mut arrivals = Vec::new();
     for (old, newc) in moved {
         if let Some(id) = state.group_index.remove(old) {
-            state.group_index.insert(*newc, id);
-            if let Some(g) = state.groups.iter_mut().find(|g| g.id == id) {
-                g.cells.remove(old);
-                g.cells.insert(*newc);
-            }
+            if let Some(g) = state.groups.iter_mut().find(|g| g.id == id) { g.cells.remove(old); }
+            arrivals.push((*newc, id));
         }
     }
+    for (newc, id) in arrivals {
+        state.group_index.insert(newc, id);
+        if let Some(g) = state.groups.iter_mut().find(|g| g.id == id) { g.cells.insert(newc); }
+    }
 }
 
 // Remove membership for deleted cells
@@ -80,7 +106,7 @@ pub fn remove_cells_from_groups(state: &mut ApplicationState, cells: &[(i32,i32)
 // Helper: set selected_group_id based on current selection (exact match)
 pub fn sync_selected_group_from_selection(state: &mut ApplicationState) {
     let sel = match &state.selection.current { Some(s) => s, None => { state.selected_group_id = None; return; } };
-    let selected: &std::collections::HashSet<(i32,i32)> = match &sel.kind { crate::core::selection::SelectionKind::Cells(set) => set };
+    let crate::core::selection::SelectionKind::Cells(selected) = &sel.kind;
     for g in &state.groups {
         if g.cells.len() == selected.len() && g.cells.iter().all(|c| selected.contains(c)) {
             state.selected_group_id = Some(g.id); return;
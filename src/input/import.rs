@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+use macroquad::prelude::*;
+use crate::core::selection::{compute_bounding_rect, Selection, SelectionKind};
+use crate::core::cell::grid_bounds;
+use crate::error::{report, FileError};
+use crate::export::cart::{export_p8, export_tic};
+use crate::import::{decode_file, extension};
+use crate::input::dispatcher::apply_changes_and_record;
+use crate::input::frames::toggle_frame_tag;
+use crate::input::groups::create_group;
+use crate::project::preset::{load_preset, save_preset, PRESET_EXTENSION};
+use crate::rendering::CanvasRenderer;
+use crate::project::format::{ProjectGroup, PROJECT_EXTENSION, TEXT_PROJECT_EXTENSION};
+use crate::project::read_project;
+use crate::state::{ApplicationState, CellChange, Clipboard};
+
+/// Decode the dialog's file and place it centered in the view as one undoable command,
+/// selected so it can be moved straight away; any groups it names (e.g. frames) are created.
+/// A workspace preset is applied to the workspace instead, and a project becomes a pending paste
+pub fn run_import(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if [PROJECT_EXTENSION, TEXT_PROJECT_EXTENSION].contains(&extension(&state.import.path).as_str()) {
+        import_project_as_paste(state);
+        return;
+    }
+    if extension(&state.import.path) == PRESET_EXTENSION {
+        let path = state.import.path.clone();
+        state.import.status = Some(match load_preset(state, &path) {
+            Ok(()) => format!("Applied workspace preset {}", path),
+            Err(e) => report("Import", &e),
+        });
+        return;
+    }
+    let imported = match decode_file(&state.import, &state.active_palette()) {
+        Ok(imported) => imported,
+        Err(e) => {
+            state.import.status = Some(report("Import", &e));
+            return;
+        }
+    };
+    let cells = imported.cells;
+    let Some(rect) = compute_bounding_rect(&cells.keys().copied().collect()) else {
+        state.import.status = Some("The file has no visible pixels".to_string());
+        return;
+    };
+
+    let center = state.camera.screen_to_cell(Vec2::new(screen_width(), screen_height()) / 2.0);
+    let dx = center.x.floor() as i32 - rect.width() / 2;
+    let dy = center.y.floor() as i32 - rect.height() / 2;
+    let changes: Vec<CellChange> = cells
+        .iter()
+        .map(|(&(x, y), &cell)| CellChange { coord: (x + dx, y + dy), before: None, after: Some(cell) })
+        .collect();
+    let placed: HashSet<(i32, i32)> = changes.iter().map(|ch| ch.coord).collect();
+    apply_changes_and_record(state, canvas, "Import", changes);
+
+    // Imported groups are animation frames, so they come in tagged for playback
+    for (name, coords) in imported.groups {
+        let id = create_group(state, name, coords.into_iter().map(|(x, y)| (x + dx, y + dy)).collect());
+        toggle_frame_tag(state, id);
+    }
+
+    if let Some(rect) = compute_bounding_rect(&placed) {
+        state.selection.current = Some(Selection { rect, kind: SelectionKind::Cells(placed) });
+        state.selected_group_id = None;
+    }
+    state.import.status = Some(format!("Imported {}x{} from {}", rect.width(), rect.height(), state.import.path));
+    log::info!("Imported {}x{} from {}", rect.width(), rect.height(), state.import.path);
+    state.show_import_dialog = false;
+    state.status_message = state.import.status.clone();
+}
+
+/// Load another project into the clipboard and start a pending paste, so it is placed with a
+/// click like copied cells. Its groups come along (renamed "project/group") if the dialog says so
+fn import_project_as_paste(state: &mut ApplicationState) {
+    let path = state.import.path.clone();
+    let document = match read_project(&path) {
+        Ok(document) => document,
+        Err(e) => {
+            state.import.status = Some(report("Import", &e));
+            return;
+        }
+    };
+    let Some(rect) = compute_bounding_rect(&document.cells.keys().copied().collect()) else {
+        state.import.status = Some("The project is empty".to_string());
+        return;
+    };
+
+    let offset = |(x, y): (i32, i32)| (x - rect.min_x, y - rect.min_y);
+    let project_name = std::path::Path::new(&path).file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let groups = if state.import.project_groups {
+        document
+            .groups
+            .into_iter()
+            .map(|group| ProjectGroup {
+                name: format!("{}/{}", project_name, group.name),
+                cells: group.cells.into_iter().map(offset).collect(),
+                frame: group.frame,
+                duration_ms: group.duration_ms,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    state.clipboard = Clipboard {
+        width: rect.width(),
+        height: rect.height(),
+        cells: document.cells.into_iter().map(|(coord, cell)| (offset(coord), cell)).collect(),
+        has_data: true,
+        groups,
+    };
+    state.pending_paste = true;
+    state.show_import_dialog = false;
+    state.import.status = Some(format!("Click to place {} ({}x{}); Esc cancels", path, rect.width(), rect.height()));
+    state.status_message = state.import.status.clone();
+}
+
+/// Write the selection (or the whole drawing) into the dialog's cart file as a sprite sheet,
+/// or the current workspace into a preset file
+pub fn run_export(state: &mut ApplicationState) {
+    if extension(&state.import.path) == PRESET_EXTENSION {
+        let path = state.import.path.clone();
+        state.import.status = Some(match save_preset(state, &path) {
+            Ok(()) => format!("Saved workspace preset to {}", path),
+            Err(e) => report("Export", &e),
+        });
+        return;
+    }
+    let rect = state.selection.current.as_ref().map(|sel| sel.rect).or_else(|| grid_bounds(&state.cells));
+    let Some(rect) = rect else {
+        state.import.status = Some("Nothing to export".to_string());
+        return;
+    };
+    let path = state.import.path.clone();
+    let result = match extension(&path).as_str() {
+        "p8" => export_p8(&state.cells, &rect, &path),
+        "lua" => export_tic(&state.cells, &rect, &path),
+        other => Err(FileError::Unsupported(format!("Can't export .{} files", other))),
+    };
+    state.import.status = Some(match result {
+        Ok(false) => format!("Wrote sprite sheet to {}", path),
+        Ok(true) => format!("Wrote {} (cropped to 128x128)", path),
+        Err(e) => report("Export", &e),
+    });
+}
@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+use macroquad::prelude::*;
+use crate::core::cell::{grid_bounds, CellGrid};
+use crate::core::selection::{Selection, SelectionKind, SelectionRect};
+use crate::error::report;
+use crate::core::frame_tag::LoopMode;
+use crate::core::group::Group;
+use crate::export::animation::{frame_cells, AnimationExport, AnimationFormat, SheetTag};
+use crate::export::png::{export_image, ExportBackground, ExportFormat};
+use crate::input::frames::{active_tag, frame_duration_ms, tag_frames, tagged_frames};
+use crate::project::error_log::append_error_log;
+use crate::project::backup::write_backup;
+use crate::project::recent::remember_recent;
+use crate::project::{open_project, snapshot_project, write_project};
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+
+/// Blocking file work requested by the UI. It is queued rather than run on the spot so the
+/// progress overlay naming it is on screen before the window stalls
+pub enum Job {
+    OpenProject(String),
+}
+
+impl Job {
+    pub fn label(&self) -> String {
+        match self {
+            Job::OpenProject(path) => format!("Opening {}...", path),
+        }
+    }
+}
+
+/// Run the job queued last frame, if any
+pub fn run_pending_job(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(job) = state.pending_job.take() else {
+        return;
+    };
+    match job {
+        Job::OpenProject(path) => {
+            state.status_message = Some(match open_project(state, canvas, &path) {
+                Ok(()) => {
+                    log::info!("Opened {} ({} cells)", path, state.cells.len());
+                    format!("Opened {}", path)
+                }
+                Err(e) => report("Open", &e),
+            });
+        }
+    }
+}
+
+/// Where a finished background write reports. Failures always reach the HUD
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IoReport {
+    Hud,
+    ExportDialog,
+    /// Success only goes to the log (automatic backups)
+    Quiet,
+}
+
+/// File encoding and writing running off the frame on a worker thread; the outcome arrives as
+/// a status line. Native only: the browser build has neither threads nor a filesystem, so
+/// there the work is skipped and the task fails at once rather than stall the frame encoding
+/// something it can't write
+pub struct IoTask {
+    report: IoReport,
+    /// Project path being saved, so a second save to it waits for this one
+    saving: Option<String>,
+    outcome: Receiver<Result<String, String>>,
+}
+
+fn spawn_io(report: IoReport, work: impl FnOnce() -> Result<String, String> + Send + 'static) -> IoTask {
+    let (sender, outcome) = channel();
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(move || {
+        let _ = sender.send(work());
+    });
+    #[cfg(target_arch = "wasm32")]
+    {
+        drop(work);
+        let _ = sender.send(Err("Writing files isn't available in the browser".to_string()));
+    }
+    IoTask { report, saving: None, outcome }
+}
+
+/// Save the document to `path` in the background; only the snapshot is taken this frame,
+/// the thumbnail is rendered with the write. While an earlier save to `path` is still running
+/// this one waits for it, then snapshots whatever the document is by then
+pub fn start_save(state: &mut ApplicationState, path: String) {
+    if state.io_tasks.iter().any(|task| task.saving.as_ref() == Some(&path)) {
+        state.status_message = Some(format!("Saving {} again once the current save finishes", path));
+        if !state.queued_saves.contains(&path) {
+            state.queued_saves.push(path);
+        }
+        return;
+    }
+    let mut file = snapshot_project(state);
+    state.status_message = Some(format!("Saving {}...", path));
+    let saving = Some(path.clone());
+    let task = spawn_io(IoReport::Hud, move || {
+        file.add_thumbnail();
+        match write_project(&file, &path) {
+            Ok(()) => {
+                remember_recent(&path);
+                Ok(format!("Saved {}", path))
+            }
+            Err(e) => Err(report("Save", &e)),
+        }
+    });
+    state.io_tasks.push(IoTask { saving, ..task });
+}
+
+/// Rasterize and write `rect` with the current export settings in the background.
+/// `region` exports come from a dragged export region and report its size
+pub fn start_export(state: &mut ApplicationState, rect: SelectionRect, path: String, region: bool) {
+    // Only the cells inside the rect end up in the image, so only those are copied
+    let cells: CellGrid = state.cells.iter().filter(|(&(x, y), _)| rect.contains(x, y)).map(|(&coord, &cell)| (coord, cell)).collect();
+    spawn_export(state, Arc::new(cells), rect, path, region);
+}
+
+/// Export the selected cells (the whole drawing without a selection) cropped to the filled
+/// ones, straight to the export path with the dialog's settings. The whole drawing is shared
+/// with the worker rather than copied; an edit made meanwhile copies it instead
+pub fn start_quick_export(state: &mut ApplicationState) {
+    let (cells, path): (Arc<CellGrid>, String) = match &state.selection.current {
+        Some(Selection { kind: SelectionKind::Cells(selected), .. }) => {
+            let cells = state.cells.iter().filter(|(coord, _)| selected.contains(coord)).map(|(&coord, &cell)| (coord, cell)).collect();
+            (Arc::new(cells), state.export.selection_path())
+        }
+        None => (Arc::clone(&state.cells), state.export.path.clone()),
+    };
+    match grid_bounds(&cells) {
+        Some(rect) => spawn_export(state, cells, rect, path, false),
+        None => state.status_message = Some(if state.selection.current.is_some() { "The selection is empty" } else { "Nothing to export" }.to_string()),
+    }
+}
+
+fn spawn_export(state: &mut ApplicationState, cells: Arc<CellGrid>, rect: SelectionRect, path: String, region: bool) {
+    let settings = state.export.clone();
+    let (background, aspect, metadata) = (state.secondary_color, state.cell_aspect, state.metadata.clone());
+    state.export.status = Some(format!("Exporting {}...", settings.format.label()));
+    state.io_tasks.push(spawn_io(IoReport::ExportDialog, move || match export_image(&cells, &rect, &settings, background, aspect, &metadata, &path) {
+        Ok(()) if region => Ok(format!("Saved {}x{} region to {}", rect.width(), rect.height(), path)),
+        Ok(()) => Ok(format!("Saved {}", path)),
+        Err(e) => Err(report("Export", &e)),
+    }));
+}
+
+/// Export one group's cells cropped to their bounds as a transparent PNG named after the
+/// group, beside the export path, in the background
+pub fn start_group_export(state: &mut ApplicationState, id: u32) {
+    start_groups_export(state, &[id]);
+}
+
+/// Export every group as its own PNG, as `start_group_export` does for one
+pub fn start_all_groups_export(state: &mut ApplicationState) {
+    let ids: Vec<u32> = state.groups.iter().map(|g| g.id).collect();
+    start_groups_export(state, &ids);
+}
+
+/// Copy out the filled cells of each group in `ids` and write one PNG per group; groups whose
+/// names would share a file get their id added. Scale and filter follow the export dialog
+fn start_groups_export(state: &mut ApplicationState, ids: &[u32]) {
+    let mut settings = state.export.clone();
+    settings.set_format(ExportFormat::Png);
+    settings.background = ExportBackground::Transparent;
+    let mut taken = HashSet::new();
+    let exports: Vec<(CellGrid, SelectionRect, String)> = ids
+        .iter()
+        .filter_map(|id| state.groups.iter().find(|g| g.id == *id))
+        .filter_map(|group| {
+            let cells: CellGrid = group.cells.iter().filter_map(|c| state.cells.get(c).map(|cell| (*c, *cell))).collect();
+            let rect = grid_bounds(&cells)?;
+            let mut path = settings.group_path(&group.name);
+            if !taken.insert(path.clone()) {
+                path = settings.group_path(&format!("{} {}", group.name, group.id));
+                taken.insert(path.clone());
+            }
+            Some((cells, rect, path))
+        })
+        .collect();
+    if exports.is_empty() {
+        state.status_message = Some(if state.groups.is_empty() { "No groups to export" } else { "The groups are empty" }.to_string());
+        return;
+    }
+
+    let (aspect, metadata) = (state.cell_aspect, state.metadata.clone());
+    let background = state.secondary_color;
+    state.status_message = Some(format!("Exporting {} group(s)...", exports.len()));
+    state.io_tasks.push(spawn_io(IoReport::Hud, move || {
+        for (cells, rect, path) in &exports {
+            export_image(cells, rect, &settings, background, aspect, &metadata, path).map_err(|e| report("Export", &e))?;
+        }
+        Ok(match exports.as_slice() {
+            [(_, _, path)] => format!("Saved {}", path),
+            _ => format!("Saved {} groups as PNGs", exports.len()),
+        })
+    }));
+}
+
+/// Write the active frame tag (every tagged frame without one) as an animated GIF beside the
+/// export path, looping the way the tag does, in the background
+pub fn start_gif_export(state: &mut ApplicationState) {
+    let tag = active_tag(state);
+    let loop_mode = tag.map_or(LoopMode::Forward, |tag| tag.loop_mode);
+    let ids: Vec<u32> = tag_frames(&state.groups, tag).iter().map(|g| g.id).collect();
+    start_animation_export(state, &ids, AnimationFormat::Gif { loop_mode }, state.export.gif_path());
+}
+
+/// Write every tagged frame into a sprite sheet beside the export path, with the frame tags
+/// as frame ranges in its data file, in the background
+pub fn start_sprite_sheet_export(state: &mut ApplicationState) {
+    let frames = tagged_frames(&state.groups);
+    let ids: Vec<u32> = frames.iter().map(|g| g.id).collect();
+    let tags = state
+        .frame_tags
+        .iter()
+        .filter_map(|tag| {
+            let covered: Vec<usize> = frames.iter().enumerate().filter(|(_, g)| g.frame.is_some_and(|f| tag.contains(f))).map(|(i, _)| i).collect();
+            Some(SheetTag { name: tag.name.clone(), from: *covered.first()?, to: *covered.last()?, loop_mode: tag.loop_mode })
+        })
+        .collect();
+    let (path, data_path) = state.export.sprite_sheet_paths();
+    start_animation_export(state, &ids, AnimationFormat::SpriteSheet { tags, data_path }, path);
+}
+
+/// Copy out the cells shown during each of the frame groups `ids` and write them in `format`
+fn start_animation_export(state: &mut ApplicationState, ids: &[u32], format: AnimationFormat, path: String) {
+    if ids.is_empty() {
+        state.status_message = Some("No frames to export - tag groups as frames first (right-click a group)".to_string());
+        return;
+    }
+    let tagged = tagged_frames(&state.groups);
+    let chosen: Vec<&Group> = ids.iter().filter_map(|id| tagged.iter().find(|g| g.id == *id).copied()).collect();
+    let frames = chosen.iter().map(|frame| frame_cells(&state.cells, &tagged, frame)).collect();
+    let durations = chosen.iter().map(|frame| frame_duration_ms(frame, state.frame_fps)).collect();
+    let options = state.export.raster_options(state.secondary_color, state.cell_aspect);
+    let export = AnimationExport { frames, durations, options, format };
+    state.status_message = Some(format!("Exporting {} frames...", ids.len()));
+    state.io_tasks.push(spawn_io(IoReport::Hud, move || match export.write(&path) {
+        Ok(()) => Ok(format!("Saved {}", path)),
+        Err(e) => Err(report("Export", &e)),
+    }));
+}
+
+/// Back the document up once the interval has passed, if it changed since the last backup
+pub fn update_backups(state: &mut ApplicationState) {
+    let now = get_time();
+    let interval = state.backup.interval_minutes as f64 * 60.0;
+    if interval == 0.0 || now - state.last_backup_time < interval || state.history.revision == state.backed_up_revision {
+        return;
+    }
+    state.last_backup_time = now;
+    state.backed_up_revision = state.history.revision;
+    let mut file = snapshot_project(state);
+    let (project_path, keep) = (state.project_path.clone(), state.backup.keep);
+    let wall_clock = macroquad::miniquad::date::now();
+    state.io_tasks.push(spawn_io(IoReport::Quiet, move || {
+        file.add_thumbnail();
+        match write_backup(&file, &project_path, keep, wall_clock) {
+            Ok(path) => Ok(format!("Backed up {} to {}", project_path, path.display())),
+            Err(e) => Err(report("Backup", &e)),
+        }
+    }));
+}
+
+/// Collect finished background writes and post their outcome
+pub fn poll_io_tasks(state: &mut ApplicationState) {
+    let mut finished = Vec::new();
+    state.io_tasks.retain(|task| match task.outcome.try_recv() {
+        Ok(outcome) => {
+            finished.push((task.report, outcome));
+            false
+        }
+        Err(TryRecvError::Empty) => true,
+        Err(TryRecvError::Disconnected) => {
+            let message = "Write failed: the worker stopped unexpectedly";
+            log::error!("{}", message);
+            append_error_log(message);
+            finished.push((task.report, Err(message.to_string())));
+            false
+        }
+    });
+    for (report, outcome) in finished {
+        let failed = outcome.is_err();
+        let message = outcome.unwrap_or_else(|e| e);
+        if !failed {
+            log::info!("{}", message);
+        }
+        if report == IoReport::ExportDialog {
+            state.export.status = Some(message.clone());
+        }
+        if failed || report != IoReport::Quiet {
+            state.status_message = Some(message);
+        }
+    }
+    // Saves that waited for an earlier one to the same path
+    for path in std::mem::take(&mut state.queued_saves) {
+        start_save(state, path);
+    }
+}
@@ -0,0 +1,52 @@
+use macroquad::prelude::*;
+use crate::core::shapes::line_cells;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use super::tools::paint_cells;
+
+/// Line tool: press to anchor, drag to preview, release to paint the line in the current
+/// color as one undo step. Shift snaps to the nearest 45°; Esc cancels the drag
+pub fn handle_line_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let cell = (world.x.floor() as i32, world.y.floor() as i32);
+
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        state.line_drag = Some((cell, cell));
+    }
+
+    if is_mouse_button_down(state.mouse.primary()) {
+        if let Some((start, _)) = state.line_drag {
+            let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            let end = if shift { snap_45(start, cell) } else { cell };
+            state.line_drag = Some((start, end));
+        }
+    }
+
+    if is_mouse_button_released(state.mouse.primary()) {
+        finish_line_drag(state, canvas);
+    }
+
+    if is_key_pressed(KeyCode::Escape) {
+        state.line_drag = None;
+    }
+}
+
+/// Paint the dragged line
+pub fn finish_line_drag(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if let Some((start, end)) = state.line_drag.take() {
+        paint_cells(state, line_cells(start, end), canvas);
+    }
+}
+
+/// `end` moved onto the nearest horizontal, vertical or diagonal through `start`
+fn snap_45(start: (i32, i32), end: (i32, i32)) -> (i32, i32) {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    if dx.abs() > 2 * dy.abs() {
+        (end.0, start.1)
+    } else if dy.abs() > 2 * dx.abs() {
+        (start.0, end.1)
+    } else {
+        let len = dx.abs().max(dy.abs());
+        (start.0 + len * dx.signum(), start.1 + len * dy.signum())
+    }
+}
@@ -3,8 +3,31 @@ pub mod ui;
 pub mod dispatcher;
 pub mod selection;
 pub mod clipboard;
+pub mod export_region;
+pub mod camera_motion;
+pub mod actions;
+pub mod groups;
+pub mod transform;
+pub mod line;
+pub mod rectangle;
+pub mod pen;
+pub mod curve;
+pub mod fill;
+pub mod dither;
+pub mod import;
+pub mod jobs;
+pub mod frames;
+pub mod frame_tags;
+pub mod symmetry;
+pub mod quick_mask;
+pub mod diff;
+pub mod autotile;
+pub mod generate;
+pub mod saved_selections;
+pub mod mouse;
+pub mod view_bounds;
 
 pub use ui::render_ui_buttons;
-pub use dispatcher::{handle_input, handle_zoom, apply_changes_and_record, undo_last};
-pub use selection::{handle_select_tool, delete_selection};
-pub use clipboard::*;
+pub use dispatcher::{handle_input, handle_zoom};
+pub use selection::{delete_selection, fill_selection};
+pub use camera_motion::update_camera_motion;
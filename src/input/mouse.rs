@@ -0,0 +1,46 @@
+use macroquad::prelude::MouseButton;
+
+/// Zoom factor per wheel notch, in the order View > Zoom Step cycles through them
+pub const ZOOM_STEPS: [f32; 4] = [1.05, 1.1, 1.25, 1.5];
+const DEFAULT_ZOOM_STEP: f32 = 1.1;
+
+/// How the mouse drives the canvas: which button paints, which way the wheel zooms and how far
+/// Menus, panels and dialogs always take the left button
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MouseSettings {
+    /// Paint with the right button; the left one takes the secondary action (autotile removal)
+    pub swap_buttons: bool,
+    /// Wheel up zooms out instead of in
+    pub invert_zoom: bool,
+    /// Zoom factor per wheel notch
+    pub zoom_step: f32,
+}
+
+impl Default for MouseSettings {
+    fn default() -> Self {
+        Self { swap_buttons: false, invert_zoom: false, zoom_step: DEFAULT_ZOOM_STEP }
+    }
+}
+
+impl MouseSettings {
+    /// Button that paints, selects and drags
+    pub fn primary(&self) -> MouseButton {
+        if self.swap_buttons { MouseButton::Right } else { MouseButton::Left }
+    }
+
+    /// Button for a tool's alternate action
+    pub fn secondary(&self) -> MouseButton {
+        if self.swap_buttons { MouseButton::Left } else { MouseButton::Right }
+    }
+
+    /// Zoom factor for one wheel movement of `scroll_y`
+    pub fn zoom_factor(&self, scroll_y: f32) -> f32 {
+        if (scroll_y > 0.0) != self.invert_zoom { self.zoom_step } else { 1.0 / self.zoom_step }
+    }
+
+    /// The zoom step after the current one in ZOOM_STEPS (the first if it isn't listed)
+    pub fn next_zoom_step(&self) -> f32 {
+        let index = ZOOM_STEPS.iter().position(|&step| step == self.zoom_step);
+        ZOOM_STEPS[index.map_or(0, |i| (i + 1) % ZOOM_STEPS.len())]
+    }
+}
@@ -0,0 +1,50 @@
+use macroquad::prelude::*;
+use crate::core::shapes::{polygon_fill_cells, polyline_cells};
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::focus;
+use super::tools::paint_cells;
+
+/// Max gap between two clicks on the same cell to count as a double-click
+const DOUBLE_CLICK_SECONDS: f64 = 0.35;
+
+/// Pen tool: each click places an anchor joined to the previous one by a line
+/// Double-clicking closes the shape (filled if `pen_fill` is on), Enter keeps it open,
+/// Esc discards the anchors. The finished outline is one undo step
+pub fn handle_pen_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let cell_coords = (world.x.floor() as i32, world.y.floor() as i32);
+
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        let now = get_time();
+        let is_double = matches!(state.canvas_last_click, Some((cell, time)) if cell == cell_coords && now - time < DOUBLE_CLICK_SECONDS);
+        state.canvas_last_click = Some((cell_coords, now));
+
+        if is_double && !state.pen_anchors.is_empty() {
+            state.canvas_last_click = None;
+            finish_pen_shape(state, canvas, true);
+        } else if state.pen_anchors.last() != Some(&cell_coords) {
+            state.pen_anchors.push(cell_coords);
+        }
+    }
+
+    if focus::enter_pressed() {
+        finish_pen_shape(state, canvas, false);
+    }
+    if is_key_pressed(KeyCode::Escape) {
+        state.pen_anchors.clear();
+    }
+}
+
+/// Rasterize the anchors with the current color and start a new shape
+fn finish_pen_shape(state: &mut ApplicationState, canvas: &mut CanvasRenderer, closed: bool) {
+    let anchors = std::mem::take(&mut state.pen_anchors);
+    if anchors.is_empty() {
+        return;
+    }
+    let mut cells = polyline_cells(&anchors, closed);
+    if closed && state.pen_fill {
+        cells.extend(polygon_fill_cells(&anchors));
+    }
+    paint_cells(state, cells, canvas);
+}
@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+use crate::core::selection::{compute_bounding_rect, Selection, SelectionKind};
+use crate::input::groups::sync_selected_group_from_selection;
+use crate::state::{ApplicationState, Mode};
+
+/// Enter quick mask mode (the selection becomes the mask, and the brush, eraser and spray
+/// paint the mask instead of the drawing) or leave it (the mask becomes the selection)
+pub fn toggle_quick_mask(state: &mut ApplicationState) {
+    match state.quick_mask.take() {
+        Some(mask) => {
+            let count = mask.len();
+            state.selection.current = compute_bounding_rect(&mask).map(|rect| Selection { rect, kind: SelectionKind::Cells(mask) });
+            sync_selected_group_from_selection(state);
+            state.status_message = Some(match count {
+                0 => "Quick mask off: nothing masked".to_string(),
+                n => format!("Quick mask off: {} cells selected", n),
+            });
+        }
+        None => {
+            if state.selection.is_moving {
+                return;
+            }
+            let mask: HashSet<(i32, i32)> = match state.selection.current.take() {
+                Some(Selection { kind: SelectionKind::Cells(set), .. }) => set,
+                None => HashSet::new(),
+            };
+            state.quick_mask = Some(mask);
+            state.selected_group_id = None;
+            if !matches!(state.mode, Mode::Paint | Mode::Erase | Mode::Spray) {
+                state.mode = Mode::Paint;
+            }
+            state.status_message = Some("Quick mask: paint to add, erase to remove, Q to select".to_string());
+        }
+    }
+}
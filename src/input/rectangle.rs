@@ -0,0 +1,49 @@
+use macroquad::prelude::*;
+use crate::core::shapes::rect_cells;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use super::tools::paint_cells;
+
+/// Rectangle tool: drag from one corner to the other, release to paint the outline (filled
+/// when `rectangle_fill` is on) in the current color as one undo step. Shift makes it a
+/// square; Esc cancels the drag
+pub fn handle_rectangle_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let cell = (world.x.floor() as i32, world.y.floor() as i32);
+
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        state.rectangle_drag = Some((cell, cell));
+    }
+
+    if is_mouse_button_down(state.mouse.primary()) {
+        if let Some((start, _)) = state.rectangle_drag {
+            let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            let end = if shift { square_corner(start, cell) } else { cell };
+            state.rectangle_drag = Some((start, end));
+        }
+    }
+
+    if is_mouse_button_released(state.mouse.primary()) {
+        finish_rectangle_drag(state, canvas);
+    }
+
+    if is_key_pressed(KeyCode::Escape) {
+        state.rectangle_drag = None;
+    }
+}
+
+/// Paint the dragged rectangle
+pub fn finish_rectangle_drag(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if let Some((start, end)) = state.rectangle_drag.take() {
+        paint_cells(state, rect_cells(start, end, state.rectangle_fill), canvas);
+    }
+}
+
+/// Far corner of the square from `start` toward `end`, as big as the longer side of the drag
+fn square_corner(start: (i32, i32), end: (i32, i32)) -> (i32, i32) {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let side = dx.abs().max(dy.abs());
+    // A straight drag along one axis still grows the square downward or rightward
+    let sign = |d: i32| if d < 0 { -1 } else { 1 };
+    (start.0 + side * sign(dx), start.1 + side * sign(dy))
+}
@@ -0,0 +1,49 @@
+use std::collections::HashSet;
+use crate::core::selection::{compute_bounding_rect, Selection, SelectionKind};
+use crate::input::groups::sync_selected_group_from_selection;
+use crate::project::format::ProjectSelection;
+use crate::state::ApplicationState;
+
+/// Store the current selection's cells under a fresh name and open the list so it can be renamed
+pub fn save_selection(state: &mut ApplicationState) {
+    let Some(Selection { kind: SelectionKind::Cells(cells), .. }) = &state.selection.current else {
+        state.status_message = Some("Select something to save first".to_string());
+        return;
+    };
+    if state.selection.is_moving {
+        return;
+    }
+    let taken: HashSet<&str> = state.saved_selections.iter().map(|s| s.name.as_str()).collect();
+    let name = (1..).map(|n| format!("Selection {}", n)).find(|name| !taken.contains(name.as_str())).unwrap_or_default();
+    state.status_message = Some(format!("Saved {} cells as {}", cells.len(), name));
+    state.saved_selections.push(ProjectSelection::new(name, cells));
+    state.show_saved_selections = true;
+}
+
+/// Make the saved selection at `index` the current selection
+pub fn activate_saved_selection(state: &mut ApplicationState, index: usize) {
+    let Some(saved) = state.saved_selections.get(index) else {
+        return;
+    };
+    if state.selection.is_moving {
+        return;
+    }
+    let cells: HashSet<(i32, i32)> = saved.cells.iter().copied().collect();
+    let name = saved.name.clone();
+    let Some(rect) = compute_bounding_rect(&cells) else {
+        state.status_message = Some(format!("{} is empty", name));
+        return;
+    };
+    state.selection.current = Some(Selection { rect, kind: SelectionKind::Cells(cells) });
+    sync_selected_group_from_selection(state);
+    state.status_message = Some(format!("Selected {}", name));
+}
+
+/// Forget the saved selection at `index`
+pub fn delete_saved_selection(state: &mut ApplicationState, index: usize) {
+    if index < state.saved_selections.len() {
+        let removed = state.saved_selections.remove(index);
+        state.saved_selection_rename = None;
+        state.status_message = Some(format!("Deleted saved selection {}", removed.name));
+    }
+}
@@ -1,8 +1,17 @@
+use std::sync::Arc;
 use macroquad::prelude::*;
 use std::collections::HashSet;
-use crate::state::ApplicationState;
+use crate::state::{ApplicationState, CellChange};
+use crate::core::cell::Cell;
+use crate::core::merge::{feather_cell, feather_weights};
+use crate::input::dispatcher::apply_changes_and_record;
+use crate::collab::share_changes;
+use crate::input::groups::{remove_cells_from_groups, select_group, sync_selected_group_from_selection, update_membership_on_move};
 use crate::rendering::CanvasRenderer;
-use crate::core::selection::{SelectionKind, Selection, SelectionRect, compute_bounding_rect, LiftedCell};
+use crate::core::selection::{SelectionKind, Selection, SelectionRect, compute_bounding_rect, filled_cells_in_rect, LiftedCell};
+
+/// Max gap between two clicks on the same cell to count as a double-click
+const DOUBLE_CLICK_SECONDS: f64 = 0.35;
 
 pub fn handle_select_tool(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
     let screen_mouse_pos = Vec2::from(mouse_position());
@@ -10,8 +19,27 @@ pub fn handle_select_tool(state: &mut ApplicationState, canvas: &mut CanvasRende
     let cell_coords = (world_mouse_pos.x.floor() as i32, world_mouse_pos.y.floor() as i32);
     let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
 
+    // The gizmo's handles sit on the selection's edge, so they win over moving it
+    if is_mouse_button_pressed(state.mouse.primary()) && crate::input::transform::grab_selection_gizmo(state) {
+        return;
+    }
+
+    // Double-click on a grouped cell selects the whole group
+    if is_mouse_button_pressed(state.mouse.primary()) {
+        let now = get_time();
+        let is_double = matches!(state.canvas_last_click, Some((cell, time)) if cell == cell_coords && now - time < DOUBLE_CLICK_SECONDS);
+        state.canvas_last_click = Some((cell_coords, now));
+        if is_double {
+            if let Some(&id) = state.group_index.get(&cell_coords) {
+                select_group(state, id);
+                state.canvas_last_click = None;
+                return;
+            }
+        }
+    }
+
     // Mouse pressed: start drag or move
-    if is_mouse_button_pressed(MouseButton::Left) {
+    if is_mouse_button_pressed(state.mouse.primary()) {
         if state.selection.contains_point(cell_coords.0, cell_coords.1) {
             // Click inside selection → start move with lift
             start_move_with_lift(state, canvas, (world_mouse_pos.x, world_mouse_pos.y));
@@ -22,12 +50,12 @@ pub fn handle_select_tool(state: &mut ApplicationState, canvas: &mut CanvasRende
     }
 
     // During drag: update end point
-    if state.selection.active_drag && is_mouse_button_down(MouseButton::Left) {
+    if state.selection.active_drag && is_mouse_button_down(state.mouse.primary()) {
         state.selection.update_drag_end(cell_coords);
     }
 
     // During move: accumulate delta
-    if state.selection.is_moving && is_mouse_button_down(MouseButton::Left) {
+    if state.selection.is_moving && is_mouse_button_down(state.mouse.primary()) {
         if let Some((prev_x, prev_y)) = state.selection.last_move_mouse {
             let delta_x = world_mouse_pos.x - prev_x;
             let delta_y = world_mouse_pos.y - prev_y;
@@ -37,7 +65,7 @@ pub fn handle_select_tool(state: &mut ApplicationState, canvas: &mut CanvasRende
     }
 
     // Mouse released: finalize
-    if is_mouse_button_released(MouseButton::Left) {
+    if is_mouse_button_released(state.mouse.primary()) {
         if state.selection.is_moving {
             drop_lifted(state, canvas);
         } else if state.selection.active_drag {
@@ -57,15 +85,25 @@ pub fn handle_select_tool(state: &mut ApplicationState, canvas: &mut CanvasRende
                         state.selection.current = Some(Selection {
                             rect,
                             kind: SelectionKind::Cells(set),
-                            preview: None,
                         });
                     }
+                    sync_selected_group_from_selection(state);
                 }
             }
         }
     }
 }
 
+/// Complete a move or marquee drag whose mouse release happened while another tool had input
+pub fn finish_interrupted_drag(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    if state.selection.is_moving {
+        drop_lifted(state, canvas);
+    } else if state.selection.active_drag {
+        finalize_selection_drag_tight(state, shift);
+    }
+}
+
 /// Finalize drag with tight bounding box (only filled cells) and optional Shift-additive selection
 fn finalize_selection_drag_tight(state: &mut ApplicationState, additive: bool) {
     state.selection.active_drag = false;
@@ -74,16 +112,12 @@ fn finalize_selection_drag_tight(state: &mut ApplicationState, additive: bool) {
         let rect = SelectionRect::from_points(start, end);
 
         // Pick only filled cells within drag rect
-        let mut picked: HashSet<(i32, i32)> = HashSet::new();
-        for (&coord, cell) in state.cells.iter() {
-            if cell.is_filled && rect.contains(coord.0, coord.1) {
-                picked.insert(coord);
-            }
-        }
+        let picked = filled_cells_in_rect(&state.cells, &rect);
 
         if picked.is_empty() {
             if !additive {
                 state.selection.current = None;
+                state.selected_group_id = None;
             }
             return;
         }
@@ -91,11 +125,8 @@ fn finalize_selection_drag_tight(state: &mut ApplicationState, additive: bool) {
         // Union for additive (Shift), replace otherwise
         let final_set = if additive {
             if let Some(sel) = &state.selection.current {
-                if let SelectionKind::Cells(existing) = &sel.kind {
-                    existing.union(&picked).cloned().collect()
-                } else {
-                    picked
-                }
+                let SelectionKind::Cells(existing) = &sel.kind;
+                existing.union(&picked).cloned().collect()
             } else {
                 picked
             }
@@ -105,23 +136,12 @@ fn finalize_selection_drag_tight(state: &mut ApplicationState, additive: bool) {
 
         // Compute tight bounding rect
         if let Some(rect) = compute_bounding_rect(&final_set) {
-            let mut selection = Selection {
+            state.selection.current = Some(Selection {
                 rect,
                 kind: SelectionKind::Cells(final_set),
-                preview: None,
-            };
-
-            // Build preview for the new selection
-            if let SelectionKind::Cells(ref cell_set) = selection.kind {
-                selection.preview = crate::rendering::selection::build_selection_preview(
-                    &state.cells,
-                    &selection.rect,
-                    cell_set
-                );
-            }
-
-            state.selection.current = Some(selection);
+            });
         }
+        sync_selected_group_from_selection(state);
     }
 }
 
@@ -131,28 +151,23 @@ fn start_move_with_lift(state: &mut ApplicationState, canvas: &mut CanvasRendere
         return;
     }
 
-    if let Some(sel) = &mut state.selection.current {
-        // Ensure preview exists before moving
-        if sel.preview.is_none() {
-            if let SelectionKind::Cells(ref cell_set) = sel.kind {
-                sel.preview = crate::rendering::selection::build_selection_preview(
-                    &state.cells,
-                    &sel.rect,
-                    cell_set
-                );
-            }
-        }
+    if let Some(sel) = &state.selection.current {
+        let SelectionKind::Cells(set) = &sel.kind;
+        // Build the preview texture before the cells leave the canvas
+        state.selection_preview = crate::rendering::selection::build_selection_preview(
+            &state.cells,
+            &sel.rect,
+            set
+        );
 
-        if let SelectionKind::Cells(set) = &sel.kind {
-            state.selection.lifted_cells.clear();
-            for &(x, y) in set.iter() {
-                if let Some(cell) = state.cells.remove(&(x, y)) {
-                    state.selection.lifted_cells.push(LiftedCell {
-                        coord: (x, y),
-                        cell,
-                    });
-                    canvas.mark_dirty((x, y));
-                }
+        state.selection.lifted_cells.clear();
+        for &(x, y) in set.iter() {
+            if let Some(cell) = Arc::make_mut(&mut state.cells).remove(&(x, y)) {
+                state.selection.lifted_cells.push(LiftedCell {
+                    coord: (x, y),
+                    cell,
+                });
+                canvas.mark_dirty((x, y));
             }
         }
 
@@ -164,7 +179,7 @@ fn start_move_with_lift(state: &mut ApplicationState, canvas: &mut CanvasRendere
     }
 }
 
-/// Drop lifted cells: reinserts at new snapped position and updates selection
+/// Drop lifted cells: reinserts at new snapped position (edges faded per `feather`) and updates selection
 fn drop_lifted(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> Option<(i32, i32)> {
     if !state.selection.is_lifted {
         return None;
@@ -174,11 +189,30 @@ fn drop_lifted(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> Opt
     let dy = state.selection.move_offset_y.round() as i32;
 
     let mut new_set: HashSet<(i32, i32)> = HashSet::new();
+    let mut moved = Vec::new();
+    // A click without a drag puts the cells back untouched
+    let feather = if dx != 0 || dy != 0 { state.feather } else { 0 };
+    let shape: HashSet<(i32, i32)> = state.selection.lifted_cells.iter().map(|l| l.coord).collect();
+    let weights = feather_weights(&shape, feather);
     for lifted in state.selection.lifted_cells.drain(..) {
         let dest = (lifted.coord.0 + dx, lifted.coord.1 + dy);
-        state.cells.insert(dest, lifted.cell);
+        let cell = match weights.get(&lifted.coord) {
+            Some(&weight) => feather_cell(state.cells.get(&dest).copied(), lifted.cell, weight),
+            None => lifted.cell,
+        };
+        Arc::make_mut(&mut state.cells).insert(dest, cell);
         canvas.mark_dirty(dest);
         new_set.insert(dest);
+        moved.push((lifted.coord, dest));
+    }
+    // Group membership travels with the cells
+    if dx != 0 || dy != 0 {
+        update_membership_on_move(state, &moved);
+        // Removals first so overlapping sources and destinations arrive in the right order
+        let removals = moved.iter().map(|&(from, _)| CellChange { coord: from, before: None, after: None });
+        let inserts = moved.iter().map(|&(_, to)| CellChange { coord: to, before: None, after: state.cells.get(&to).copied() });
+        let changes: Vec<CellChange> = removals.chain(inserts).collect();
+        share_changes(state, &changes, false);
     }
 
     if let Some(sel) = &mut state.selection.current {
@@ -186,8 +220,8 @@ fn drop_lifted(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> Opt
         if let Some(rect) = compute_bounding_rect(&new_set) {
             sel.rect = rect;
         }
-        sel.preview = None; // Lazily rebuild on next move if needed
     }
+    state.selection_preview = None;
 
     state.selection.is_lifted = false;
     state.selection.is_moving = false;
@@ -200,14 +234,58 @@ fn drop_lifted(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> Opt
 /// Delete selected cells (called from dispatcher with canvas access)
 pub fn delete_selection(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
     if let Some(sel) = &state.selection.current {
-        if let SelectionKind::Cells(coords) = &sel.kind {
-            for &coord in coords {
-                if state.cells.remove(&coord).is_some() {
-                    canvas.mark_dirty(coord);
-                }
+        let mut removed = Vec::new();
+        let SelectionKind::Cells(coords) = &sel.kind;
+        for &coord in coords {
+            if Arc::make_mut(&mut state.cells).remove(&coord).is_some() {
+                canvas.mark_dirty(coord);
+                removed.push(coord);
             }
         }
+        remove_cells_from_groups(state, &removed);
+        let changes: Vec<CellChange> = removed.iter().map(|&coord| CellChange { coord, before: None, after: None }).collect();
+        share_changes(state, &changes, false);
         state.selection.current = None;
+        state.selected_group_id = None;
         state.selection.is_moving = false;
     }
 }
+
+/// Fill the selection with the current color as one undoable command
+/// `whole_rect` fills every cell of the bounding rect (holes included); otherwise only the
+/// selection's exact cell set is recolored; alpha lock leaves empty cells empty. The selection
+/// then covers everything filled
+pub fn fill_selection(state: &mut ApplicationState, canvas: &mut CanvasRenderer, whole_rect: bool) {
+    if state.selection.is_moving {
+        return;
+    }
+    let Some(sel) = &state.selection.current else {
+        return;
+    };
+
+    let targets: HashSet<(i32, i32)> = if whole_rect {
+        let rect = sel.rect;
+        (rect.min_y..=rect.max_y)
+            .flat_map(|y| (rect.min_x..=rect.max_x).map(move |x| (x, y)))
+            .collect()
+    } else {
+        match &sel.kind {
+            SelectionKind::Cells(set) => set.clone(),
+        }
+    };
+
+    let fill = Cell::with_color(state.current_color);
+    let changes: Vec<CellChange> = targets
+        .iter()
+        .filter(|coord| state.cells.get(coord) != Some(&fill))
+        .filter(|coord| !state.alpha_lock || state.cells.get(coord).is_some_and(|cell| cell.is_filled))
+        .map(|&coord| CellChange { coord, before: None, after: Some(fill) })
+        .collect();
+    if !changes.is_empty() {
+        apply_changes_and_record(state, canvas, if whole_rect { "Fill Selection Rect" } else { "Fill Selection" }, changes);
+    }
+
+    if let Some(rect) = compute_bounding_rect(&targets) {
+        state.selection.current = Some(Selection { rect, kind: SelectionKind::Cells(targets) });
+    }
+}
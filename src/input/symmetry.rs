@@ -0,0 +1,65 @@
+use macroquad::prelude::*;
+use crate::core::symmetry::Symmetry;
+use crate::state::ApplicationState;
+
+/// Screen distance from the pivot handle within which a press grabs it
+pub const PIVOT_GRAB_RADIUS: f32 = 10.0;
+
+/// Whether the mouse is over the symmetry pivot handle (only shown while symmetry is on)
+pub fn pivot_hovered(state: &ApplicationState) -> bool {
+    if !state.symmetry.is_active() {
+        return false;
+    }
+    let (cx, cy) = state.symmetry.center;
+    let handle = state.camera.world_to_screen(Vec2::new(cx, cy));
+    handle.distance(Vec2::from(mouse_position())) <= PIVOT_GRAB_RADIUS
+}
+
+/// Drag the symmetry pivot: a press on its handle moves it (snapped to cell edges and
+/// centers) until release, whatever the tool. Returns true while the drag owns the mouse
+pub fn handle_symmetry_drag(state: &mut ApplicationState) -> bool {
+    if is_mouse_button_pressed(state.mouse.primary()) && pivot_hovered(state) {
+        state.symmetry_dragging = true;
+    }
+    if !state.symmetry_dragging {
+        return false;
+    }
+
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    state.symmetry.set_center((world.x, world.y));
+    if !is_mouse_button_down(state.mouse.primary()) {
+        state.symmetry_dragging = false;
+        let (cx, cy) = state.symmetry.center;
+        state.status_message = Some(format!("Symmetry pivot at ({}, {})", cx, cy));
+    }
+    true
+}
+
+/// Toggle one mirror axis; turning symmetry on from off puts the pivot in the middle of the view
+pub fn toggle_symmetry_axis(state: &mut ApplicationState, axis: fn(&mut Symmetry) -> &mut bool) {
+    let was_active = state.symmetry.is_active();
+    let on = axis(&mut state.symmetry);
+    *on = !*on;
+    if !was_active && state.symmetry.is_active() {
+        center_symmetry_on_view(state);
+    }
+}
+
+/// Step through the radial segment counts
+pub fn cycle_symmetry_segments(state: &mut ApplicationState) {
+    let was_active = state.symmetry.is_active();
+    state.symmetry.segments = state.symmetry.next_segments();
+    if !was_active && state.symmetry.is_active() {
+        center_symmetry_on_view(state);
+    }
+    state.status_message = Some(match state.symmetry.segments {
+        1 => "Radial symmetry off".to_string(),
+        n => format!("Radial symmetry: {} segments", n),
+    });
+}
+
+/// Move the pivot to the cell edge nearest the middle of the screen
+pub fn center_symmetry_on_view(state: &mut ApplicationState) {
+    let world = state.camera.screen_to_cell(Vec2::new(screen_width() / 2.0, screen_height() / 2.0));
+    state.symmetry.set_center((world.x, world.y));
+}
@@ -1,69 +1,95 @@
+use std::sync::Arc;
 use macroquad::prelude::*;
-use crate::state::ApplicationState;
+use crate::state::{ApplicationState, CellChange, Command};
 use crate::core::*;
+use crate::core::brush::scatter_offsets;
+use crate::core::color::Rgba;
+use crate::core::shapes::line_cells;
+use crate::core::selection::SelectionRect;
+use crate::input::actions::shift_down;
+use crate::input::dispatcher::record_command;
 use crate::rendering::CanvasRenderer;
 
-/// Bresenham line algorithm - returns all grid cells between two points
-fn bresenham(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
-    let mut cells = Vec::new();
-    let (mut x0, mut y0) = from;
-    let (x1, y1) = to;
-
-    let dx = (x1 - x0).abs();
-    let dy = (y1 - y0).abs();
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let sy = if y0 < y1 { 1 } else { -1 };
-    let mut err = dx - dy;
-
-    loop {
-        cells.push((x0, y0));
-
-        if x0 == x1 && y0 == y1 {
-            break;
-        }
-
-        let e2 = 2 * err;
-        if e2 > -dy {
-            err -= dy;
-            x0 += sx;
-        }
-        if e2 < dx {
-            err += dx;
-            y0 += sy;
-        }
-    }
-
-    cells
-}
-
 /// Set a cell to a specific color or clear it (None = erase)
 /// This is the unified abstraction for both painting and erasing
-fn set_cell(
+/// Returns the applied change, or None if the cell was already in that state (or is empty
+/// and alpha lock keeps it that way)
+pub fn set_cell(
     state: &mut ApplicationState,
     cell_coords: (i32, i32),
     new_cell: Option<Cell>,
     canvas_renderer: &mut CanvasRenderer,
-) {
+) -> Option<CellChange> {
     match new_cell {
         Some(cell) => {
             // Painting: check if we're actually changing the cell
             let needs_update = match state.cells.get(&cell_coords) {
-                Some(existing_cell) => existing_cell.color != cell.color,
-                None => true,
+                Some(existing_cell) => existing_cell.color != cell.color && (existing_cell.is_filled || !state.alpha_lock),
+                None => !state.alpha_lock,
             };
 
             if needs_update {
-                state.cells.insert(cell_coords, cell);
+                let before = Arc::make_mut(&mut state.cells).insert(cell_coords, cell);
                 canvas_renderer.mark_dirty(cell_coords);
+                return Some(CellChange { coord: cell_coords, before, after: Some(cell) });
             }
+            None
         }
         None => {
             // Erasing: remove cell if it exists
-            if state.cells.remove(&cell_coords).is_some() {
+            let before = Arc::make_mut(&mut state.cells).remove(&cell_coords);
+            if before.is_some() {
                 canvas_renderer.mark_dirty(cell_coords);
+                return Some(CellChange { coord: cell_coords, before, after: None });
             }
+            None
+        }
+    }
+}
+
+/// `set_cell` for freehand strokes: applies to the cell and its symmetry copies, adding the
+/// changes to the in-progress stroke. In quick mask mode the mask is painted instead
+fn set_stroke_cell(state: &mut ApplicationState, cell_coords: (i32, i32), new_cell: Option<Cell>, canvas_renderer: &mut CanvasRenderer) {
+    if let Some(mask) = &mut state.quick_mask {
+        for coords in state.symmetry.images(cell_coords) {
+            if new_cell.is_some() {
+                mask.insert(coords);
+            } else {
+                mask.remove(&coords);
+            }
+        }
+        return;
+    }
+    for coords in state.symmetry.images(cell_coords) {
+        if let Some(change) = set_cell(state, coords, new_cell, canvas_renderer) {
+            state.stroke_changes.push(change);
+        }
+    }
+}
+
+/// Push the in-progress stroke onto the history as a single command, logged under the current tool
+pub fn commit_stroke(state: &mut ApplicationState) {
+    if !state.stroke_changes.is_empty() {
+        let changes = std::mem::take(&mut state.stroke_changes);
+        let kind = format!("{:?}", state.mode);
+        record_command(state, &kind, Command { changes });
+    }
+}
+
+/// Paint the given cells with the current color as a single undo command
+pub fn paint_cells(state: &mut ApplicationState, cells: impl IntoIterator<Item = (i32, i32)>, canvas_renderer: &mut CanvasRenderer) {
+    let color = state.current_color;
+    paint_colored_cells(state, cells.into_iter().map(|coords| (coords, color)), canvas_renderer);
+}
+
+/// Paint each cell with its own color as a single undo command
+pub fn paint_colored_cells(state: &mut ApplicationState, cells: impl IntoIterator<Item = ((i32, i32), Rgba)>, canvas_renderer: &mut CanvasRenderer) {
+    for (coords, color) in cells {
+        if let Some(change) = set_cell(state, coords, Some(Cell::with_color(color)), canvas_renderer) {
+            state.stroke_changes.push(change);
         }
     }
+    commit_stroke(state);
 }
 
 /// Handle mouse input for painting or erasing with stroke interpolation
@@ -76,7 +102,7 @@ pub fn perform_drawing(
     let cell_coords = (mouse_world.x.floor() as i32, mouse_world.y.floor() as i32);
 
     // Mouse just pressed - start new stroke
-    if is_mouse_button_pressed(MouseButton::Left) {
+    if is_mouse_button_pressed(state.mouse.primary()) {
         state.last_painted_cell = Some(cell_coords);
 
         let new_cell = if is_erasing {
@@ -84,13 +110,13 @@ pub fn perform_drawing(
         } else {
             Some(Cell::with_color(state.current_color))
         };
-        set_cell(state, cell_coords, new_cell, canvas_renderer);
+        set_stroke_cell(state, cell_coords, new_cell, canvas_renderer);
     }
     // Mouse held - interpolate stroke
-    else if is_mouse_button_down(MouseButton::Left) {
+    else if is_mouse_button_down(state.mouse.primary()) {
         if let Some(last_cell) = state.last_painted_cell {
             // Interpolate all cells between last and current
-            let cells_to_paint = bresenham(last_cell, cell_coords);
+            let cells_to_paint = line_cells(last_cell, cell_coords);
 
             for coords in cells_to_paint {
                 let new_cell = if is_erasing {
@@ -98,14 +124,77 @@ pub fn perform_drawing(
                 } else {
                     Some(Cell::with_color(state.current_color))
                 };
-                set_cell(state, coords, new_cell, canvas_renderer);
+                set_stroke_cell(state, coords, new_cell, canvas_renderer);
             }
 
             state.last_painted_cell = Some(cell_coords);
         }
     }
     // Mouse released - end stroke
-    else if is_mouse_button_released(MouseButton::Left) {
+    else if is_mouse_button_released(state.mouse.primary()) {
         state.last_painted_cell = None;
+        commit_stroke(state);
+    }
+}
+
+/// Handle mouse input for the spray brush
+/// Scatters cells around the cursor every frame while held; the whole stroke is one undo step
+pub fn perform_spray(
+    state: &mut ApplicationState,
+    mouse_world: &Vec2,
+    canvas_renderer: &mut CanvasRenderer,
+) {
+    let center = (mouse_world.x.floor() as i32, mouse_world.y.floor() as i32);
+
+    if is_mouse_button_down(state.mouse.primary()) {
+        let offsets = scatter_offsets(&mut state.spray_rng, state.spray_radius, state.spray_density);
+        for (dx, dy) in offsets {
+            let coords = (center.0 + dx, center.1 + dy);
+            let new_cell = Some(Cell::with_color(state.current_color));
+            set_stroke_cell(state, coords, new_cell, canvas_renderer);
+        }
+    }
+
+    if is_mouse_button_released(state.mouse.primary()) {
+        commit_stroke(state);
+    }
+}
+
+/// Shift+drag in Erase mode: drag a rectangle and clear every cell inside it on release
+/// Returns true while the gesture owns the mouse so the regular eraser stays out of the way
+pub fn perform_erase_rect(state: &mut ApplicationState, mouse_world: &Vec2, canvas_renderer: &mut CanvasRenderer) -> bool {
+    let cell_coords = (mouse_world.x.floor() as i32, mouse_world.y.floor() as i32);
+
+    if is_mouse_button_pressed(state.mouse.primary()) && shift_down() {
+        state.erase_rect_drag = Some((cell_coords, cell_coords));
+        return true;
+    }
+
+    let Some((start, _)) = state.erase_rect_drag else {
+        return false;
+    };
+
+    if is_mouse_button_down(state.mouse.primary()) {
+        state.erase_rect_drag = Some((start, cell_coords));
+    }
+    if is_mouse_button_released(state.mouse.primary()) {
+        finish_erase_rect(state, canvas_renderer);
+    }
+    if is_key_pressed(KeyCode::Escape) {
+        state.erase_rect_drag = None;
+    }
+    true
+}
+
+/// Clear the dragged rect (if any) as a single undo command
+pub fn finish_erase_rect(state: &mut ApplicationState, canvas_renderer: &mut CanvasRenderer) {
+    if let Some((start, end)) = state.erase_rect_drag.take() {
+        let rect = SelectionRect::from_points(start, end);
+        let removed = clear_rect(Arc::make_mut(&mut state.cells), &rect);
+        canvas_renderer.mark_rect_dirty(&rect);
+        state.stroke_changes.extend(
+            removed.into_iter().map(|(coord, cell)| CellChange { coord, before: Some(cell), after: None }),
+        );
+        commit_stroke(state);
     }
 }
@@ -0,0 +1,473 @@
+use std::sync::Arc;
+use macroquad::prelude::*;
+use std::collections::HashSet;
+use crate::state::{ApplicationState, CellChange, Command, Confirm, Mode};
+use crate::core::cell::{grid_bounds, Cell, CellGrid};
+use crate::core::selection::{compute_bounding_rect, Selection, SelectionKind, SelectionRect};
+use crate::core::transform::{invert, rect_center, resample_nearest, SelectionTransform, MAX_SCALE, MAX_SHEAR};
+use crate::rendering::CanvasRenderer;
+use super::dispatcher::{apply_changes_and_record, record_command};
+use super::groups::{remove_cells_from_groups, update_membership_on_move};
+
+/// Move the whole document by (dx, dy) as one undoable command
+/// Groups, the selection and the camera move along so nothing appears to change on screen;
+/// undo restores the cells only
+pub fn translate_document(state: &mut ApplicationState, canvas: &mut CanvasRenderer, dx: i32, dy: i32) {
+    if move_document(state, canvas, dx, dy) {
+        state.camera.pan_by(Vec2::new(dx as f32, dy as f32));
+    }
+}
+
+/// Shift everything by (dx, dy) cells with the camera left in place, so the drawing visibly
+/// moves; reports how many cells end up outside the document bounds when those are set
+pub fn shift_document(state: &mut ApplicationState, canvas: &mut CanvasRenderer, dx: i32, dy: i32) {
+    if !move_document(state, canvas, dx, dy) {
+        state.status_message = Some("Nothing shifted".to_string());
+        return;
+    }
+    let mut message = format!("Shifted everything by ({}, {})", dx, dy);
+    if let Some(bounds) = state.document_bounds {
+        let outside = state.cells.keys().filter(|&&(x, y)| !bounds.contains(x, y)).count();
+        if outside > 0 {
+            message.push_str(&format!(" - {} cells outside the document bounds", outside));
+        }
+    }
+    state.status_message = Some(message);
+}
+
+/// Shared body of `translate_document` and `shift_document`; false if nothing moved
+fn move_document(state: &mut ApplicationState, canvas: &mut CanvasRenderer, dx: i32, dy: i32) -> bool {
+    if (dx == 0 && dy == 0) || state.selection.is_moving {
+        return false;
+    }
+
+    let cmd = Command::translate(&state.cells, dx, dy);
+    cmd.apply(Arc::make_mut(&mut state.cells));
+    for ch in cmd.changes.iter() {
+        canvas.mark_dirty(ch.coord);
+    }
+    record_command(state, "Translate Document", cmd);
+
+    let moved: Vec<_> = state.group_index.keys().map(|&(x, y)| ((x, y), (x + dx, y + dy))).collect();
+    update_membership_on_move(state, &moved);
+
+    if let Some(sel) = &mut state.selection.current {
+        let r = sel.rect;
+        sel.rect = SelectionRect { min_x: r.min_x + dx, min_y: r.min_y + dy, max_x: r.max_x + dx, max_y: r.max_y + dy };
+        let SelectionKind::Cells(set) = &mut sel.kind;
+        *set = set.iter().map(|&(x, y)| (x + dx, y + dy)).collect::<HashSet<_>>();
+    }
+    true
+}
+
+/// Shift the drawing so its bounding box starts at (0, 0)
+pub fn recenter_document(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    match grid_bounds(&state.cells) {
+        Some(bounds) => {
+            translate_document(state, canvas, -bounds.min_x, -bounds.min_y);
+            state.status_message = Some("Drawing moved to (0, 0)".to_string());
+        }
+        None => state.status_message = Some("Nothing to re-center".to_string()),
+    }
+}
+
+/// Toggle fixed-size mode; the bounds come from the selection if there is one, else the
+/// drawing's bounding box (select the main drawing first to leave strays outside)
+pub fn toggle_document_bounds(state: &mut ApplicationState) {
+    if state.document_bounds.take().is_some() {
+        state.status_message = Some("Document bounds off".to_string());
+        return;
+    }
+    let bounds = state.selection.current.as_ref().map(|sel| sel.rect).or_else(|| grid_bounds(&state.cells));
+    state.status_message = Some(match bounds {
+        Some(rect) => format!("Document bounds {}x{} at ({}, {})", rect.width(), rect.height(), rect.min_x, rect.min_y),
+        None => "Nothing to bound".to_string(),
+    });
+    state.document_bounds = bounds;
+}
+
+/// Report the content bounds and, in fixed-size mode, ask before deleting cells outside them
+pub fn trim_to_bounds(state: &mut ApplicationState) {
+    let Some(bounds) = state.document_bounds else {
+        state.status_message = Some(match grid_bounds(&state.cells) {
+            Some(rect) => format!(
+                "Content is {}x{} from ({}, {}) to ({}, {}); set document bounds to trim",
+                rect.width(), rect.height(), rect.min_x, rect.min_y, rect.max_x, rect.max_y
+            ),
+            None => "Drawing is empty".to_string(),
+        });
+        return;
+    };
+
+    let outside = cells_outside(state, &bounds).len();
+    if outside == 0 {
+        state.status_message = Some(format!("No cells outside the {}x{} bounds", bounds.width(), bounds.height()));
+        return;
+    }
+    state.confirm = Some(Confirm {
+        message: format!("Delete {} cell(s) outside the {}x{} bounds?", outside, bounds.width(), bounds.height()),
+        confirm_label: "Delete",
+        on_confirm: delete_outside_bounds,
+    });
+}
+
+/// Delete every cell outside the document bounds as one undoable command
+fn delete_outside_bounds(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(bounds) = state.document_bounds else {
+        return;
+    };
+    let outside = cells_outside(state, &bounds);
+    let changes = outside.iter().map(|&coord| CellChange { coord, before: None, after: None }).collect();
+    apply_changes_and_record(state, canvas, "Trim To Bounds", changes);
+    remove_cells_from_groups(state, &outside);
+    state.status_message = Some(format!("Trimmed {} stray cell(s)", outside.len()));
+}
+
+fn cells_outside(state: &ApplicationState, bounds: &SelectionRect) -> Vec<(i32, i32)> {
+    state.cells.keys().copied().filter(|&(x, y)| !bounds.contains(x, y)).collect()
+}
+
+/// Filled cells of the selection, keyed by position
+fn selected_cells(state: &ApplicationState) -> CellGrid {
+    let Some(sel) = &state.selection.current else {
+        return CellGrid::new();
+    };
+    let SelectionKind::Cells(set) = &sel.kind;
+    set.iter().filter_map(|c| state.cells.get(c).filter(|cell| cell.is_filled).map(|cell| (*c, *cell))).collect()
+}
+
+/// Ask before replacing the drawing with just the selected cells
+pub fn extract_selection(state: &mut ApplicationState) {
+    let count = selected_cells(state).len();
+    if count == 0 || state.selection.is_moving {
+        state.status_message = Some("Select the cells to extract first".to_string());
+        return;
+    }
+    state.confirm = Some(Confirm {
+        message: format!("Start a new drawing from the {} selected cell(s)?", count),
+        confirm_label: "Extract",
+        on_confirm: extract_selection_to_new_document,
+    });
+}
+
+/// New document holding the selected cells moved to (0, 0), with document bounds cropped to
+/// them. Cell size, aspect and palette carry over; saving goes to a sibling "-extract" path
+/// so the source project isn't overwritten
+fn extract_selection_to_new_document(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let selected = selected_cells(state);
+    let Some(rect) = compute_bounding_rect(&selected.keys().copied().collect()) else {
+        return;
+    };
+    let cells: CellGrid = selected.into_iter().map(|((x, y), cell)| ((x - rect.min_x, y - rect.min_y), cell)).collect();
+    let grid = state.grid;
+    let path = extract_path(&state.project_path);
+
+    crate::project::new_document(state, canvas);
+    state.set_grid(grid);
+    canvas.mark_cells_dirty(cells.keys().copied());
+    state.cells = Arc::new(cells);
+    let bounds = SelectionRect { min_x: 0, min_y: 0, max_x: rect.width() - 1, max_y: rect.height() - 1 };
+    state.document_bounds = Some(bounds);
+    state.project_path = path;
+    let screen_center = Vec2::new(screen_width(), screen_height()) / 2.0;
+    state.camera.center_on(Vec2::new(rect.width() as f32, rect.height() as f32) / 2.0, screen_center);
+    state.status_message = Some(format!("Extracted {}x{}; Save writes {}", rect.width(), rect.height(), state.project_path));
+}
+
+/// "art/scene.tnsp" -> "art/scene-extract.tnsp"
+fn extract_path(project_path: &str) -> String {
+    let path = std::path::Path::new(project_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("drawing");
+    let name = match path.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}-extract.{}", stem, extension),
+        None => format!("{}-extract", stem),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Start previewing a transform of the selection (no-op without a settled selection)
+pub fn begin_selection_transform(state: &mut ApplicationState) {
+    if state.selection.current.is_none() || state.selection.is_moving {
+        state.status_message = Some("Select something to transform first".to_string());
+        return;
+    }
+    state.selection_transform = Some(SelectionTransform::IDENTITY);
+}
+
+/// Selection contents before and after the pending transform
+pub struct TransformedSelection {
+    /// Filled cells the transform takes from
+    pub sources: Vec<(i32, i32)>,
+    /// Resampled cells it writes
+    pub result: Vec<((i32, i32), Cell)>,
+}
+
+/// Resampled selection contents under the pending transform, for preview and apply
+pub fn transformed_selection(state: &ApplicationState) -> Option<TransformedSelection> {
+    let transform = state.selection_transform?;
+    let sel = state.selection.current.as_ref()?;
+    let SelectionKind::Cells(set) = &sel.kind;
+    let source: CellGrid = set.iter().filter_map(|c| state.cells.get(c).map(|cell| (*c, *cell))).collect();
+    let result = resample_nearest(&source, rect_center(&sel.rect), transform.matrix());
+    Some(TransformedSelection { sources: source.into_keys().collect(), result })
+}
+
+/// Replace the selection's cells with their transformed version as one undo command;
+/// the selection follows the new cells
+pub fn apply_selection_transform(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(TransformedSelection { sources, result }) = transformed_selection(state) else {
+        cancel_selection_transform(state);
+        return;
+    };
+    cancel_selection_transform(state);
+
+    // Clear the originals first; destinations that were originals start out empty
+    let source_set: HashSet<(i32, i32)> = sources.iter().copied().collect();
+    let mut changes: Vec<CellChange> = sources.iter()
+        .map(|&coord| CellChange { coord, before: state.cells.get(&coord).copied(), after: None })
+        .collect();
+    changes.extend(result.iter().map(|&(coord, cell)| CellChange {
+        coord,
+        before: if source_set.contains(&coord) { None } else { state.cells.get(&coord).copied() },
+        after: Some(cell),
+    }));
+    let cmd = Command { changes };
+    cmd.apply(Arc::make_mut(&mut state.cells));
+    for ch in cmd.changes.iter() {
+        canvas.mark_dirty(ch.coord);
+    }
+    record_command(state, "Transform Selection", cmd);
+    remove_cells_from_groups(state, &sources);
+
+    let new_set: HashSet<(i32, i32)> = result.iter().map(|(coord, _)| *coord).collect();
+    state.selection.current = compute_bounding_rect(&new_set)
+        .map(|rect| Selection { rect, kind: SelectionKind::Cells(new_set) });
+}
+
+/// Screen distance within which a click grabs a handle
+const HANDLE_GRAB_PIXELS: f32 = 10.0;
+
+/// How far the rotation handle sits out past the top edge, in screen pixels
+const ROTATE_HANDLE_OFFSET: f32 = 28.0;
+
+/// Below this on-screen diagonal the select tool shows no gizmo, so small selections can
+/// still be grabbed and moved
+const MIN_GIZMO_PIXELS: f32 = 48.0;
+
+/// Shift-dragging the rotation handle snaps to multiples of this many degrees
+const ROTATE_SNAP_DEGREES: f32 = 15.0;
+
+/// A draggable part of the selection's transform gizmo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransformHandle {
+    /// Stretches the sides it sits on: `x` is -1, 0 or 1 for the left, middle or right of
+    /// the box and `y` likewise for top, middle or bottom
+    Scale { x: i8, y: i8 },
+    /// Turns the box about its center
+    Rotate,
+    /// Top edge in the transform panel, dragged sideways
+    ShearX,
+    /// Right edge in the transform panel, dragged up and down
+    ShearY,
+}
+
+/// A gizmo handle being dragged
+#[derive(Clone, Copy, Debug)]
+pub struct TransformDrag {
+    pub handle: TransformHandle,
+    /// Transform when the handle was grabbed
+    pub start: SelectionTransform,
+    /// Pointer angle about the pivot when grabbed, in degrees (rotation handle only)
+    pub grab_angle: f32,
+    /// Grabbed on the selection with the select tool rather than in the transform panel,
+    /// so letting go applies the transform
+    pub apply_on_release: bool,
+}
+
+/// World position of a point of the selection box under `transform`: (-1, -1) is the
+/// top-left corner, (0, 0) the center and (1, 1) the bottom-right corner
+fn gizmo_point(rect: &SelectionRect, transform: &SelectionTransform, fx: f32, fy: f32) -> Vec2 {
+    let (cx, cy) = rect_center(rect);
+    let half = Vec2::new(fx * rect.width() as f32 / 2.0, fy * rect.height() as f32 / 2.0);
+    Vec2::new(cx, cy) + mul(transform.matrix(), half)
+}
+
+fn mul(m: [[f32; 2]; 2], v: Vec2) -> Vec2 {
+    Vec2::new(m[0][0] * v.x + m[0][1] * v.y, m[1][0] * v.x + m[1][1] * v.y)
+}
+
+/// World corners of the transformed selection box, clockwise from top-left
+pub fn transform_quad(rect: &SelectionRect, transform: &SelectionTransform) -> [Vec2; 4] {
+    [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].map(|(fx, fy)| gizmo_point(rect, transform, fx, fy))
+}
+
+/// The gizmo's handles and their screen positions; empty when it isn't showing
+/// It shows on a settled selection with the select tool and while a transform is pending.
+/// Corners and edges scale and the handle above the top edge rotates; the transform panel
+/// trades the top and right edge handles for shear handles
+pub fn transform_handles(state: &ApplicationState) -> Vec<(TransformHandle, Vec2)> {
+    let Some(sel) = &state.selection.current else {
+        return Vec::new();
+    };
+    let pending = state.selection_transform.is_some();
+    if !pending && (state.mode != Mode::Select || state.selection.is_moving || state.selection.active_drag) {
+        return Vec::new();
+    }
+    let panel = pending && !state.transform_drag.is_some_and(|drag| drag.apply_on_release);
+    let transform = state.selection_transform.unwrap_or(SelectionTransform::IDENTITY);
+    let point = |fx: f32, fy: f32| state.camera.world_to_screen(gizmo_point(&sel.rect, &transform, fx, fy));
+    if !pending && point(-1.0, -1.0).distance(point(1.0, 1.0)) < MIN_GIZMO_PIXELS {
+        return Vec::new();
+    }
+
+    let mut handles = Vec::new();
+    for y in -1..=1i8 {
+        for x in -1..=1i8 {
+            let handle = match (panel, x, y) {
+                (_, 0, 0) => continue,
+                (true, 0, -1) => TransformHandle::ShearX,
+                (true, 1, 0) => TransformHandle::ShearY,
+                _ => TransformHandle::Scale { x, y },
+            };
+            handles.push((handle, point(x as f32, y as f32)));
+        }
+    }
+    let top = point(0.0, -1.0);
+    let outward = (top - point(0.0, 0.0)).try_normalize().unwrap_or(Vec2::NEG_Y);
+    handles.push((TransformHandle::Rotate, top + outward * ROTATE_HANDLE_OFFSET));
+    handles
+}
+
+/// Handle within grabbing distance of the pointer, nearest first
+fn handle_under_pointer(state: &ApplicationState) -> Option<TransformHandle> {
+    let screen_mouse = Vec2::from(mouse_position());
+    transform_handles(state)
+        .into_iter()
+        .map(|(handle, p)| (handle, p.distance(screen_mouse)))
+        .filter(|&(_, distance)| distance <= HANDLE_GRAB_PIXELS)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(handle, _)| handle)
+}
+
+/// Pointer angle about the selection's pivot, in degrees clockwise from +x
+fn pointer_angle(rect: &SelectionRect, world: Vec2) -> f32 {
+    let (cx, cy) = rect_center(rect);
+    (world.y - cy).atan2(world.x - cx).to_degrees()
+}
+
+/// Start dragging `handle`; the drag previews on the selection's texture
+fn grab_handle(state: &mut ApplicationState, handle: TransformHandle, apply_on_release: bool) {
+    let Some(sel) = &state.selection.current else {
+        return;
+    };
+    let start = state.selection_transform.unwrap_or(SelectionTransform::IDENTITY);
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let grab_angle = pointer_angle(&sel.rect, world);
+    if state.selection_preview.is_none() {
+        let SelectionKind::Cells(set) = &sel.kind;
+        state.selection_preview = crate::rendering::selection::build_selection_preview(&state.cells, &sel.rect, set);
+    }
+    state.selection_transform = Some(start);
+    state.transform_drag = Some(TransformDrag { handle, start, grab_angle, apply_on_release });
+}
+
+/// With the select tool, a press on a gizmo handle starts a direct transform that applies
+/// when the button is let go; true if a handle was grabbed
+pub fn grab_selection_gizmo(state: &mut ApplicationState) -> bool {
+    match handle_under_pointer(state) {
+        Some(handle) => {
+            grab_handle(state, handle, true);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Drop the pending transform without changing anything
+pub fn cancel_selection_transform(state: &mut ApplicationState) {
+    state.selection_transform = None;
+    state.transform_drag = None;
+    state.selection_preview = None;
+}
+
+/// While a transform is pending the canvas drives its gizmo instead of the tool
+pub fn handle_transform_handles(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(rect) = state.selection.current.as_ref().map(|sel| sel.rect) else {
+        cancel_selection_transform(state);
+        return;
+    };
+    if is_mouse_button_pressed(state.mouse.primary()) && state.transform_drag.is_none() {
+        if let Some(handle) = handle_under_pointer(state) {
+            grab_handle(state, handle, false);
+        }
+    }
+    let Some(drag) = state.transform_drag else {
+        return;
+    };
+
+    if !is_mouse_button_down(state.mouse.primary()) {
+        state.transform_drag = None;
+        if drag.apply_on_release {
+            if state.selection_transform == Some(SelectionTransform::IDENTITY) {
+                cancel_selection_transform(state);
+            } else {
+                apply_selection_transform(state, canvas);
+            }
+        }
+        return;
+    }
+
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    state.selection_transform = Some(dragged_transform(&rect, &drag, world, shift));
+}
+
+/// The transform with `drag`'s handle moved to `world`, so the handle stays under the
+/// pointer; Shift keeps the proportions when scaling from a corner and snaps rotation
+fn dragged_transform(rect: &SelectionRect, drag: &TransformDrag, world: Vec2, shift: bool) -> SelectionTransform {
+    let mut t = drag.start;
+    let (cx, cy) = rect_center(rect);
+    let offset = world - Vec2::new(cx, cy);
+    let half = Vec2::new(rect.width() as f32 / 2.0, rect.height() as f32 / 2.0);
+    match drag.handle {
+        TransformHandle::Rotate => {
+            let mut angle = t.angle + pointer_angle(rect, world) - drag.grab_angle;
+            if shift {
+                angle = (angle / ROTATE_SNAP_DEGREES).round() * ROTATE_SNAP_DEGREES;
+            }
+            t.angle = (angle + 180.0).rem_euclid(360.0) - 180.0;
+        }
+        TransformHandle::Scale { x, y } => {
+            // Pointer in the box's own axes, before shear and rotation
+            let Some(inverse) = invert(t.shear_rotation()) else {
+                return t;
+            };
+            let local = mul(inverse, offset);
+            let fit = |value: f32, extent: i32| value.clamp(1.0 / extent as f32, MAX_SCALE);
+            if x != 0 {
+                t.scale_x = fit(local.x * x as f32 / half.x, rect.width());
+            }
+            if y != 0 {
+                t.scale_y = fit(local.y * y as f32 / half.y, rect.height());
+            }
+            if shift && x != 0 && y != 0 {
+                let factor = (t.scale_x / drag.start.scale_x).max(t.scale_y / drag.start.scale_y);
+                t.scale_x = fit(drag.start.scale_x * factor, rect.width());
+                t.scale_y = fit(drag.start.scale_y * factor, rect.height());
+            }
+        }
+        TransformHandle::ShearX | TransformHandle::ShearY => {
+            // Pointer with the rotation undone; the shear handles sit on the scaled edges
+            let (sin, cos) = t.angle.to_radians().sin_cos();
+            let unrotated = Vec2::new(cos * offset.x + sin * offset.y, cos * offset.y - sin * offset.x);
+            if drag.handle == TransformHandle::ShearX {
+                let half_h = (half.y * t.scale_y).max(0.5);
+                t.shear_x = (-unrotated.x / half_h).clamp(-MAX_SHEAR, MAX_SHEAR);
+            } else {
+                let half_w = (half.x * t.scale_x).max(0.5);
+                t.shear_y = (unrotated.y / half_w).clamp(-MAX_SHEAR, MAX_SHEAR);
+            }
+        }
+    }
+    t
+}
@@ -1,6 +1,28 @@
 use macroquad::prelude::*;
 
-use crate::state::{Mode, ApplicationState};
+use crate::input::actions::{perform, Action, KeyBinding};
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::icons::{ToolIcons, ICON_SCALE, ICON_SIZE};
+use crate::ui::focus;
+use crate::ui::menu_bar::MENU_BAR_HEIGHT;
+
+/// Tool palette runs down the left edge, just below the menu bar
+const TOOLBAR_X: f32 = 10.0;
+const TOOLBAR_Y: f32 = MENU_BAR_HEIGHT + 10.0;
+const TOOL_BUTTON_SIZE: f32 = 36.0;
+const TOOL_GAP: f32 = 4.0;
+/// Extra space between groups
+const GROUP_GAP: f32 = 10.0;
+/// Room left at the bottom of the screen for the HUD text
+const HUD_RESERVE: f32 = 150.0;
+
+/// Toolbar buttons grouped by function: drawing tools, selection/navigation, panels
+const TOOL_GROUPS: &[&[Action]] = &[
+    &[Action::ToolPaint, Action::ToolErase, Action::ToolSpray, Action::ToolLine, Action::ToolRectangle, Action::ToolPen, Action::ToolCurve, Action::ToolFill, Action::ToolDither, Action::ToolAutotile],
+    &[Action::ToolSelect, Action::ToolPan],
+    &[Action::TogglePalette],
+];
 
 pub fn draw_button(text: &str, x: f32, y: f32, width: f32, height: f32, is_active: bool) -> bool {
     let color = if is_active { DARKGRAY } else { GRAY };
@@ -11,35 +33,127 @@ pub fn draw_button(text: &str, x: f32, y: f32, width: f32, height: f32, is_activ
     let text_x = x + (width - text_size.width) / 2.0;
     let text_y = y + (height + text_size.height) / 2.0;
     draw_text(text, text_x, text_y, 20.0, BLACK);
-    is_mouse_button_pressed(MouseButton::Left) && rect.contains(Vec2::from(mouse_position()))
+    focus::pressed(rect)
 }
 
-pub fn render_ui_buttons(state: &mut ApplicationState) -> bool {
-    let mut over_ui = false;
-    let mouse_pos = Vec2::from(mouse_position());
+/// Square pixel-art button; the active tool gets a highlighted background
+/// Returns (clicked, hovered)
+fn icon_button(icon: Option<&Texture2D>, x: f32, y: f32, active: bool) -> (bool, bool) {
+    let rect = Rect::new(x, y, TOOL_BUTTON_SIZE, TOOL_BUTTON_SIZE);
+    let hovered = rect.contains(Vec2::from(mouse_position()));
+    let background = if active {
+        Color::from_rgba(150, 190, 255, 255)
+    } else if hovered {
+        Color::from_rgba(215, 215, 225, 255)
+    } else {
+        Color::from_rgba(190, 190, 195, 255)
+    };
+    draw_rectangle(x, y, TOOL_BUTTON_SIZE, TOOL_BUTTON_SIZE, background);
+    draw_rectangle_lines(x, y, TOOL_BUTTON_SIZE, TOOL_BUTTON_SIZE, if active { 3.0 } else { 2.0 }, BLACK);
 
-    // Draw buttons
-    if draw_button("Paint", 10.0, 10.0, 80.0, 30.0, state.mode == Mode::Paint) {
-        state.mode = Mode::Paint;
+    if let Some(texture) = icon {
+        let size = ICON_SIZE as f32 * ICON_SCALE;
+        let offset = (TOOL_BUTTON_SIZE - size) / 2.0;
+        draw_texture_ex(texture, x + offset, y + offset, WHITE, DrawTextureParams {
+            dest_size: Some(Vec2::splat(size)),
+            ..Default::default()
+        });
     }
-    if draw_button("Erase", 100.0, 10.0, 80.0, 30.0, state.mode == Mode::Erase) {
-        state.mode = Mode::Erase;
+
+    (focus::pressed(rect), hovered)
+}
+
+/// Action name plus its shortcut, drawn to the right of the hovered button
+fn draw_tooltip(action: Action, button: Vec2, keymap: &[(KeyBinding, Action)]) {
+    let text = match action.binding(keymap) {
+        Some(binding) => format!("{} ({})", action.label(), binding.label()),
+        None => action.label().to_string(),
+    };
+    let size = measure_text(&text, None, 16, 1.0);
+    let x = button.x + TOOL_BUTTON_SIZE + 6.0;
+    let y = button.y + (TOOL_BUTTON_SIZE - 22.0) / 2.0;
+    draw_rectangle(x, y, size.width + 12.0, 22.0, Color::from_rgba(255, 255, 225, 255));
+    draw_rectangle_lines(x, y, size.width + 12.0, 22.0, 1.0, BLACK);
+    draw_text(&text, x + 6.0, y + 16.0, 16.0, BLACK);
+}
+
+/// Places square buttons top to bottom, starting a new column when the screen runs out
+struct ColumnLayout {
+    x: f32,
+    y: f32,
+    bottom: f32,
+    bounds: Rect,
+}
+
+impl ColumnLayout {
+    fn new() -> Self {
+        let bottom = (screen_height() - HUD_RESERVE).max(TOOLBAR_Y + TOOL_BUTTON_SIZE);
+        Self { x: TOOLBAR_X, y: TOOLBAR_Y, bottom, bounds: Rect::new(TOOLBAR_X, TOOLBAR_Y, 0.0, 0.0) }
     }
-    if draw_button("Pan", 190.0, 10.0, 80.0, 30.0, state.mode == Mode::Pan) {
-        state.mode = Mode::Pan;
+
+    /// Top-left of the next button
+    fn next(&mut self) -> Vec2 {
+        if self.y + TOOL_BUTTON_SIZE > self.bottom && self.y > TOOLBAR_Y {
+            self.x += TOOL_BUTTON_SIZE + TOOL_GAP;
+            self.y = TOOLBAR_Y;
+        }
+        let pos = Vec2::new(self.x, self.y);
+        self.bounds = self.bounds.combine_with(Rect::new(pos.x, pos.y, TOOL_BUTTON_SIZE, TOOL_BUTTON_SIZE));
+        self.y += TOOL_BUTTON_SIZE + TOOL_GAP;
+        pos
     }
-    if draw_button("Select", 280.0, 10.0, 80.0, 30.0, state.mode == Mode::Select) {
-        state.mode = Mode::Select;
+
+    /// Separate groups, unless a wrap just started a fresh column
+    fn group_break(&mut self) {
+        if self.y > TOOLBAR_Y {
+            self.y += GROUP_GAP;
+        }
     }
-    if draw_button("Palette", 370.0, 10.0, 80.0, 30.0, state.show_palette) {
-        state.show_palette = !state.show_palette;
+}
+
+/// Vertical tool palette with icon buttons and tooltips; registers its rect with the UI
+/// hit-test regions
+pub fn render_ui_buttons(state: &mut ApplicationState, canvas: &mut CanvasRenderer, icons: &ToolIcons) {
+    let mut layout = ColumnLayout::new();
+    let mut tooltip = None;
+
+    for group in TOOL_GROUPS {
+        for &action in group.iter() {
+            let pos = layout.next();
+            let active = action.checked(state).unwrap_or(false);
+            let (clicked, hovered) = icon_button(icons.get(action), pos.x, pos.y, active);
+            if clicked {
+                perform(action, state, canvas);
+            }
+            if hovered {
+                tooltip = Some((action, pos));
+            }
+        }
+        layout.group_break();
     }
 
-    // Check if mouse is over any button
-    if mouse_pos.y >= 10.0 && mouse_pos.y <= 40.0 && mouse_pos.x >= 10.0 && mouse_pos.x <= 450.0 {
-        over_ui = true;
+    // Primary/secondary color swatches (secondary peeks out behind primary)
+    let pos = layout.next();
+    if draw_color_swatches(state, pos.x + 3.0, pos.y + 3.0) {
+        state.show_palette = true;
     }
 
-    over_ui
+    state.ui_regions.push(layout.bounds);
+
+    // Drawn last so it sits over the swatches
+    if let Some((action, pos)) = tooltip {
+        draw_tooltip(action, pos, &state.keymap);
+    }
 }
 
+/// Draw the overlapping primary/secondary swatches; returns true when clicked
+fn draw_color_swatches(state: &ApplicationState, x: f32, y: f32) -> bool {
+    let size = 20.0;
+    let (sx, sy) = (x + 10.0, y + 10.0);
+    draw_rectangle(sx, sy, size, size, Color::from(state.secondary_color));
+    draw_rectangle_lines(sx, sy, size, size, 2.0, BLACK);
+    draw_rectangle(x, y, size, size, Color::from(state.current_color));
+    draw_rectangle_lines(x, y, size, size, 2.0, BLACK);
+
+    focus::pressed(Rect::new(x, y, size + 10.0, size + 10.0))
+}
@@ -0,0 +1,85 @@
+use macroquad::prelude::*;
+use crate::core::cell::grid_bounds;
+use crate::core::selection::SelectionRect;
+use crate::input::actions::Action;
+use crate::state::ApplicationState;
+
+/// Cells of empty canvas the clamped view may show past the drawing's edge
+const VIEW_BOUNDS_MARGIN: f32 = 64.0;
+/// Seconds between rescans of the drawing's bounds
+const BOUNDS_REFRESH_INTERVAL: f64 = 0.5;
+
+/// Rescan the drawing's bounds now and then, and pull the view back toward the drawing when
+/// the clamp is on; call after input so this frame's pans and zooms are covered
+pub fn update_view_bounds(state: &mut ApplicationState) {
+    let now = get_time();
+    if now - state.drawing_bounds_time >= BOUNDS_REFRESH_INTERVAL {
+        state.drawing_bounds = grid_bounds(&state.cells);
+        state.drawing_bounds_time = now;
+    }
+    if !state.clamp_view {
+        return;
+    }
+
+    // An empty canvas keeps the view around the origin
+    let rect = state.drawing_bounds.unwrap_or(SelectionRect::from_points((0, 0), (0, 0)));
+    let screen_center = Vec2::new(screen_width(), screen_height()) / 2.0;
+    let center = state.camera.screen_to_cell(screen_center);
+    let min = Vec2::new(rect.min_x as f32, rect.min_y as f32) - VIEW_BOUNDS_MARGIN;
+    let max = Vec2::new(rect.max_x as f32 + 1.0, rect.max_y as f32 + 1.0) + VIEW_BOUNDS_MARGIN;
+    let clamped = center.clamp(min, max);
+    if clamped != center {
+        state.camera.center_on(clamped, screen_center);
+        state.pan_velocity = Vec2::ZERO;
+    }
+}
+
+/// "You are N cells from your drawing" once none of it is on screen, naming the key that
+/// returns
+pub fn lost_hint(state: &ApplicationState) -> Option<String> {
+    let rect = state.drawing_bounds?;
+    let (min_x, min_y, max_x, max_y) = state.camera.visible_world_rect(screen_width(), screen_height());
+    let visible = rect.max_x as f32 + 1.0 > min_x && (rect.min_x as f32) < max_x && rect.max_y as f32 + 1.0 > min_y && (rect.min_y as f32) < max_y;
+    if visible {
+        return None;
+    }
+    let center = state.camera.screen_to_cell(Vec2::new(screen_width(), screen_height()) / 2.0);
+    let dx = (rect.min_x as f32 - center.x).max(center.x - rect.max_x as f32 - 1.0).max(0.0);
+    let dy = (rect.min_y as f32 - center.y).max(center.y - rect.max_y as f32 - 1.0).max(0.0);
+    let distance = dx.max(dy).round() as i64;
+    Some(match Action::ReturnToDrawing.binding(&state.keymap) {
+        Some(binding) => format!("You are {} cells from your drawing - press {} to return", distance, binding.label()),
+        None => format!("You are {} cells from your drawing - View > {} brings you back", distance, Action::ReturnToDrawing.label()),
+    })
+}
+
+/// Center the view on the drawing (on the origin when the canvas is empty)
+pub fn return_to_drawing(state: &mut ApplicationState) {
+    let target = match grid_bounds(&state.cells) {
+        Some(rect) => IVec2::new(rect.min_x + rect.max_x + 1, rect.min_y + rect.max_y + 1).as_vec2() / 2.0,
+        None => Vec2::ZERO,
+    };
+    state.camera.center_on(target, Vec2::new(screen_width(), screen_height()) / 2.0);
+    state.pan_velocity = Vec2::ZERO;
+}
+
+/// "12, -4", "12 -4" or "(12,-4)" as a cell
+pub fn parse_cell(text: &str) -> Option<(i32, i32)> {
+    let cleaned = text.trim().trim_start_matches('(').trim_end_matches(')');
+    let mut parts = cleaned.split(|c: char| c == ',' || c.is_whitespace()).filter(|part| !part.is_empty());
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((x, y))
+}
+
+/// Center the view on a cell, optionally marking it for a few seconds
+pub fn go_to_cell(state: &mut ApplicationState, (x, y): (i32, i32), mark: bool) {
+    state.camera.center_on(Vec2::new(x as f32 + 0.5, y as f32 + 0.5), Vec2::new(screen_width(), screen_height()) / 2.0);
+    state.pan_velocity = Vec2::ZERO;
+    state.cell_marker = mark.then(|| ((x, y), get_time()));
+    state.status_message = Some(if state.clamp_view {
+        format!("Went to ({}, {}); the view clamp may pull back toward the drawing", x, y)
+    } else {
+        format!("Went to ({}, {})", x, y)
+    });
+}
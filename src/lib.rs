@@ -1,8 +1,21 @@
-mod core;
+// Document model, public so benches and tests can drive it without a window
+pub mod core;
 mod state;
 mod rendering;
 mod input;
 mod ui;
+mod export;
+mod import;
+// File formats, public so benches and tests can round-trip documents
+pub mod project;
+mod collab;
+// Failures of file I/O, shared by the project, import and export modules
+mod error;
+// Ring-buffer logger behind the console overlay
+mod logging;
+// Shared-canvas server, driven by the tiny-neo-space-server binary
+#[cfg(feature = "server")]
+pub mod server;
 mod app;
 
 use wasm_bindgen::prelude::*;
@@ -11,6 +24,6 @@ use wasm_bindgen_futures::spawn_local;
 #[wasm_bindgen(start)]
 pub fn start() {
     spawn_local(async {
-        app::run().await;
+        app::run(app::StartupArgs::default()).await;
     });
 }
@@ -0,0 +1,71 @@
+//! In-memory log: every `log` record (the app's and macroquad's) is kept in a ring buffer
+//! for the console overlay, and echoed to stderr on native builds
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Lines kept for the console; older ones are dropped
+const MAX_LOG_LINES: usize = 500;
+/// Environment variable choosing the most verbose level recorded ("debug", "trace", ...)
+const LOG_LEVEL_VAR: &str = "TINY_NEO_SPACE_LOG";
+
+pub struct LogLine {
+    /// Seconds since the app started
+    pub time: f64,
+    pub level: Level,
+    pub text: String,
+}
+
+static LINES: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+static LOGGER: RingLogger = RingLogger;
+/// Wall-clock time `init` ran; records are stamped relative to it
+static START: OnceLock<f64> = OnceLock::new();
+
+struct RingLogger;
+
+impl Log for RingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // Records also come from worker threads, so no macroquad context functions here
+        let now = macroquad::miniquad::date::now();
+        let time = now - START.get().copied().unwrap_or(now);
+        let line = LogLine { time, level: record.level(), text: record.args().to_string() };
+        #[cfg(not(target_arch = "wasm32"))]
+        eprintln!("[{:>8.2} {:<5}] {}", line.time, line.level, line.text);
+        if let Ok(mut lines) = LINES.lock() {
+            if lines.len() == MAX_LOG_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the logger; the level comes from TINY_NEO_SPACE_LOG and defaults to info
+pub fn init() {
+    let level = std::env::var(LOG_LEVEL_VAR)
+        .ok()
+        .and_then(|value| value.parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::Info);
+    START.get_or_init(macroquad::miniquad::date::now);
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// Call `f` with the newest `count` lines, oldest first
+pub fn with_recent_lines(count: usize, f: impl FnOnce(&mut dyn Iterator<Item = &LogLine>)) {
+    if let Ok(lines) = LINES.lock() {
+        let skip = lines.len().saturating_sub(count);
+        f(&mut lines.iter().skip(skip));
+    }
+}
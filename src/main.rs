@@ -3,9 +3,42 @@ mod state;
 mod rendering;
 mod input;
 mod ui;
+mod export;
+mod import;
+mod project;
+mod collab;
+// Failures of file I/O, shared by the project, import and export modules
+mod error;
+// Ring-buffer logger behind the console overlay
+mod logging;
 mod app;
 
-#[macroquad::main("tiny-neo-space")]
-async fn main() {
-    app::run().await;
+fn main() {
+    // Arguments are checked before the window opens so mistakes are reported in the terminal
+    let startup = match parse_args(std::env::args().skip(1)) {
+        Ok(startup) => startup,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    macroquad::Window::new("tiny-neo-space", app::run(startup));
+}
+
+/// The editor's command line; only the native binary has one, the wasm build starts empty
+fn parse_args(args: impl Iterator<Item = String>) -> Result<app::StartupArgs, String> {
+    let mut startup = app::StartupArgs::default();
+    for arg in args {
+        if arg.starts_with("--") {
+            return Err(format!("Unknown argument '{}' (usage: tiny-neo-space [PROJECT_OR_IMAGE])", arg));
+        }
+        if startup.open.is_some() {
+            return Err("Only one file can be opened at startup".to_string());
+        }
+        if !std::path::Path::new(&arg).is_file() {
+            return Err(format!("No such file: {}", arg));
+        }
+        startup.open = Some(arg);
+    }
+    Ok(startup)
 }
@@ -0,0 +1,17 @@
+const ACTION_LOG_FILE_NAME: &str = ".tiny-neo-space-actions.log";
+
+/// The log lives next to the recent list in the home directory
+pub fn action_log_path() -> std::path::PathBuf {
+    let dir = std::env::var_os("HOME").map(std::path::PathBuf::from).unwrap_or_default();
+    dir.join(ACTION_LOG_FILE_NAME)
+}
+
+/// Append one tab-separated line: seconds since the epoch, action type, cells touched
+/// Failures are ignored; the log is a debugging aid and must never interrupt drawing
+pub fn append_action_log(kind: &str, cell_count: usize) {
+    use std::io::Write;
+    let line = format!("{:.3}\t{}\t{}\n", macroquad::miniquad::date::now(), kind, cell_count);
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(action_log_path()) {
+        let _ = file.write_all(line.as_bytes());
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+use crate::error::FileError;
+use crate::project::format::{ProjectFile, PROJECT_EXTENSION};
+use crate::project::write_project;
+
+/// Folder beside the project that holds its backups
+const BACKUP_DIR: &str = "backups";
+/// Length of the "YYYYMMDD-HHMMSS" stamp in backup names
+const STAMP_LEN: usize = 15;
+
+/// How often the open document is backed up, and how many backups each project keeps
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BackupSettings {
+    /// Minutes between backups; 0 turns automatic backups off
+    pub interval_minutes: u32,
+    pub keep: usize,
+}
+
+impl BackupSettings {
+    pub const DEFAULT: Self = Self { interval_minutes: 5, keep: 10 };
+    pub const INTERVAL_OPTIONS: &[u32] = &[0, 1, 5, 15, 30];
+    pub const KEEP_OPTIONS: &[usize] = &[5, 10, 20, 50];
+
+    pub fn next_interval(self) -> Self {
+        Self { interval_minutes: next_option(Self::INTERVAL_OPTIONS, self.interval_minutes), ..self }
+    }
+
+    pub fn next_keep(self) -> Self {
+        Self { keep: next_option(Self::KEEP_OPTIONS, self.keep), ..self }
+    }
+}
+
+fn next_option<T: Copy + PartialEq>(options: &[T], current: T) -> T {
+    let i = options.iter().position(|&o| o == current).map_or(0, |i| (i + 1) % options.len());
+    options[i]
+}
+
+/// "art/scene.tnsp" -> ("art/backups", "scene")
+fn backup_location(project_path: &str) -> (PathBuf, String) {
+    let path = Path::new(project_path);
+    let dir = path.parent().unwrap_or(Path::new("")).join(BACKUP_DIR);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    (dir, stem)
+}
+
+/// Write `file` as a new timestamped backup of `project_path`, then delete the oldest
+/// backups beyond `keep`. Returns the backup's path
+pub fn write_backup(file: &ProjectFile, project_path: &str, keep: usize, now: f64) -> Result<PathBuf, FileError> {
+    let (dir, stem) = backup_location(project_path);
+    std::fs::create_dir_all(&dir).map_err(|e| FileError::write(&dir.to_string_lossy(), e))?;
+    let path = dir.join(format!("{}-{}.{}", stem, timestamp(now), PROJECT_EXTENSION));
+    write_project(file, &path.to_string_lossy())?;
+
+    for old in list_backups(project_path).into_iter().skip(keep.max(1)) {
+        std::fs::remove_file(&old).map_err(|e| FileError::write(&old.to_string_lossy(), e))?;
+    }
+    Ok(path)
+}
+
+/// Backups of `project_path`, newest first
+pub fn list_backups(project_path: &str) -> Vec<PathBuf> {
+    let (dir, stem) = backup_location(project_path);
+    let prefix = format!("{}-", stem);
+    let suffix = format!(".{}", PROJECT_EXTENSION);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            // Exactly "<stem>-<stamp>.tnsp", so "scene" doesn't pick up "scene-2"'s backups
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            name.strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(&suffix))
+                .is_some_and(is_stamp)
+        })
+        .collect();
+    // Stamps sort chronologically as text
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+fn is_stamp(text: &str) -> bool {
+    text.len() == STAMP_LEN
+        && text.char_indices().all(|(i, c)| if i == 8 { c == '-' } else { c.is_ascii_digit() })
+}
+
+/// UTC "YYYY-MM-DD" for seconds since the Unix epoch
+pub fn date_stamp(seconds: f64) -> String {
+    let stamp = timestamp(seconds);
+    format!("{}-{}-{}", &stamp[0..4], &stamp[4..6], &stamp[6..8])
+}
+
+/// UTC "YYYYMMDD-HHMMSS" for seconds since the Unix epoch
+fn timestamp(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as i64;
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    // Days to civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
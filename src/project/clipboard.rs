@@ -0,0 +1,31 @@
+use crate::error::FileError;
+use crate::project::error_log::append_error_log;
+use crate::state::Clipboard;
+
+const CLIPBOARD_FILE_NAME: &str = ".tiny-neo-space-clipboard";
+
+/// The clipboard lives next to the recent list in the home directory
+fn clipboard_path() -> std::path::PathBuf {
+    let dir = std::env::var_os("HOME").map(std::path::PathBuf::from).unwrap_or_default();
+    dir.join(CLIPBOARD_FILE_NAME)
+}
+
+/// Clipboard saved by a previous session, or an empty one when there is none
+pub fn load_clipboard() -> Clipboard {
+    std::fs::read_to_string(clipboard_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_else(Clipboard::empty)
+}
+
+/// Written on every copy/cut rather than on exit, since closing the window ends the
+/// process without a shutdown hook; failures only reach the error log like the recent list's
+pub fn save_clipboard(clipboard: &Clipboard) {
+    let path = clipboard_path();
+    let written = serde_json::to_string(clipboard)
+        .map_err(std::io::Error::from)
+        .and_then(|text| std::fs::write(&path, text));
+    if let Err(e) = written {
+        append_error_log(&FileError::write(&path.to_string_lossy(), e).to_string());
+    }
+}
@@ -0,0 +1,22 @@
+const ERROR_LOG_FILE_NAME: &str = ".tiny-neo-space-errors.log";
+
+/// The log lives next to the recent list in the home directory
+pub fn error_log_path() -> std::path::PathBuf {
+    let dir = std::env::var_os("HOME").map(std::path::PathBuf::from).unwrap_or_default();
+    dir.join(ERROR_LOG_FILE_NAME)
+}
+
+/// Append one tab-separated line: seconds since the epoch, then the message
+/// If the log itself can't be written, stderr is the last resort
+pub fn append_error_log(message: &str) {
+    use std::io::Write;
+    let line = format!("{:.3}\t{}\n", macroquad::miniquad::date::now(), message);
+    let written = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(error_log_path())
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+    if written.is_err() {
+        eprint!("{}", line);
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use macroquad::prelude::Vec2;
+use crate::state::{ApplicationState, PaletteMode};
+use crate::core::camera::{MAX_ZOOM, MIN_ZOOM};
+use crate::core::color::Rgba;
+use crate::error::FileError;
+use crate::core::cell::{clear_grid, grid_bounds, GridSettings};
+use crate::core::history::Command;
+use crate::rendering::CanvasRenderer;
+use crate::project::format::{Document, ProjectFile, ProjectMetadata, ProjectView, DEFAULT_PROJECT_PATH, TEXT_PROJECT_EXTENSION};
+use crate::project::migrate::parse_project;
+use crate::project::text_format::encode_text_project;
+use crate::project::recent::remember_recent;
+
+/// Capture the current document for saving, with recent undo steps if `save_history` is on
+pub fn snapshot_project(state: &ApplicationState) -> ProjectFile {
+    let history: &[Command] = if state.save_history { &state.history.stack } else { &[] };
+    let mut file = ProjectFile::from_document(&state.cells, state.cell_aspect, state.grid, &state.custom_palette, history, &state.groups, &state.metadata);
+    file.saved_selections = state.saved_selections.clone();
+    file.frame_tags = state.frame_tags.clone();
+    file.view = Some(ProjectView {
+        origin: (state.camera.origin.x, state.camera.origin.y),
+        zoom: state.camera.zoom,
+        rotation: state.camera.rotation,
+        flip_x: state.camera.flip_x,
+        primary: state.current_color.to_hex(),
+        secondary: state.secondary_color.to_hex(),
+        palette_mode: format!("{:?}", state.palette_mode),
+    });
+    file
+}
+
+/// Encode a captured document and write it to `path`; needs no app state, so it can run off the frame.
+/// A `.tnst` path gets the plain-text format, anything else JSON
+pub fn write_project(file: &ProjectFile, path: &str) -> Result<(), FileError> {
+    let text = if is_text_project(path) {
+        encode_text_project(file)
+    } else {
+        serde_json::to_string_pretty(file).map_err(|e| FileError::write(path, e))?
+    };
+    write_replacing(path, &text).map_err(|e| FileError::write(path, e))
+}
+
+/// Write to a temp file beside `path` and rename it over `path`, so a crash mid-write never
+/// leaves a truncated file behind
+pub fn write_replacing(path: &str, contents: &str) -> std::io::Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+/// Whether saving to `path` writes the plain-text format
+pub fn is_text_project(path: &str) -> bool {
+    std::path::Path::new(path).extension().is_some_and(|e| e.eq_ignore_ascii_case(TEXT_PROJECT_EXTENSION))
+}
+
+/// Start an empty document; the next save goes to the default path
+pub fn new_document(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    // A background save still reading the old grid keeps it; otherwise it is cleared in place
+    let cleared = match Arc::get_mut(&mut state.cells) {
+        Some(cells) => clear_grid(cells),
+        None => {
+            let old = std::mem::take(&mut state.cells);
+            grid_bounds(&old)
+        }
+    };
+    if let Some(bounds) = cleared {
+        canvas.mark_rect_dirty(&bounds);
+    }
+    state.selection.clear();
+    state.selection_preview = None;
+    state.quick_mask = None;
+    state.diff = None;
+    crate::input::groups::clear_groups(state);
+    state.document_bounds = None;
+    state.strays = None;
+    state.set_grid(GridSettings::DEFAULT);
+    state.history.clear();
+    state.backed_up_revision = state.history.revision;
+    state.project_path = DEFAULT_PROJECT_PATH.to_string();
+    state.metadata = ProjectMetadata::new(macroquad::miniquad::date::now());
+    state.saved_selections.clear();
+    state.frame_tags.clear();
+    state.active_frame_tag = None;
+}
+
+/// Read and upgrade the project at `path` without touching the app state
+pub fn read_project(path: &str) -> Result<Document, FileError> {
+    let text = std::fs::read_to_string(path).map_err(|e| FileError::read(path, e))?;
+    parse_project(&text)
+        .and_then(|file| file.into_document())
+        .map_err(|e| FileError::format(path, e))
+}
+
+/// Replace the current document with the one at `path`, upgrading older formats
+/// Selection and redo steps belong to the previous document and are dropped; undo steps
+/// saved in the file (if any) become the new history
+pub fn open_project(state: &mut ApplicationState, canvas: &mut CanvasRenderer, path: &str) -> Result<(), FileError> {
+    let document = read_project(path)?;
+    load_document(state, canvas, document);
+    state.project_path = path.to_string();
+    remember_recent(path);
+    Ok(())
+}
+
+/// Replace the current document with a backup of it; the project path is kept, so the
+/// next save overwrites the project with the restored drawing
+pub fn restore_backup(state: &mut ApplicationState, canvas: &mut CanvasRenderer, backup: &str) -> Result<(), FileError> {
+    let document = read_project(backup)?;
+    load_document(state, canvas, document);
+    Ok(())
+}
+
+fn load_document(state: &mut ApplicationState, canvas: &mut CanvasRenderer, document: Document) {
+    // Redraw chunks that held old cells as well as those receiving new ones
+    canvas.mark_all_dirty();
+    canvas.mark_cells_dirty(document.cells.keys().copied());
+
+    state.cells = Arc::new(document.cells);
+    state.set_cell_aspect(document.cell_aspect);
+    state.set_grid(document.grid);
+    state.custom_palette = document.custom_palette;
+    state.metadata = document.metadata;
+    state.saved_selections = document.saved_selections;
+    state.frame_tags = document.frame_tags;
+    state.active_frame_tag = None;
+    if let Some(view) = document.view {
+        apply_view(state, &view);
+    }
+    state.selection.clear();
+    state.selection_preview = None;
+    state.quick_mask = None;
+    state.diff = None;
+    crate::input::groups::clear_groups(state);
+    for group in document.groups {
+        let id = crate::input::groups::create_group(state, group.name, group.cells.into_iter().collect());
+        crate::input::groups::set_group_frame(state, id, group.frame);
+        crate::input::frames::set_frame_duration(state, id, group.duration_ms);
+    }
+    state.document_bounds = None;
+    state.strays = None;
+    state.history.clear();
+    for cmd in document.history {
+        state.history.push(cmd);
+    }
+    // Matches a file on disk, so nothing to back up until it changes
+    state.backed_up_revision = state.history.revision;
+}
+
+/// Put the camera, picked colors and palette tab back the way they were saved
+fn apply_view(state: &mut ApplicationState, view: &ProjectView) {
+    state.camera.origin = Vec2::new(view.origin.0, view.origin.1);
+    state.camera.zoom = view.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+    state.camera.rotation = view.rotation;
+    state.camera.flip_x = view.flip_x;
+    state.pan_velocity = Vec2::ZERO;
+    if let (Some(primary), Some(secondary)) = (Rgba::from_hex(&view.primary), Rgba::from_hex(&view.secondary)) {
+        state.current_color = primary;
+        state.secondary_color = secondary;
+    }
+    state.palette_mode = match view.palette_mode.as_str() {
+        "Extended" => PaletteMode::Extended,
+        "Custom" => PaletteMode::Custom,
+        _ => PaletteMode::Basic,
+    };
+    state.palette_page = 0;
+}
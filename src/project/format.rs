@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use crate::core::cell::{Cell, CellAspect, CellGrid, GridSettings};
+use crate::core::color::Rgba;
+use crate::core::frame_tag::FrameTag;
+use crate::core::group::Group;
+use crate::core::history::{CellChange, Command};
+use crate::project::backup::date_stamp;
+use crate::project::thumbnail::render_thumbnail;
+
+/// Version written by this build; bump it (and add a migration) whenever the schema changes
+pub const FORMAT_VERSION: u32 = 10;
+
+/// Extension of project files
+pub const PROJECT_EXTENSION: &str = "tnsp";
+
+/// Extension of projects saved in the line-oriented text format (see `text_format`)
+pub const TEXT_PROJECT_EXTENSION: &str = "tnst";
+
+/// Default file name for new projects
+pub const DEFAULT_PROJECT_PATH: &str = "drawing.tnsp";
+
+/// Serialized project document (JSON)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectFile {
+    pub version: u32,
+    /// Cell shape as (w, h)
+    pub cell_aspect: (u32, u32),
+    /// Screen pixels per cell at 100% zoom (added in version 4)
+    pub cell_pixels: u32,
+    /// Cells between major grid lines, 0 for none (added in version 4)
+    pub major_grid_every: u32,
+    pub cells: Vec<ProjectCell>,
+    /// User palette as hex strings
+    #[serde(default)]
+    pub custom_palette: Vec<String>,
+    /// Small preview of the drawing as base64 PNG (added in version 2)
+    pub thumbnail: Option<String>,
+    /// Most recent undo steps, oldest first; empty unless saving history is on (added in version 3)
+    pub history: Vec<ProjectCommand>,
+    /// Named groups, in gutter order (added in version 5)
+    pub groups: Vec<ProjectGroup>,
+    /// Title, author and the like (added in version 6)
+    pub metadata: ProjectMetadata,
+    /// Named selections kept for reuse, in list order (added in version 7)
+    pub saved_selections: Vec<ProjectSelection>,
+    /// View and picked colors at save time; None opens with the current view (added in version 8)
+    pub view: Option<ProjectView>,
+    /// Named frame ranges with their loop modes, in list order (added in version 9)
+    pub frame_tags: Vec<FrameTag>,
+}
+
+/// Descriptive fields edited in the metadata panel and embedded in exported PNGs
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ProjectMetadata {
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    /// "YYYY-MM-DD" the document was started; empty when unknown (files from before version 6)
+    pub created: String,
+}
+
+impl ProjectMetadata {
+    /// Empty metadata for a document started at `now` (seconds since the Unix epoch)
+    pub fn new(now: f64) -> Self {
+        Self { created: date_stamp(now), ..Self::default() }
+    }
+
+    /// Non-empty fields as PNG text chunk (keyword, text) pairs, standard keywords where
+    /// the PNG spec has one
+    pub fn text_chunks(&self) -> Vec<(&'static str, String)> {
+        [
+            ("Title", self.title.clone()),
+            ("Author", self.author.clone()),
+            ("Description", self.description.clone()),
+            ("Keywords", self.tags.join(", ")),
+            ("Creation Time", self.created.clone()),
+        ]
+        .into_iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .chain([("Software", "tiny-neo-space".to_string())])
+        .collect()
+    }
+}
+
+/// A named set of cells, optionally tagged as an animation frame
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectGroup {
+    pub name: String,
+    /// Member cells as [x, y] pairs, sorted
+    pub cells: Vec<(i32, i32)>,
+    pub frame: Option<u32>,
+    /// Frame duration in milliseconds; None follows the playback rate (added in version 10)
+    pub duration_ms: Option<u32>,
+}
+
+/// A selection saved under a name
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectSelection {
+    pub name: String,
+    /// Selected cells as [x, y] pairs, sorted
+    pub cells: Vec<(i32, i32)>,
+}
+
+impl ProjectSelection {
+    pub fn new(name: String, cells: &HashSet<(i32, i32)>) -> Self {
+        let mut cells: Vec<(i32, i32)> = cells.iter().copied().collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        Self { name, cells }
+    }
+}
+
+/// Where the editor was looking and which colors were picked, restored on open
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProjectView {
+    /// Camera origin in world cells, as [x, y]
+    pub origin: (f32, f32),
+    pub zoom: f32,
+    /// Radians
+    pub rotation: f32,
+    pub flip_x: bool,
+    /// "#rrggbbaa"
+    pub primary: String,
+    pub secondary: String,
+    /// Palette window tab: "Basic", "Extended" or "Custom"
+    pub palette_mode: String,
+}
+
+/// Most undo steps written to a project file
+pub const MAX_SAVED_HISTORY: usize = 20;
+
+/// One undo step
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectCommand {
+    pub changes: Vec<ProjectChange>,
+}
+
+/// One cell change within an undo step; colors are "#rrggbbaa", null for an empty cell
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectChange {
+    pub x: i32,
+    pub y: i32,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// One filled cell
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectCell {
+    pub x: i32,
+    pub y: i32,
+    /// "#rrggbbaa"
+    pub color: String,
+}
+
+/// The parts of the application state a project file round-trips
+pub struct Document {
+    pub cells: CellGrid,
+    pub cell_aspect: CellAspect,
+    pub grid: GridSettings,
+    pub custom_palette: Vec<Rgba>,
+    /// Undo steps restored from the file, oldest first
+    pub history: Vec<Command>,
+    pub groups: Vec<ProjectGroup>,
+    pub metadata: ProjectMetadata,
+    pub saved_selections: Vec<ProjectSelection>,
+    pub view: Option<ProjectView>,
+    pub frame_tags: Vec<FrameTag>,
+}
+
+impl ProjectFile {
+    /// Snapshot a document at the current format version; cells are sorted so saves are stable
+    /// Only the newest MAX_SAVED_HISTORY entries of `history` are kept; saved selections,
+    /// frame tags and the view start empty and are filled in by the caller when it has them.
+    /// The thumbnail is left out too: `add_thumbnail` renders it, off the frame when saving
+    pub fn from_document(cells: &CellGrid, cell_aspect: CellAspect, grid: GridSettings, custom_palette: &[Rgba], history: &[Command], groups: &[Group], metadata: &ProjectMetadata) -> Self {
+        let mut filled: Vec<ProjectCell> = cells
+            .iter()
+            .filter(|(_, cell)| cell.is_filled)
+            .map(|(&(x, y), cell)| ProjectCell { x, y, color: cell.color.to_hex() })
+            .collect();
+        filled.sort_by_key(|cell| (cell.y, cell.x));
+
+        let hex = |cell: Option<Cell>| cell.filter(|c| c.is_filled).map(|c| c.color.to_hex());
+        let history = history[history.len().saturating_sub(MAX_SAVED_HISTORY)..]
+            .iter()
+            .map(|cmd| ProjectCommand {
+                changes: cmd
+                    .changes
+                    .iter()
+                    .map(|ch| ProjectChange { x: ch.coord.0, y: ch.coord.1, before: hex(ch.before), after: hex(ch.after) })
+                    .collect(),
+            })
+            .collect();
+
+        let groups = groups
+            .iter()
+            .map(|g| {
+                let mut cells: Vec<(i32, i32)> = g.cells.iter().copied().collect();
+                cells.sort_by_key(|&(x, y)| (y, x));
+                ProjectGroup { name: g.name.clone(), cells, frame: g.frame, duration_ms: g.duration_ms }
+            })
+            .collect();
+
+        Self {
+            version: FORMAT_VERSION,
+            cell_aspect: (cell_aspect.w, cell_aspect.h),
+            cell_pixels: grid.cell_pixels,
+            major_grid_every: grid.major_every,
+            cells: filled,
+            custom_palette: custom_palette.iter().map(|c| c.to_hex()).collect(),
+            thumbnail: None,
+            history,
+            groups,
+            metadata: metadata.clone(),
+            saved_selections: Vec::new(),
+            view: None,
+            frame_tags: Vec::new(),
+        }
+    }
+
+    /// Render the thumbnail from the snapshot's own cells (malformed colors are skipped)
+    pub fn add_thumbnail(&mut self) {
+        let cells: CellGrid = self
+            .cells
+            .iter()
+            .filter_map(|cell| Rgba::from_hex(&cell.color).map(|color| ((cell.x, cell.y), Cell::with_color(color))))
+            .collect();
+        let (w, h) = self.cell_aspect;
+        self.thumbnail = render_thumbnail(&cells, CellAspect { w, h });
+    }
+
+    /// Rebuild the document, rejecting malformed colors and zero-sized cells; an out-of-range
+    /// cell size is clamped
+    pub fn into_document(self) -> Result<Document, String> {
+        let parse = |hex: &str| Rgba::from_hex(hex).ok_or_else(|| format!("Invalid color '{}'", hex));
+
+        let mut cells = CellGrid::new();
+        for cell in self.cells {
+            cells.insert((cell.x, cell.y), Cell::with_color(parse(&cell.color)?));
+        }
+
+        let (w, h) = self.cell_aspect;
+        if w == 0 || h == 0 {
+            return Err(format!("Invalid cell aspect {}:{}", w, h));
+        }
+
+        let custom_palette = self.custom_palette.iter().map(|hex| parse(hex)).collect::<Result<_, _>>()?;
+        if let Some(view) = &self.view {
+            parse(&view.primary)?;
+            parse(&view.secondary)?;
+        }
+
+        let parse_cell = |hex: &Option<String>| hex.as_deref().map(|hex| parse(hex).map(Cell::with_color)).transpose();
+        let mut history = Vec::new();
+        for cmd in self.history {
+            let changes = cmd
+                .changes
+                .iter()
+                .map(|ch| Ok(CellChange { coord: (ch.x, ch.y), before: parse_cell(&ch.before)?, after: parse_cell(&ch.after)? }))
+                .collect::<Result<_, String>>()?;
+            history.push(Command { changes });
+        }
+
+        let grid = GridSettings {
+            cell_pixels: self.cell_pixels.clamp(GridSettings::MIN_CELL_PIXELS, GridSettings::MAX_CELL_PIXELS),
+            major_every: self.major_grid_every,
+        };
+
+        Ok(Document { cells, cell_aspect: CellAspect { w, h }, grid, custom_palette, history, groups: self.groups, metadata: self.metadata, saved_selections: self.saved_selections, view: self.view, frame_tags: self.frame_tags })
+    }
+}
@@ -0,0 +1,121 @@
+use serde_json::Value;
+use crate::project::format::{ProjectFile, ProjectMetadata, FORMAT_VERSION};
+use crate::project::text_format::{text_project_to_value, TEXT_HEADER};
+
+/// Upgrades a raw document from version `n` to `n + 1`; `MIGRATIONS[n - 1]` handles version `n`
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// One entry per past format version, in order. Append here when FORMAT_VERSION is bumped,
+/// and never edit an existing entry: old files depend on every step staying the same
+const MIGRATIONS: &[Migration] = &[
+    v1_add_thumbnail,
+    v2_add_history,
+    v3_add_grid_settings,
+    v4_add_groups,
+    v5_add_metadata,
+    v6_add_saved_selections,
+    v7_add_view,
+    v8_add_frame_tags,
+    v9_add_frame_durations,
+];
+
+/// v1 → v2: projects gained an embedded thumbnail; older files simply have none
+fn v1_add_thumbnail(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("thumbnail".to_string(), Value::Null);
+    Ok(())
+}
+
+/// v2 → v3: projects can carry recent undo steps; older files have none
+fn v2_add_history(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("history".to_string(), Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// v3 → v4: cell size and major grid spacing became per-document; older files get the
+/// values that used to be fixed
+fn v3_add_grid_settings(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("cell_pixels".to_string(), Value::from(24));
+    obj.insert("major_grid_every".to_string(), Value::from(16));
+    Ok(())
+}
+
+/// v4 → v5: groups (and their frame tags) are saved with the document; older files have none
+fn v4_add_groups(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("groups".to_string(), Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// v5 → v6: documents carry a title, author, description, tags and creation date; older
+/// files get empty ones (the creation date is unknown)
+fn v5_add_metadata(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("metadata".to_string(), serde_json::to_value(ProjectMetadata::default()).map_err(|e| e.to_string())?);
+    Ok(())
+}
+
+/// v6 → v7: selections can be saved under a name with the document; older files have none
+fn v6_add_saved_selections(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("saved_selections".to_string(), Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// v7 → v8: the view and picked colors are saved; older files open with the current view
+fn v7_add_view(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("view".to_string(), Value::Null);
+    Ok(())
+}
+
+/// v8 → v9: frame tags; older files have none
+fn v8_add_frame_tags(doc: &mut Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("project root is not an object")?;
+    obj.insert("frame_tags".to_string(), Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// v9 → v10: groups gained a frame duration; older frames follow the playback rate
+fn v9_add_frame_durations(doc: &mut Value) -> Result<(), String> {
+    let groups = doc.get_mut("groups").and_then(Value::as_array_mut).ok_or("project groups are not a list")?;
+    for group in groups {
+        let group = group.as_object_mut().ok_or("project group is not an object")?;
+        group.insert("duration_ms".to_string(), Value::Null);
+    }
+    Ok(())
+}
+
+/// Parse a project of any supported version, JSON or text, and bring it up to FORMAT_VERSION.
+/// The format is told by the content rather than the extension, so renamed files still open
+pub fn parse_project(text: &str) -> Result<ProjectFile, String> {
+    let mut doc: Value = if text.starts_with(TEXT_HEADER) {
+        text_project_to_value(text)?
+    } else {
+        serde_json::from_str(text).map_err(|e| format!("Not a valid project file: {}", e))?
+    };
+
+    let version = doc
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or("Not a tiny-neo-space project (missing format version)")? as u32;
+
+    if version > FORMAT_VERSION {
+        return Err(format!(
+            "This project uses format version {}, but this build only supports up to {}; please update the app",
+            version, FORMAT_VERSION
+        ));
+    }
+    if version == 0 {
+        return Err("Invalid project format version 0".to_string());
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        migration(&mut doc).map_err(|e| format!("Failed to upgrade project from version {}: {}", from + 1, e))?;
+        doc["version"] = Value::from(from as u32 + 2);
+    }
+
+    serde_json::from_value(doc).map_err(|e| format!("Corrupt project file: {}", e))
+}
@@ -0,0 +1,17 @@
+//! Project files: the on-disk document format and its plain-text variant, version migrations, save/open,
+//! embedded thumbnails, the recent-projects list, the saved clipboard,
+//! the action and error logs, workspace presets and rotating backups
+
+pub mod format;
+pub mod text_format;
+pub mod migrate;
+pub mod file;
+pub mod thumbnail;
+pub mod recent;
+pub mod clipboard;
+pub mod action_log;
+pub mod error_log;
+pub mod preset;
+pub mod backup;
+
+pub use file::{is_text_project, new_document, open_project, read_project, restore_backup, snapshot_project, write_project};
@@ -0,0 +1,148 @@
+use macroquad::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::core::color::Rgba;
+use crate::error::FileError;
+use crate::input::actions::{key_from_name, Action, KeyBinding};
+use crate::input::mouse::MouseSettings;
+use crate::rendering::hud::HudCorner;
+use crate::state::ApplicationState;
+use crate::ui::theme::Theme;
+
+/// Extension of workspace preset files
+pub const PRESET_EXTENSION: &str = "tnspreset";
+
+/// Shareable workspace: keymap, mouse, theme and panel layout (JSON). Documents are untouched
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WorkspacePreset {
+    pub keymap: Vec<PresetBinding>,
+    /// Missing from presets saved before mouse settings existed
+    #[serde(default)]
+    pub mouse: PresetMouse,
+    pub theme: PresetTheme,
+    pub layout: PresetLayout,
+}
+
+/// One binding; `key` is a KeyCode name such as "F2" or "LeftBracket", `action` an Action name
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PresetBinding {
+    pub key: String,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    pub action: String,
+}
+
+/// `zoom_step` is the zoom factor per wheel notch and must be above 1
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PresetMouse {
+    pub swap_buttons: bool,
+    pub invert_zoom: bool,
+    pub zoom_step: f32,
+}
+
+impl Default for PresetMouse {
+    fn default() -> Self {
+        let mouse = MouseSettings::default();
+        Self { swap_buttons: mouse.swap_buttons, invert_zoom: mouse.invert_zoom, zoom_step: mouse.zoom_step }
+    }
+}
+
+/// Colors are "#rrggbbaa"
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PresetTheme {
+    pub name: String,
+    pub background: String,
+    pub grid: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PresetLayout {
+    pub show_palette: bool,
+    pub palette_position: (f32, f32),
+    pub show_preview: bool,
+    pub show_hud: bool,
+    /// HudCorner name, e.g. "TopRight"
+    pub hud_corner: String,
+    pub hud_compact: bool,
+}
+
+impl WorkspacePreset {
+    pub fn from_state(state: &ApplicationState) -> Self {
+        Self {
+            keymap: state
+                .keymap
+                .iter()
+                .map(|(binding, action)| PresetBinding {
+                    key: format!("{:?}", binding.key),
+                    ctrl: binding.ctrl,
+                    shift: binding.shift,
+                    action: action.name(),
+                })
+                .collect(),
+            mouse: PresetMouse {
+                swap_buttons: state.mouse.swap_buttons,
+                invert_zoom: state.mouse.invert_zoom,
+                zoom_step: state.mouse.zoom_step,
+            },
+            theme: PresetTheme {
+                name: state.theme.name.clone(),
+                background: state.theme.background.to_hex(),
+                grid: state.theme.grid.to_hex(),
+            },
+            layout: PresetLayout {
+                show_palette: state.show_palette,
+                palette_position: (state.palette_position.x, state.palette_position.y),
+                show_preview: state.show_preview,
+                show_hud: state.show_hud,
+                hud_corner: format!("{:?}", state.hud_layout.corner),
+                hud_compact: state.hud_layout.compact,
+            },
+        }
+    }
+
+    /// Validate everything first, so a bad preset leaves the workspace as it was
+    pub fn apply(self, state: &mut ApplicationState) -> Result<(), String> {
+        let keymap = self
+            .keymap
+            .iter()
+            .map(|entry| {
+                let key = key_from_name(&entry.key).ok_or_else(|| format!("Unknown key '{}'", entry.key))?;
+                let action = Action::from_name(&entry.action).ok_or_else(|| format!("Unknown action '{}'", entry.action))?;
+                Ok((KeyBinding { key, ctrl: entry.ctrl, shift: entry.shift }, action))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if !(self.mouse.zoom_step > 1.0 && self.mouse.zoom_step.is_finite()) {
+            return Err(format!("Zoom step {} must be above 1", self.mouse.zoom_step));
+        }
+        let color = |hex: &str| Rgba::from_hex(hex).ok_or_else(|| format!("Invalid color '{}'", hex));
+        let theme = Theme { name: self.theme.name, background: color(&self.theme.background)?, grid: color(&self.theme.grid)? };
+        let corner = HudCorner::ALL
+            .iter()
+            .copied()
+            .find(|corner| format!("{:?}", corner) == self.layout.hud_corner)
+            .ok_or_else(|| format!("Unknown HUD corner '{}'", self.layout.hud_corner))?;
+
+        state.keymap = keymap;
+        state.mouse = MouseSettings { swap_buttons: self.mouse.swap_buttons, invert_zoom: self.mouse.invert_zoom, zoom_step: self.mouse.zoom_step };
+        state.theme = theme;
+        state.show_palette = self.layout.show_palette;
+        state.palette_position = Vec2::new(self.layout.palette_position.0, self.layout.palette_position.1);
+        state.show_preview = self.layout.show_preview;
+        state.show_hud = self.layout.show_hud;
+        state.hud_layout.corner = corner;
+        state.hud_layout.compact = self.layout.hud_compact;
+        Ok(())
+    }
+}
+
+pub fn save_preset(state: &ApplicationState, path: &str) -> Result<(), FileError> {
+    let json = serde_json::to_string_pretty(&WorkspacePreset::from_state(state)).map_err(|e| FileError::write(path, e))?;
+    std::fs::write(path, json).map_err(|e| FileError::write(path, e))
+}
+
+pub fn load_preset(state: &mut ApplicationState, path: &str) -> Result<(), FileError> {
+    let text = std::fs::read_to_string(path).map_err(|e| FileError::read(path, e))?;
+    let preset: WorkspacePreset = serde_json::from_str(&text).map_err(|e| FileError::format(path, format!("Not a workspace preset: {}", e)))?;
+    preset.apply(state).map_err(|e| FileError::format(path, e))
+}
@@ -0,0 +1,70 @@
+use macroquad::prelude::*;
+use crate::error::FileError;
+use crate::project::error_log::append_error_log;
+use crate::project::migrate::parse_project;
+use crate::project::thumbnail::decode_thumbnail;
+
+/// How many recently used projects are remembered
+pub const MAX_RECENT: usize = 8;
+const RECENT_FILE_NAME: &str = ".tiny-neo-space-recent";
+
+/// A recently used project as shown on the Open Recent screen
+pub struct RecentProject {
+    pub path: String,
+    /// None when the file is missing, unreadable, or predates thumbnails
+    pub thumbnail: Option<Texture2D>,
+}
+
+/// The recent list lives in the home directory (or the working directory as a fallback)
+fn recent_list_path() -> std::path::PathBuf {
+    let dir = std::env::var_os("HOME").map(std::path::PathBuf::from).unwrap_or_default();
+    dir.join(RECENT_FILE_NAME)
+}
+
+/// Paths of recently saved or opened projects, newest first
+pub fn load_recent_paths() -> Vec<String> {
+    std::fs::read_to_string(recent_list_path())
+        .map(|text| text.lines().filter(|l| !l.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Move `path` to the front of the recent list; failures only reach the error log since the
+/// list is a convenience
+pub fn remember_recent(path: &str) {
+    let absolute = std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string());
+    let mut paths = load_recent_paths();
+    paths.retain(|p| *p != absolute);
+    paths.insert(0, absolute);
+    paths.truncate(MAX_RECENT);
+    let list_path = recent_list_path();
+    if let Err(e) = std::fs::write(&list_path, paths.join("\n")) {
+        append_error_log(&FileError::write(&list_path.to_string_lossy(), e).to_string());
+    }
+}
+
+/// Load the recent list along with each project's embedded thumbnail
+pub fn load_recent_projects() -> Vec<RecentProject> {
+    load_project_cards(load_recent_paths())
+}
+
+/// Each project at `paths` with its embedded thumbnail, as the picker shows them
+pub fn load_project_cards(paths: Vec<String>) -> Vec<RecentProject> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let thumbnail = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| parse_project(&text).ok())
+                .and_then(|file| file.thumbnail)
+                .and_then(|encoded| decode_thumbnail(&encoded))
+                .map(|(w, h, pixels)| {
+                    let texture = Texture2D::from_rgba8(w, h, &pixels);
+                    texture.set_filter(FilterMode::Nearest);
+                    texture
+                });
+            RecentProject { path, thumbnail }
+        })
+        .collect()
+}
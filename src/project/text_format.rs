@@ -0,0 +1,202 @@
+//! Line-oriented project format for version control: one entry per line, cells and group
+//! members sorted, no thumbnail, undo history or view, so edits show up as small, mergeable diffs
+//!
+//! ```text
+//! tiny-neo-space-project 10
+//! aspect 1 1
+//! cell-pixels 24
+//! major-grid 16
+//! title Forest tiles
+//! tag tiles
+//! created 2026-10-17
+//! palette #94b0c2ff
+//! group 1 Walk 1
+//! member 0 0
+//! group 2@250 Walk 2
+//! member 1 0
+//! selection Roof
+//! member 0 0
+//! frame-tag 1 4 ping_pong Walk
+//! cell 0 0 #333c57ff
+//! ```
+//!
+//! A group line holds its frame number (or `-`), with `@` and its duration in milliseconds
+//! when it has one, then its name; a saved selection line holds its name. The `member` lines after either belong to it. A frame tag line holds its first
+//! and last frame numbers, its loop mode (`forward`, `ping_pong` or `once`) then its name.
+//! Text values escape backslashes and newlines as `\\` and `\n`
+
+use serde_json::{json, Map, Value};
+use crate::project::format::ProjectFile;
+
+/// First word of every text project; the format version follows it
+pub const TEXT_HEADER: &str = "tiny-neo-space-project";
+
+/// Serialize a project as text; the same document always gives the same bytes
+pub fn encode_text_project(file: &ProjectFile) -> String {
+    let mut lines = vec![
+        format!("{} {}", TEXT_HEADER, file.version),
+        format!("aspect {} {}", file.cell_aspect.0, file.cell_aspect.1),
+        format!("cell-pixels {}", file.cell_pixels),
+        format!("major-grid {}", file.major_grid_every),
+    ];
+
+    let meta = &file.metadata;
+    for (key, value) in [("title", &meta.title), ("author", &meta.author), ("description", &meta.description)] {
+        if !value.is_empty() {
+            lines.push(format!("{} {}", key, escape(value)));
+        }
+    }
+    lines.extend(meta.tags.iter().map(|tag| format!("tag {}", escape(tag))));
+    if !meta.created.is_empty() {
+        lines.push(format!("created {}", escape(&meta.created)));
+    }
+
+    lines.extend(file.custom_palette.iter().map(|hex| format!("palette {}", hex)));
+    for group in &file.groups {
+        let frame = match (group.frame, group.duration_ms) {
+            (Some(frame), Some(ms)) => format!("{}@{}", frame, ms),
+            (Some(frame), None) => frame.to_string(),
+            (None, _) => "-".to_string(),
+        };
+        lines.push(format!("group {} {}", frame, escape(&group.name)));
+        lines.extend(group.cells.iter().map(|(x, y)| format!("member {} {}", x, y)));
+    }
+    for selection in &file.saved_selections {
+        lines.push(format!("selection {}", escape(&selection.name)));
+        lines.extend(selection.cells.iter().map(|(x, y)| format!("member {} {}", x, y)));
+    }
+    for tag in &file.frame_tags {
+        lines.push(format!("frame-tag {} {} {} {}", tag.start, tag.end, tag.loop_mode.key(), escape(&tag.name)));
+    }
+    lines.extend(file.cells.iter().map(|cell| format!("cell {} {} {}", cell.x, cell.y, cell.color)));
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Parse a text project into the same raw document shape as the JSON format, so it goes
+/// through the regular version migrations
+pub fn text_project_to_value(text: &str) -> Result<Value, String> {
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let version = match lines.next().map(|(_, line)| line.split_once(' ')) {
+        Some(Some((TEXT_HEADER, version))) => version.trim().parse::<u32>().map_err(|_| format!("Invalid format version '{}'", version))?,
+        _ => return Err("Not a tiny-neo-space text project".to_string()),
+    };
+
+    let mut doc = Map::new();
+    doc.insert("version".to_string(), json!(version));
+    doc.insert("thumbnail".to_string(), Value::Null);
+    doc.insert("history".to_string(), json!([]));
+    doc.insert("view".to_string(), Value::Null);
+    let (mut cells, mut palette, mut groups, mut tags) = (Vec::new(), Vec::new(), Vec::<Value>::new(), Vec::new());
+    let (mut selections, mut frame_tags) = (Vec::<Value>::new(), Vec::new());
+    // Whether `member` lines currently go to the last saved selection rather than the last group
+    let mut in_selection = false;
+    let mut metadata = Map::new();
+
+    for (index, line) in lines {
+        let err = |message: &str| format!("Line {}: {}", index + 1, message);
+        let (key, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let numbers = || -> Result<Vec<i64>, String> {
+            rest.split_whitespace().map(|n| n.parse::<i64>().map_err(|_| err(&format!("'{}' is not a number", n)))).collect()
+        };
+        match key {
+            "aspect" => match numbers()?.as_slice() {
+                &[w, h] => {
+                    doc.insert("cell_aspect".to_string(), json!([w, h]));
+                }
+                _ => return Err(err("expected 'aspect <w> <h>'")),
+            },
+            "cell-pixels" | "major-grid" => match numbers()?.as_slice() {
+                &[n] => {
+                    let field = if key == "cell-pixels" { "cell_pixels" } else { "major_grid_every" };
+                    doc.insert(field.to_string(), json!(n));
+                }
+                _ => return Err(err(&format!("expected '{} <n>'", key))),
+            },
+            "title" | "author" | "description" | "created" => {
+                metadata.insert(key.to_string(), json!(unescape(rest)));
+            }
+            "tag" => tags.push(json!(unescape(rest))),
+            "palette" => palette.push(json!(rest.trim())),
+            "group" => {
+                let (frame, name) = rest.split_once(' ').unwrap_or((rest, ""));
+                let (frame, duration) = frame.split_once('@').map_or((frame, None), |(frame, ms)| (frame, Some(ms)));
+                let number = |n: &str| n.parse::<u32>().map_err(|_| err(&format!("invalid frame '{}'", n)));
+                let frame = match frame {
+                    "-" => Value::Null,
+                    n => json!(number(n)?),
+                };
+                let duration = match duration {
+                    Some(ms) => json!(ms.parse::<u32>().map_err(|_| err(&format!("invalid frame duration '{}'", ms)))?),
+                    None => Value::Null,
+                };
+                groups.push(json!({ "name": unescape(name), "cells": [], "frame": frame, "duration_ms": duration }));
+                in_selection = false;
+            }
+            "selection" => {
+                selections.push(json!({ "name": unescape(rest), "cells": [] }));
+                in_selection = true;
+            }
+            "frame-tag" => {
+                let mut parts = rest.splitn(4, ' ');
+                let (start, end, loop_mode, name) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                    (Some(start), Some(end), Some(loop_mode), name) => (start, end, loop_mode, name.unwrap_or("")),
+                    _ => return Err(err("expected 'frame-tag <start> <end> <loop> <name>'")),
+                };
+                let frame = |n: &str| n.parse::<u32>().map_err(|_| err(&format!("invalid frame '{}'", n)));
+                frame_tags.push(json!({ "name": unescape(name), "start": frame(start)?, "end": frame(end)?, "loop_mode": loop_mode }));
+            }
+            "member" => {
+                let owner = if in_selection { selections.last_mut() } else { groups.last_mut() };
+                match (numbers()?.as_slice(), owner) {
+                    (&[x, y], Some(owner)) => owner["cells"].as_array_mut().expect("member cells are an array").push(json!([x, y])),
+                    (_, None) => return Err(err("'member' before any 'group' or 'selection'")),
+                    _ => return Err(err("expected 'member <x> <y>'")),
+                }
+            }
+            "cell" => match rest.split_whitespace().collect::<Vec<_>>().as_slice() {
+                &[x, y, color] => {
+                    let coord = |n: &str| n.parse::<i64>().map_err(|_| err(&format!("'{}' is not a number", n)));
+                    cells.push(json!({ "x": coord(x)?, "y": coord(y)?, "color": color }));
+                }
+                _ => return Err(err("expected 'cell <x> <y> <#rrggbbaa>'")),
+            },
+            other => return Err(err(&format!("unknown entry '{}'", other))),
+        }
+    }
+
+    metadata.insert("tags".to_string(), Value::Array(tags));
+    for field in ["title", "author", "description", "created"] {
+        metadata.entry(field).or_insert(json!(""));
+    }
+    doc.insert("cells".to_string(), Value::Array(cells));
+    doc.insert("custom_palette".to_string(), Value::Array(palette));
+    doc.insert("groups".to_string(), Value::Array(groups));
+    doc.insert("saved_selections".to_string(), Value::Array(selections));
+    doc.insert("frame_tags".to_string(), Value::Array(frame_tags));
+    doc.insert("metadata".to_string(), Value::Object(metadata));
+    Ok(Value::Object(doc))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
@@ -0,0 +1,73 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::{ImageFormat, RgbaImage};
+use crate::core::cell::{grid_bounds, CellAspect, CellGrid};
+use crate::core::color::Rgba;
+
+/// Longest side of an embedded thumbnail in pixels
+pub const THUMBNAIL_SIZE: u32 = 96;
+
+/// Render the drawing's bounding box into a PNG no larger than THUMBNAIL_SIZE, base64 encoded
+/// Cells are sampled straight into the thumbnail, so however far apart they are no more than
+/// THUMBNAIL_SIZE² pixels are ever allocated. Small drawings are upscaled by whole cells;
+/// large ones average the cells falling in each pixel
+pub fn render_thumbnail(cells: &CellGrid, aspect: CellAspect) -> Option<String> {
+    let rect = grid_bounds(cells)?;
+    // Drawing size in aspect pixels; f64 since a far-flung stray makes these huge
+    let span_w = rect.width() as f64 * aspect.w as f64;
+    let span_h = rect.height() as f64 * aspect.h as f64;
+    let longest = span_w.max(span_h);
+    let size = THUMBNAIL_SIZE as f64;
+    let per_pixel = if longest <= size { (size / longest).floor() } else { size / longest };
+    let width = ((span_w * per_pixel).round() as u32).clamp(1, THUMBNAIL_SIZE);
+    let height = ((span_h * per_pixel).round() as u32).clamp(1, THUMBNAIL_SIZE);
+    let (cell_w, cell_h) = (aspect.w as f64 * per_pixel, aspect.h as f64 * per_pixel);
+
+    // Per pixel: summed RGBA of the cells touching it and how many there were
+    let mut sums = vec![[0u64; 5]; (width * height) as usize];
+    for (&(x, y), cell) in cells.iter().filter(|(_, cell)| cell.is_filled) {
+        let left = (x - rect.min_x) as f64 * cell_w;
+        let top = (y - rect.min_y) as f64 * cell_h;
+        let columns = pixel_span(left, left + cell_w, width);
+        for py in pixel_span(top, top + cell_h, height) {
+            for px in columns.clone() {
+                let sum = &mut sums[(py * width + px) as usize];
+                let Rgba { r, g, b, a } = cell.color;
+                for (total, channel) in sum.iter_mut().zip([r, g, b, a]) {
+                    *total += channel as u64;
+                }
+                sum[4] += 1;
+            }
+        }
+    }
+
+    // A pixel covering many cells fades by how few of them are filled
+    let footprint = (1.0 / cell_w).max(1.0) * (1.0 / cell_h).max(1.0);
+    let mut image = RgbaImage::new(width, height);
+    for (pixel, sum) in image.pixels_mut().zip(&sums) {
+        if sum[4] > 0 {
+            let count = sum[4];
+            let coverage = (count as f64 / footprint).min(1.0);
+            let alpha = (sum[3] / count) as f64 * coverage;
+            pixel.0 = [(sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8, alpha.round() as u8];
+        }
+    }
+
+    let mut png = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png).ok()?;
+    Some(BASE64.encode(png))
+}
+
+/// Pixels along one axis that the span [start, end) touches, at least one
+fn pixel_span(start: f64, end: f64, limit: u32) -> std::ops::Range<u32> {
+    let first = (start.floor().max(0.0) as u32).min(limit - 1);
+    let last = (end.ceil() as u32).clamp(first + 1, limit);
+    first..last
+}
+
+/// Decode an embedded thumbnail into (width, height, RGBA8 pixels)
+pub fn decode_thumbnail(encoded: &str) -> Option<(u16, u16, Vec<u8>)> {
+    let png = BASE64.decode(encoded).ok()?;
+    let image = image::load_from_memory_with_format(&png, ImageFormat::Png).ok()?.to_rgba8();
+    Some((image.width() as u16, image.height() as u16, image.into_raw()))
+}
@@ -1,32 +1,95 @@
 use macroquad::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::core::*;
 use crate::core::camera::Camera as AppCamera;
+use crate::core::raster::chunk_mips;
 
 const CHUNK_SIZE: i32 = 64; // 64×64 cells per chunk
-const CHUNK_TEXTURE_SIZE: u32 = 512; // 512×512 pixels (8px per cell)
-const CELL_TEXTURE_SIZE: u32 = 8; // Each cell is 8×8 pixels in chunk texture
+const ATLAS_SLOTS_PER_ROW: u16 = 15; // 15×15 slots per page
+const ATLAS_SLOTS_PER_PAGE: usize = ATLAS_SLOTS_PER_ROW as usize * ATLAS_SLOTS_PER_ROW as usize;
+const MIP_LEVELS: usize = 4; // 64, 32, 16 and 8 texels per chunk side
+const REBUILD_BUDGET_CHUNKS: usize = 24; // Max chunk rebuilds per frame
+const REBUILD_BUDGET_SECONDS: f64 = 0.004; // Stop starting new rebuilds after ~4ms
+const REBUILD_WATCHDOG_SECONDS: f64 = 0.25; // Backlogs older than this get a progress readout
+const REBUILD_BATCH: usize = 8; // Chunks rasterized in parallel between budget checks
+
+/// One resolution of the chunk atlas; every chunk owns the same slot index in each level
+struct AtlasLevel {
+    /// Texels per chunk side (64 >> level)
+    texels: u16,
+    pages: Vec<Texture2D>,
+}
+
+impl AtlasLevel {
+    /// Slot side length: the chunk texels plus a 1-texel gutter on each side
+    fn slot_size(&self) -> u16 {
+        self.texels + 2
+    }
+
+    /// Page and texel offset (gutter included) of a slot
+    fn locate(&self, slot: usize) -> (usize, u16, u16) {
+        let index = (slot % ATLAS_SLOTS_PER_PAGE) as u16;
+        (
+            slot / ATLAS_SLOTS_PER_PAGE,
+            (index % ATLAS_SLOTS_PER_ROW) * self.slot_size(),
+            (index / ATLAS_SLOTS_PER_ROW) * self.slot_size(),
+        )
+    }
+}
 
 struct Chunk {
-    render_target: RenderTarget,
+    /// Atlas slot index, shared by all mip levels
+    slot: usize,
     dirty: bool,
 }
 
-/// Chunked canvas renderer with cached RenderTargets and dirty rebuilds
-/// Partitions the world into 64×64 cell chunks, each rendered into a texture
-/// Only rebuilds dirty chunks and only draws visible chunks
+/// Chunked canvas renderer with a shared, mipmapped texture atlas and dirty rebuilds
+/// Partitions the world into 64×64 cell chunks, each stored as a slot of an atlas page
+/// (one texel per cell at level 0, box-filtered halvings above). Only rebuilds dirty chunks
+/// and only draws visible chunks; chunks sharing a page are drawn back to back so macroquad
+/// batches them into one draw call
 pub struct CanvasRenderer {
     chunks: HashMap<(i32, i32), Chunk>,
+    levels: Vec<AtlasLevel>,
+    /// Number of slots handed out so far (slots are never reclaimed)
+    allocated_slots: usize,
+    /// Rebuild work that didn't fit in one frame: (chunks in the backlog, when it started)
+    backlog: Option<(usize, f64)>,
+    /// Cells drawn as empty without touching the document (frame playback)
+    hidden: HashSet<(i32, i32)>,
 }
 
 impl CanvasRenderer {
     pub fn new() -> Self {
         Self {
             chunks: HashMap::new(),
+            levels: (0..MIP_LEVELS)
+                .map(|level| AtlasLevel { texels: (CHUNK_SIZE as u16) >> level, pages: Vec::new() })
+                .collect(),
+            allocated_slots: 0,
+            backlog: None,
+            hidden: HashSet::new(),
         }
     }
 
+    /// Draw `hidden` as empty from now on, redrawing the chunks whose cells changed visibility
+    pub fn set_hidden(&mut self, hidden: HashSet<(i32, i32)>) {
+        let changed: Vec<(i32, i32)> = self.hidden.symmetric_difference(&hidden).copied().collect();
+        self.mark_cells_dirty(changed);
+        self.hidden = hidden;
+    }
+
+    /// (chunks rebuilt, chunks in total) while a rebuild has been running across frames for a while
+    pub fn rebuild_progress(&self) -> Option<(usize, usize)> {
+        let (total, started) = self.backlog?;
+        if get_time() - started < REBUILD_WATCHDOG_SECONDS {
+            return None;
+        }
+        let remaining = self.chunks.values().filter(|chunk| chunk.dirty).count();
+        Some((total.saturating_sub(remaining), total))
+    }
+
     /// Convert cell coordinates to chunk coordinates
     #[inline]
     fn cell_to_chunk(cell_x: i32, cell_y: i32) -> (i32, i32) {
@@ -36,25 +99,30 @@ impl CanvasRenderer {
         )
     }
 
-    /// Convert cell coordinates to local coordinates within chunk (0-63)
-    #[inline]
-    fn cell_to_local(cell_x: i32, cell_y: i32) -> (i32, i32) {
-        (
-            cell_x.rem_euclid(CHUNK_SIZE),
-            cell_y.rem_euclid(CHUNK_SIZE)
-        )
-    }
-
-    /// Get or create a chunk at the given chunk coordinates
+    /// Get or create a chunk at the given chunk coordinates, assigning it the next free atlas slot
     fn get_or_create_chunk(&mut self, chunk_coords: (i32, i32)) -> &mut Chunk {
-        self.chunks.entry(chunk_coords).or_insert_with(|| {
-            let rt = render_target(CHUNK_TEXTURE_SIZE, CHUNK_TEXTURE_SIZE);
-            rt.texture.set_filter(FilterMode::Nearest);
-            Chunk {
-                render_target: rt,
-                dirty: true, // New chunks start dirty
+        if !self.chunks.contains_key(&chunk_coords) {
+            let slot = self.allocated_slots;
+            self.allocated_slots += 1;
+
+            // Grow every level by a page when the first slot of a new page is handed out
+            if slot / ATLAS_SLOTS_PER_PAGE == self.levels[0].pages.len() {
+                for (level, atlas) in self.levels.iter_mut().enumerate() {
+                    let page_size = ATLAS_SLOTS_PER_ROW * atlas.slot_size();
+                    let blank = Image::gen_image_color(page_size, page_size, Color::new(0.0, 0.0, 0.0, 0.0));
+                    let texture = Texture2D::from_image(&blank);
+                    // Full-resolution cells stay crisp; downsampled levels blend between texels
+                    texture.set_filter(if level == 0 { FilterMode::Nearest } else { FilterMode::Linear });
+                    atlas.pages.push(texture);
+                }
             }
-        })
+
+            self.chunks.insert(chunk_coords, Chunk {
+                slot,
+                dirty: true, // New chunks start dirty
+            });
+        }
+        self.chunks.get_mut(&chunk_coords).unwrap()
     }
 
     /// Mark a cell as dirty (needs redrawing)
@@ -66,142 +134,233 @@ impl CanvasRenderer {
         chunk.dirty = true;
     }
 
+    /// Mark the chunks holding `coords` dirty, touching each chunk once
+    pub fn mark_cells_dirty(&mut self, coords: impl IntoIterator<Item = (i32, i32)>) {
+        let chunks: HashSet<(i32, i32)> = coords.into_iter().map(|(x, y)| Self::cell_to_chunk(x, y)).collect();
+        for chunk_coords in chunks {
+            self.get_or_create_chunk(chunk_coords).dirty = true;
+        }
+    }
+
+    /// Mark every existing chunk overlapping `rect` dirty; a cleared rect needs no new chunks
+    pub fn mark_rect_dirty(&mut self, rect: &SelectionRect) {
+        let (min_x, min_y) = Self::cell_to_chunk(rect.min_x, rect.min_y);
+        let (max_x, max_y) = Self::cell_to_chunk(rect.max_x, rect.max_y);
+        for (&(x, y), chunk) in self.chunks.iter_mut() {
+            if (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y) {
+                chunk.dirty = true;
+            }
+        }
+    }
+
+    /// Mark every chunk dirty, e.g. when the whole document is replaced
+    pub fn mark_all_dirty(&mut self) {
+        for chunk in self.chunks.values_mut() {
+            chunk.dirty = true;
+        }
+    }
+
     /// Check if screen size changed (kept for compatibility)
     pub fn update_if_screen_resized(&mut self) {
         // Not needed with chunked rendering
     }
 
-    /// Rebuild all dirty chunks by rendering their cells into RenderTargets
-    pub fn update(&mut self, cells: &CellGrid) {
+    /// Rebuild dirty chunks within a per-frame budget, visible chunks first
+    /// Large batches (e.g. an image import) are spread over several frames instead of stalling one
+    pub fn update(&mut self, cells: &CellGrid, camera: &AppCamera) {
         // Collect dirty chunk coordinates (can't mutate while iterating)
-        let dirty_chunks: Vec<(i32, i32)> = self.chunks
+        let mut dirty_chunks: Vec<(i32, i32)> = self.chunks
             .iter()
             .filter(|(_, chunk)| chunk.dirty)
             .map(|(coords, _)| *coords)
             .collect();
+        if dirty_chunks.is_empty() {
+            self.backlog = None;
+            return;
+        }
 
-        for chunk_coords in dirty_chunks {
-            self.rebuild_chunk(chunk_coords, cells);
+        // Track backlogs that outlast a frame; a larger batch dirtied meanwhile raises the total
+        let pending = dirty_chunks.len();
+        if let Some((total, _)) = &mut self.backlog {
+            *total = (*total).max(pending);
+        } else if pending > REBUILD_BUDGET_CHUNKS {
+            self.backlog = Some((pending, get_time()));
         }
-    }
 
-    /// Rebuild a single chunk's RenderTarget
-    fn rebuild_chunk(&mut self, chunk_coords: (i32, i32), cells: &CellGrid) {
-        let chunk = match self.chunks.get_mut(&chunk_coords) {
-            Some(c) => c,
-            None => return,
+        // Visible chunks first, then by distance from the view center
+        let (min_chunk, max_chunk) = Self::visible_chunk_range(camera, screen_width(), screen_height());
+        let center = (min_chunk + max_chunk).as_vec2() / 2.0;
+        let is_visible = |(x, y): (i32, i32)| {
+            x >= min_chunk.x && x <= max_chunk.x && y >= min_chunk.y && y <= max_chunk.y
         };
+        dirty_chunks.sort_by(|&a, &b| {
+            let dist_a = Vec2::new(a.0 as f32, a.1 as f32).distance_squared(center);
+            let dist_b = Vec2::new(b.0 as f32, b.1 as f32).distance_squared(center);
+            is_visible(b).cmp(&is_visible(a)).then(dist_a.total_cmp(&dist_b))
+        });
 
-        let rt = chunk.render_target.clone();
+        // Always make progress on at least one batch, then stop at whichever budget runs out first
+        dirty_chunks.truncate(REBUILD_BUDGET_CHUNKS);
+        let start = get_time();
+        for batch in dirty_chunks.chunks(REBUILD_BATCH) {
+            // Pixel data is built on the CPU (in parallel where threads exist); only the upload
+            // touches the GPU, and it stays on the render thread
+            let slots = rasterize_chunks(cells, &self.hidden, batch);
+            for (&chunk_coords, images) in batch.iter().zip(slots) {
+                self.upload_chunk(chunk_coords, &images);
+            }
+            if get_time() - start > REBUILD_BUDGET_SECONDS {
+                break;
+            }
+        }
+    }
 
-        // Use Camera2D with positive Y zoom to avoid coordinate flip
-        // This uses screen coordinates: (0,0) at top-left, Y-down
-        let camera = Camera2D {
-            render_target: Some(rt),
-            target: vec2(CHUNK_TEXTURE_SIZE as f32 / 2.0, CHUNK_TEXTURE_SIZE as f32 / 2.0),
-            zoom: vec2(
-                2.0 / CHUNK_TEXTURE_SIZE as f32,
-                2.0 / CHUNK_TEXTURE_SIZE as f32  // Positive Y zoom - no flip!
+    /// Inclusive chunk coordinate range covering a `screen_w` × `screen_h` viewport
+    fn visible_chunk_range(camera: &AppCamera, screen_w: f32, screen_h: f32) -> (IVec2, IVec2) {
+        // Get visible world rect in cell coordinates
+        let (min_x, min_y, max_x, max_y) = camera.visible_world_rect(screen_w, screen_h);
+
+        // Convert to chunk coordinates
+        (
+            IVec2::new(
+                (min_x.floor() as i32).div_euclid(CHUNK_SIZE),
+                (min_y.floor() as i32).div_euclid(CHUNK_SIZE),
             ),
-            ..Default::default()
+            IVec2::new(
+                (max_x.ceil() as i32).div_euclid(CHUNK_SIZE),
+                (max_y.ceil() as i32).div_euclid(CHUNK_SIZE),
+            ),
+        )
+    }
+
+    /// Copy a chunk's prebuilt slot images into every mip level of the atlas
+    fn upload_chunk(&mut self, chunk_coords: (i32, i32), images: &[Image]) {
+        let chunk = match self.chunks.get_mut(&chunk_coords) {
+            Some(c) => c,
+            None => return,
         };
 
-        set_camera(&camera);
-        clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
-
-        // Calculate chunk's world cell range
-        let chunk_min_x = chunk_coords.0 * CHUNK_SIZE;
-        let chunk_min_y = chunk_coords.1 * CHUNK_SIZE;
-
-        // Render all cells in this chunk
-        for local_x in 0..CHUNK_SIZE {
-            for local_y in 0..CHUNK_SIZE {
-                let cell_x = chunk_min_x + local_x;
-                let cell_y = chunk_min_y + local_y;
-
-                if let Some(cell) = cells.get(&(cell_x, cell_y)) {
-                    if cell.is_filled {
-                        // Convert local cell coords to pixel coords in texture
-                        let px_x = local_x as f32 * CELL_TEXTURE_SIZE as f32;
-                        let px_y = local_y as f32 * CELL_TEXTURE_SIZE as f32;
-
-                        draw_rectangle(
-                            px_x,
-                            px_y,
-                            CELL_TEXTURE_SIZE as f32,
-                            CELL_TEXTURE_SIZE as f32,
-                            cell.color
-                        );
-                    }
-                }
-            }
+        for (atlas, image) in self.levels.iter().zip(images) {
+            let (page, x, y) = atlas.locate(chunk.slot);
+            atlas.pages[page].update_part(image, x as i32, y as i32, image.width as i32, image.height as i32);
         }
 
-        set_default_camera();
-
         chunk.dirty = false;
     }
 
+    /// Pick the atlas level for the current on-screen cell size
+    /// Integer scales (and anything from 2px up) map cells to whole pixels cleanly; below that,
+    /// use the level whose texels are at least ~2 screen pixels so overviews don't shimmer
+    fn mip_level(camera: &AppCamera) -> usize {
+        let cell_px = camera.cell_size().min_element();
+        if cell_px >= 2.0 || cell_px.fract() == 0.0 {
+            return 0;
+        }
+        let level = (2.0 / cell_px).log2().floor() as usize;
+        level.min(MIP_LEVELS - 1)
+    }
+
     /// Draw all visible chunks to screen with frustum culling
     pub fn draw(&self, _cells: &CellGrid, camera: &AppCamera) {
-        let screen_w = screen_width();
-        let screen_h = screen_height();
+        self.draw_viewport(camera, screen_width(), screen_height());
+    }
 
-        // Get visible world rect in cell coordinates
-        let (min_x, min_y, max_x, max_y) = camera.visible_world_rect(screen_w, screen_h);
+    /// Draw chunks visible in a `screen_w` × `screen_h` viewport (screen or render target)
+    pub fn draw_viewport(&self, camera: &AppCamera, screen_w: f32, screen_h: f32) {
+        let (min_chunk, max_chunk) = Self::visible_chunk_range(camera, screen_w, screen_h);
 
-        // Convert to chunk coordinates
-        let min_chunk_x = (min_x.floor() as i32).div_euclid(CHUNK_SIZE);
-        let min_chunk_y = (min_y.floor() as i32).div_euclid(CHUNK_SIZE);
-        let max_chunk_x = (max_x.ceil() as i32).div_euclid(CHUNK_SIZE);
-        let max_chunk_y = (max_y.ceil() as i32).div_euclid(CHUNK_SIZE);
-
-        // Draw all visible chunks
-        for chunk_x in min_chunk_x..=max_chunk_x {
-            for chunk_y in min_chunk_y..=max_chunk_y {
+        // Collect visible chunks grouped by atlas page, so consecutive draws share a texture
+        let mut visible: Vec<((i32, i32), &Chunk)> = Vec::new();
+        for chunk_x in min_chunk.x..=max_chunk.x {
+            for chunk_y in min_chunk.y..=max_chunk.y {
                 if let Some(chunk) = self.chunks.get(&(chunk_x, chunk_y)) {
-                    // Calculate chunk position in world cells
-                    let chunk_world_x = chunk_x * CHUNK_SIZE;
-                    let chunk_world_y = chunk_y * CHUNK_SIZE;
-
-                    // Convert to screen space
-                    let screen_pos = camera.cell_to_screen((chunk_world_x, chunk_world_y));
-
-                    // Calculate size in screen pixels
-                    let chunk_size_px = CHUNK_SIZE as f32 * camera.pixel_scale();
-
-                    // --- Pixel Rounding for Seam Elimination ---
-                    // At non-integer zoom levels, floating-point rounding can cause 1-pixel
-                    // seams between adjacent chunk textures. Optionally round screen positions
-                    // and sizes to whole pixels for crisp, seam-free rendering.
-                    //
-                    // When to enable:
-                    // - If visible seams appear between chunks at certain zoom levels
-                    // - When pixel-perfect alignment is more important than sub-pixel smoothness
-                    //
-                    // Trade-offs:
-                    // - Enables: Eliminates seams, crisper rendering at most zoom levels
-                    // - Disables: Smoother zoom transitions, sub-pixel positioning maintained
-                    //
-                    // To enable, uncomment the following lines and use the rounded values below:
-                    //
-                    // let screen_x = screen_pos.x.round();
-                    // let screen_y = screen_pos.y.round();
-                    // let size = chunk_size_px.round();
-
-                    // Draw the chunk texture
-                    draw_texture_ex(
-                        &chunk.render_target.texture,
-                        screen_pos.x,  // Replace with screen_x if rounding enabled
-                        screen_pos.y,  // Replace with screen_y if rounding enabled
-                        WHITE,
-                        DrawTextureParams {
-                            dest_size: Some(vec2(chunk_size_px, chunk_size_px)),  // Replace with (size, size) if rounding enabled
-                            ..Default::default()
-                        }
-                    );
+                    visible.push(((chunk_x, chunk_y), chunk));
                 }
             }
         }
+        let atlas = &self.levels[Self::mip_level(camera)];
+        visible.sort_by_key(|(_, chunk)| chunk.slot / ATLAS_SLOTS_PER_PAGE);
+
+        // Calculate size in screen pixels
+        let chunk_size_px = CHUNK_SIZE as f32 * camera.cell_size();
+
+        for ((chunk_x, chunk_y), chunk) in visible {
+            // Calculate chunk position in world cells
+            let chunk_world_x = chunk_x * CHUNK_SIZE;
+            let chunk_world_y = chunk_y * CHUNK_SIZE;
+
+            // Convert to screen space (anchored at the max-x corner when the view is mirrored)
+            let chunk_min = vec2(chunk_world_x as f32, chunk_world_y as f32);
+            let chunk_max = chunk_min + Vec2::splat(CHUNK_SIZE as f32);
+            let screen_pos = camera.world_to_screen(camera.quad_anchor(chunk_min, chunk_max));
+
+            // --- Pixel Rounding for Seam Elimination ---
+            // At non-integer zoom levels, floating-point rounding can cause 1-pixel
+            // seams between adjacent chunk textures. Optionally round screen positions
+            // and sizes to whole pixels for crisp, seam-free rendering.
+            //
+            // When to enable:
+            // - If visible seams appear between chunks at certain zoom levels
+            // - When pixel-perfect alignment is more important than sub-pixel smoothness
+            //
+            // Trade-offs:
+            // - Enables: Eliminates seams, crisper rendering at most zoom levels
+            // - Disables: Smoother zoom transitions, sub-pixel positioning maintained
+            //
+            // To enable, uncomment the following lines and use the rounded values below:
+            //
+            // let screen_x = screen_pos.x.round();
+            // let screen_y = screen_pos.y.round();
+            // let size = chunk_size_px.round();
+
+            // Only sample the chunk's texels, skipping the gutter
+            let (page, slot_x, slot_y) = atlas.locate(chunk.slot);
+            let texels = atlas.texels as f32;
+            let source = Rect::new(slot_x as f32 + 1.0, slot_y as f32 + 1.0, texels, texels);
+
+            // Draw the chunk from its atlas page
+            draw_texture_ex(
+                &atlas.pages[page],
+                screen_pos.x,  // Replace with screen_x if rounding enabled
+                screen_pos.y,  // Replace with screen_y if rounding enabled
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(chunk_size_px),  // Replace with (size, size) if rounding enabled
+                    source: Some(source),
+                    // Rotate around the chunk's own corner so it lands where the camera maps it
+                    rotation: camera.rotation,
+                    pivot: Some(screen_pos),
+                    flip_x: camera.flip_x,
+                    ..Default::default()
+                }
+            );
+        }
     }
 }
+
+/// Build slot images for a batch of chunks, one worker per chunk on native targets
+#[cfg(not(target_arch = "wasm32"))]
+fn rasterize_chunks(cells: &CellGrid, hidden: &HashSet<(i32, i32)>, batch: &[(i32, i32)]) -> Vec<Vec<Image>> {
+    use rayon::prelude::*;
+    batch.par_iter().map(|&coords| rasterize_chunk(cells, hidden, coords)).collect()
+}
+
+/// Build slot images for a batch of chunks (WASM has no worker threads by default)
+#[cfg(target_arch = "wasm32")]
+fn rasterize_chunks(cells: &CellGrid, hidden: &HashSet<(i32, i32)>, batch: &[(i32, i32)]) -> Vec<Vec<Image>> {
+    batch.iter().map(|&coords| rasterize_chunk(cells, hidden, coords)).collect()
+}
+
+/// CPU-side pixel data for one chunk: an RGBA slot image (gutter included) per mip level,
+/// with `hidden` cells left empty
+fn rasterize_chunk(cells: &CellGrid, hidden: &HashSet<(i32, i32)>, chunk_coords: (i32, i32)) -> Vec<Image> {
+    let min = (chunk_coords.0 * CHUNK_SIZE, chunk_coords.1 * CHUNK_SIZE);
+    chunk_mips(cells, hidden, min, CHUNK_SIZE, MIP_LEVELS)
+        .into_iter()
+        .map(|raster| Image {
+            bytes: raster.pixels,
+            width: raster.width as u16,
+            height: raster.height as u16,
+        })
+        .collect()
+}
@@ -0,0 +1,9 @@
+use macroquad::prelude::Color;
+use crate::core::color::Rgba;
+
+/// Document colors are stored as `Rgba`; macroquad only sees them at draw time
+impl From<Rgba> for Color {
+    fn from(c: Rgba) -> Self {
+        Color::from_rgba(c.r, c.g, c.b, c.a)
+    }
+}
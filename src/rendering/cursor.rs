@@ -2,23 +2,33 @@ use macroquad::prelude::*;
 
 use crate::state::Mode;
 use crate::core::camera::Camera as AppCamera;
+use crate::core::cell::CellGrid;
+use crate::rendering::shapes::{cell_span, draw_world_rect, draw_world_rect_lines};
 
-pub fn draw_cursor_based_on_mode(mode: &Mode, camera: &AppCamera, screen_mouse: Vec2) {
+/// Zoom at and above which precision mode takes over from the system cursor
+pub const PRECISION_ZOOM: f32 = 2.0;
+/// Cells shown across the loupe (odd, so the hovered cell is centered)
+const LOUPE_CELLS: i32 = 5;
+/// Screen pixels per cell inside the loupe
+const LOUPE_CELL_PIXELS: f32 = 24.0;
+const LOUPE_MARGIN: f32 = 10.0;
+
+pub fn draw_cursor_based_on_mode(mode: &Mode, camera: &AppCamera, screen_mouse: Vec2, spray_radius: i32) {
     let world_mouse = camera.screen_to_cell(screen_mouse);
     let cell_coords = (world_mouse.x.floor() as i32, world_mouse.y.floor() as i32);
-    let cell_screen_pos = camera.cell_to_screen(cell_coords);
-    let cell_size = camera.pixel_scale();
+    let (cell_min, cell_max) = cell_span(cell_coords, cell_coords);
+    let cell_size = camera.cell_size();
 
     match mode {
-        Mode::Paint => {
+        Mode::Paint | Mode::Fill => {
             // Draw highlight box around the cell
-            draw_rectangle_lines(cell_screen_pos.x, cell_screen_pos.y, cell_size, cell_size, 2.0, Color::from_rgba(0, 0, 0, 150));
+            draw_world_rect_lines(camera, cell_min, cell_max, 2.0, Color::from_rgba(0, 0, 0, 150));
             // Small cursor dot
             draw_circle(screen_mouse.x, screen_mouse.y, 3.0, BLACK);
         }
         Mode::Erase => {
             // Draw red highlight for eraser
-            draw_rectangle_lines(cell_screen_pos.x, cell_screen_pos.y, cell_size, cell_size, 2.0, Color::from_rgba(255, 100, 100, 200));
+            draw_world_rect_lines(camera, cell_min, cell_max, 2.0, Color::from_rgba(255, 100, 100, 200));
             // Eraser cursor
             draw_rectangle(screen_mouse.x - 5.0, screen_mouse.y - 5.0, 10.0, 10.0, Color::from_rgba(255, 100, 100, 150));
         }
@@ -26,11 +36,74 @@ pub fn draw_cursor_based_on_mode(mode: &Mode, camera: &AppCamera, screen_mouse:
             // Hand cursor for panning
             draw_circle(screen_mouse.x, screen_mouse.y, 4.0, DARKGRAY);
         }
-        Mode::Select => {
+        Mode::Select | Mode::ExportRegion | Mode::Line | Mode::Rectangle | Mode::Pen | Mode::Curve | Mode::Dither | Mode::Autotile => {
             // Crosshair cursor for selection
             let size = 8.0;
             draw_line(screen_mouse.x - size, screen_mouse.y, screen_mouse.x + size, screen_mouse.y, 2.0, Color::from_rgba(100, 100, 200, 200));
             draw_line(screen_mouse.x, screen_mouse.y - size, screen_mouse.x, screen_mouse.y + size, 2.0, Color::from_rgba(100, 100, 200, 200));
         }
+        Mode::Spray => {
+            // Outline of the spray disc, centered on the hovered cell
+            let center = camera.world_to_screen((cell_min + cell_max) / 2.0);
+            let radius = (spray_radius as f32 + 0.5) * cell_size;
+            draw_ellipse_lines(center.x, center.y, radius.x, radius.y, camera.rotation.to_degrees(), 1.5, Color::from_rgba(0, 0, 0, 150));
+            draw_circle(screen_mouse.x, screen_mouse.y, 2.0, BLACK);
+        }
+    }
+}
+
+/// Full-screen guides through the hovered cell's row and column, for lining up distant elements
+pub fn draw_crosshair(camera: &AppCamera, screen_mouse: Vec2) {
+    let world_mouse = camera.screen_to_cell(screen_mouse);
+    let (cx, cy) = (world_mouse.x.floor(), world_mouse.y.floor());
+    let (min_x, min_y, max_x, max_y) = camera.visible_world_rect(screen_width(), screen_height());
+    let strip = Color::from_rgba(80, 80, 200, 30);
+    let line = Color::from_rgba(80, 80, 200, 140);
+
+    // Row
+    draw_world_rect(camera, Vec2::new(min_x, cy), Vec2::new(max_x, cy + 1.0), strip);
+    let (a, b) = (camera.world_to_screen(Vec2::new(min_x, cy + 0.5)), camera.world_to_screen(Vec2::new(max_x, cy + 0.5)));
+    draw_line(a.x, a.y, b.x, b.y, 1.0, line);
+
+    // Column
+    draw_world_rect(camera, Vec2::new(cx, min_y), Vec2::new(cx + 1.0, max_y), strip);
+    let (a, b) = (camera.world_to_screen(Vec2::new(cx + 0.5, min_y)), camera.world_to_screen(Vec2::new(cx + 0.5, max_y)));
+    draw_line(a.x, a.y, b.x, b.y, 1.0, line);
+}
+
+/// Precision cursor: a hairline crosshair at the exact mouse position, its sub-cell
+/// coordinate, and a loupe of the surrounding cells in the bottom-right corner
+pub fn draw_precision_cursor(camera: &AppCamera, cells: &CellGrid, screen_mouse: Vec2) {
+    let world_mouse = camera.screen_to_cell(screen_mouse);
+    let (cx, cy) = (world_mouse.x.floor() as i32, world_mouse.y.floor() as i32);
+    let ink = Color::from_rgba(0, 0, 0, 200);
+
+    // Hairline crosshair with a gap so the target pixel stays visible
+    for (dx, dy) in [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)] {
+        let (near, far) = (screen_mouse + Vec2::new(dx, dy) * 3.0, screen_mouse + Vec2::new(dx, dy) * 12.0);
+        draw_line(near.x, near.y, far.x, far.y, 1.0, ink);
+    }
+    let coords = format!("{:.2}, {:.2}", world_mouse.x, world_mouse.y);
+    draw_text(&coords, screen_mouse.x + 14.0, screen_mouse.y - 8.0, 16.0, ink);
+
+    // Loupe
+    let side = LOUPE_CELLS as f32 * LOUPE_CELL_PIXELS;
+    let origin = Vec2::new(screen_width() - side - LOUPE_MARGIN, screen_height() - side - LOUPE_MARGIN);
+    draw_rectangle(origin.x - 2.0, origin.y - 2.0, side + 4.0, side + 4.0, WHITE);
+    let half = LOUPE_CELLS / 2;
+    for row in 0..LOUPE_CELLS {
+        for col in 0..LOUPE_CELLS {
+            let (x, y) = (origin.x + col as f32 * LOUPE_CELL_PIXELS, origin.y + row as f32 * LOUPE_CELL_PIXELS);
+            if let Some(cell) = cells.get(&(cx + col - half, cy + row - half)) {
+                let c = cell.color;
+                draw_rectangle(x, y, LOUPE_CELL_PIXELS, LOUPE_CELL_PIXELS, Color::from_rgba(c.r, c.g, c.b, c.a));
+            }
+            draw_rectangle_lines(x, y, LOUPE_CELL_PIXELS, LOUPE_CELL_PIXELS, 1.0, Color::from_rgba(0, 0, 0, 40));
+        }
     }
+    let center = origin + Vec2::splat(half as f32 * LOUPE_CELL_PIXELS);
+    draw_rectangle_lines(center.x, center.y, LOUPE_CELL_PIXELS, LOUPE_CELL_PIXELS, 2.0, BLACK);
+    let fraction = world_mouse - world_mouse.floor();
+    draw_circle(center.x + fraction.x * LOUPE_CELL_PIXELS, center.y + fraction.y * LOUPE_CELL_PIXELS, 2.0, RED);
+    draw_rectangle_lines(origin.x - 2.0, origin.y - 2.0, side + 4.0, side + 4.0, 2.0, BLACK);
 }
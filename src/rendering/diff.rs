@@ -0,0 +1,22 @@
+use macroquad::prelude::*;
+use crate::core::diff::CellDiff;
+use crate::rendering::shapes::{cell_span, draw_world_rect, draw_world_rect_lines};
+use crate::state::ApplicationState;
+
+/// Tint and outline every differing cell: added green, removed red, changed yellow
+pub fn draw_diff_overlay(state: &ApplicationState) {
+    let Some(diff) = &state.diff else {
+        return;
+    };
+    let view = state.view();
+    for &(cell, kind) in &diff.cells {
+        let (r, g, b) = match kind {
+            CellDiff::Added => (0.2, 0.85, 0.3),
+            CellDiff::Removed => (0.95, 0.2, 0.2),
+            CellDiff::Changed => (1.0, 0.85, 0.1),
+        };
+        let (min, max) = cell_span(cell, cell);
+        draw_world_rect(view, min, max, Color::new(r, g, b, 0.35));
+        draw_world_rect_lines(view, min, max, 1.5, Color::new(r, g, b, 0.9));
+    }
+}
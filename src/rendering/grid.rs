@@ -1,5 +1,7 @@
 use macroquad::prelude::*;
+use macroquad::models::{Mesh, Vertex};
 use crate::core::camera::Camera as AppCamera;
+use crate::core::cell::GridSettings;
 
 /// Compute LOD level and blend factor from zoom
 fn compute_lod(zoom: f32) -> (i32, f32) {
@@ -17,102 +19,248 @@ fn snap_px(v: f32) -> f32 {
     v.round() + 0.5
 }
 
-pub struct GridRenderer {}
+/// Grid lines closer together than this (screen pixels) collapse to major lines only
+const MIN_LINE_SPACING: f32 = 4.0;
+/// Lines kept when a too-dense grid has no major lines configured
+const FALLBACK_MAJOR_CELLS: i32 = 16;
+/// Alpha multiplier for major lines
+const MAJOR_EMPHASIS: f32 = 1.8;
+/// Above this many lines the grid becomes a flat tint instead
+const MAX_GRID_LINES: i32 = 2000;
+/// Lines per mesh; macroquad's batcher holds at most 5000 indices (6 per line quad)
+const LINES_PER_MESH: usize = 800;
+
+/// Everything but the camera origin that the grid meshes depend on; panning only moves them
+#[derive(Clone, Copy, PartialEq)]
+struct GridKey {
+    zoom: f32,
+    aspect: f32,
+    rotation: f32,
+    flip_x: bool,
+    screen: Vec2,
+    grid: GridSettings,
+    color: Color,
+}
+
+impl GridKey {
+    fn new(camera: &AppCamera, screen: Vec2, grid: GridSettings, color: Color) -> Self {
+        Self { zoom: camera.zoom, aspect: camera.aspect, rotation: camera.rotation, flip_x: camera.flip_x, screen, grid, color }
+    }
+}
+
+/// Cells spanned by grid lines, inclusive, with every edge on a multiple of the LOD step
+#[derive(Clone, Copy, PartialEq)]
+struct CellRange {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+}
+
+impl CellRange {
+    /// Lines covering the camera's view of a `screen`-sized viewport
+    fn visible(camera: &AppCamera, screen: Vec2, step: i32) -> Self {
+        let (min_x, min_y, max_x, max_y) = camera.visible_world_rect(screen.x, screen.y);
+        // Euclidean division keeps the alignment correct for negative coordinates
+        Self {
+            min_x: (min_x.floor() as i32).div_euclid(step) * step,
+            min_y: (min_y.floor() as i32).div_euclid(step) * step,
+            max_x: (max_x.ceil() as i32).div_euclid(step) * step + step,
+            max_y: (max_y.ceil() as i32).div_euclid(step) * step + step,
+        }
+    }
+
+    /// Grown by half its size on every side, so panning stays inside it for a while
+    fn padded(self, step: i32) -> Self {
+        let pad_x = (self.max_x - self.min_x) / 2 / step * step + step;
+        let pad_y = (self.max_y - self.min_y) / 2 / step * step + step;
+        Self { min_x: self.min_x - pad_x, min_y: self.min_y - pad_y, max_x: self.max_x + pad_x, max_y: self.max_y + pad_y }
+    }
+
+    fn contains(&self, other: &CellRange) -> bool {
+        self.min_x <= other.min_x && self.min_y <= other.min_y && self.max_x >= other.max_x && self.max_y >= other.max_y
+    }
+}
+
+/// How the grid shows at the current zoom
+#[derive(Clone, Copy)]
+enum GridLod {
+    /// Lines every `step` cells, the ones dropped at the next level faded by `blend`
+    Lines { step: i32, blend: f32 },
+    /// Too dense to draw; a flat tint stands in
+    Tint,
+}
+
+/// Draws the cell grid as a few prebuilt meshes of line quads instead of one
+/// draw_line call per line. The meshes cover a margin around the view and are
+/// shifted while panning, rebuilt only when the view leaves them or zoom,
+/// rotation, screen size or grid settings change
+pub struct GridRenderer {
+    key: Option<GridKey>,
+    /// Cells the meshes cover; None for the tint, which stays put on screen
+    range: Option<CellRange>,
+    /// Whole-pixel screen position of the range's top-left cell the vertices sit at
+    anchor: Vec2,
+    meshes: Vec<Mesh>,
+}
 
 impl GridRenderer {
     pub fn new() -> Self {
-        GridRenderer {}
+        GridRenderer { key: None, range: None, anchor: Vec2::ZERO, meshes: Vec::new() }
     }
 
     pub fn update_if_needed(&mut self) {
         // No longer needed since we're rendering directly
     }
 
-    pub fn draw(&self, camera: &AppCamera) {
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-        let (min_x, min_y, max_x, max_y) = camera.visible_world_rect(screen_w, screen_h);
+    /// Draw minor lines every cell (thinned out by zoom) with `grid`'s major lines emphasized
+    pub fn draw(&mut self, camera: &AppCamera, grid: GridSettings, color: Color) {
+        let screen = Vec2::new(screen_width(), screen_height());
+        let key = GridKey::new(camera, screen, grid, color);
+        let major = grid.major_every as i32;
+        let lod = grid_lod(camera, screen, major);
+        let visible = match lod {
+            GridLod::Lines { step, .. } => Some(CellRange::visible(camera, screen, step)),
+            GridLod::Tint => None,
+        };
+        let covered = match (self.range, visible) {
+            (Some(range), Some(visible)) => range.contains(&visible),
+            (None, None) => true,
+            _ => false,
+        };
+        if self.key != Some(key) || !covered {
+            self.range = match lod {
+                GridLod::Lines { step, blend } => {
+                    let range = visible.expect("lines have a visible range").padded(step);
+                    self.meshes = build_grid_meshes(camera, range, step, blend, major, color);
+                    self.anchor = camera.cell_to_screen((range.min_x, range.min_y)).round();
+                    Some(range)
+                }
+                GridLod::Tint => {
+                    self.meshes = vec![tint_mesh(screen, Color::new(color.r, color.g, color.b, color.a * 0.3))];
+                    None
+                }
+            };
+            self.key = Some(key);
+        }
+        // Whole-pixel shifts keep the lines on the pixel centers they were snapped to
+        if let Some(range) = self.range {
+            let anchor = camera.cell_to_screen((range.min_x, range.min_y)).round();
+            if anchor != self.anchor {
+                let shift = (anchor - self.anchor).extend(0.0);
+                for vertex in self.meshes.iter_mut().flat_map(|mesh| mesh.vertices.iter_mut()) {
+                    vertex.position += shift;
+                }
+                self.anchor = anchor;
+            }
+        }
+        for mesh in &self.meshes {
+            draw_mesh(mesh);
+        }
+    }
+}
+
+/// LOD step and fade for the camera's zoom, or a tint when even major lines would crowd
+fn grid_lod(camera: &AppCamera, screen: Vec2, major: i32) -> GridLod {
+    let (min_x, min_y, max_x, max_y) = camera.visible_world_rect(screen.x, screen.y);
 
-        // Compute LOD step and fade factor
-        let (step, blend) = compute_lod(camera.zoom);
+    // Compute LOD step and fade factor
+    let (mut step, mut blend) = compute_lod(camera.zoom);
 
-        // Compute start/end aligned to step using Euclidean division (correct for negatives)
-        let start_x = (min_x.floor() as i32).div_euclid(step) * step;
-        let start_y = (min_y.floor() as i32).div_euclid(step) * step;
-        let end_x   = (max_x.ceil()  as i32).div_euclid(step) * step + step;
-        let end_y   = (max_y.ceil()  as i32).div_euclid(step) * step + step;
+    // Too dense (e.g. squashed cells): keep only major lines, and below that just tint
+    let spacing = camera.cell_size().min_element();
+    if step as f32 * spacing < MIN_LINE_SPACING {
+        step = step.max(if major > 0 { major } else { FALLBACK_MAJOR_CELLS });
+        blend = 0.0;
+    }
+    let line_count = ((max_x - min_x) + (max_y - min_y)) / step as f32;
+    if step as f32 * spacing < MIN_LINE_SPACING || line_count > MAX_GRID_LINES as f32 {
+        return GridLod::Tint;
+    }
+    GridLod::Lines { step, blend }
+}
 
-        let thickness = 1.0; // screen-space pixels
-        let base = Color::new(0.70, 0.75, 0.85, 0.45);
+/// Line quads every `step` cells across `range`, split into meshes
+fn build_grid_meshes(camera: &AppCamera, range: CellRange, step: i32, blend: f32, major: i32, base: Color) -> Vec<Mesh> {
+    let mut lines = LineBatch::new();
 
-        // Draw vertical lines
-        let mut x = start_x;
-        while x <= end_x {
-            if x % step != 0 {
-                x += 1;
-                continue;
-            }
+    // Vertical lines at every step, then horizontal ones (mirror of vertical)
+    for x in (range.min_x..=range.max_x).step_by(step as usize) {
+        if let Some(col) = line_color(x, step, blend, major, base) {
+            lines.push(camera.cell_to_screen((x, range.min_y)), camera.cell_to_screen((x, range.max_y)), col);
+        }
+    }
+    for y in (range.min_y..=range.max_y).step_by(step as usize) {
+        if let Some(col) = line_color(y, step, blend, major, base) {
+            lines.push(camera.cell_to_screen((range.min_x, y)), camera.cell_to_screen((range.max_x, y)), col);
+        }
+    }
+    lines.finish()
+}
 
-            // Determine if this line survives to the next LOD level
-            let survives_next = (x % (step * 2)) == 0;
-            let mut alpha_mul = if survives_next {
-                1.0
-            } else {
-                1.0 - blend
-            };
+/// A flat overlay covering the screen, standing in for a grid too dense to draw
+fn tint_mesh(screen: Vec2, color: Color) -> Mesh {
+    let corners = [Vec2::ZERO, Vec2::new(screen.x, 0.0), screen, Vec2::new(0.0, screen.y)];
+    let vertices = corners.iter().map(|p| Vertex { position: p.extend(0.0), uv: Vec2::ZERO, color }).collect();
+    Mesh { vertices, indices: vec![0, 1, 2, 0, 2, 3], texture: None }
+}
 
-            // Emphasize tile boundaries (every 16 cells)
-            let is_tile = (x % 16) == 0;
-            if is_tile {
-                alpha_mul = (alpha_mul * 1.15).min(1.0);
-            }
+/// Color of the grid line at cell coordinate `at`, or None when it has faded out
+fn line_color(at: i32, step: i32, blend: f32, major: i32, base: Color) -> Option<Color> {
+    // Determine if this line survives to the next LOD level
+    let survives_next = (at % (step * 2)) == 0;
+    let mut alpha_mul = if survives_next {
+        1.0
+    } else {
+        1.0 - blend
+    };
 
-            // Only draw if visible
-            if alpha_mul > 0.001 {
-                let p0 = camera.cell_to_screen((x, start_y));
-                let p1 = camera.cell_to_screen((x, end_y));
-                let col = Color::new(base.r, base.g, base.b, base.a * alpha_mul);
-                draw_line(
-                    snap_px(p0.x), snap_px(p0.y),
-                    snap_px(p1.x), snap_px(p1.y),
-                    thickness, col
-                );
-            }
-            x += step;
-        }
+    // Emphasize major lines
+    let is_major = major > 0 && (at % major) == 0;
+    if is_major {
+        alpha_mul *= MAJOR_EMPHASIS;
+    }
 
-        // Draw horizontal lines (mirror of vertical)
-        let mut y = start_y;
-        while y <= end_y {
-            if y % step != 0 {
-                y += 1;
-                continue;
-            }
+    // Only draw if visible
+    (alpha_mul > 0.001).then(|| Color::new(base.r, base.g, base.b, (base.a * alpha_mul).min(1.0)))
+}
 
-            let survives_next = (y % (step * 2)) == 0;
-            let mut alpha_mul = if survives_next {
-                1.0
-            } else {
-                1.0 - blend
-            };
+/// Accumulates 1px screen-space lines as quads, starting a new mesh every LINES_PER_MESH
+struct LineBatch {
+    meshes: Vec<Mesh>,
+    vertices: Vec<Vertex>,
+    indices: Vec<u16>,
+}
 
-            let is_tile = (y % 16) == 0;
-            if is_tile {
-                alpha_mul = (alpha_mul * 1.15).min(1.0);
-            }
+impl LineBatch {
+    fn new() -> Self {
+        Self { meshes: Vec::new(), vertices: Vec::new(), indices: Vec::new() }
+    }
 
-            if alpha_mul > 0.001 {
-                let p0 = camera.cell_to_screen((start_x, y));
-                let p1 = camera.cell_to_screen((end_x, y));
-                let col = Color::new(base.r, base.g, base.b, base.a * alpha_mul);
-                draw_line(
-                    snap_px(p0.x), snap_px(p0.y),
-                    snap_px(p1.x), snap_px(p1.y),
-                    thickness, col
-                );
-            }
-            y += step;
+    fn push(&mut self, p0: Vec2, p1: Vec2, color: Color) {
+        let (p0, p1) = (Vec2::new(snap_px(p0.x), snap_px(p0.y)), Vec2::new(snap_px(p1.x), snap_px(p1.y)));
+        // Half the 1px thickness on each side of the line
+        let normal = (p1 - p0).normalize_or_zero().perp() * 0.5;
+        let first = self.vertices.len() as u16;
+        for p in [p0 + normal, p1 + normal, p1 - normal, p0 - normal] {
+            self.vertices.push(Vertex { position: p.extend(0.0), uv: Vec2::ZERO, color });
+        }
+        self.indices.extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+        if self.vertices.len() / 4 == LINES_PER_MESH {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.vertices.is_empty() {
+            let vertices = std::mem::take(&mut self.vertices);
+            let indices = std::mem::take(&mut self.indices);
+            self.meshes.push(Mesh { vertices, indices, texture: None });
         }
     }
 
+    fn finish(mut self) -> Vec<Mesh> {
+        self.flush();
+        self.meshes
+    }
 }
@@ -1,5 +1,7 @@
 use macroquad::prelude::*;
 use crate::core::camera::Camera as AppCamera;
+use crate::core::budget::BudgetReport;
+use crate::ui::menu_bar::MENU_BAR_HEIGHT;
 
 pub struct Hud {
     fps: i32,
@@ -26,20 +28,150 @@ impl Hud {
         }
     }
 
-    pub fn draw(&self, camera: &AppCamera) {
-        let y_start = screen_height() - 80.0;
-        let line_height = 20.0;
+    pub fn draw(&self, camera: &AppCamera, status: Option<&str>, budget: Option<&BudgetReport>, session: Option<&str>, lost: Option<&str>, layout: HudLayout) {
+        let mut lines: Vec<(String, Color)> = Vec::new();
 
-        // FPS
-        let fps_text = format!("FPS: {}", self.fps);
-        draw_text(&fps_text, 10.0, y_start, 18.0, BLACK);
+        // Shared session role and paint cooldown
+        if let Some(session) = session {
+            lines.push((session.to_string(), DARKBLUE));
+        }
+
+        // Sprite budget (red when a limit is exceeded)
+        if let Some(report) = budget {
+            let limit = report.budget;
+            let text = format!(
+                "Budget: {}/{} colors, {}x{} of {}x{}",
+                report.colors, limit.max_colors, report.width, report.height, limit.max_width, limit.max_height
+            );
+            let color = if report.over_colors() || report.over_size() { RED } else { DARKGREEN };
+            lines.push((text, color));
+        }
+
+        // Last file operation result
+        if let Some(status) = status {
+            lines.push((status.to_string(), DARKGRAY));
+        }
+
+        // The way back when the drawing is off screen
+        if let Some(lost) = lost {
+            lines.push((lost.to_string(), ORANGE));
+        }
+
+        // FPS, zoom level (as percentage) and camera position (origin)
+        lines.push((format!("FPS: {}", self.fps), BLACK));
+        lines.push((format!("Zoom: {:.0}%", camera.zoom * 100.0), BLACK));
+        lines.push((format!("Position: ({:.1}, {:.1})", camera.origin.x, camera.origin.y), BLACK));
+
+        // View rotation (only when rotated)
+        let degrees = camera.rotation.to_degrees().round() as i32 % 360;
+        if degrees != 0 || camera.flip_x {
+            let flip = if camera.flip_x { " (flipped)" } else { "" };
+            lines.push((format!("Rotation: {}°{}", degrees, flip), BLACK));
+        }
+
+        if layout.compact {
+            draw_compact(&lines, layout.corner);
+        } else {
+            draw_stacked(&lines, layout.corner);
+        }
+    }
+}
+
+const FONT_SIZE: f32 = 18.0;
+const LINE_HEIGHT: f32 = 20.0;
+const MARGIN: f32 = 10.0;
+const COMPACT_SEPARATOR: &str = "  |  ";
+
+/// Screen corner the HUD is anchored to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HudCorner {
+    BottomLeft,
+    BottomRight,
+    TopRight,
+    TopLeft,
+}
 
-        // Zoom level (as percentage)
-        let zoom_text = format!("Zoom: {:.0}%", camera.zoom * 100.0);
-        draw_text(&zoom_text, 10.0, y_start + line_height, 18.0, BLACK);
+impl HudCorner {
+    pub const ALL: [HudCorner; 4] = [HudCorner::BottomLeft, HudCorner::BottomRight, HudCorner::TopRight, HudCorner::TopLeft];
 
-        // Camera position (origin)
-        let pos_text = format!("Position: ({:.1}, {:.1})", camera.origin.x, camera.origin.y);
-        draw_text(&pos_text, 10.0, y_start + line_height * 2.0, 18.0, BLACK);
+    pub fn label(self) -> &'static str {
+        match self {
+            HudCorner::BottomLeft => "Bottom Left",
+            HudCorner::BottomRight => "Bottom Right",
+            HudCorner::TopRight => "Top Right",
+            HudCorner::TopLeft => "Top Left",
+        }
+    }
+
+    /// Clockwise around the screen
+    pub fn next(self) -> Self {
+        match self {
+            HudCorner::BottomLeft => HudCorner::TopLeft,
+            HudCorner::TopLeft => HudCorner::TopRight,
+            HudCorner::TopRight => HudCorner::BottomRight,
+            HudCorner::BottomRight => HudCorner::BottomLeft,
+        }
+    }
+
+    fn is_top(self) -> bool {
+        matches!(self, HudCorner::TopLeft | HudCorner::TopRight)
+    }
+
+    fn is_right(self) -> bool {
+        matches!(self, HudCorner::TopRight | HudCorner::BottomRight)
+    }
+
+    /// Baseline of the first of `count` lines
+    fn first_baseline(self, count: usize) -> f32 {
+        if self.is_top() {
+            MENU_BAR_HEIGHT + MARGIN + FONT_SIZE
+        } else {
+            screen_height() - MARGIN - LINE_HEIGHT * (count.max(1) - 1) as f32
+        }
+    }
+
+    /// Left edge of text `width` pixels wide
+    fn text_x(self, width: f32) -> f32 {
+        if self.is_right() { screen_width() - MARGIN - width } else { MARGIN }
+    }
+}
+
+/// Where and how the HUD is drawn
+#[derive(Clone, Copy, Debug)]
+pub struct HudLayout {
+    pub corner: HudCorner,
+    /// Everything on one line instead of a block
+    pub compact: bool,
+}
+
+impl HudLayout {
+    pub fn new() -> Self {
+        Self { corner: HudCorner::BottomLeft, compact: false }
+    }
+}
+
+/// One line per entry, stacked away from the corner's edge
+fn draw_stacked(lines: &[(String, Color)], corner: HudCorner) {
+    let first = corner.first_baseline(lines.len());
+    for (i, (text, color)) in lines.iter().enumerate() {
+        let width = measure_text(text, None, FONT_SIZE as u16, 1.0).width;
+        draw_text(text, corner.text_x(width), first + i as f32 * LINE_HEIGHT, FONT_SIZE, *color);
+    }
+}
+
+/// All entries on a single line, each keeping its color
+fn draw_compact(lines: &[(String, Color)], corner: HudCorner) {
+    let measure = |text: &str| measure_text(text, None, FONT_SIZE as u16, 1.0).width;
+    let separator = measure(COMPACT_SEPARATOR);
+    let total = lines.iter().map(|(text, _)| measure(text)).sum::<f32>() + separator * lines.len().saturating_sub(1) as f32;
+    let mut x = corner.text_x(total);
+    let y = corner.first_baseline(1);
+    for (i, (text, color)) in lines.iter().enumerate() {
+        if i > 0 {
+            draw_text(COMPACT_SEPARATOR, x, y, FONT_SIZE, GRAY);
+            x += separator;
+        }
+        draw_text(text, x, y, FONT_SIZE, *color);
+        x += measure(text);
     }
 }
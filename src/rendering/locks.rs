@@ -0,0 +1,57 @@
+use macroquad::prelude::*;
+use crate::core::camera::Camera as AppCamera;
+use crate::state::ApplicationState;
+
+/// Screen pixels between hatch lines
+const HATCH_SPACING: f32 = 10.0;
+
+/// Locked regions of a shared canvas: a subtle diagonal hatch (over just the cells of a locked
+/// group), an outline and the owners
+pub fn draw_region_locks(state: &ApplicationState) {
+    let Some(session) = &state.collab else {
+        return;
+    };
+    let camera = &state.camera;
+    let cell = camera.cell_size();
+    let step = HATCH_SPACING / cell.x.min(cell.y);
+    let hatch = Color::from_rgba(90, 90, 90, 70);
+
+    for lock in &session.locks {
+        let r = lock.rect;
+        let (x0, y0, x1, y1) = (r.min_x as f32, r.min_y as f32, r.max_x as f32 + 1.0, r.max_y as f32 + 1.0);
+        if lock.cells.is_empty() {
+            draw_hatch(camera, (x0, y0, x1, y1), step, hatch);
+        } else {
+            for &(x, y) in &lock.cells {
+                draw_hatch(camera, (x as f32, y as f32, x as f32 + 1.0, y as f32 + 1.0), step, hatch);
+            }
+        }
+
+        let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)].map(|(x, y)| camera.world_to_screen(Vec2::new(x, y)));
+        for i in 0..4 {
+            let (a, b) = (corners[i], corners[(i + 1) % 4]);
+            draw_line(a.x, a.y, b.x, b.y, 1.0, Color::from_rgba(90, 90, 90, 160));
+        }
+
+        let owners = if lock.owners.is_empty() { "admins".to_string() } else { lock.owners.join(", ") };
+        let label = format!("Lock {}: {}", lock.id, owners);
+        let top = corners.iter().fold(corners[0], |best, &p| if p.y < best.y { p } else { best });
+        draw_text(&label, top.x, top.y - 4.0, 14.0, DARKGRAY);
+    }
+}
+
+/// Lines y = x - c across a world-space box, in world space so they follow rotation and flip
+/// `c` sits on multiples of `step`, so boxes side by side hatch as one
+fn draw_hatch(camera: &AppCamera, (x0, y0, x1, y1): (f32, f32, f32, f32), step: f32, color: Color) {
+    let mut c = ((x0 - y1) / step).ceil() * step;
+    while c < x1 - y0 {
+        let start = x0.max(y0 + c);
+        let end = x1.min(y1 + c);
+        if start < end {
+            let a = camera.world_to_screen(Vec2::new(start, start - c));
+            let b = camera.world_to_screen(Vec2::new(end, end - c));
+            draw_line(a.x, a.y, b.x, b.y, 1.0, color);
+        }
+        c += step;
+    }
+}
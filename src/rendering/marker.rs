@@ -0,0 +1,23 @@
+use macroquad::prelude::*;
+use crate::rendering::shapes::{cell_span, draw_world_rect_lines};
+use crate::state::ApplicationState;
+
+/// How long a Go to Cell marker stays up, in seconds
+const MARKER_SECONDS: f64 = 4.0;
+
+/// Pulsing outline on the cell Go to Cell jumped to, fading out after a few seconds
+pub fn draw_cell_marker(state: &ApplicationState) {
+    let Some((cell, dropped)) = state.cell_marker else {
+        return;
+    };
+    let age = get_time() - dropped;
+    if age >= MARKER_SECONDS {
+        return;
+    }
+    let fade = (1.0 - age / MARKER_SECONDS) as f32;
+    let pulse = ((age * 6.0).sin() * 0.5 + 0.5) as f32;
+    let (min, max) = cell_span(cell, cell);
+    let grow = Vec2::splat(0.5 + pulse);
+    draw_world_rect_lines(&state.camera, min, max, 2.0, Color::new(1.0, 0.3, 0.2, fade));
+    draw_world_rect_lines(&state.camera, min - grow, max + grow, 1.5, Color::new(1.0, 0.3, 0.2, fade * (1.0 - pulse)));
+}
@@ -1,11 +1,31 @@
 pub mod canvas;
+pub mod color;
 pub mod grid;
 pub mod cursor;
 pub mod hud;
 pub mod selection;
+pub mod preview;
+pub mod shapes;
+pub mod tool_preview;
+pub mod peers;
+pub mod locks;
+pub mod transform;
+pub mod progress;
+pub mod symmetry;
+pub mod diff;
+pub mod marker;
 
 pub use canvas::CanvasRenderer;
 pub use grid::GridRenderer;
-pub use cursor::draw_cursor_based_on_mode;
-pub use hud::Hud;
+pub use cursor::{draw_crosshair, draw_cursor_based_on_mode, draw_precision_cursor, PRECISION_ZOOM};
+pub use hud::{Hud, HudLayout};
+pub use preview::PreviewRenderer;
 pub use selection::{draw_selection_overlay, draw_selection_action_bar};
+pub use tool_preview::draw_tool_preview;
+pub use peers::draw_peer_cursors;
+pub use locks::draw_region_locks;
+pub use transform::ViewTransform;
+pub use progress::draw_progress_overlay;
+pub use symmetry::draw_symmetry_guides;
+pub use diff::draw_diff_overlay;
+pub use marker::draw_cell_marker;
@@ -0,0 +1,28 @@
+use macroquad::prelude::*;
+use crate::state::ApplicationState;
+
+/// Other participants' cursors: a dot in their paint color with a "name · tool" tag
+pub fn draw_peer_cursors(state: &ApplicationState) {
+    let Some(session) = &state.collab else {
+        return;
+    };
+    for peer in session.peers.values() {
+        let Some(world) = peer.cursor else {
+            continue;
+        };
+        let pos = state.camera.world_to_screen(world);
+        let color = Color::from(peer.color);
+        draw_circle(pos.x, pos.y, 5.0, color);
+        draw_circle_lines(pos.x, pos.y, 5.0, 1.5, BLACK);
+
+        let label = if peer.tool.is_empty() { peer.name.clone() } else { format!("{} · {}", peer.name, peer.tool) };
+        let size = measure_text(&label, None, 14, 1.0);
+        let (x, y) = (pos.x + 8.0, pos.y + 8.0);
+        draw_rectangle(x, y, size.width + 8.0, 18.0, Color::new(color.r, color.g, color.b, 0.85));
+        draw_rectangle_lines(x, y, size.width + 8.0, 18.0, 1.0, BLACK);
+        // Dark text on light colors, light text on dark ones
+        let luma = 0.299 * color.r + 0.587 * color.g + 0.114 * color.b;
+        let text_color = if luma > 0.5 { BLACK } else { WHITE };
+        draw_text(&label, x + 4.0, y + 13.0, 14.0, text_color);
+    }
+}
@@ -0,0 +1,96 @@
+use macroquad::prelude::*;
+use crate::state::ApplicationState;
+use crate::core::camera::{Camera as AppCamera, BASE_CELL_PIXELS};
+use crate::core::cell::grid_bounds;
+use crate::rendering::CanvasRenderer;
+use crate::ui::menu_bar::MENU_BAR_HEIGHT;
+
+const PREVIEW_SIZE: u32 = 160; // Panel content in screen pixels
+const TITLE_HEIGHT: f32 = 20.0;
+const MARGIN: f32 = 10.0;
+
+/// Always-on-top panel showing the drawing (or selection) at a fixed 1× or 2× scale
+/// Reuses the canvas chunk textures, rendered through an offscreen target for clipping
+pub struct PreviewRenderer {
+    target: RenderTarget,
+}
+
+impl PreviewRenderer {
+    pub fn new() -> Self {
+        let target = render_target(PREVIEW_SIZE, PREVIEW_SIZE);
+        target.texture.set_filter(FilterMode::Nearest);
+        Self { target }
+    }
+
+    pub(crate) fn panel_rect() -> Rect {
+        let size = PREVIEW_SIZE as f32;
+        Rect::new(screen_width() - size - MARGIN, MENU_BAR_HEIGHT + MARGIN, size, size + TITLE_HEIGHT)
+    }
+
+    /// Returns true if the mouse is over the panel
+    pub fn is_hovered(&self, state: &ApplicationState) -> bool {
+        state.show_preview && Self::panel_rect().contains(Vec2::from(mouse_position()))
+    }
+
+    /// Draw the panel; clicking it toggles between 1× and 2×
+    pub fn draw(&self, state: &mut ApplicationState, canvas: &CanvasRenderer) {
+        if !state.show_preview {
+            return;
+        }
+
+        let panel = Self::panel_rect();
+        if is_mouse_button_pressed(MouseButton::Left) && panel.contains(Vec2::from(mouse_position())) {
+            state.preview_scale = if state.preview_scale == 1 { 2 } else { 1 };
+        }
+        let scale = state.preview_scale as f32;
+
+        // Preview the selection if there is one, otherwise the whole drawing
+        let region = state.selection.current.as_ref().map(|sel| sel.rect).or_else(|| grid_bounds(&state.cells));
+
+        let size = PREVIEW_SIZE as f32;
+        let camera2d = Camera2D {
+            render_target: Some(self.target.clone()),
+            target: vec2(size / 2.0, size / 2.0),
+            zoom: vec2(2.0 / size, 2.0 / size),
+            ..Default::default()
+        };
+        set_camera(&camera2d);
+        clear_background(WHITE);
+
+        if let Some(rect) = region {
+            // Center the region in the panel at exactly `scale` screen pixels per cell
+            let center = vec2(
+                (rect.min_x + rect.max_x + 1) as f32 / 2.0,
+                (rect.min_y + rect.max_y + 1) as f32 / 2.0,
+            );
+            let preview_camera = AppCamera {
+                origin: Vec2::ZERO,
+                zoom: scale / BASE_CELL_PIXELS,
+                base_cell_pixels: BASE_CELL_PIXELS,
+                aspect: state.camera.aspect,
+                rotation: 0.0,
+                flip_x: false,
+            };
+            let half_extent = Vec2::splat(size / 2.0) / preview_camera.cell_size();
+            let preview_camera = AppCamera { origin: center - half_extent, ..preview_camera };
+            canvas.draw_viewport(&preview_camera, size, size);
+        }
+
+        set_default_camera();
+
+        // Title bar + content
+        draw_rectangle(panel.x, panel.y, panel.w, TITLE_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+        draw_text(&format!("Preview {}x", state.preview_scale), panel.x + 6.0, panel.y + 14.0, 16.0, WHITE);
+        draw_texture_ex(
+            &self.target.texture,
+            panel.x,
+            panel.y + TITLE_HEIGHT,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(size, size)),
+                ..Default::default()
+            },
+        );
+        draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, 2.0, BLACK);
+    }
+}
@@ -0,0 +1,21 @@
+use macroquad::prelude::*;
+
+const PANEL_WIDTH: f32 = 280.0;
+const PANEL_HEIGHT: f32 = 56.0;
+
+/// Centered panel for work that spans frames (or blocks the next one): a label and,
+/// when the amount of work is known, a bar filled to `fraction`
+pub fn draw_progress_overlay(label: &str, fraction: Option<f32>) {
+    let x = (screen_width() - PANEL_WIDTH) / 2.0;
+    let y = (screen_height() - PANEL_HEIGHT) / 2.0;
+    draw_rectangle(x, y, PANEL_WIDTH, PANEL_HEIGHT, Color::from_rgba(250, 250, 250, 235));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, PANEL_HEIGHT, 1.0, DARKGRAY);
+    draw_text(label, x + 12.0, y + 22.0, 18.0, BLACK);
+
+    // A single blocking write has no measurable progress, so its track stays empty
+    let bar = Rect::new(x + 12.0, y + 34.0, PANEL_WIDTH - 24.0, 10.0);
+    draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, 1.0, GRAY);
+    if let Some(fraction) = fraction {
+        draw_rectangle(bar.x, bar.y, bar.w * fraction.clamp(0.0, 1.0), bar.h, DARKGRAY);
+    }
+}
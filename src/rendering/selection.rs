@@ -1,110 +1,101 @@
 use macroquad::prelude::*;
 use crate::state::ApplicationState;
-use crate::core::camera::Camera as AppCamera;
-use crate::input::delete_selection;
+use crate::input::{delete_selection, fill_selection};
 use crate::rendering::CanvasRenderer;
 use crate::core::cell::CellGrid;
 use crate::core::selection::{SelectionRect, SelectionKind};
+use crate::rendering::shapes::{cell_span, draw_world_rect, draw_world_rect_lines, draw_world_texture, screen_bounds};
+use crate::rendering::transform::ViewTransform;
+use crate::ui::focus;
 
 pub fn draw_selection_overlay(state: &ApplicationState) {
-    let camera = &state.camera;
+    let view = state.view();
 
     // Draw active drag rectangle (translucent)
     if state.selection.active_drag {
         if let (Some(start), Some(end)) = (state.selection.drag_start, state.selection.drag_end) {
-            draw_selection_rect(camera, start, end, Color::new(0.3, 0.6, 1.0, 0.15), 2.0);
+            draw_selection_rect(view, start, end, Color::new(0.3, 0.6, 1.0, 0.15), 2.0);
+        }
+    }
+
+    // Draw export region drag (independent of the selection)
+    if let Some((start, end)) = state.export_region_drag {
+        draw_selection_rect(view, start, end, Color::new(0.2, 0.8, 0.4, 0.15), 2.0);
+    }
+
+    // Draw the rect a pending generator will fill
+    if let Some((start, end)) = state.generator_drag {
+        draw_selection_rect(view, start, end, Color::new(0.8, 0.5, 0.2, 0.15), 2.0);
+    }
+
+    // Draw fixed document bounds
+    if let Some(bounds) = state.document_bounds {
+        let (min, max) = cell_span((bounds.min_x, bounds.min_y), (bounds.max_x, bounds.max_y));
+        draw_world_rect_lines(view, min, max, 2.0, Color::new(0.4, 0.4, 0.4, 0.8));
+    }
+
+    // Draw rect-erase drag
+    if let Some((start, end)) = state.erase_rect_drag {
+        draw_selection_rect(view, start, end, Color::new(1.0, 0.3, 0.3, 0.15), 2.0);
+    }
+
+    // Quick mask: masked cells tinted red over the drawing
+    if let Some(mask) = &state.quick_mask {
+        let tint = Color::new(1.0, 0.2, 0.2, 0.35);
+        for &cell in mask {
+            let (min, max) = cell_span(cell, cell);
+            draw_world_rect(view, min, max, tint);
         }
     }
 
     // Draw finalized selection
     if let Some(sel) = &state.selection.current {
-        if let SelectionKind::Cells(cell_set) = &sel.kind {
-            let rect = &sel.rect;
-            let pixel_scale = camera.pixel_scale();
-
-            // Per-cell fill and outline (only if not moving, since cells are lifted during move)
-            if !state.selection.is_moving {
-                let fill_color = Color::new(0.3, 0.6, 1.0, 0.12);
-                let line_color = Color::new(0.5, 0.8, 1.0, 0.8);
-
-                for &(x, y) in cell_set.iter() {
-                    let pos = camera.cell_to_screen((x, y));
-                    draw_rectangle(pos.x, pos.y, pixel_scale, pixel_scale, fill_color);
-                    draw_rectangle_lines(pos.x, pos.y, pixel_scale, pixel_scale, 1.0, line_color);
-                }
+        let SelectionKind::Cells(cell_set) = &sel.kind;
+        let rect = &sel.rect;
+        let (rect_min, rect_max) = cell_span((rect.min_x, rect.min_y), (rect.max_x, rect.max_y));
+
+        // Per-cell fill and outline (only if not moving, since cells are lifted during move)
+        if !state.selection.is_moving {
+            let fill_color = Color::new(0.3, 0.6, 1.0, 0.12);
+            let line_color = Color::new(0.5, 0.8, 1.0, 0.8);
+
+            for &(x, y) in cell_set.iter() {
+                let (min, max) = cell_span((x, y), (x, y));
+                draw_world_rect(view, min, max, fill_color);
+                draw_world_rect_lines(view, min, max, 1.0, line_color);
             }
+        }
 
-            // Overall bounding rectangle outline
-            let min_screen = camera.cell_to_screen((rect.min_x, rect.min_y));
-            let max_screen = camera.cell_to_screen((rect.max_x + 1, rect.max_y + 1));
-            let w = max_screen.x - min_screen.x;
-            let h = max_screen.y - min_screen.y;
-
-            draw_rectangle_lines(
-                min_screen.x,
-                min_screen.y,
-                w,
-                h,
-                1.0,
-                Color::new(0.5, 0.8, 1.0, 0.5)
-            );
-
-            // During move: draw preview texture at offset and yellow target outline
-            if state.selection.is_moving {
-                let offset_px_x = state.selection.move_offset_x * pixel_scale;
-                let offset_px_y = state.selection.move_offset_y * pixel_scale;
-
-                // If we have a preview texture, draw it at the offset position
-                if let Some(preview) = &sel.preview {
-                    let width = (rect.max_x - rect.min_x + 1) as f32 * pixel_scale;
-                    let height = (rect.max_y - rect.min_y + 1) as f32 * pixel_scale;
-
-                    draw_texture_ex(
-                        &preview.texture,
-                        min_screen.x + offset_px_x,
-                        min_screen.y + offset_px_y,
-                        WHITE,
-                        DrawTextureParams {
-                            dest_size: Some(vec2(width, height)),
-                            ..Default::default()
-                        }
-                    );
-                }
-
-                // Yellow target outline at prospective drop location
-                draw_rectangle_lines(
-                    min_screen.x + offset_px_x,
-                    min_screen.y + offset_px_y,
-                    w,
-                    h,
-                    1.0,
-                    Color::new(1.0, 1.0, 0.3, 0.6)
-                );
+        // Overall bounding rectangle outline
+        draw_world_rect_lines(view, rect_min, rect_max, 1.0, Color::new(0.5, 0.8, 1.0, 0.5));
+
+        // During move: draw preview texture at offset and yellow target outline
+        if state.selection.is_moving {
+            let offset = Vec2::new(state.selection.move_offset_x, state.selection.move_offset_y);
+            let (moved_min, moved_max) = (rect_min + offset, rect_max + offset);
+
+            // If we have a preview texture, draw it at the offset position
+            if let Some(preview) = &state.selection_preview {
+                draw_world_texture(view, &preview.texture, moved_min, moved_max);
             }
+
+            // Yellow target outline at prospective drop location
+            draw_world_rect_lines(view, moved_min, moved_max, 1.0, Color::new(1.0, 1.0, 0.3, 0.6));
         }
     }
 }
 
 fn draw_selection_rect(
-    camera: &AppCamera,
+    view: &impl ViewTransform,
     p1: (i32, i32),
     p2: (i32, i32),
     fill_color: Color,
     border_width: f32,
 ) {
-    let min_x = p1.0.min(p2.0);
-    let max_x = p1.0.max(p2.0);
-    let min_y = p1.1.min(p2.1);
-    let max_y = p1.1.max(p2.1);
-
-    let min_screen = camera.cell_to_screen((min_x, min_y));
-    let max_screen = camera.cell_to_screen((max_x + 1, max_y + 1));
-
-    let w = max_screen.x - min_screen.x;
-    let h = max_screen.y - min_screen.y;
+    let (min, max) = cell_span((p1.0.min(p2.0), p1.1.min(p2.1)), (p1.0.max(p2.0), p1.1.max(p2.1)));
 
-    draw_rectangle(min_screen.x, min_screen.y, w, h, fill_color);
-    draw_rectangle_lines(min_screen.x, min_screen.y, w, h, border_width,
+    draw_world_rect(view, min, max, fill_color);
+    draw_world_rect_lines(view, min, max, border_width,
         Color::new(fill_color.r, fill_color.g, fill_color.b, 0.9));
 }
 
@@ -112,15 +103,15 @@ fn draw_selection_rect(
 pub fn draw_selection_action_bar(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
     if let Some(sel) = &state.selection.current {
         let rect = &sel.rect;
-        let camera = &state.camera;
+        let view = state.view();
 
-        // Position bar below selection
-        let min_screen = camera.cell_to_screen((rect.min_x, rect.min_y));
-        let max_screen = camera.cell_to_screen((rect.max_x + 1, rect.max_y + 1));
+        // Position bar below selection's on-screen bounds
+        let (rect_min, rect_max) = cell_span((rect.min_x, rect.min_y), (rect.max_x, rect.max_y));
+        let bounds = screen_bounds(view, rect_min, rect_max);
 
-        let bar_y = max_screen.y + 4.0;
-        let bar_x = min_screen.x;
-        let bar_width = (max_screen.x - min_screen.x).max(80.0);
+        let bar_y = bounds.bottom() + 4.0;
+        let bar_x = bounds.x;
+        let bar_width = bounds.w.max(234.0);
         let bar_height = 28.0;
 
         // Don't draw if off-screen
@@ -136,6 +127,10 @@ pub fn draw_selection_action_bar(state: &mut ApplicationState, canvas: &mut Canv
         // Delete button
         if draw_action_button("Delete", bar_x + 4.0, bar_y + 2.0, 70.0, 24.0) {
             delete_selection(state, canvas);
+        } else if draw_action_button("Fill", bar_x + 80.0, bar_y + 2.0, 70.0, 24.0) {
+            fill_selection(state, canvas, false);
+        } else if draw_action_button("Fill rect", bar_x + 156.0, bar_y + 2.0, 74.0, 24.0) {
+            fill_selection(state, canvas, true);
         }
     }
 }
@@ -159,7 +154,7 @@ fn draw_action_button(label: &str, x: f32, y: f32, w: f32, h: f32) -> bool {
     let text_y = y + (h + text_size.height) / 2.0;
     draw_text(label, text_x, text_y, 14.0, WHITE);
 
-    is_mouse_button_pressed(MouseButton::Left) && is_hovered
+    focus::pressed(rect)
 }
 
 /// Build a RenderTarget preview of the selected cells
@@ -202,7 +197,7 @@ pub fn build_selection_preview(
             if cell.is_filled {
                 let local_x = (x - rect.min_x) as f32 * cell_size as f32;
                 let local_y = (y - rect.min_y) as f32 * cell_size as f32;
-                draw_rectangle(local_x, local_y, cell_size as f32, cell_size as f32, cell.color);
+                draw_rectangle(local_x, local_y, cell_size as f32, cell_size as f32, Color::from(cell.color));
             }
         }
     }
@@ -0,0 +1,63 @@
+use macroquad::prelude::*;
+use macroquad::models::{Mesh, Vertex};
+use crate::rendering::transform::ViewTransform;
+
+/// Screen corners (clockwise from top-left) of the world rect spanning [min, max)
+/// These follow the view transform, so the quad is not necessarily axis-aligned
+pub fn world_quad(view: &impl ViewTransform, min: Vec2, max: Vec2) -> [Vec2; 4] {
+    [
+        view.to_screen(min),
+        view.to_screen(Vec2::new(max.x, min.y)),
+        view.to_screen(max),
+        view.to_screen(Vec2::new(min.x, max.y)),
+    ]
+}
+
+/// World rect covering cells `min..=max`
+pub fn cell_span(min: (i32, i32), max: (i32, i32)) -> (Vec2, Vec2) {
+    (
+        Vec2::new(min.0 as f32, min.1 as f32),
+        Vec2::new(max.0 as f32 + 1.0, max.1 as f32 + 1.0),
+    )
+}
+
+/// Fill a world-space rect
+pub fn draw_world_rect(view: &impl ViewTransform, min: Vec2, max: Vec2, color: Color) {
+    let [a, b, c, d] = world_quad(view, min, max);
+    draw_triangle(a, b, c, color);
+    draw_triangle(a, c, d, color);
+}
+
+/// Outline a world-space rect
+pub fn draw_world_rect_lines(view: &impl ViewTransform, min: Vec2, max: Vec2, thickness: f32, color: Color) {
+    let quad = world_quad(view, min, max);
+    for i in 0..4 {
+        let (p, q) = (quad[i], quad[(i + 1) % 4]);
+        draw_line(p.x, p.y, q.x, q.y, thickness, color);
+    }
+}
+
+/// Screen-space axis-aligned bounds of a world rect (for placing screen UI next to it)
+pub fn screen_bounds(view: &impl ViewTransform, min: Vec2, max: Vec2) -> Rect {
+    let quad = world_quad(view, min, max);
+    let lo = quad.iter().fold(Vec2::splat(f32::MAX), |acc, p| acc.min(*p));
+    let hi = quad.iter().fold(Vec2::splat(f32::MIN), |acc, p| acc.max(*p));
+    Rect::new(lo.x, lo.y, hi.x - lo.x, hi.y - lo.y)
+}
+
+/// Draw a texture covering the world rect [min, max) as a textured quad, so it follows
+/// whatever rotation or mirroring the view applies
+pub fn draw_world_texture(view: &impl ViewTransform, texture: &Texture2D, min: Vec2, max: Vec2) {
+    draw_texture_quad(texture, world_quad(view, min, max));
+}
+
+/// Draw a texture stretched over any screen quad, corners clockwise from its top-left
+pub fn draw_texture_quad(texture: &Texture2D, quad: [Vec2; 4]) {
+    let uvs = [Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), Vec2::new(0.0, 1.0)];
+    let vertices = quad
+        .iter()
+        .zip(uvs)
+        .map(|(p, uv)| Vertex { position: p.extend(0.0), uv, color: WHITE })
+        .collect();
+    draw_mesh(&Mesh { vertices, indices: vec![0, 1, 2, 0, 2, 3], texture: Some(texture.clone()) });
+}
@@ -0,0 +1,43 @@
+use macroquad::prelude::*;
+use crate::input::symmetry::{pivot_hovered, PIVOT_GRAB_RADIUS};
+use crate::state::ApplicationState;
+
+/// Mirror axes as full-screen lines, radial segments as spokes, and the draggable pivot
+pub fn draw_symmetry_guides(state: &ApplicationState) {
+    let symmetry = &state.symmetry;
+    if !symmetry.is_active() {
+        return;
+    }
+    let camera = &state.camera;
+    let center = Vec2::new(symmetry.center.0, symmetry.center.1);
+    let (min_x, min_y, max_x, max_y) = camera.visible_world_rect(screen_width(), screen_height());
+    // Long enough to cross the whole view from anywhere inside it
+    let reach = Vec2::new(max_x - min_x, max_y - min_y).length() + center.distance(Vec2::new(min_x, min_y));
+
+    let axis = Color::from_rgba(200, 60, 160, 200);
+    for angle in symmetry.axis_angles() {
+        let dir = Vec2::from_angle(angle) * reach;
+        let (a, b) = (camera.world_to_screen(center - dir), camera.world_to_screen(center + dir));
+        draw_line(a.x, a.y, b.x, b.y, 1.5, axis);
+    }
+
+    if symmetry.segments > 1 {
+        let spoke = Color::from_rgba(200, 60, 160, 110);
+        let origin = camera.world_to_screen(center);
+        for i in 0..symmetry.segments {
+            // First spoke points up
+            let angle = -std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::TAU / symmetry.segments as f32;
+            let end = camera.world_to_screen(center + Vec2::from_angle(angle) * reach);
+            draw_line(origin.x, origin.y, end.x, end.y, 1.0, spoke);
+        }
+    }
+
+    let handle = camera.world_to_screen(center);
+    let fill = if state.symmetry_dragging || pivot_hovered(state) {
+        Color::from_rgba(255, 200, 240, 255)
+    } else {
+        Color::from_rgba(255, 255, 255, 220)
+    };
+    draw_circle(handle.x, handle.y, PIVOT_GRAB_RADIUS * 0.6, fill);
+    draw_circle_lines(handle.x, handle.y, PIVOT_GRAB_RADIUS * 0.6, 2.0, axis);
+}
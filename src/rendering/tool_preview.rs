@@ -0,0 +1,267 @@
+use macroquad::prelude::*;
+use crate::core::dither::dither_gradient;
+use std::collections::HashSet;
+use crate::core::cell::Cell;
+use crate::core::autotile::AutotileTemplate;
+use crate::core::merge::feather_weights;
+use crate::core::selection::{compute_bounding_rect, SelectionKind};
+use crate::input::clipboard::paste_anchor;
+use crate::input::transform::{transform_handles, transform_quad, transformed_selection, TransformHandle};
+use crate::core::shapes::{line_cells, polygon_fill_cells, polyline_cells, rect_cells};
+use crate::state::{ApplicationState, Mode};
+use crate::rendering::shapes::{cell_span, draw_texture_quad, draw_world_rect, draw_world_rect_lines};
+
+/// Live preview of shape tools before they commit: translucent cells in the current color
+pub fn draw_tool_preview(state: &ApplicationState) {
+    if let (Mode::Line, Some(drag)) = (&state.mode, state.line_drag) {
+        draw_line_preview(state, drag);
+    }
+    if let (Mode::Rectangle, Some(drag)) = (&state.mode, state.rectangle_drag) {
+        draw_rectangle_preview(state, drag);
+    }
+    if state.mode == Mode::Pen && !state.pen_anchors.is_empty() {
+        draw_pen_preview(state);
+    }
+    if state.mode == Mode::Curve {
+        draw_curve_preview(state);
+    }
+    if let (Mode::Dither, Some(drag)) = (&state.mode, state.dither_drag) {
+        draw_dither_preview(state, drag);
+    }
+    if let (Mode::Autotile, Some(template)) = (&state.mode, &state.autotile) {
+        draw_autotile_preview(state, template);
+    }
+    if state.pending_paste {
+        draw_paste_preview(state);
+    }
+    // Dragging a handle previews on the selection's texture; the exact resampled cells show
+    // once it is let go
+    match (&state.transform_drag, &state.selection_preview) {
+        (Some(_), Some(preview)) => draw_transform_drag_preview(state, &preview.texture),
+        _ => {
+            if let Some(transformed) = transformed_selection(state) {
+                draw_transform_preview(state, &transformed.sources, &transformed.result);
+            }
+        }
+    }
+    draw_transform_gizmo(state);
+}
+
+/// Outline of the tile under the cursor, which the autotile tool paints as a whole
+fn draw_autotile_preview(state: &ApplicationState, template: &AutotileTemplate) {
+    let world = state.camera.screen_to_cell(Vec2::from(mouse_position()));
+    let tile = template.tile_at((world.x.floor() as i32, world.y.floor() as i32));
+    let first = (tile.0 * template.size, tile.1 * template.size);
+    let last = (first.0 + template.size - 1, first.1 + template.size - 1);
+    let (min, max) = cell_span(first, last);
+    draw_world_rect_lines(&state.camera, min, max, 1.5, Color::from_rgba(70, 160, 80, 220));
+}
+
+/// Clipboard contents at the cursor, as they would land with the current merge mode and feather
+fn draw_paste_preview(state: &ApplicationState) {
+    let camera = &state.camera;
+    let anchor = paste_anchor(state);
+    let shape: HashSet<(i32, i32)> = state.clipboard.cells.keys().copied().collect();
+    let weights = feather_weights(&shape, state.feather);
+    for (&(dx, dy), &cell) in state.clipboard.cells.iter() {
+        let dest = (anchor.0 + dx, anchor.1 + dy);
+        let weight = weights.get(&(dx, dy)).copied();
+        if let Some(merged) = state.paste_merge.merge_feathered(state.cells.get(&dest).copied(), cell, weight) {
+            let (min, max) = cell_span(dest, dest);
+            let mut color = Color::from(merged.color);
+            color.a *= 0.8;
+            draw_world_rect(camera, min, max, color);
+        }
+    }
+    let far = (anchor.0 + state.clipboard.width - 1, anchor.1 + state.clipboard.height - 1);
+    let (min, max) = cell_span(anchor, far);
+    draw_world_rect_lines(camera, min, max, 1.5, Color::from_rgba(90, 90, 200, 220));
+}
+
+/// Knock back the original cells and draw the transformed ones over them
+fn draw_transform_preview(state: &ApplicationState, sources: &[(i32, i32)], result: &[((i32, i32), Cell)]) {
+    let camera = &state.camera;
+    let backdrop = Color::from_rgba(255, 255, 255, 200);
+    for &cell in sources {
+        let (min, max) = cell_span(cell, cell);
+        draw_world_rect(camera, min, max, backdrop);
+    }
+    for &(coord, cell) in result {
+        let (min, max) = cell_span(coord, coord);
+        draw_world_rect(camera, min, max, Color::from(cell.color));
+    }
+    let coords: HashSet<(i32, i32)> = result.iter().map(|(coord, _)| *coord).collect();
+    if let Some(rect) = compute_bounding_rect(&coords) {
+        let (min, max) = cell_span((rect.min_x, rect.min_y), (rect.max_x, rect.max_y));
+        draw_world_rect_lines(camera, min, max, 1.5, Color::from_rgba(90, 90, 200, 220));
+    }
+}
+
+/// Knock back the original cells and stretch the selection's texture over the transformed box
+fn draw_transform_drag_preview(state: &ApplicationState, texture: &Texture2D) {
+    let (Some(sel), Some(transform)) = (&state.selection.current, &state.selection_transform) else {
+        return;
+    };
+    let camera = &state.camera;
+    let backdrop = Color::from_rgba(255, 255, 255, 200);
+    let SelectionKind::Cells(set) = &sel.kind;
+    for &cell in set.iter().filter(|c| state.cells.contains_key(c)) {
+        let (min, max) = cell_span(cell, cell);
+        draw_world_rect(camera, min, max, backdrop);
+    }
+    let quad = transform_quad(&sel.rect, transform).map(|p| camera.world_to_screen(p));
+    draw_texture_quad(texture, quad);
+    for i in 0..4 {
+        let (p, q) = (quad[i], quad[(i + 1) % 4]);
+        draw_line(p.x, p.y, q.x, q.y, 1.5, Color::from_rgba(90, 90, 200, 220));
+    }
+}
+
+/// Handles of the selection's transform gizmo: squares scale, diamonds shear and the circle
+/// on a stalk above the top edge rotates
+fn draw_transform_gizmo(state: &ApplicationState) {
+    let handles = transform_handles(state);
+    let outline = Color::from_rgba(90, 90, 200, 255);
+    let grabbed = state.transform_drag.map(|drag| drag.handle);
+    let top = handles.iter().find(|(handle, _)| matches!(handle, TransformHandle::Scale { x: 0, y: -1 } | TransformHandle::ShearX));
+    let rotate = handles.iter().find(|(handle, _)| *handle == TransformHandle::Rotate);
+    if let (Some((_, a)), Some((_, b))) = (top, rotate) {
+        draw_line(a.x, a.y, b.x, b.y, 1.5, outline);
+    }
+    for &(handle, p) in handles.iter() {
+        let fill = if grabbed == Some(handle) { Color::from_rgba(150, 190, 255, 255) } else { WHITE };
+        match handle {
+            TransformHandle::Scale { .. } => {
+                draw_rectangle(p.x - 4.0, p.y - 4.0, 8.0, 8.0, fill);
+                draw_rectangle_lines(p.x - 4.0, p.y - 4.0, 8.0, 8.0, 1.5, outline);
+            }
+            TransformHandle::Rotate => {
+                draw_circle(p.x, p.y, 5.0, fill);
+                draw_circle_lines(p.x, p.y, 5.0, 1.5, outline);
+            }
+            TransformHandle::ShearX | TransformHandle::ShearY => {
+                let (a, b, c, d) = (p + Vec2::new(0.0, -6.0), p + Vec2::new(6.0, 0.0), p + Vec2::new(0.0, 6.0), p + Vec2::new(-6.0, 0.0));
+                draw_triangle(a, b, c, fill);
+                draw_triangle(a, c, d, fill);
+                for (s, e) in [(a, b), (b, c), (c, d), (d, a)] {
+                    draw_line(s.x, s.y, e.x, e.y, 1.5, outline);
+                }
+            }
+        }
+    }
+}
+
+/// Selections up to this many cells get a live dither preview; bigger ones only the arrow
+const DITHER_PREVIEW_MAX_CELLS: usize = 20_000;
+
+fn draw_dither_preview(state: &ApplicationState, (from, to): ((f32, f32), (f32, f32))) {
+    let camera = &state.camera;
+    if let Some(sel) = &state.selection.current {
+        let SelectionKind::Cells(set) = &sel.kind;
+        if set.len() <= DITHER_PREVIEW_MAX_CELLS {
+            for (cell, rgba) in dither_gradient(set.iter().copied(), from, to, state.current_color, state.secondary_color) {
+                let (min, max) = cell_span(cell, cell);
+                let mut color = Color::from(rgba);
+                color.a *= 0.7;
+                draw_world_rect(camera, min, max, color);
+            }
+        }
+    }
+
+    // Direction arrow from primary (start) to secondary (end)
+    let a = camera.world_to_screen(Vec2::new(from.0, from.1));
+    let b = camera.world_to_screen(Vec2::new(to.0, to.1));
+    draw_line(a.x, a.y, b.x, b.y, 2.0, BLACK);
+    draw_circle(a.x, a.y, 5.0, Color::from(state.current_color));
+    draw_circle_lines(a.x, a.y, 5.0, 1.5, BLACK);
+    draw_circle(b.x, b.y, 5.0, Color::from(state.secondary_color));
+    draw_circle_lines(b.x, b.y, 5.0, 1.5, BLACK);
+}
+
+fn draw_curve_preview(state: &ApplicationState) {
+    let Some(curve) = &state.curve else {
+        return;
+    };
+    let camera = &state.camera;
+    let mut color = Color::from(state.current_color);
+    color.a *= 0.5;
+    for cell in curve.cells(state.curve_steps) {
+        let (min, max) = cell_span(cell, cell);
+        draw_world_rect(camera, min, max, color);
+    }
+
+    // Handle arms, then the handles themselves (endpoints square, controls round)
+    let screen = curve.points.map(|(x, y)| camera.world_to_screen(Vec2::new(x, y)));
+    let arm = Color::from_rgba(90, 90, 200, 200);
+    let control_handles: &[usize] = if curve.cubic { &[1, 2] } else { &[1] };
+    let arms: &[(usize, usize)] = if curve.cubic { &[(0, 1), (3, 2)] } else { &[(0, 1), (3, 1)] };
+    for &(a, b) in arms {
+        draw_line(screen[a].x, screen[a].y, screen[b].x, screen[b].y, 1.0, arm);
+    }
+    for &i in &[0, 3] {
+        draw_rectangle(screen[i].x - 4.0, screen[i].y - 4.0, 8.0, 8.0, WHITE);
+        draw_rectangle_lines(screen[i].x - 4.0, screen[i].y - 4.0, 8.0, 8.0, 2.0, arm);
+    }
+    for &i in control_handles {
+        draw_circle(screen[i].x, screen[i].y, 5.0, WHITE);
+        draw_circle_lines(screen[i].x, screen[i].y, 5.0, 2.0, arm);
+    }
+}
+
+/// Ghost of the line being dragged, with its anchor outlined
+fn draw_line_preview(state: &ApplicationState, (start, end): ((i32, i32), (i32, i32))) {
+    let camera = &state.camera;
+    let mut color = Color::from(state.current_color);
+    color.a *= 0.5;
+    for cell in line_cells(start, end) {
+        let (min, max) = cell_span(cell, cell);
+        draw_world_rect(camera, min, max, color);
+    }
+    let (min, max) = cell_span(start, start);
+    draw_world_rect_lines(camera, min, max, 1.5, Color::from_rgba(40, 40, 40, 220));
+}
+
+/// Ghost of the rectangle being dragged, with a size readout by the cursor
+fn draw_rectangle_preview(state: &ApplicationState, (start, end): ((i32, i32), (i32, i32))) {
+    let camera = &state.camera;
+    let mut color = Color::from(state.current_color);
+    color.a *= 0.5;
+    for cell in rect_cells(start, end, state.rectangle_fill) {
+        let (min, max) = cell_span(cell, cell);
+        draw_world_rect(camera, min, max, color);
+    }
+    let (width, height) = ((end.0 - start.0).abs() + 1, (end.1 - start.1).abs() + 1);
+    let (mx, my) = mouse_position();
+    draw_text(&format!("{}x{}", width, height), mx + 14.0, my - 8.0, 16.0, BLACK);
+}
+
+fn draw_pen_preview(state: &ApplicationState) {
+    let camera = &state.camera;
+    let world = camera.screen_to_cell(Vec2::from(mouse_position()));
+    let cursor = (world.x.floor() as i32, world.y.floor() as i32);
+
+    // Placed segments plus a rubber band to the cursor
+    let mut points = state.pen_anchors.clone();
+    points.push(cursor);
+    let mut color = Color::from(state.current_color);
+    color.a *= 0.5;
+    if state.pen_fill && points.len() > 2 {
+        let mut fill = color;
+        fill.a *= 0.5;
+        for cell in polygon_fill_cells(&points) {
+            let (min, max) = cell_span(cell, cell);
+            draw_world_rect(camera, min, max, fill);
+        }
+    }
+    for cell in polyline_cells(&points, false) {
+        let (min, max) = cell_span(cell, cell);
+        draw_world_rect(camera, min, max, color);
+    }
+
+    // Anchor markers; the first is heavier since closing the shape joins back to it
+    for (i, &anchor) in state.pen_anchors.iter().enumerate() {
+        let (min, max) = cell_span(anchor, anchor);
+        let thickness = if i == 0 { 3.0 } else { 1.5 };
+        draw_world_rect_lines(camera, min, max, thickness, Color::from_rgba(40, 40, 40, 220));
+    }
+}
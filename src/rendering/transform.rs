@@ -0,0 +1,16 @@
+use macroquad::prelude::*;
+use crate::core::camera::Camera as AppCamera;
+
+/// World → screen mapping that overlays draw through. Overlays only ever ask for points,
+/// never for rotation, flip or zoom, so any view transform an implementation applies
+/// (rotation, mirroring, non-square cells, ...) carries over to them unchanged
+pub trait ViewTransform {
+    /// Screen position of a world point (cell units)
+    fn to_screen(&self, world: Vec2) -> Vec2;
+}
+
+impl ViewTransform for AppCamera {
+    fn to_screen(&self, world: Vec2) -> Vec2 {
+        self.world_to_screen(world)
+    }
+}
@@ -0,0 +1,654 @@
+//! Headless shared-canvas server (the `server` feature): owns the authoritative cell grid,
+//! relays cursors and chat between editors, enforces roles, paint rate limits and region
+//! locks, and saves the canvas as a project file (locks go in a JSON file beside it)
+
+use std::net::TcpListener;
+use std::time::{Duration, Instant};
+use crate::collab::client::Connection;
+use crate::collab::limits::{PaintBudget, RateLimit, Role};
+use crate::collab::protocol::{
+    ClientMessage, RegionLock, ServerMessage, WireCell, DEFAULT_PORT, IDLE_TIMEOUT_SECS, MAX_CHAT_CHARS, PING_INTERVAL_SECS,
+};
+use crate::core::cell::{Cell, CellAspect, CellGrid, GridSettings};
+use crate::project::format::{ProjectFile, ProjectMetadata};
+use crate::project::migrate::parse_project;
+use crate::project::file::write_replacing;
+
+/// How often a changed canvas is written to disk
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+/// Sleep between polls when nothing arrived
+const IDLE_SLEEP: Duration = Duration::from_millis(10);
+/// Longest message a client may send
+const MAX_LINE_BYTES: usize = 8 << 20;
+/// Unsent bytes a client may fall behind by before it is dropped (room for a large snapshot)
+const MAX_OUTBOX_BYTES: usize = 256 << 20;
+/// Most cells one Paint may change
+const MAX_PAINT_CHANGES: usize = 100_000;
+/// Cursor, chat and lock messages a connection may send: bursts of 60, 30 a second after
+/// that. Editors send cursors at most 20 times a second; paint has its own budget
+const MESSAGE_RATE: RateLimit = RateLimit { cells: 60, seconds: 2.0 };
+
+/// Command-line settings
+pub struct ServerConfig {
+    /// Address to listen on
+    pub bind: String,
+    /// Project file the canvas is loaded from and saved to
+    pub data_path: String,
+    /// Role given to everyone who joins without the admin token
+    pub default_role: Role,
+    /// Paint limit for editors (admins are exempt)
+    pub rate: RateLimit,
+    /// Clients presenting this token in Hello become admins; None disables admins
+    pub admin_token: Option<String>,
+}
+
+impl ServerConfig {
+    /// Parse `--bind ADDR`, `--data PATH`, `--default-role ROLE`, `--rate CELLS/SECONDS`
+    /// and `--admin-token TOKEN`
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Self {
+            bind: format!("0.0.0.0:{}", DEFAULT_PORT),
+            data_path: "shared-canvas.tnsp".to_string(),
+            default_role: Role::Editor,
+            rate: RateLimit::UNLIMITED,
+            admin_token: None,
+        };
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--bind" => config.bind = value()?,
+                "--data" => config.data_path = value()?,
+                "--default-role" => {
+                    let name = value()?;
+                    config.default_role = parse_role(&name).ok_or_else(|| format!("Unknown role '{}'", name))?;
+                }
+                "--rate" => {
+                    let text = value()?;
+                    config.rate = parse_rate(&text).ok_or_else(|| format!("Rate '{}' is not CELLS/SECONDS", text))?;
+                }
+                "--admin-token" => config.admin_token = Some(value()?),
+                _ => {
+                    return Err(format!(
+                        "Unknown argument '{}' (expected --bind, --data, --default-role, --rate, --admin-token)",
+                        arg
+                    ))
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// "viewer", "editor" or "admin"
+fn parse_role(name: &str) -> Option<Role> {
+    match name.to_ascii_lowercase().as_str() {
+        "viewer" => Some(Role::Viewer),
+        "editor" => Some(Role::Editor),
+        "admin" => Some(Role::Admin),
+        _ => None,
+    }
+}
+
+/// "CELLS/SECONDS", e.g. "200/10"
+fn parse_rate(text: &str) -> Option<RateLimit> {
+    let (cells, seconds) = text.split_once('/')?;
+    let limit = RateLimit { cells: cells.trim().parse().ok()?, seconds: seconds.trim().parse().ok()? };
+    (limit.seconds > 0.0).then_some(limit)
+}
+
+struct Client {
+    id: u32,
+    /// Set by Hello; clients that haven't introduced themselves receive nothing
+    name: Option<String>,
+    connection: Connection,
+    role: Role,
+    budget: PaintBudget,
+    /// Relayed messages other than paint, against MESSAGE_RATE
+    messages: PaintBudget,
+    /// When anything last arrived; silent past IDLE_TIMEOUT_SECS means the link is gone
+    last_heard: Instant,
+    /// Lets a reconnect under the same name replace this connection while it goes stale
+    resume: String,
+}
+
+struct Server {
+    listener: TcpListener,
+    clients: Vec<Client>,
+    next_id: u32,
+    cells: CellGrid,
+    cell_aspect: CellAspect,
+    grid: GridSettings,
+    data_path: String,
+    /// Unsaved changes since the last write
+    dirty: bool,
+    last_save: Instant,
+    default_role: Role,
+    rate: RateLimit,
+    admin_token: Option<String>,
+    /// Clock for paint budgets
+    started: Instant,
+    locks: Vec<RegionLock>,
+    next_lock_id: u32,
+    last_ping: Instant,
+}
+
+/// Run until the process is killed
+pub fn run(config: ServerConfig) -> Result<(), String> {
+    let mut server = Server::start(config)?;
+    loop {
+        let busy = server.accept() | server.poll_clients();
+        server.save_if_due();
+        server.ping_if_due();
+        if !busy {
+            std::thread::sleep(IDLE_SLEEP);
+        }
+    }
+}
+
+/// The saved canvas, or an empty one the first time the server runs
+fn load_canvas(path: &str) -> Result<(CellGrid, CellAspect, GridSettings), String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => {
+            let document = parse_project(&text)?.into_document()?;
+            Ok((document.cells, document.cell_aspect, document.grid))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok((CellGrid::new(), CellAspect::SQUARE, GridSettings::DEFAULT)),
+        Err(e) => Err(format!("Could not read {}: {}", path, e)),
+    }
+}
+
+fn covers(lock: &RegionLock, x: i32, y: i32) -> bool {
+    lock.rect.contains(x, y) && (lock.cells.is_empty() || lock.cells.binary_search(&(x, y)).is_ok())
+}
+
+/// Hard to guess, so only the connection that was welcomed can take its name back; the
+/// randomly keyed std hasher is enough for that without pulling in a RNG
+fn resume_token(id: u32) -> String {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u32(id);
+    hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
+    format!("{:016x}", hasher.finish())
+}
+
+fn locks_path(data_path: &str) -> String {
+    format!("{}.locks.json", data_path)
+}
+
+/// Saved region locks, or none the first time
+fn load_locks(data_path: &str) -> Result<Vec<RegionLock>, String> {
+    let path = locks_path(data_path);
+    match std::fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| format!("Corrupt {}: {}", path, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Could not read {}: {}", path, e)),
+    }
+}
+
+impl Server {
+    /// Load the canvas and locks and start listening
+    fn start(config: ServerConfig) -> Result<Self, String> {
+        let (cells, cell_aspect, grid) = load_canvas(&config.data_path)?;
+        let locks = load_locks(&config.data_path)?;
+        let listener = TcpListener::bind(&config.bind).map_err(|e| format!("Could not listen on {}: {}", config.bind, e))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        log::info!("Serving {} ({} cells) on {}", config.data_path, cells.len(), config.bind);
+
+        Ok(Server {
+            listener,
+            clients: Vec::new(),
+            next_id: 1,
+            cells,
+            cell_aspect,
+            grid,
+            data_path: config.data_path,
+            dirty: false,
+            last_save: Instant::now(),
+            default_role: config.default_role,
+            rate: config.rate,
+            admin_token: config.admin_token,
+            started: Instant::now(),
+            next_lock_id: locks.iter().map(|lock| lock.id + 1).max().unwrap_or(1),
+            locks,
+            last_ping: Instant::now(),
+        })
+    }
+
+    /// Take any waiting connections; true if one arrived
+    fn accept(&mut self) -> bool {
+        let mut accepted = false;
+        while let Ok((stream, address)) = self.listener.accept() {
+            match Connection::from_stream(stream).map(|c| c.with_limits(MAX_LINE_BYTES, MAX_OUTBOX_BYTES)) {
+                Ok(connection) => {
+                    log::info!("Connection {} from {}", self.next_id, address);
+                    let (budget, messages) = (PaintBudget::new(self.rate, self.now()), PaintBudget::new(MESSAGE_RATE, self.now()));
+                    self.clients.push(Client {
+                        id: self.next_id,
+                        name: None,
+                        connection,
+                        role: self.default_role,
+                        budget,
+                        messages,
+                        last_heard: Instant::now(),
+                        resume: resume_token(self.next_id),
+                    });
+                    self.next_id += 1;
+                    accepted = true;
+                }
+                Err(e) => log::warn!("Rejected connection from {}: {}", address, e),
+            }
+        }
+        accepted
+    }
+
+    /// Handle everything clients sent; true if any message arrived
+    fn poll_clients(&mut self) -> bool {
+        let mut busy = false;
+        let mut i = 0;
+        while i < self.clients.len() {
+            match self.clients[i].connection.poll::<ClientMessage>() {
+                Ok(messages) if messages.is_empty() && self.clients[i].last_heard.elapsed().as_secs_f64() > IDLE_TIMEOUT_SECS => {
+                    log::info!("{} timed out", self.clients[i].id);
+                    self.disconnect(i);
+                }
+                Ok(messages) => {
+                    if !messages.is_empty() {
+                        busy = true;
+                        self.clients[i].last_heard = Instant::now();
+                    }
+                    // A resuming Hello can remove an earlier client, so follow this one by id
+                    let id = self.clients[i].id;
+                    for message in messages {
+                        self.handle(i, message);
+                        i = self.clients.iter().position(|c| c.id == id).unwrap_or(i);
+                    }
+                    i += 1;
+                }
+                Err(_) => self.disconnect(i),
+            }
+        }
+        busy
+    }
+
+    /// Ping everyone each PING_INTERVAL_SECS so editors can tell a quiet canvas from a dead link
+    fn ping_if_due(&mut self) {
+        if self.last_ping.elapsed().as_secs_f64() >= PING_INTERVAL_SECS {
+            self.last_ping = Instant::now();
+            self.broadcast_except(0, &ServerMessage::Ping);
+        }
+    }
+
+    fn handle(&mut self, mut index: usize, message: ClientMessage) {
+        let id = self.clients[index].id;
+        // Hello exactly once, before anything else
+        let introduced = self.clients[index].name.is_some();
+        if introduced == matches!(message, ClientMessage::Hello { .. }) {
+            return;
+        }
+        // Paint is metered by the paint budget, and a reconnect replays queued strokes at once
+        let metered = !matches!(message, ClientMessage::Hello { .. } | ClientMessage::Paint { .. });
+        let now = self.now();
+        if metered && !self.clients[index].messages.try_spend(1, now) {
+            if matches!(message, ClientMessage::Chat { .. }) {
+                self.reply(index, "Too many messages; slow down".to_string());
+            }
+            return;
+        }
+        match message {
+            ClientMessage::Hello { name, token, resume } => {
+                let stale = match self.check_name(&name, resume.as_deref()) {
+                    Ok(stale) => stale,
+                    Err(reason) => {
+                        log::warn!("{} refused: {}", id, reason);
+                        self.send_to(index, &ServerMessage::Refused { reason });
+                        return;
+                    }
+                };
+                // A reconnect whose old connection hasn't timed out yet takes over its role and budget
+                if let Some(stale) = stale {
+                    log::info!("{} resumes {} as {}", id, self.clients[stale].id, name);
+                    self.clients[index].role = self.clients[stale].role;
+                    self.clients[index].budget = self.clients[stale].budget;
+                    self.disconnect(stale);
+                    if stale < index {
+                        index -= 1;
+                    }
+                }
+                if token.is_some() && token == self.admin_token {
+                    self.clients[index].role = Role::Admin;
+                }
+                let role = self.clients[index].role;
+                log::info!("{} joined as {} ({})", id, name, role.label());
+                let resume = self.clients[index].resume.clone();
+                let intro: Vec<ServerMessage> = [ServerMessage::Welcome { id, resume }, ServerMessage::Permissions { role, limit: self.rate }]
+                    .into_iter()
+                    .chain(self.clients.iter().filter_map(|c| {
+                        c.name.clone().map(|name| ServerMessage::PeerJoined { id: c.id, name })
+                    }))
+                    .chain([ServerMessage::Snapshot { cells: self.snapshot() }, ServerMessage::Locks { locks: self.locks.clone() }])
+                    .collect();
+                for message in intro {
+                    self.send_to(index, &message);
+                }
+                self.broadcast_except(id, &ServerMessage::PeerJoined { id, name: name.clone() });
+                self.clients[index].name = Some(name);
+            }
+            ClientMessage::Cursor { x, y, color, tool } => {
+                self.broadcast_except(id, &ServerMessage::Cursor { id, x, y, color, tool });
+            }
+            ClientMessage::Chat { text } => {
+                if text.chars().count() > MAX_CHAT_CHARS {
+                    self.reply(index, format!("Chat lines are limited to {} characters", MAX_CHAT_CHARS));
+                    return;
+                }
+                if let Some(command) = text.strip_prefix('/') {
+                    self.chat_command(index, command);
+                    return;
+                }
+                let name = self.clients[index].name.clone().unwrap_or_default();
+                self.broadcast_except(0, &ServerMessage::Chat { name, text });
+            }
+            ClientMessage::Paint { changes } => {
+                if let Err(reason) = self.check_paint(index, &changes) {
+                    let cells = changes.iter().map(|c| self.current(c.x, c.y)).collect();
+                    self.send_to(index, &ServerMessage::Rejected { reason, cells });
+                    return;
+                }
+                for change in &changes {
+                    match change.color {
+                        Some(color) => {
+                            self.cells.insert((change.x, change.y), Cell::with_color(color));
+                        }
+                        None => {
+                            self.cells.remove(&(change.x, change.y));
+                        }
+                    }
+                }
+                self.dirty = true;
+                self.broadcast_except(id, &ServerMessage::Cells { changes });
+            }
+            ClientMessage::Lock { rect, owners, mut cells } => {
+                if self.clients[index].role != Role::Admin {
+                    self.reply(index, "Only admins can lock regions".to_string());
+                    return;
+                }
+                cells.retain(|&(x, y)| rect.contains(x, y));
+                cells.sort_unstable();
+                cells.dedup();
+                self.locks.push(RegionLock { id: self.next_lock_id, rect, owners, cells });
+                self.next_lock_id += 1;
+                self.locks_changed();
+            }
+            ClientMessage::Unlock { id: lock_id } => {
+                if self.clients[index].role != Role::Admin {
+                    self.reply(index, "Only admins can unlock regions".to_string());
+                    return;
+                }
+                self.locks.retain(|lock| lock.id != lock_id);
+                self.locks_changed();
+            }
+            ClientMessage::Ping => {}
+        }
+    }
+
+    /// Names identify people to locks and `/role`, so each may be used by one connection at a
+    /// time. The exception is a reconnect presenting the holder's resume token: its index comes
+    /// back so the stale connection can be replaced
+    fn check_name(&self, name: &str, resume: Option<&str>) -> Result<Option<usize>, String> {
+        if name.trim().is_empty() || name == "server" {
+            return Err(format!("'{}' can't be used as a name", name));
+        }
+        match self.clients.iter().position(|c| c.name.as_deref() == Some(name)) {
+            Some(holder) if resume == Some(self.clients[holder].resume.as_str()) => Ok(Some(holder)),
+            Some(_) => Err(format!("someone called {} is already connected", name)),
+            None => Ok(None),
+        }
+    }
+
+    fn now(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    /// Viewers can't paint; editors need to own every lock they touch and spend their budget;
+    /// admins are unrestricted
+    fn check_paint(&mut self, index: usize, changes: &[WireCell]) -> Result<(), String> {
+        let now = self.now();
+        let client = &mut self.clients[index];
+        let name = client.name.as_deref().unwrap_or_default();
+        let locked = |lock: &RegionLock| {
+            !lock.owners.iter().any(|owner| owner == name) && changes.iter().any(|c| covers(lock, c.x, c.y))
+        };
+        match client.role {
+            _ if changes.len() > MAX_PAINT_CHANGES => Err(format!("more than {} cells in one stroke", MAX_PAINT_CHANGES)),
+            Role::Viewer => Err("viewers can't paint".to_string()),
+            Role::Admin => Ok(()),
+            Role::Editor => match self.locks.iter().find(|lock| locked(lock)) {
+                Some(lock) => Err(format!("region locked (lock {})", lock.id)),
+                None if !client.budget.fits(changes.len()) => Err(format!("stroke exceeds {} cells", client.budget.limit.cells)),
+                None if client.budget.try_spend(changes.len(), now) => Ok(()),
+                None => Err(format!("rate limit, wait {:.1}s", client.budget.wait_for(changes.len(), now))),
+            },
+        }
+    }
+
+    /// Save the locks and tell everyone
+    fn locks_changed(&mut self) {
+        let path = locks_path(&self.data_path);
+        let result = serde_json::to_string(&self.locks)
+            .map_err(|e| e.to_string())
+            .and_then(|json| write_replacing(&path, &json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log::error!("Could not save {}: {}", path, e);
+        }
+        self.broadcast_except(0, &ServerMessage::Locks { locks: self.locks.clone() });
+    }
+
+    /// Add or remove a lock owner
+    fn set_lock_owner(&mut self, lock_id: &str, name: &str, allow: bool) -> String {
+        let Some(lock) = lock_id.parse::<u32>().ok().and_then(|id| self.locks.iter_mut().find(|lock| lock.id == id)) else {
+            return format!("No lock {}", lock_id);
+        };
+        lock.owners.retain(|owner| owner != name);
+        if allow {
+            lock.owners.push(name.to_string());
+        }
+        self.locks_changed();
+        format!("Lock {} {} {}", lock_id, if allow { "now allows" } else { "no longer allows" }, name)
+    }
+
+    fn reply(&mut self, index: usize, text: String) {
+        self.send_to(index, &ServerMessage::Chat { name: "server".to_string(), text });
+    }
+
+    /// Admin commands typed into chat: `/role NAME ROLE`, `/allow LOCK NAME`, `/deny LOCK NAME`
+    fn chat_command(&mut self, index: usize, command: &str) {
+        let reply = match command.split_whitespace().collect::<Vec<_>>().as_slice() {
+            _ if self.clients[index].role != Role::Admin => "Only admins can use commands".to_string(),
+            ["role", name, role] => match parse_role(role) {
+                Some(role) => self.set_role(name, role),
+                None => format!("Unknown role '{}'", role),
+            },
+            ["allow", lock_id, name] => self.set_lock_owner(lock_id, name, true),
+            ["deny", lock_id, name] => self.set_lock_owner(lock_id, name, false),
+            _ => "Commands: /role NAME viewer|editor|admin, /allow LOCK NAME, /deny LOCK NAME".to_string(),
+        };
+        self.reply(index, reply);
+    }
+
+    /// Give every client called `name` a new role
+    fn set_role(&mut self, name: &str, role: Role) -> String {
+        let limit = self.rate;
+        let mut count = 0;
+        for client in self.clients.iter_mut().filter(|c| c.name.as_deref() == Some(name)) {
+            client.role = role;
+            let _ = client.connection.send(&ServerMessage::Permissions { role, limit });
+            count += 1;
+        }
+        match count {
+            0 => format!("Nobody called {} is connected", name),
+            _ => format!("{} is now {}", name, role.label()),
+        }
+    }
+
+    /// The server's value for one cell, as sent back with a rejection
+    fn current(&self, x: i32, y: i32) -> WireCell {
+        let color = self.cells.get(&(x, y)).filter(|c| c.is_filled).map(|c| c.color);
+        WireCell { x, y, color }
+    }
+
+    fn snapshot(&self) -> Vec<WireCell> {
+        self.cells
+            .iter()
+            .filter(|(_, cell)| cell.is_filled)
+            .map(|(&(x, y), cell)| WireCell { x, y, color: Some(cell.color) })
+            .collect()
+    }
+
+    /// A failed send is noticed (and the client dropped) on its next poll
+    fn send_to(&mut self, index: usize, message: &ServerMessage) {
+        let _ = self.clients[index].connection.send(message);
+    }
+
+    /// Send to every introduced client except `skip_id` (0 skips nobody)
+    fn broadcast_except(&mut self, skip_id: u32, message: &ServerMessage) {
+        for client in self.clients.iter_mut().filter(|c| c.id != skip_id && c.name.is_some()) {
+            let _ = client.connection.send(message);
+        }
+    }
+
+    fn disconnect(&mut self, index: usize) {
+        let client = self.clients.remove(index);
+        log::info!("{} disconnected", client.id);
+        if client.name.is_some() {
+            self.broadcast_except(client.id, &ServerMessage::PeerLeft { id: client.id });
+        }
+    }
+
+    fn save_if_due(&mut self) {
+        if !self.dirty || self.last_save.elapsed() < SAVE_INTERVAL {
+            return;
+        }
+        self.last_save = Instant::now();
+        let mut file = ProjectFile::from_document(&self.cells, self.cell_aspect, self.grid, &[], &[], &[], &ProjectMetadata::default());
+        file.add_thumbnail();
+        let result = serde_json::to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|json| write_replacing(&self.data_path, &json).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => self.dirty = false,
+            Err(e) => log::error!("Could not save {}: {}", self.data_path, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Rgba;
+
+    fn server(test: &str) -> Server {
+        let data_path = std::env::temp_dir().join(format!("tiny-neo-space-{}-{}.tnsp", test, std::process::id()));
+        let config = ServerConfig {
+            bind: "127.0.0.1:0".to_string(),
+            data_path: data_path.to_string_lossy().into_owned(),
+            default_role: Role::Editor,
+            rate: RateLimit::UNLIMITED,
+            admin_token: None,
+        };
+        Server::start(config).unwrap()
+    }
+
+    /// Let the server accept and handle whatever has arrived
+    fn pump(server: &mut Server) {
+        for _ in 0..20 {
+            server.accept();
+            server.poll_clients();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Connect and introduce `name`, dropping the welcome messages
+    fn join(server: &mut Server, name: &str) -> Connection {
+        hello(server, name, None).0
+    }
+
+    /// Connect and send Hello, returning the connection and everything the server answered
+    fn hello(server: &mut Server, name: &str, resume: Option<String>) -> (Connection, Vec<ServerMessage>) {
+        let address = server.listener.local_addr().unwrap().to_string();
+        let mut connection = Connection::connect(&address).unwrap();
+        connection.send(&ClientMessage::Hello { name: name.to_string(), token: None, resume }).unwrap();
+        pump(server);
+        let messages = received(&mut connection);
+        (connection, messages)
+    }
+
+    fn received(connection: &mut Connection) -> Vec<ServerMessage> {
+        std::thread::sleep(Duration::from_millis(20));
+        connection.poll().unwrap()
+    }
+
+    fn chats(messages: Vec<ServerMessage>) -> Vec<(String, String)> {
+        messages
+            .into_iter()
+            .filter_map(|message| match message {
+                ServerMessage::Chat { name, text } => Some((name, text)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn long_chat_lines_are_refused() {
+        let mut server = server("chat");
+        let mut alice = join(&mut server, "alice");
+        let mut bob = join(&mut server, "bob");
+        received(&mut alice);
+        alice.send(&ClientMessage::Chat { text: "x".repeat(MAX_CHAT_CHARS + 1) }).unwrap();
+        alice.send(&ClientMessage::Chat { text: "é".repeat(MAX_CHAT_CHARS) }).unwrap();
+        pump(&mut server);
+
+        assert_eq!(chats(received(&mut bob)), vec![("alice".to_string(), "é".repeat(MAX_CHAT_CHARS))]);
+        let replies = chats(received(&mut alice));
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0].0, "server");
+        assert!(replies[0].1.contains("limited"));
+    }
+
+    #[test]
+    fn cursor_floods_are_cut_to_the_message_rate() {
+        let mut server = server("cursor");
+        let mut alice = join(&mut server, "alice");
+        let mut bob = join(&mut server, "bob");
+        received(&mut alice);
+        for i in 0..200 {
+            alice.send(&ClientMessage::Cursor { x: i as f32, y: 0.0, color: Rgba::WHITE, tool: "paint".to_string() }).unwrap();
+        }
+        pump(&mut server);
+
+        let relayed = received(&mut bob).iter().filter(|message| matches!(message, ServerMessage::Cursor { .. })).count();
+        // The burst plus whatever refilled while the test ran
+        assert!((MESSAGE_RATE.cells as usize..100).contains(&relayed), "{} cursors relayed", relayed);
+    }
+
+    #[test]
+    fn reconnects_with_the_resume_token_replace_the_stale_connection() {
+        let mut server = server("resume");
+        let (_stale, welcome) = hello(&mut server, "alice", None);
+        let resume = welcome
+            .iter()
+            .find_map(|message| match message {
+                ServerMessage::Welcome { resume, .. } => Some(resume.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        let (_, refused) = hello(&mut server, "alice", Some("guess".to_string()));
+        assert!(refused.iter().any(|message| matches!(message, ServerMessage::Refused { .. })));
+
+        let (_fresh, welcome) = hello(&mut server, "alice", Some(resume));
+        assert!(welcome.iter().any(|message| matches!(message, ServerMessage::Welcome { .. })));
+        let names: Vec<_> = server.clients.iter().filter_map(|c| c.name.as_deref()).collect();
+        assert_eq!(names, vec!["alice"]);
+    }
+}
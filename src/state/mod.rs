@@ -10,10 +10,42 @@ use macroquad::prelude::*;
 use crate::core::*;
 use crate::core::camera::Camera as AppCamera;
 use crate::core::cell::Cell;
-use std::collections::HashMap;
+use crate::core::color::{generate_gba_extended_palette, Rgba, GBA_PALETTE};
+use crate::core::rng::Rng;
+use crate::core::budget::{check_budget, BudgetReport, SpriteBudget};
+use crate::core::group::Group;
+use crate::core::frame_tag::FrameTag;
+use crate::core::strays::StrayNavigator;
+use crate::core::shapes::Curve;
+use crate::core::transform::SelectionTransform;
+use crate::core::merge::PasteMerge;
+use crate::core::symmetry::Symmetry;
+use crate::core::autotile::AutotileTemplate;
+use crate::core::generate::Generator;
+use crate::collab::CollabSession;
+use crate::export::png::ExportSettings;
+use crate::import::ImportSettings;
+use crate::input::actions::{Action, KeyBinding, KEYMAP};
+use crate::input::diff::DiffView;
+use crate::input::mouse::MouseSettings;
+use crate::input::transform::TransformDrag;
+use crate::input::frames::{FramePlayback, DEFAULT_FRAME_FPS};
+use crate::input::jobs::{IoTask, Job};
+use crate::project::backup::BackupSettings;
+use crate::project::format::{ProjectGroup, ProjectMetadata, ProjectSelection, DEFAULT_PROJECT_PATH};
+use crate::project::recent::RecentProject;
+use crate::rendering::{CanvasRenderer, HudLayout, ViewTransform};
+use crate::ui::file_picker::FilePicker;
+use crate::ui::hit_test::UiRegions;
+use crate::ui::metadata_panel::MetadataField;
+use crate::ui::theme::Theme;
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+pub use crate::core::history::{CellChange, Command, History};
 
 /// Represents the current editing mode of the application
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Debug)]
 pub enum Mode {
     /// Paint mode - adds cells with the current color
     Paint,
@@ -23,14 +55,39 @@ pub enum Mode {
     Pan,
     /// Select mode - select and move cells
     Select,
+    /// Spray mode - scatters random cells within the brush radius
+    Spray,
+    /// Export region mode - drag a rect to export exactly those cells
+    ExportRegion,
+    /// Line mode - drag a straight line from the press to the release
+    Line,
+    /// Rectangle mode - drag an outlined or filled rectangle from corner to corner
+    Rectangle,
+    /// Pen mode - click anchors joined by lines, double-click to close the shape
+    Pen,
+    /// Curve mode - drag a Bezier curve, then adjust its handles
+    Curve,
+    /// Fill mode - flood the clicked region with the current color
+    Fill,
+    /// Dither mode - drag across the selection for a primary-to-secondary dithered band
+    Dither,
+    /// Autotile mode - paint terrain tiles; edge and corner variants come from the template
+    Autotile,
 }
 
-/// Clipboard for storing copied/cut cells
+/// Clipboard for storing copied/cut cells; persisted between sessions by `project::clipboard`
+#[derive(Serialize, Deserialize)]
 pub struct Clipboard {
     pub width: i32,
     pub height: i32,
+    /// JSON map keys must be strings, so cells are stored as a list of (coord, cell) pairs
+    #[serde(with = "cell_pairs")]
     pub cells: HashMap<(i32, i32), Cell>,
     pub has_data: bool,
+    /// Groups created along with the cells when pasted, relative like the cells
+    /// (filled by importing a project; copies don't carry groups)
+    #[serde(default)]
+    pub groups: Vec<ProjectGroup>,
 }
 
 impl Clipboard {
@@ -40,53 +97,49 @@ impl Clipboard {
             height: 0,
             cells: HashMap::new(),
             has_data: false,
+            groups: Vec::new(),
         }
     }
 }
 
-/// Represents a change to a single cell for undo/redo
-pub struct CellChange {
-    pub coord: (i32, i32),
-    pub before: Option<Cell>,
-    pub after: Option<Cell>,
-}
+mod cell_pairs {
+    use super::{Cell, HashMap};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-/// Represents a command that can be undone
-pub struct Command {
-    pub changes: Vec<CellChange>,
-}
+    pub fn serialize<S: Serializer>(cells: &HashMap<(i32, i32), Cell>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut pairs: Vec<_> = cells.iter().collect();
+        pairs.sort_by_key(|(&(x, y), _)| (y, x));
+        pairs.serialize(serializer)
+    }
 
-/// History stack for undo/redo functionality
-pub struct History {
-    pub stack: Vec<Command>,
-    pub max: usize,
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<(i32, i32), Cell>, D::Error> {
+        let pairs: Vec<((i32, i32), Cell)> = Vec::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
 }
 
-impl History {
-    pub fn new(max: usize) -> Self {
-        Self {
-            stack: Vec::new(),
-            max,
-        }
-    }
+/// Seed for the spray brush RNG
+pub const SPRAY_SEED: u64 = 0x5EED_5EED;
 
-    pub fn push(&mut self, cmd: Command) {
-        self.stack.push(cmd);
-        if self.stack.len() > self.max {
-            self.stack.remove(0);
-        }
-    }
+/// Seed for the pattern generators' RNG
+pub const GENERATOR_SEED: u64 = 0x6E4E_7A7E;
 
-    pub fn pop(&mut self) -> Option<Command> {
-        self.stack.pop()
-    }
+/// A destructive operation waiting for the user to confirm it
+pub struct Confirm {
+    /// Question shown in the dialog
+    pub message: String,
+    /// Label of the button that goes ahead
+    pub confirm_label: &'static str,
+    /// Runs once the user accepts
+    pub on_confirm: fn(&mut ApplicationState, &mut CanvasRenderer),
 }
 
 /// Palette display mode
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum PaletteMode {
     Basic,
     Extended,
+    Custom,
 }
 
 /// The main application state containing all global state
@@ -95,10 +148,29 @@ pub struct ApplicationState {
     pub mode: Mode,
     /// Whether the color palette UI is visible
     pub show_palette: bool,
+    /// Whether the color harmony suggestions panel is shown
+    pub show_harmony: bool,
     /// The currently selected color for painting
-    pub current_color: Color,
-    /// The grid of cells (sparse HashMap-based grid)
-    pub cells: CellGrid,
+    pub current_color: Rgba,
+    /// Secondary color, swapped with the current color via `X`
+    pub secondary_color: Rgba,
+    /// The grid of cells (sparse HashMap-based grid), shared with background exports;
+    /// edits go through `Arc::make_mut`, which copies it only while an export still holds it
+    pub cells: std::sync::Arc<CellGrid>,
+    /// Document cell shape (mirrored into `camera.aspect` for display)
+    pub cell_aspect: CellAspect,
+    /// Document cell size and major grid spacing
+    pub grid: GridSettings,
+    /// Whether the canvas settings panel is open
+    pub show_canvas_settings: bool,
+    /// Offsets (dx, dy) of the open "Shift Everything" dialog, None when closed
+    pub shift_canvas: Option<(i32, i32)>,
+    /// Coordinates being typed into the open "Go to Cell" dialog, None when closed
+    pub go_to_cell: Option<String>,
+    /// Go to Cell drops a marker on the cell it centers
+    pub go_to_marker: bool,
+    /// Cell Go to Cell last marked and when (seconds since start); it fades on its own
+    pub cell_marker: Option<((i32, i32), f64)>,
     /// Camera with zoom and pan support
     pub camera: AppCamera,
     /// Position of the color palette window
@@ -111,22 +183,243 @@ pub struct ApplicationState {
     pub pan_drag_start_screen: Option<Vec2>,
     /// Pan tool state: drag start camera origin
     pub pan_drag_start_origin: Option<Vec2>,
+    /// Pan tool state: mouse position on the previous frame of a drag
+    pub pan_last_mouse: Option<Vec2>,
+    /// Screen-space pan velocity (pixels per second) used to glide after release
+    pub pan_velocity: Vec2,
     /// Temporary pan mode activated by middle mouse button
     pub temp_pan_active: bool,
     /// Previous mode before temporary pan (to restore after middle mouse release)
     pub temp_pan_previous_mode: Option<Mode>,
+    /// Space held: pan overrides the current tool without touching its stroke state
+    pub space_pan_active: bool,
+    /// The left button was down when Space was pressed, so the tool's stroke is paused
+    pub space_pan_paused_stroke: bool,
+    /// Transform being previewed on the selection, until applied or cancelled
+    pub selection_transform: Option<SelectionTransform>,
+    /// Transform gizmo handle being dragged
+    pub transform_drag: Option<TransformDrag>,
     /// Selection system state
     pub selection: SelectionState,
+    /// Texture of the selection while it is lifted and moved or being transformed
+    pub selection_preview: Option<RenderTarget>,
     /// Last painted cell coordinates for stroke interpolation
     pub last_painted_cell: Option<(i32, i32)>,
+    /// Shift+drag in Erase mode: start and current end cell of the rect being erased
+    pub erase_rect_drag: Option<((i32, i32), (i32, i32))>,
+    /// Changes made by the stroke in progress, committed as one undo command on release
+    pub stroke_changes: Vec<CellChange>,
+    /// Line tool drag: anchor and current end cell
+    pub line_drag: Option<((i32, i32), (i32, i32))>,
+    /// Rectangle tool drag: anchor corner and current opposite corner
+    pub rectangle_drag: Option<((i32, i32), (i32, i32))>,
+    /// The rectangle tool fills the inside as well as the outline
+    pub rectangle_fill: bool,
+    /// Pen tool anchors placed so far for the shape in progress
+    pub pen_anchors: Vec<(i32, i32)>,
+    /// Fill the interior when the pen closes a shape
+    pub pen_fill: bool,
+    /// Curve being edited by the curve tool
+    pub curve: Option<Curve>,
+    /// Curve handle being dragged (0 start, 1-2 controls, 3 end)
+    pub curve_drag: Option<usize>,
+    /// New curves are cubic (two controls) rather than quadratic
+    pub curve_cubic: bool,
+    /// Straight segments a curve is rasterized with
+    pub curve_steps: u32,
+    /// Fill tool: colors within this RGBA distance of the clicked one are replaced too
+    pub fill_tolerance: f32,
+    /// Fill tool: replace every matching cell in bounds, not just the connected region
+    pub fill_global: bool,
+    /// The tolerance slider is being dragged
+    pub fill_tolerance_dragging: bool,
+    /// Dither tool drag: start and current end in world cell units
+    pub dither_drag: Option<((f32, f32), (f32, f32))>,
+    /// Variants the autotile tool paints with, cut from a template sheet on the canvas
+    pub autotile: Option<AutotileTemplate>,
+    /// Autotile stroke in progress: last tile painted and whether it adds terrain (left
+    /// button) or removes it (right button)
+    pub autotile_stroke: Option<((i32, i32), bool)>,
+    /// Generator waiting for a rect to be dragged, when started with nothing selected
+    pub pending_generator: Option<Generator>,
+    /// Rect being dragged for the pending generator
+    pub generator_drag: Option<((i32, i32), (i32, i32))>,
+    /// Randomness for maze, noise and Voronoi generators
+    pub generator_rng: Rng,
+    /// Spray brush radius in cells
+    pub spray_radius: i32,
+    /// Fraction of the spray disc painted per frame
+    pub spray_density: f32,
+    /// Seeded RNG for the spray brush (same seed + same input = same strokes)
+    pub spray_rng: Rng,
     /// Clipboard for copy/cut/paste operations
     pub clipboard: Clipboard,
+    /// Ctrl+V pressed: the clipboard follows the cursor until a click places it
+    pub pending_paste: bool,
+    /// How pasted cells combine with existing ones
+    pub paste_merge: PasteMerge,
+    /// Cells of edge falloff for pasted and moved content (0 = hard edges)
+    pub feather: u8,
     /// Undo/redo history
     pub history: History,
+    /// File used by Save / Open
+    pub project_path: String,
+    /// Title, author, tags and creation date of the open document
+    pub metadata: ProjectMetadata,
+    /// Whether the document info (metadata) panel is open
+    pub show_metadata: bool,
+    /// Metadata field being typed into and its text so far
+    pub metadata_edit: Option<(MetadataField, String)>,
+    /// Named selections saved with the document
+    pub saved_selections: Vec<ProjectSelection>,
+    /// Whether the saved selections panel is open
+    pub show_saved_selections: bool,
+    /// Saved selection being renamed in the panel: its index and the name typed so far
+    pub saved_selection_rename: Option<(usize, String)>,
+    /// Write recent undo steps into saved projects so they survive reopening
+    pub save_history: bool,
+    /// Append every committed command to the action log file
+    pub log_actions: bool,
+    /// Shared session server, "host" or "host:port" (TINY_NEO_SPACE_SERVER overrides the default)
+    pub collab_address: String,
+    /// Live shared session, if joined
+    pub collab: Option<CollabSession>,
+    /// Chat input line is open and receiving keystrokes
+    pub chat_open: bool,
+    pub chat_input: String,
+    /// Outcome of the last save/open, shown in the HUD
+    pub status_message: Option<String>,
+    /// How often the document is backed up and how many backups are kept
+    pub backup: BackupSettings,
+    /// When the last automatic backup was started (seconds, app clock)
+    pub last_backup_time: f64,
+    /// History revision the last backup (or open) captured
+    pub backed_up_revision: u64,
+    /// Project whose backups the restore picker is showing; the picker reuses `open_recent`
+    pub restoring_backup_of: Option<String>,
+    /// File work queued for the next frame, announced by the progress overlay meanwhile
+    pub pending_job: Option<Job>,
+    /// Saves and exports being written in the background
+    pub io_tasks: Vec<IoTask>,
+    /// Project paths to save again once the save already writing them finishes
+    pub queued_saves: Vec<String>,
+    /// Show the sprite budget readout in the HUD
+    pub show_budget: bool,
+    /// Limits the budget readout checks against
+    pub budget: SpriteBudget,
+    /// Last budget check and the history revision it was made at
+    pub budget_report: Option<(u64, BudgetReport)>,
+    /// Full-screen guides through the hovered cell's row and column
+    pub show_crosshair: bool,
+    /// Mirror axes and radial copies applied to freehand strokes
+    pub symmetry: Symmetry,
+    /// The symmetry pivot is being dragged
+    pub symmetry_dragging: bool,
+    /// Cells painted in quick mask mode (None when the mode is off); becomes the selection on exit
+    pub quick_mask: Option<HashSet<(i32, i32)>>,
+    /// Alpha lock: painting only recolors filled cells and never fills empty ones
+    pub alpha_lock: bool,
+    /// Whether the HUD (FPS, zoom, position, status) is drawn
+    pub show_hud: bool,
+    /// HUD corner and compact single-line mode
+    pub hud_layout: HudLayout,
+    /// Active key bindings, KEYMAP unless a workspace preset replaced them
+    pub keymap: Vec<(KeyBinding, Action)>,
+    /// Painting button, wheel zoom direction and step
+    pub mouse: MouseSettings,
+    /// Keep the view within a margin of the drawing so it can't be scrolled away and lost
+    pub clamp_view: bool,
+    /// Bounds of the filled cells, rescanned every so often for the clamp and the HUD's way back
+    pub drawing_bounds: Option<SelectionRect>,
+    /// When `drawing_bounds` was last rescanned (seconds)
+    pub drawing_bounds_time: f64,
+    /// Canvas background and grid colors
+    pub theme: Theme,
+    /// At high zoom, hide the system cursor and show the sub-cell position with a loupe
+    pub precision_cursor: bool,
+    /// F1 help overlay (key bindings, tools, version)
+    pub show_help: bool,
+    /// How far the help sheet is scrolled, in pixels, when it doesn't fit the window
+    pub help_scroll: f32,
+    /// Backtick console showing recent log lines
+    pub show_console: bool,
+    /// Index of the open menu-bar menu, if any
+    pub open_menu: Option<usize>,
+    /// Open Recent screen contents while it is shown
+    pub open_recent: Option<Vec<RecentProject>>,
+    /// Open / Save As picker while it is shown
+    pub file_picker: Option<FilePicker>,
+    /// Named groups of cells, listed in the groups gutter
+    pub groups: Vec<Group>,
+    /// Id handed to the next group created
+    pub next_group_id: u32,
+    /// Reverse lookup: which group a cell belongs to
+    pub group_index: HashMap<(i32, i32), u32>,
+    /// Group whose cells exactly match the current selection
+    pub selected_group_id: Option<u32>,
+    /// Tagged groups being played as animation frames, if playing
+    pub frame_playback: Option<FramePlayback>,
+    /// Cells captured as the "before" side of snapshot diffs
+    pub diff_snapshot: Option<CellGrid>,
+    /// Differences highlighted on the canvas, when the diff view is on
+    pub diff: Option<DiffView>,
+    /// Frames per second for tagged-group playback
+    pub frame_fps: f32,
+    /// Named frame ranges, saved with the project
+    pub frame_tags: Vec<FrameTag>,
+    /// Index into `frame_tags` of the tag playback and GIF export use; None plays every frame
+    pub active_frame_tag: Option<usize>,
+    /// Whether the timeline (frame durations and tags) is shown
+    pub show_timeline: bool,
+    /// Frame tag being renamed in the list, with the text typed so far
+    pub frame_tag_rename: Option<(usize, String)>,
+    /// Width of the groups gutter in screen pixels
+    pub groups_gutter_width: f32,
+    /// Group being renamed in the gutter
+    pub group_renaming_id: Option<u32>,
+    /// Text typed so far while renaming a group
+    pub group_rename_buffer: String,
+    /// Last clicked gutter entry and when, for double-click detection
+    pub group_last_click_id: Option<u32>,
+    pub group_last_click_time: f64,
+    /// Group whose right-click context menu is open, and where
+    pub group_context_target: Option<u32>,
+    pub group_context_pos: Vec2,
+    /// Last canvas cell clicked in Select mode and when, for double-click detection
+    pub canvas_last_click: Option<((i32, i32), f64)>,
+    /// Fixed document size: cells outside these bounds are strays that Trim removes
+    pub document_bounds: Option<SelectionRect>,
+    /// Stray cell navigator while it is open
+    pub strays: Option<StrayNavigator>,
+    /// UI rects registered this frame, for canvas hit-testing
+    pub ui_regions: UiRegions,
+    /// Confirmation prompt currently shown, if any
+    pub confirm: Option<Confirm>,
     /// Current palette mode (Basic or Extended)
     pub palette_mode: PaletteMode,
     /// Current palette page index
     pub palette_page: usize,
+    /// Whether the 1:1 preview panel is visible
+    pub show_preview: bool,
+    /// Preview panel scale in screen pixels per cell (1 or 2)
+    pub preview_scale: u8,
+    /// Whether live view mirroring to an image file is active
+    pub mirror_enabled: bool,
+    /// Whether the PNG export dialog is open
+    pub show_export_dialog: bool,
+    /// Options used by the PNG exporter
+    pub export: ExportSettings,
+    /// Import dialog visibility (Ctrl+I)
+    pub show_import_dialog: bool,
+    pub import: ImportSettings,
+    /// Export region drag: start and current end cell
+    pub export_region_drag: Option<((i32, i32), (i32, i32))>,
+    /// Mode to return to once the region export finishes
+    pub export_region_previous_mode: Option<Mode>,
+    /// User-built palette (e.g. generated ramps)
+    pub custom_palette: Vec<Rgba>,
+    /// Number of steps produced by the ramp generator
+    pub ramp_steps: usize,
 }
 
 impl ApplicationState {
@@ -135,22 +428,195 @@ impl ApplicationState {
         ApplicationState {
             mode: Mode::Paint,
             show_palette: false,
-            current_color: BLUE,
-            cells: CellGrid::new(),
+            show_harmony: false,
+            current_color: Rgba::BLUE,
+            secondary_color: Rgba::WHITE,
+            cells: Default::default(),
+            cell_aspect: CellAspect::SQUARE,
+            grid: GridSettings::DEFAULT,
+            show_canvas_settings: false,
+            shift_canvas: None,
+            go_to_cell: None,
+            go_to_marker: true,
+            cell_marker: None,
             camera: AppCamera::new(),
-            palette_position: Vec2::new(10.0, 50.0),
+            palette_position: Vec2::new(60.0, 34.0),
             palette_dragging: false,
             palette_drag_offset: Vec2::ZERO,
             pan_drag_start_screen: None,
             pan_drag_start_origin: None,
+            pan_last_mouse: None,
+            pan_velocity: Vec2::ZERO,
             temp_pan_active: false,
             temp_pan_previous_mode: None,
+            space_pan_active: false,
+            space_pan_paused_stroke: false,
+            selection_transform: None,
+            transform_drag: None,
             selection: SelectionState::new(),
+            selection_preview: None,
             last_painted_cell: None,
+            erase_rect_drag: None,
+            stroke_changes: Vec::new(),
+            pen_anchors: Vec::new(),
+            pen_fill: false,
+            line_drag: None,
+            rectangle_drag: None,
+            rectangle_fill: false,
+            curve: None,
+            curve_drag: None,
+            curve_cubic: true,
+            curve_steps: 24,
+            fill_tolerance: 0.0,
+            fill_global: false,
+            fill_tolerance_dragging: false,
+            dither_drag: None,
+            autotile: None,
+            autotile_stroke: None,
+            pending_generator: None,
+            generator_drag: None,
+            generator_rng: Rng::new(GENERATOR_SEED),
+            spray_radius: 4,
+            spray_density: 0.05,
+            spray_rng: Rng::new(SPRAY_SEED),
             clipboard: Clipboard::empty(),
+            pending_paste: false,
+            paste_merge: PasteMerge::Replace,
+            feather: 0,
             history: History::new(50),
+            project_path: DEFAULT_PROJECT_PATH.to_string(),
+            metadata: ProjectMetadata::new(macroquad::miniquad::date::now()),
+            show_metadata: false,
+            metadata_edit: None,
+            saved_selections: Vec::new(),
+            show_saved_selections: false,
+            saved_selection_rename: None,
+            save_history: false,
+            log_actions: false,
+            collab_address: std::env::var("TINY_NEO_SPACE_SERVER").unwrap_or_else(|_| "127.0.0.1".to_string()),
+            collab: None,
+            chat_open: false,
+            chat_input: String::new(),
+            status_message: None,
+            backup: BackupSettings::DEFAULT,
+            last_backup_time: 0.0,
+            backed_up_revision: 0,
+            restoring_backup_of: None,
+            pending_job: None,
+            io_tasks: Vec::new(),
+            queued_saves: Vec::new(),
+            show_budget: false,
+            budget: SpriteBudget::GBA_SPRITE,
+            budget_report: None,
+            show_crosshair: false,
+            symmetry: Symmetry::OFF,
+            symmetry_dragging: false,
+            quick_mask: None,
+            alpha_lock: false,
+            show_hud: true,
+            hud_layout: HudLayout::new(),
+            keymap: KEYMAP.to_vec(),
+            mouse: MouseSettings::default(),
+            clamp_view: false,
+            drawing_bounds: None,
+            drawing_bounds_time: f64::NEG_INFINITY,
+            theme: Theme::light(),
+            precision_cursor: false,
+            show_help: false,
+            help_scroll: 0.0,
+            show_console: false,
+            open_menu: None,
+            open_recent: None,
+            file_picker: None,
+            groups: Vec::new(),
+            next_group_id: 1,
+            group_index: HashMap::new(),
+            selected_group_id: None,
+            frame_playback: None,
+            diff_snapshot: None,
+            diff: None,
+            frame_fps: DEFAULT_FRAME_FPS,
+            frame_tags: Vec::new(),
+            active_frame_tag: None,
+            show_timeline: false,
+            frame_tag_rename: None,
+            groups_gutter_width: 160.0,
+            group_renaming_id: None,
+            group_rename_buffer: String::new(),
+            group_last_click_id: None,
+            group_last_click_time: 0.0,
+            group_context_target: None,
+            group_context_pos: Vec2::ZERO,
+            canvas_last_click: None,
+            document_bounds: None,
+            strays: None,
+            ui_regions: UiRegions::default(),
+            confirm: None,
             palette_mode: PaletteMode::Basic,
             palette_page: 0,
+            show_preview: false,
+            preview_scale: 1,
+            mirror_enabled: false,
+            show_export_dialog: false,
+            show_import_dialog: false,
+            import: ImportSettings::new(),
+            export: ExportSettings::new(),
+            export_region_drag: None,
+            export_region_previous_mode: None,
+            custom_palette: Vec::new(),
+            ramp_steps: 5,
+        }
+    }
+
+    /// Change the document cell shape and keep the camera mapping in sync
+    pub fn set_cell_aspect(&mut self, aspect: CellAspect) {
+        self.cell_aspect = aspect;
+        self.camera.aspect = aspect.ratio();
+    }
+
+    /// Change the document grid settings and keep the camera's cell size in sync
+    pub fn set_grid(&mut self, grid: GridSettings) {
+        self.grid = grid;
+        self.camera.base_cell_pixels = grid.cell_pixels as f32;
+    }
+
+    /// World → screen mapping for overlays (the camera today; any future view transform
+    /// lives behind this)
+    pub fn view(&self) -> &impl ViewTransform {
+        &self.camera
+    }
+
+    /// Whether keys are text right now: a group name, chat line, import path, file name,
+    /// metadata field, selection or tag name, or cell is being typed
+    pub fn is_typing(&self) -> bool {
+        self.group_renaming_id.is_some()
+            || self.chat_open
+            || self.go_to_cell.is_some()
+            || self.show_import_dialog
+            || self.file_picker.is_some()
+            || self.metadata_edit.is_some()
+            || self.saved_selection_rename.is_some()
+            || self.frame_tag_rename.is_some()
+    }
+
+    /// The drawing checked against `budget`, rescanned only after edits or a budget change
+    pub fn budget_report(&mut self) -> BudgetReport {
+        match self.budget_report {
+            Some((revision, report)) if revision == self.history.revision && report.budget == self.budget => report,
+            _ => {
+                let report = check_budget(&self.cells, self.budget);
+                self.budget_report = Some((self.history.revision, report));
+                report
+            }
+        }
+    }
+
+    /// Colors of the palette page currently chosen in the palette window
+    pub fn active_palette(&self) -> Vec<Rgba> {
+        match self.palette_mode {
+            PaletteMode::Basic => GBA_PALETTE.iter().flatten().copied().collect(),
+            PaletteMode::Extended => generate_gba_extended_palette(),
+            PaletteMode::Custom => self.custom_palette.clone(),
         }
     }
 }
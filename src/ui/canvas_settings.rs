@@ -0,0 +1,75 @@
+use macroquad::prelude::*;
+use crate::core::cell::GridSettings;
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+
+const DIALOG_WIDTH: f32 = 260.0;
+const DIALOG_HEIGHT: f32 = 150.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+/// Cell size change per -/+ click
+const CELL_PIXELS_STEP: u32 = 4;
+
+/// Per-document canvas settings: cell size, major grid spacing and cell shape.
+/// All of them are saved with the project
+/// Returns true if the mouse is over the dialog
+pub fn render_canvas_settings(state: &mut ApplicationState) -> bool {
+    if !state.show_canvas_settings {
+        return false;
+    }
+
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - DIALOG_HEIGHT) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Canvas Settings", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Row 1: cell size at 100% zoom
+    let row_y = body_y + 6.0;
+    let mut grid = state.grid;
+    draw_text(&format!("Cell size {}px", grid.cell_pixels), x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button("-", x + 150.0, row_y, 30.0) {
+        grid.cell_pixels = grid.cell_pixels.saturating_sub(CELL_PIXELS_STEP).max(GridSettings::MIN_CELL_PIXELS);
+    }
+    if dialog_button("+", x + 185.0, row_y, 30.0) {
+        grid.cell_pixels = (grid.cell_pixels + CELL_PIXELS_STEP).min(GridSettings::MAX_CELL_PIXELS);
+    }
+
+    // Row 2: major grid lines (click to cycle)
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("Major lines", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    let major_label = match grid.major_every {
+        0 => "Off".to_string(),
+        n => format!("Every {}", n),
+    };
+    if dialog_button(&major_label, x + 150.0, row_y, 100.0) {
+        grid.major_every = grid.next_major();
+    }
+    if grid != state.grid {
+        state.set_grid(grid);
+    }
+
+    // Row 3: cell shape (click to cycle)
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("Cell shape", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    let aspect = state.cell_aspect;
+    if dialog_button(&format!("{}:{}", aspect.w, aspect.h), x + 150.0, row_y, 60.0) {
+        state.set_cell_aspect(aspect.next_preset());
+    }
+
+    // Row 4: close
+    let row_y = row_y + ROW_HEIGHT;
+    if dialog_button("Close", x + 8.0, row_y, 64.0) || is_key_pressed(KeyCode::Escape) {
+        state.show_canvas_settings = false;
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, DIALOG_HEIGHT).contains(mouse_pos)
+}
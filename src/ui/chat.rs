@@ -0,0 +1,68 @@
+use macroquad::prelude::*;
+use crate::collab::protocol::MAX_CHAT_CHARS;
+use crate::state::ApplicationState;
+
+const PANEL_WIDTH: f32 = 320.0;
+const LINE_HEIGHT: f32 = 18.0;
+/// Chat lines visible at once (the session keeps more)
+const VISIBLE_LINES: usize = 8;
+
+/// Chat log in the bottom-right corner while in a shared session, with an input line
+/// while typing (T opens it, Enter sends, Escape closes); registers its rect for hit-testing
+pub fn render_chat_overlay(state: &mut ApplicationState) {
+    let Some(session) = &state.collab else {
+        return;
+    };
+    if session.chat.is_empty() && !state.chat_open {
+        return;
+    }
+
+    let right_margin = if state.groups.is_empty() { 10.0 } else { state.groups_gutter_width + 10.0 };
+    let lines = session.chat.len().min(VISIBLE_LINES);
+    let input_h = if state.chat_open { LINE_HEIGHT + 6.0 } else { 0.0 };
+    let h = lines as f32 * LINE_HEIGHT + input_h + 8.0;
+    let x = screen_width() - PANEL_WIDTH - right_margin;
+    let y = screen_height() - h - 10.0;
+
+    draw_rectangle(x, y, PANEL_WIDTH, h, Color::from_rgba(250, 250, 250, 210));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, h, 1.0, GRAY);
+    let mut line_y = y + 4.0 + LINE_HEIGHT - 5.0;
+    for line in &session.chat[session.chat.len() - lines..] {
+        draw_text(&format!("{}: {}", line.name, line.text), x + 6.0, line_y, 16.0, BLACK);
+        line_y += LINE_HEIGHT;
+    }
+
+    if state.chat_open {
+        let input_y = y + h - input_h - 2.0;
+        draw_rectangle(x + 4.0, input_y, PANEL_WIDTH - 8.0, input_h - 2.0, WHITE);
+        draw_rectangle_lines(x + 4.0, input_y, PANEL_WIDTH - 8.0, input_h - 2.0, 1.0, DARKGRAY);
+        draw_text(&format!("{}> {}_", session.name, state.chat_input), x + 8.0, input_y + 16.0, 16.0, BLACK);
+        handle_chat_typing(state);
+    }
+
+    state.ui_regions.push(Rect::new(x, y, PANEL_WIDTH, h));
+}
+
+/// Type into the chat line; Enter sends, Escape discards
+fn handle_chat_typing(state: &mut ApplicationState) {
+    while let Some(ch) = get_char_pressed() {
+        if !ch.is_control() && state.chat_input.chars().count() < MAX_CHAT_CHARS {
+            state.chat_input.push(ch);
+        }
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        state.chat_input.pop();
+    }
+    if is_key_pressed(KeyCode::Escape) {
+        state.chat_open = false;
+        state.chat_input.clear();
+    } else if is_key_pressed(KeyCode::Enter) {
+        let text = std::mem::take(&mut state.chat_input);
+        state.chat_open = false;
+        if let (Some(session), false) = (&mut state.collab, text.trim().is_empty()) {
+            if let Err(e) = session.send_chat(text) {
+                state.status_message = Some(format!("Chat failed: {}", e));
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use macroquad::prelude::*;
+use crate::input::ui::draw_button;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::focus;
+
+const PANEL_WIDTH: f32 = 420.0;
+const PANEL_HEIGHT: f32 = 110.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+
+/// Modal yes/no prompt for destructive commands
+/// Enter or the confirm button runs the pending operation; Esc or Cancel drops it
+pub fn render_confirm_dialog(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> bool {
+    let Some(confirm) = &state.confirm else {
+        return false;
+    };
+
+    let x = ((screen_width() - PANEL_WIDTH) / 2.0).max(0.0);
+    let y = ((screen_height() - PANEL_HEIGHT) / 2.0).max(0.0);
+
+    // Dim the canvas behind the prompt
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 120));
+
+    // Title bar
+    draw_rectangle(x, y, PANEL_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(150, 80, 80, 255));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Are you sure?", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, PANEL_WIDTH, PANEL_HEIGHT - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, PANEL_WIDTH, PANEL_HEIGHT - TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text(&confirm.message, x + 10.0, body_y + 22.0, 16.0, BLACK);
+
+    let button_y = body_y + PANEL_HEIGHT - TITLE_BAR_HEIGHT - 40.0;
+    let accepted = draw_button(confirm.confirm_label, x + PANEL_WIDTH - 220.0, button_y, 100.0, 30.0, false)
+        || focus::enter_pressed();
+    let cancelled = draw_button("Cancel", x + PANEL_WIDTH - 110.0, button_y, 100.0, 30.0, false)
+        || is_key_pressed(KeyCode::Escape);
+
+    if accepted {
+        if let Some(confirm) = state.confirm.take() {
+            (confirm.on_confirm)(state, canvas);
+        }
+    } else if cancelled {
+        state.confirm = None;
+    }
+
+    true
+}
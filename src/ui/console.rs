@@ -0,0 +1,39 @@
+use macroquad::prelude::*;
+use log::Level;
+use crate::logging::with_recent_lines;
+use crate::state::ApplicationState;
+
+const LINE_HEIGHT: f32 = 16.0;
+/// Share of the window height the console covers, from the top
+const CONSOLE_HEIGHT_FRACTION: f32 = 0.4;
+
+fn level_color(level: Level) -> Color {
+    match level {
+        Level::Error => Color::from_rgba(255, 110, 110, 255),
+        Level::Warn => Color::from_rgba(250, 200, 90, 255),
+        Level::Info => Color::from_rgba(220, 220, 220, 255),
+        Level::Debug | Level::Trace => Color::from_rgba(150, 150, 150, 255),
+    }
+}
+
+/// Backtick console: the most recent log lines over the top of the window, newest at the bottom
+/// Returns whether the mouse is over it
+pub fn render_console(state: &ApplicationState) -> bool {
+    if !state.show_console {
+        return false;
+    }
+    let height = (screen_height() * CONSOLE_HEIGHT_FRACTION).floor();
+    draw_rectangle(0.0, 0.0, screen_width(), height, Color::from_rgba(20, 20, 24, 225));
+    draw_line(0.0, height, screen_width(), height, 1.0, GRAY);
+
+    let rows = ((height - 8.0) / LINE_HEIGHT).floor().max(0.0) as usize;
+    with_recent_lines(rows, |lines| {
+        for (i, line) in lines.enumerate() {
+            let y = (i as f32 + 1.0) * LINE_HEIGHT;
+            let text = format!("{:>8.2} {:<5} {}", line.time, line.level, line.text);
+            draw_text(&text, 8.0, y, 16.0, level_color(line.level));
+        }
+    });
+
+    mouse_position().1 < height
+}
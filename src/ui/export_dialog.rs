@@ -0,0 +1,159 @@
+use macroquad::prelude::*;
+use crate::core::cell::grid_bounds;
+use crate::core::raster::MAX_RASTER_PIXELS;
+use crate::export::png::{MAX_EXPORT_SCALE, MIN_EXPORT_SCALE};
+use crate::export::print_size::{fit_scale, inches_to_cm, pixels_to_inches, DPI_OPTIONS, PRINT_PRESETS};
+use crate::input::jobs::start_export;
+use crate::state::{ApplicationState, Mode};
+use crate::ui::focus;
+
+const DIALOG_WIDTH: f32 = 240.0;
+const DIALOG_HEIGHT: f32 = 334.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+
+/// Small modal-style window for image export options (toggled with Ctrl+E)
+/// Returns true if the mouse is over the dialog
+pub fn render_export_dialog(state: &mut ApplicationState) -> bool {
+    if !state.show_export_dialog {
+        return false;
+    }
+
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - DIALOG_HEIGHT) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Export Image", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Row 1: file format (click to cycle); the path's extension follows it
+    let row_y = body_y + 6.0;
+    draw_text("Format", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(state.export.format.label(), x + 110.0, row_y, 60.0) {
+        let format = state.export.format.next();
+        state.export.set_format(format);
+    }
+
+    // Row 2: background (click to cycle)
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("Background", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(state.export.background.label(), x + 110.0, row_y, 120.0) {
+        state.export.background = state.export.background.next();
+    }
+
+    // Row 3: scale
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text(&format!("Scale {}x", state.export.scale), x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button("-", x + 110.0, row_y, 30.0) {
+        state.export.scale = (state.export.scale - 1).max(MIN_EXPORT_SCALE);
+    }
+    if dialog_button("+", x + 145.0, row_y, 30.0) {
+        state.export.scale = (state.export.scale + 1).min(MAX_EXPORT_SCALE);
+    }
+
+    // Row 4: smoothing filter (click to cycle); multiplies the scale
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("Smoothing", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(state.export.filter.label(), x + 110.0, row_y, 80.0) {
+        state.export.filter = state.export.filter.next();
+    }
+
+    // Row 5: grid lines
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("Grid lines", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    let grid_label = if state.export.grid_lines { "On" } else { "Off" };
+    if dialog_button(grid_label, x + 110.0, row_y, 50.0) {
+        state.export.grid_lines = !state.export.grid_lines;
+    }
+
+    // Row 6: DPI (click to cycle)
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("DPI", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(&state.export.dpi.to_string(), x + 110.0, row_y, 50.0) {
+        let i = DPI_OPTIONS.iter().position(|&d| d == state.export.dpi).unwrap_or(0);
+        state.export.dpi = DPI_OPTIONS[(i + 1) % DPI_OPTIONS.len()];
+    }
+
+    // Row 7: print preset; clicking fits the scale to the next preset
+    let row_y = row_y + ROW_HEIGHT;
+    let bounds = grid_bounds(&state.cells);
+    draw_text("Fit to", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(PRINT_PRESETS[state.export.preset].name, x + 110.0, row_y, 120.0) {
+        state.export.preset = (state.export.preset + 1) % PRINT_PRESETS.len();
+        if let Some(rect) = bounds {
+            let preset = &PRINT_PRESETS[state.export.preset];
+            let aspect = state.cell_aspect;
+            let factor = state.export.filter.factor();
+            let (w, h) = (rect.width() as u32, rect.height() as u32);
+            match fit_scale(w.saturating_mul(aspect.w * factor), h.saturating_mul(aspect.h * factor), state.export.dpi, preset) {
+                Some(scale) => state.export.scale = scale.clamp(MIN_EXPORT_SCALE, MAX_EXPORT_SCALE),
+                None => state.export.status = Some(format!("Too large for {}", preset.name)),
+            }
+        }
+    }
+
+    // Rows 8 and 9: image and physical size of the drawing's bounding box
+    let options = state.export.raster_options(state.secondary_color, state.cell_aspect);
+    let size = bounds.map(|rect| options.output_size(&rect));
+    let (pixels, physical) = match size {
+        Some(Some((w, h))) => {
+            let (w_in, h_in) = (pixels_to_inches(w, state.export.dpi), pixels_to_inches(h, state.export.dpi));
+            (format!("{} x {} px", w, h), format!("{:.2} x {:.2} in ({:.1} x {:.1} cm)", w_in, h_in, inches_to_cm(w_in), inches_to_cm(h_in)))
+        }
+        Some(None) => (format!("Over {} megapixels", MAX_RASTER_PIXELS >> 20), "Lower the scale to export".to_string()),
+        None => ("Empty drawing".to_string(), String::new()),
+    };
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text(&pixels, x + 8.0, row_y + 17.0, 16.0, BLACK);
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text(&physical, x + 8.0, row_y + 17.0, 16.0, BLACK);
+
+    // Row 10: actions
+    let row_y = row_y + ROW_HEIGHT;
+    if dialog_button("Export", x + 8.0, row_y, 80.0) {
+        match grid_bounds(&state.cells) {
+            Some(rect) => start_export(state, rect, state.export.path.clone(), false),
+            None => state.export.status = Some("Nothing to export".to_string()),
+        }
+    }
+    if dialog_button("Region", x + 96.0, row_y, 64.0) {
+        // Hide the dialog and let the user drag the region on the canvas
+        state.show_export_dialog = false;
+        state.export_region_previous_mode = Some(state.mode.clone());
+        state.mode = Mode::ExportRegion;
+    }
+    if dialog_button("Close", x + 168.0, row_y, 64.0) {
+        state.show_export_dialog = false;
+    }
+
+    // Status line
+    if let Some(status) = &state.export.status {
+        draw_text(status, x + 8.0, row_y + ROW_HEIGHT + 16.0, 14.0, DARKGRAY);
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, DIALOG_HEIGHT).contains(mouse_pos)
+}
+
+/// Draw a dialog button and report whether it was clicked or activated from the keyboard
+pub fn dialog_button(label: &str, x: f32, y: f32, w: f32) -> bool {
+    let h = ROW_HEIGHT - 4.0;
+    let rect = Rect::new(x, y, w, h);
+    let hovered = rect.contains(Vec2::from(mouse_position()));
+    let color = if hovered {
+        Color::from_rgba(200, 200, 210, 255)
+    } else {
+        Color::from_rgba(180, 180, 180, 255)
+    };
+    draw_rectangle(x, y, w, h, color);
+    draw_rectangle_lines(x, y, w, h, 2.0, BLACK);
+    let text_size = measure_text(label, None, 14, 1.0);
+    draw_text(label, x + (w - text_size.width) / 2.0, y + (h + text_size.height) / 2.0, 14.0, BLACK);
+    focus::pressed(rect)
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+use macroquad::prelude::*;
+use crate::input::jobs::{start_save, Job};
+use crate::project::format::{PROJECT_EXTENSION, TEXT_PROJECT_EXTENSION};
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+use crate::ui::focus;
+
+const DIALOG_WIDTH: f32 = 480.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+/// Directory entries shown at once; the rest are reached by typing
+const MAX_LISTED: usize = 10;
+
+/// Open or Save As: a typed path plus the folders and projects beside it
+pub struct FilePicker {
+    /// Save As rather than Open
+    pub saving: bool,
+    pub path: String,
+    /// Folder the listing was read from, so it's re-read when the path moves elsewhere
+    listed_dir: Option<String>,
+    /// Sub-folders (with a trailing '/') then project files, by name
+    entries: Vec<String>,
+}
+
+impl FilePicker {
+    pub fn new(saving: bool, path: &str) -> Self {
+        Self { saving, path: path.to_string(), listed_dir: None, entries: Vec::new() }
+    }
+
+    /// Folder part of the typed path; "." when there is none
+    fn dir(&self) -> String {
+        if self.path.ends_with('/') {
+            return self.path.clone();
+        }
+        match Path::new(&self.path).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().into_owned(),
+            _ => ".".to_string(),
+        }
+    }
+
+    /// Re-read the listing if the typed path now points into another folder
+    fn refresh(&mut self) {
+        let dir = self.dir();
+        if self.listed_dir.as_deref() == Some(dir.as_str()) {
+            return;
+        }
+        let is_project = |name: &str| {
+            let extension = Path::new(name).extension().and_then(|e| e.to_str()).unwrap_or_default().to_ascii_lowercase();
+            extension == PROJECT_EXTENSION || extension == TEXT_PROJECT_EXTENSION
+        };
+        let (mut folders, mut files) = (Vec::new(), Vec::new());
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                folders.push(format!("{}/", name));
+            } else if is_project(&name) {
+                files.push(name);
+            }
+        }
+        folders.sort();
+        files.sort();
+        self.entries = std::iter::once("../".to_string()).chain(folders).chain(files).collect();
+        self.listed_dir = Some(dir);
+    }
+
+    /// Path after clicking `entry`: a folder keeps the typed file name, a file replaces it
+    fn choose(&self, entry: &str) -> String {
+        let dir = Path::new(&self.dir()).join(entry.trim_end_matches('/'));
+        if entry.ends_with('/') {
+            let name = Path::new(&self.path).file_name().filter(|_| !self.path.ends_with('/'));
+            match name {
+                Some(name) => dir.join(name).to_string_lossy().into_owned(),
+                None => format!("{}/", dir.to_string_lossy()),
+            }
+        } else {
+            dir.to_string_lossy().into_owned()
+        }
+    }
+}
+
+/// File picker for Open (Ctrl+O) and Save As (Ctrl+Shift+S): type a path or click a folder or
+/// project beside it; Enter or the button opens/saves, Escape or Cancel closes
+/// Modal while shown: returns true so the canvas ignores the mouse
+pub fn render_file_picker(state: &mut ApplicationState) -> bool {
+    let Some(picker) = &mut state.file_picker else {
+        return false;
+    };
+    picker.refresh();
+
+    let listed = picker.entries.len().min(MAX_LISTED);
+    let height = TITLE_BAR_HEIGHT + 12.0 + (listed as f32 + 2.0) * ROW_HEIGHT;
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - height) / 2.0;
+
+    // Dim the canvas behind the picker
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 120));
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text(if picker.saving { "Save Project As" } else { "Open Project" }, x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, height - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, height - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Path field (always focused while the picker is open)
+    let mut row_y = body_y + 6.0;
+    draw_text("File", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    draw_rectangle(x + 50.0, row_y, DIALOG_WIDTH - 58.0, ROW_HEIGHT - 4.0, WHITE);
+    draw_rectangle_lines(x + 50.0, row_y, DIALOG_WIDTH - 58.0, ROW_HEIGHT - 4.0, 1.0, DARKGRAY);
+    draw_text(&format!("{}_", picker.path), x + 54.0, row_y + 17.0, 16.0, BLACK);
+    row_y += ROW_HEIGHT;
+
+    // Folders and projects beside the typed path
+    let mut chosen = None;
+    for entry in picker.entries.iter().take(MAX_LISTED) {
+        if dialog_button(entry, x + 50.0, row_y, DIALOG_WIDTH - 58.0) {
+            chosen = Some(picker.choose(entry));
+        }
+        row_y += ROW_HEIGHT;
+    }
+    if let Some(path) = chosen {
+        picker.path = path;
+    }
+
+    // Actions
+    let confirmed = dialog_button(if picker.saving { "Save" } else { "Open" }, x + 8.0, row_y, 80.0) || focus::enter_pressed();
+    let cancelled = dialog_button("Cancel", x + 96.0, row_y, 80.0) || is_key_pressed(KeyCode::Escape);
+
+    // Typing
+    while let Some(ch) = get_char_pressed() {
+        if !ch.is_control() {
+            picker.path.push(ch);
+        }
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        picker.path.pop();
+    }
+
+    if cancelled {
+        state.file_picker = None;
+    } else if confirmed {
+        confirm(state);
+    }
+    true
+}
+
+/// Open the typed project, or save to it (as .tnsp when no extension was typed)
+fn confirm(state: &mut ApplicationState) {
+    let Some(picker) = state.file_picker.take() else {
+        return;
+    };
+    let path = picker.path.trim().to_string();
+    if path.is_empty() || path.ends_with('/') {
+        state.status_message = Some("Type a file name first".to_string());
+        state.file_picker = Some(picker);
+        return;
+    }
+    if picker.saving {
+        let path = match Path::new(&path).extension() {
+            Some(_) => path,
+            None => format!("{}.{}", path, PROJECT_EXTENSION),
+        };
+        state.project_path = path.clone();
+        start_save(state, path);
+    } else {
+        state.pending_job = Some(Job::OpenProject(path));
+    }
+}
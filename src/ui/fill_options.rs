@@ -0,0 +1,56 @@
+use macroquad::prelude::*;
+use crate::core::fill::MAX_FILL_TOLERANCE;
+use crate::state::{ApplicationState, Mode};
+use crate::ui::focus;
+use crate::ui::menu_bar::MENU_BAR_HEIGHT;
+
+const PANEL_WIDTH: f32 = 360.0;
+const PANEL_HEIGHT: f32 = 32.0;
+const TRACK_X: f32 = 90.0;
+const TRACK_WIDTH: f32 = 140.0;
+
+/// Fill tool options strip (tolerance slider and contiguous/global switch), shown
+/// below the menu bar while the fill tool is active; registers its rect for hit-testing
+pub fn render_fill_options(state: &mut ApplicationState) {
+    if state.mode != Mode::Fill {
+        return;
+    }
+
+    let x = ((screen_width() - PANEL_WIDTH) / 2.0).max(0.0);
+    let y = MENU_BAR_HEIGHT + 6.0;
+    let mouse_pos = Vec2::from(mouse_position());
+    let panel = Rect::new(x, y, PANEL_WIDTH, PANEL_HEIGHT);
+    draw_rectangle(x, y, PANEL_WIDTH, PANEL_HEIGHT, Color::from_rgba(230, 230, 230, 240));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, PANEL_HEIGHT, 2.0, BLACK);
+
+    // Tolerance slider: press on the track (or keep dragging) to set it
+    draw_text("Tolerance", x + 8.0, y + 21.0, 16.0, BLACK);
+    let track = Rect::new(x + TRACK_X, y + 10.0, TRACK_WIDTH, 12.0);
+    if is_mouse_button_pressed(MouseButton::Left) && track.contains(mouse_pos) {
+        state.fill_tolerance_dragging = true;
+    }
+    if !is_mouse_button_down(MouseButton::Left) {
+        state.fill_tolerance_dragging = false;
+    }
+    if state.fill_tolerance_dragging {
+        let t = ((mouse_pos.x - track.x) / track.w).clamp(0.0, 1.0);
+        state.fill_tolerance = (t * MAX_FILL_TOLERANCE).round();
+    }
+    draw_rectangle(track.x, track.y + 4.0, track.w, 4.0, DARKGRAY);
+    let knob_x = track.x + state.fill_tolerance / MAX_FILL_TOLERANCE * track.w;
+    draw_rectangle(knob_x - 4.0, track.y, 8.0, track.h, Color::from_rgba(80, 80, 150, 255));
+    draw_text(&format!("{}", state.fill_tolerance as i32), track.right() + 8.0, y + 21.0, 16.0, BLACK);
+
+    // Contiguous / global switch
+    let label = if state.fill_global { "Global" } else { "Contiguous" };
+    let button = Rect::new(x + PANEL_WIDTH - 96.0, y + 4.0, 88.0, PANEL_HEIGHT - 8.0);
+    draw_rectangle(button.x, button.y, button.w, button.h, Color::from_rgba(180, 180, 180, 255));
+    draw_rectangle_lines(button.x, button.y, button.w, button.h, 2.0, BLACK);
+    let size = measure_text(label, None, 14, 1.0);
+    draw_text(label, button.x + (button.w - size.width) / 2.0, button.y + (button.h + size.height) / 2.0, 14.0, BLACK);
+    if focus::pressed(button) {
+        state.fill_global = !state.fill_global;
+    }
+
+    state.ui_regions.push(panel);
+}
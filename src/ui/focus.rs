@@ -0,0 +1,97 @@
+//! Keyboard focus for the widget toolkit: every button registers itself as it's drawn, in
+//! draw order, and Tab / Shift+Tab step through them (the arrow keys too once something has
+//! focus). Enter activates the focused widget, Escape drops focus. The list is rebuilt each
+//! frame, so opening or closing a dialog just adds or removes stops
+
+use std::sync::Mutex;
+use macroquad::prelude::*;
+use crate::state::ApplicationState;
+
+/// Keys the focus ring takes over while a widget has focus
+pub const NAVIGATION_KEYS: [KeyCode; 6] = [KeyCode::Tab, KeyCode::Enter, KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right];
+
+const RING_COLOR: Color = Color::new(1.0, 0.55, 0.0, 1.0);
+const RING_THICKNESS: f32 = 2.0;
+/// Gap between a widget's edge and its ring
+const RING_OFFSET: f32 = 2.0;
+
+struct FocusState {
+    /// Draw-order index of the focused widget
+    focused: Option<usize>,
+    /// Widgets registered so far this frame
+    registered: usize,
+    /// Widgets registered last frame, the range Tab cycles over
+    last_count: usize,
+    /// Enter was pressed this frame with a widget focused
+    activate: bool,
+}
+
+static FOCUS: Mutex<FocusState> = Mutex::new(FocusState { focused: None, registered: 0, last_count: 0, activate: false });
+
+/// Read this frame's navigation keys; call once before any UI is drawn
+/// Text entry and a pending paste (whose Tab cycles the merge mode) keep the keys and drop focus
+pub fn begin_frame(state: &ApplicationState) {
+    let Ok(mut focus) = FOCUS.lock() else {
+        return;
+    };
+    focus.last_count = focus.registered;
+    focus.registered = 0;
+    focus.activate = false;
+
+    if state.is_typing() || state.pending_paste || focus.last_count == 0 {
+        focus.focused = None;
+        return;
+    }
+    let count = focus.last_count;
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    let step_back = (is_key_pressed(KeyCode::Tab) && shift) || (focus.focused.is_some() && (is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::Left)));
+    let step_forward = (is_key_pressed(KeyCode::Tab) && !shift) || (focus.focused.is_some() && (is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::Right)));
+
+    focus.focused = match focus.focused.map(|index| index.min(count - 1)) {
+        _ if is_key_pressed(KeyCode::Escape) => None,
+        None if step_forward => Some(0),
+        None if step_back => Some(count - 1),
+        Some(index) if step_forward => Some((index + 1) % count),
+        Some(index) if step_back => Some((index + count - 1) % count),
+        current => current,
+    };
+    focus.activate = focus.focused.is_some() && is_key_pressed(KeyCode::Enter);
+}
+
+/// True while a widget holds keyboard focus
+pub fn is_active() -> bool {
+    FOCUS.lock().is_ok_and(|focus| focus.focused.is_some())
+}
+
+/// Enter pressed with no widget focused, so it belongs to the dialog's default action
+pub fn enter_pressed() -> bool {
+    is_key_pressed(KeyCode::Enter) && !is_active()
+}
+
+/// Register a focusable widget drawn at `rect`; draws the focus ring when it has focus
+/// Returns true when Enter activates it
+pub fn focusable(rect: Rect) -> bool {
+    let Ok(mut focus) = FOCUS.lock() else {
+        return false;
+    };
+    let index = focus.registered;
+    focus.registered += 1;
+    if focus.focused != Some(index) {
+        return false;
+    }
+    draw_rectangle_lines(
+        rect.x - RING_OFFSET,
+        rect.y - RING_OFFSET,
+        rect.w + RING_OFFSET * 2.0,
+        rect.h + RING_OFFSET * 2.0,
+        RING_THICKNESS,
+        RING_COLOR,
+    );
+    focus.activate
+}
+
+/// Register a focusable widget and report whether it was clicked or activated with Enter
+pub fn pressed(rect: Rect) -> bool {
+    let clicked = is_mouse_button_pressed(MouseButton::Left) && rect.contains(Vec2::from(mouse_position()));
+    focusable(rect) || clicked
+}
@@ -0,0 +1,82 @@
+use macroquad::prelude::*;
+use crate::input::view_bounds::{go_to_cell, parse_cell};
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+
+const DIALOG_WIDTH: f32 = 260.0;
+const DIALOG_HEIGHT: f32 = 122.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+
+/// "Go to Cell" window: type x, y and press Enter or Go to center the view on that cell,
+/// dropping a short-lived marker there if Mark is on. Returns true if the mouse is over the dialog
+pub fn render_go_to_cell(state: &mut ApplicationState) -> bool {
+    let Some(text) = &state.go_to_cell else {
+        return false;
+    };
+    let target = parse_cell(text);
+
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - DIALOG_HEIGHT) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Go to Cell", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Row 1: the typed coordinates
+    let mut row_y = body_y + 6.0;
+    draw_rectangle(x + 8.0, row_y, DIALOG_WIDTH - 16.0, ROW_HEIGHT - 4.0, WHITE);
+    draw_rectangle_lines(x + 8.0, row_y, DIALOG_WIDTH - 16.0, ROW_HEIGHT - 4.0, 1.0, DARKGRAY);
+    draw_text(&format!("{}_", text), x + 12.0, row_y + 17.0, 16.0, BLACK);
+    row_y += ROW_HEIGHT;
+
+    // Row 2: what Enter will do
+    let (hint, color) = match target {
+        Some((cx, cy)) => (format!("Center on ({}, {})", cx, cy), DARKGRAY),
+        None => ("Type x, y - e.g. 12, -4".to_string(), Color::from_rgba(170, 40, 40, 255)),
+    };
+    draw_text(&hint, x + 8.0, row_y + 17.0, 16.0, color);
+    row_y += ROW_HEIGHT;
+
+    // Row 3: actions
+    let mark_label = if state.go_to_marker { "Mark: on" } else { "Mark: off" };
+    if dialog_button(mark_label, x + 8.0, row_y, 90.0) {
+        state.go_to_marker = !state.go_to_marker;
+    }
+    let go = dialog_button("Go", x + 106.0, row_y, 64.0) || is_key_pressed(KeyCode::Enter);
+    let close = dialog_button("Close", x + 178.0, row_y, 64.0) || is_key_pressed(KeyCode::Escape);
+    if go {
+        if let Some(cell) = target {
+            go_to_cell(state, cell, state.go_to_marker);
+            state.go_to_cell = None;
+        }
+    } else if close {
+        state.go_to_cell = None;
+    } else {
+        handle_typing(state);
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, DIALOG_HEIGHT).contains(mouse_pos)
+}
+
+/// Digits, signs, commas and spaces go into the field; Backspace deletes
+fn handle_typing(state: &mut ApplicationState) {
+    let Some(text) = &mut state.go_to_cell else {
+        return;
+    };
+    while let Some(ch) = get_char_pressed() {
+        if ch.is_ascii_digit() || matches!(ch, '-' | '+' | ',' | ' ' | '(' | ')') {
+            text.push(ch);
+        }
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        text.pop();
+    }
+}
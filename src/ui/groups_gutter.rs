@@ -1,10 +1,18 @@
 use macroquad::prelude::*;
 use crate::state::ApplicationState;
-use crate::rendering::CanvasRenderer;
+use crate::rendering::{CanvasRenderer, PreviewRenderer};
+use crate::ui::focus;
+use crate::ui::menu_bar::MENU_BAR_HEIGHT;
 
+/// Groups list docked to the right edge (below the preview panel when it is open)
+/// Hidden until the first group exists; returns true if the mouse is over it
 pub fn render_groups_gutter(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> bool {
-    let w = state.groups_gutter_width; let h = screen_height();
-    let x = 0.0; let y = 0.0; let mouse = Vec2::from(mouse_position());
+    if state.groups.is_empty() {
+        return false;
+    }
+    let top = if state.show_preview { PreviewRenderer::panel_rect().bottom() + 10.0 } else { MENU_BAR_HEIGHT };
+    let w = state.groups_gutter_width; let h = screen_height() - top;
+    let x = screen_width() - w; let y = top; let mouse = Vec2::from(mouse_position());
     let over = Rect::new(x, y, w, h).contains(mouse);
 
     // Panel background
@@ -18,8 +26,8 @@ pub fn render_groups_gutter(state: &mut ApplicationState, canvas: &mut CanvasRen
     for g in &state.groups.clone() { // clone for borrow ease
         let item_rect = Rect::new(x+6.0, cur_y, w-12.0, item_h);
         let is_exact = state.selected_group_id == Some(g.id);
-        let selection_cells = state.selection.current.as_ref().and_then(|sel| match &sel.kind { crate::core::selection::SelectionKind::Cells(s) => Some(s), });
-        let is_partial = selection_cells.map_or(false, |selset| selset.iter().any(|c| g.cells.contains(c)));
+        let selection_cells = state.selection.current.as_ref().map(|sel| match &sel.kind { crate::core::selection::SelectionKind::Cells(s) => s, });
+        let is_partial = selection_cells.is_some_and(|selset| selset.iter().any(|c| g.cells.contains(c)));
 
         let bg = if is_exact { Color::from_rgba(180,210,255,255) } else if is_partial { Color::from_rgba(210,225,255,255) } else { Color::from_rgba(230,230,235,255) };
         draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, bg);
@@ -31,6 +39,16 @@ pub fn render_groups_gutter(state: &mut ApplicationState, canvas: &mut CanvasRen
         if renaming { label = state.group_rename_buffer.clone(); }
         draw_text(&label, item_rect.x + 6.0, item_rect.y + 15.0, 16.0, BLACK);
 
+        // Animation frame tag (with its duration when it has its own), right-aligned
+        if let Some(frame) = g.frame {
+            let tag = match g.duration_ms {
+                Some(ms) => format!("F{} {}ms", frame, ms),
+                None => format!("F{}", frame),
+            };
+            let tag_w = measure_text(&tag, None, 14, 1.0).width;
+            draw_text(&tag, item_rect.right() - tag_w - 6.0, item_rect.y + 15.0, 14.0, DARKBLUE);
+        }
+
         // Mouse interactions
         let clicked_left = is_mouse_button_pressed(MouseButton::Left) && item_rect.contains(mouse);
         let clicked_right = is_mouse_button_pressed(MouseButton::Right) && item_rect.contains(mouse);
@@ -51,12 +69,23 @@ pub fn render_groups_gutter(state: &mut ApplicationState, canvas: &mut CanvasRen
             state.group_context_pos = mouse;
         }
 
+        // Enter on the focused row selects it, like a single click
+        if focus::focusable(item_rect) {
+            crate::input::groups::select_group(state, g.id);
+        }
+
         cur_y += item_h + 4.0;
     }
 
-    // Handle renaming commit on Enter
+    // Handle renaming: type into the buffer, commit on Enter, cancel on Escape
     if let Some(id) = state.group_renaming_id {
-        if is_key_pressed(KeyCode::Enter) {
+        while let Some(ch) = get_char_pressed() {
+            if !ch.is_control() { state.group_rename_buffer.push(ch); }
+        }
+        if is_key_pressed(KeyCode::Backspace) { state.group_rename_buffer.pop(); }
+        if is_key_pressed(KeyCode::Escape) {
+            state.group_renaming_id = None; state.group_rename_buffer.clear();
+        } else if is_key_pressed(KeyCode::Enter) {
             crate::input::groups::rename_group(state, id, state.group_rename_buffer.clone());
             state.group_renaming_id = None; state.group_rename_buffer.clear();
         }
@@ -65,18 +94,24 @@ pub fn render_groups_gutter(state: &mut ApplicationState, canvas: &mut CanvasRen
     // Context menu
     if let Some(id) = state.group_context_target {
         let px = state.group_context_pos.x; let py = state.group_context_pos.y;
-        let menu_w = 120.0; let menu_h = 48.0; let item_h = 22.0;
+        let menu_w = 120.0; let menu_h = 92.0; let item_h = 22.0;
         draw_rectangle(px, py, menu_w, menu_h, Color::from_rgba(250,250,250,255));
         draw_rectangle_lines(px, py, menu_w, menu_h, 1.0, BLACK);
         let ungroup_rect = Rect::new(px, py, menu_w, item_h);
         let delete_rect = Rect::new(px, py + item_h, menu_w, item_h);
+        let frame_rect = Rect::new(px, py + item_h * 2.0, menu_w, item_h);
+        let export_rect = Rect::new(px, py + item_h * 3.0, menu_w, item_h);
+        let is_frame = state.groups.iter().any(|g| g.id == id && g.frame.is_some());
         draw_text("Ungroup", px + 8.0, py + 15.0, 16.0, BLACK);
         draw_text("Delete", px + 8.0, py + 15.0 + item_h, 16.0, BLACK);
-        if is_mouse_button_pressed(MouseButton::Left) {
-            if ungroup_rect.contains(mouse) { crate::input::groups::ungroup(state, id); state.group_context_target = None; }
-            else if delete_rect.contains(mouse) { crate::input::groups::delete_group_and_cells(state, canvas, id); state.group_context_target = None; }
-            else { state.group_context_target = None; }
-        }
+        draw_text(if is_frame { "Untag Frame" } else { "Tag as Frame" }, px + 8.0, py + 15.0 + item_h * 2.0, 16.0, BLACK);
+        draw_text("Export PNG", px + 8.0, py + 15.0 + item_h * 3.0, 16.0, BLACK);
+        let [ungroup, delete, frame, export] = [ungroup_rect, delete_rect, frame_rect, export_rect].map(focus::pressed);
+        if ungroup { crate::input::groups::ungroup(state, id); state.group_context_target = None; }
+        else if delete { crate::input::groups::delete_group_and_cells(state, canvas, id); state.group_context_target = None; }
+        else if frame { crate::input::frames::toggle_frame_tag(state, id); state.group_context_target = None; }
+        else if export { crate::input::jobs::start_group_export(state, id); state.group_context_target = None; }
+        else if is_mouse_button_pressed(MouseButton::Left) { state.group_context_target = None; }
     }
 
     over
@@ -0,0 +1,61 @@
+use macroquad::prelude::*;
+use crate::core::color::Harmony;
+use crate::state::ApplicationState;
+use crate::ui::focus;
+
+const PANEL_WIDTH: f32 = 160.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 26.0;
+const SWATCH_SIZE: f32 = 20.0;
+/// Width of the palette window this panel docks beside
+const PALETTE_WIDTH: f32 = 200.0;
+
+/// Complementary/analogous/triadic/split suggestions for the current color, docked to the
+/// right of the palette window. Left click sets the primary color, right click the secondary.
+/// Returns true if the mouse is over the panel
+pub fn render_harmony_panel(state: &mut ApplicationState) -> bool {
+    if !state.show_harmony {
+        return false;
+    }
+
+    let x = if state.show_palette { state.palette_position.x + PALETTE_WIDTH + 4.0 } else { state.palette_position.x };
+    let y = state.palette_position.y;
+    let height = TITLE_BAR_HEIGHT + Harmony::ALL.len() as f32 * ROW_HEIGHT + 6.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    // Title bar
+    draw_rectangle(x, y, PANEL_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Harmonies", x + 8.0, y + 17.0, 16.0, WHITE);
+    draw_rectangle(x + PANEL_WIDTH - 24.0, y + 5.0, 15.0, 15.0, Color::from(state.current_color));
+    draw_rectangle_lines(x + PANEL_WIDTH - 24.0, y + 5.0, 15.0, 15.0, 1.5, BLACK);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, PANEL_WIDTH, height - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, PANEL_WIDTH, height - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    let base = state.current_color;
+    for (row, harmony) in Harmony::ALL.into_iter().enumerate() {
+        let row_y = body_y + 4.0 + row as f32 * ROW_HEIGHT;
+        draw_text(harmony.label(), x + 8.0, row_y + 15.0, 14.0, BLACK);
+
+        for (i, rgba) in harmony.colors(base).into_iter().enumerate() {
+            let rect = Rect::new(x + 88.0 + i as f32 * (SWATCH_SIZE + 4.0), row_y, SWATCH_SIZE, SWATCH_SIZE);
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from(rgba));
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.5, BLACK);
+
+            if focus::focusable(rect) {
+                state.current_color = rgba;
+            } else if rect.contains(mouse_pos) {
+                if is_mouse_button_pressed(MouseButton::Left) {
+                    state.current_color = rgba;
+                } else if is_mouse_button_pressed(MouseButton::Right) {
+                    state.secondary_color = rgba;
+                }
+            }
+        }
+    }
+
+    Rect::new(x, y, PANEL_WIDTH, height).contains(mouse_pos)
+}
@@ -0,0 +1,102 @@
+use macroquad::prelude::*;
+use crate::input::actions::{Action, HELD_BINDINGS};
+use crate::state::ApplicationState;
+
+const PANEL_WIDTH: f32 = 660.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const LINE_HEIGHT: f32 = 18.0;
+const COLUMN_WIDTH: f32 = 320.0;
+const KEY_COLUMN: f32 = 110.0;
+/// Gap kept above and below the sheet when it is taller than the window
+const SCREEN_MARGIN: f32 = 8.0;
+/// Pixels scrolled per wheel notch
+const SCROLL_STEP: f32 = LINE_HEIGHT * 3.0;
+
+/// What each tool does, shown under the key bindings
+const TOOL_DESCRIPTIONS: &[(Action, &str)] = &[
+    (Action::ToolPaint, "Click or drag to fill cells with the primary color"),
+    (Action::ToolErase, "Click or drag to clear cells"),
+    (Action::ToolSelect, "Drag to pick filled cells, drag inside to move, Shift adds"),
+    (Action::ToolPan, "Drag to move the view"),
+    (Action::ToolSpray, "Scatter cells around the cursor; [ ] size, Shift+[ ] density"),
+    (Action::ToolLine, "Drag from start to end; Shift snaps to 45°, Esc cancels"),
+    (Action::ToolRectangle, "Drag corner to corner; Shift for a square, filled with Fill Rectangles"),
+    (Action::ToolPen, "Click anchors; double-click closes, Enter keeps open, Esc cancels"),
+    (Action::ToolFill, "Click to flood a region; tolerance and Global in the options strip"),
+    (Action::ToolDither, "Drag across the selection: primary to secondary, Bayer dithered"),
+    (Action::ToolAutotile, "Drag to paint terrain, right-drag to remove; template from the selection"),
+    (Action::ToolCurve, "Drag start to end, drag handles; click away or Enter commits"),
+];
+
+/// F1 cheat sheet, generated from the keymap so it always matches the real bindings
+/// Modal while shown: F1 or Esc closes it, the wheel scrolls it when it's taller than the window
+pub fn render_help_overlay(state: &mut ApplicationState) -> bool {
+    if !state.show_help {
+        return false;
+    }
+
+    // Every keymap entry plus the held/mouse bindings, split over two columns
+    let mut entries: Vec<(String, &str)> = state
+        .keymap
+        .iter()
+        .map(|(binding, action)| (binding.label(), action.label()))
+        .collect();
+    entries.extend(HELD_BINDINGS.iter().map(|&(keys, what)| (keys.to_string(), what)));
+    let rows = entries.len().div_ceil(2);
+
+    let bindings_height = rows as f32 * LINE_HEIGHT;
+    let tools_height = (TOOL_DESCRIPTIONS.len() + 1) as f32 * LINE_HEIGHT;
+    let height = TITLE_BAR_HEIGHT + 16.0 + bindings_height + 12.0 + tools_height + 12.0 + LINE_HEIGHT + 8.0;
+    let x = ((screen_width() - PANEL_WIDTH) / 2.0).max(0.0);
+    let max_scroll = (height + 2.0 * SCREEN_MARGIN - screen_height()).max(0.0);
+    let scroll_y = mouse_wheel().1;
+    if scroll_y != 0.0 {
+        state.help_scroll -= scroll_y.signum() * SCROLL_STEP;
+    }
+    state.help_scroll = state.help_scroll.clamp(0.0, max_scroll);
+    let y = if max_scroll > 0.0 { SCREEN_MARGIN - state.help_scroll } else { (screen_height() - height) / 2.0 };
+
+    // Dim the canvas behind the sheet
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 120));
+
+    // Title bar
+    draw_rectangle(x, y, PANEL_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    let title = if max_scroll > 0.0 { "Keyboard shortcuts (F1 / Esc to close, wheel to scroll)" } else { "Keyboard shortcuts (F1 / Esc to close)" };
+    draw_text(title, x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, PANEL_WIDTH, height - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, PANEL_WIDTH, height - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Key bindings
+    let top = body_y + 8.0;
+    for (i, (keys, what)) in entries.iter().enumerate() {
+        let col_x = x + 10.0 + (i / rows) as f32 * COLUMN_WIDTH;
+        let line_y = top + (i % rows) as f32 * LINE_HEIGHT + 13.0;
+        draw_text(keys, col_x, line_y, 15.0, Color::from_rgba(40, 40, 120, 255));
+        draw_text(what, col_x + KEY_COLUMN, line_y, 15.0, BLACK);
+    }
+
+    // Tools
+    let mut line_y = top + bindings_height + 12.0 + 13.0;
+    draw_text("Tools", x + 10.0, line_y, 16.0, BLACK);
+    for (action, description) in TOOL_DESCRIPTIONS {
+        line_y += LINE_HEIGHT;
+        draw_text(action.label(), x + 10.0, line_y, 15.0, Color::from_rgba(40, 40, 120, 255));
+        draw_text(description, x + 10.0 + KEY_COLUMN, line_y, 15.0, BLACK);
+    }
+
+    // Version
+    line_y += LINE_HEIGHT + 12.0;
+    let version = format!("tiny-neo-space {}", env!("CARGO_PKG_VERSION"));
+    draw_text(&version, x + 10.0, line_y, 14.0, DARKGRAY);
+
+    if is_key_pressed(KeyCode::F1) || is_key_pressed(KeyCode::Escape) {
+        state.show_help = false;
+        state.help_scroll = 0.0;
+    }
+
+    true
+}
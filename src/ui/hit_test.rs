@@ -0,0 +1,22 @@
+use macroquad::prelude::*;
+
+/// Screen rects claimed by UI panels this frame; the canvas ignores the mouse over any of them
+/// Cleared at the start of every frame, then each panel pushes the rect it drew
+#[derive(Default)]
+pub struct UiRegions {
+    rects: Vec<Rect>,
+}
+
+impl UiRegions {
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    pub fn push(&mut self, rect: Rect) {
+        self.rects.push(rect);
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.rects.iter().any(|r| r.contains(point))
+    }
+}
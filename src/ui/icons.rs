@@ -0,0 +1,283 @@
+use macroquad::prelude::*;
+use crate::input::actions::Action;
+
+/// Icon bitmaps are ICON_SIZE × ICON_SIZE characters, drawn at ICON_SCALE× on screen
+pub const ICON_SIZE: usize = 12;
+pub const ICON_SCALE: f32 = 2.0;
+
+const PAINT: [&str; ICON_SIZE] = [
+    ".........##.",
+    "........#yy#",
+    ".......#yy#.",
+    "......#yy#..",
+    ".....#yy#...",
+    "....#yy#....",
+    "...#yy#.....",
+    "..#yy#......",
+    ".#pp#.......",
+    ".#p#........",
+    "##..........",
+    "#...........",
+];
+
+const LINE: [&str; ICON_SIZE] = [
+    "............",
+    "..........##",
+    ".........##.",
+    "........##..",
+    ".......##...",
+    "......##....",
+    ".....##.....",
+    "....##......",
+    "...##.......",
+    "..##........",
+    ".##.........",
+    "##..........",
+];
+
+const RECTANGLE: [&str; ICON_SIZE] = [
+    "............",
+    "............",
+    ".##########.",
+    ".#oooooooo#.",
+    ".#oooooooo#.",
+    ".#oooooooo#.",
+    ".#oooooooo#.",
+    ".#oooooooo#.",
+    ".#oooooooo#.",
+    ".##########.",
+    "............",
+    "............",
+];
+
+const PEN: [&str; ICON_SIZE] = [
+    "............",
+    ".#..........",
+    ".##.........",
+    ".#o#........",
+    ".#oo#.......",
+    ".#ooo#......",
+    ".#oo#o#.....",
+    ".#o#.#o#....",
+    ".##...#o#...",
+    ".......#o#..",
+    "........###.",
+    "............",
+];
+
+const CURVE: [&str; ICON_SIZE] = [
+    "............",
+    "......###...",
+    "....##...#..",
+    "...#......#.",
+    "..#.......#.",
+    "..#......#..",
+    ".#.....##...",
+    ".#...##.....",
+    ".#..#.......",
+    "##..........",
+    "##..........",
+    "............",
+];
+
+const FILL: [&str; ICON_SIZE] = [
+    "....##......",
+    "...#oo#.....",
+    "..#oooo#....",
+    ".#oooooo#...",
+    "#oooooooo#..",
+    ".#oooooo#b..",
+    "..#oooo#bbb.",
+    "...#oo#.bbb.",
+    "....##..bbb.",
+    ".........b..",
+    "............",
+    "............",
+];
+
+const DITHER: [&str; ICON_SIZE] = [
+    "############",
+    "#bbbbbbbbbb#",
+    "#bbbbbbbbbb#",
+    "#b.b.b.b.bb#",
+    "#bbbbbbbbbb#",
+    "#.b.b.b.b.b#",
+    "#b.b.b.b.b.#",
+    "#..........#",
+    "#.b...b...b#",
+    "#..........#",
+    "#..........#",
+    "############",
+];
+
+const AUTOTILE: [&str; ICON_SIZE] = [
+    "#####.#####.",
+    "#ggg#.#ggg#.",
+    "#ggg#.#ggg#.",
+    "#ggg#.#ggg#.",
+    "#####.#####.",
+    "............",
+    "#####.......",
+    "#ggg#.......",
+    "#ggg#.......",
+    "#ggg#.......",
+    "#####.......",
+    "............",
+];
+
+const ERASE: [&str; ICON_SIZE] = [
+    "............",
+    ".....######.",
+    "....#pppp##.",
+    "...#pppp#o#.",
+    "..#pppp#oo#.",
+    ".#pppp#oo#..",
+    ".######oo#..",
+    ".#oooo#o#...",
+    ".#oooo##....",
+    ".######.....",
+    "............",
+    "............",
+];
+
+const SPRAY: [&str; ICON_SIZE] = [
+    "............",
+    "..#.....#...",
+    "......#.....",
+    "....#...#.#.",
+    ".#..........",
+    "......#.....",
+    "..#.#....#..",
+    "........#...",
+    ".#...#......",
+    "....#...#.#.",
+    "..#.........",
+    "............",
+];
+
+const SELECT: [&str; ICON_SIZE] = [
+    "............",
+    ".##.##.##.#.",
+    "............",
+    ".#........#.",
+    ".#........#.",
+    "............",
+    "............",
+    ".#........#.",
+    ".#........#.",
+    "............",
+    ".##.##.##.#.",
+    "............",
+];
+
+const PAN: [&str; ICON_SIZE] = [
+    ".....##.....",
+    "....####....",
+    "...######...",
+    ".#...##...#.",
+    "##...##...##",
+    "############",
+    "############",
+    "##...##...##",
+    ".#...##...#.",
+    "...######...",
+    "....####....",
+    ".....##.....",
+];
+
+const PALETTE: [&str; ICON_SIZE] = [
+    "............",
+    "...######...",
+    "..#oooooo#..",
+    ".#orrooggo#.",
+    ".#orrooggo#.",
+    ".#oooooooo#.",
+    ".#obboo###..",
+    ".#obbo#.....",
+    ".#oooo#.....",
+    "..#ooo#.....",
+    "...###......",
+    "............",
+];
+
+fn pixel_color(ch: char) -> [u8; 4] {
+    match ch {
+        '#' => [0, 0, 0, 255],
+        'o' => [255, 255, 255, 255],
+        'y' => [247, 206, 70, 255],
+        'p' => [240, 140, 170, 255],
+        'r' => [220, 60, 60, 255],
+        'g' => [70, 180, 80, 255],
+        'b' => [60, 100, 220, 255],
+        _ => [0, 0, 0, 0],
+    }
+}
+
+fn build_texture(rows: &[&str; ICON_SIZE]) -> Texture2D {
+    let mut bytes = Vec::with_capacity(ICON_SIZE * ICON_SIZE * 4);
+    for row in rows {
+        let mut chars = row.chars();
+        for _ in 0..ICON_SIZE {
+            bytes.extend_from_slice(&pixel_color(chars.next().unwrap_or('.')));
+        }
+    }
+    let texture = Texture2D::from_rgba8(ICON_SIZE as u16, ICON_SIZE as u16, &bytes);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
+
+/// Toolbar icons, uploaded once at startup from the bitmaps above
+pub struct ToolIcons {
+    paint: Texture2D,
+    erase: Texture2D,
+    spray: Texture2D,
+    line: Texture2D,
+    rectangle: Texture2D,
+    pen: Texture2D,
+    curve: Texture2D,
+    fill: Texture2D,
+    dither: Texture2D,
+    autotile: Texture2D,
+    select: Texture2D,
+    pan: Texture2D,
+    palette: Texture2D,
+}
+
+impl ToolIcons {
+    pub fn new() -> Self {
+        Self {
+            paint: build_texture(&PAINT),
+            erase: build_texture(&ERASE),
+            spray: build_texture(&SPRAY),
+            line: build_texture(&LINE),
+            rectangle: build_texture(&RECTANGLE),
+            pen: build_texture(&PEN),
+            curve: build_texture(&CURVE),
+            fill: build_texture(&FILL),
+            dither: build_texture(&DITHER),
+            autotile: build_texture(&AUTOTILE),
+            select: build_texture(&SELECT),
+            pan: build_texture(&PAN),
+            palette: build_texture(&PALETTE),
+        }
+    }
+
+    pub fn get(&self, action: Action) -> Option<&Texture2D> {
+        match action {
+            Action::ToolPaint => Some(&self.paint),
+            Action::ToolErase => Some(&self.erase),
+            Action::ToolSpray => Some(&self.spray),
+            Action::ToolLine => Some(&self.line),
+            Action::ToolRectangle => Some(&self.rectangle),
+            Action::ToolPen => Some(&self.pen),
+            Action::ToolCurve => Some(&self.curve),
+            Action::ToolFill => Some(&self.fill),
+            Action::ToolDither => Some(&self.dither),
+            Action::ToolAutotile => Some(&self.autotile),
+            Action::ToolSelect => Some(&self.select),
+            Action::ToolPan => Some(&self.pan),
+            Action::TogglePalette => Some(&self.palette),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use macroquad::prelude::*;
+use crate::import::{can_export, MAX_TILES_PER_ROW, MIN_TILES_PER_ROW};
+use crate::import::svg::SVG_SCALES;
+use crate::input::import::{run_export, run_import};
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+use crate::ui::focus;
+
+const DIALOG_WIDTH: f32 = 540.0;
+const DIALOG_HEIGHT: f32 = 262.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+
+/// File types the dialog understands, shown as a hint under the path
+const SUPPORTED_HINT: &str = "PNG, GIF, GBA .4bpp/.bin (+ .pal), PICO-8 .p8, TIC-80 .lua, .piskel, SVG, .tnsp/.tnst, .tnspreset";
+
+/// Import window (Ctrl+I): type a path, Enter or Import places the file in the view
+/// (a project follows the cursor as a paste);
+/// cart formats and workspace presets can also be written with Export
+/// Returns true if the mouse is over the dialog
+pub fn render_import_dialog(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> bool {
+    if !state.show_import_dialog {
+        return false;
+    }
+
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - DIALOG_HEIGHT) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Import", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Row 1: path field (always focused while the dialog is open)
+    let row_y = body_y + 6.0;
+    draw_text("File", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    draw_rectangle(x + 50.0, row_y, DIALOG_WIDTH - 58.0, ROW_HEIGHT - 4.0, WHITE);
+    draw_rectangle_lines(x + 50.0, row_y, DIALOG_WIDTH - 58.0, ROW_HEIGHT - 4.0, 1.0, DARKGRAY);
+    draw_text(&format!("{}_", state.import.path), x + 54.0, row_y + 17.0, 16.0, BLACK);
+    draw_text(SUPPORTED_HINT, x + 50.0, row_y + ROW_HEIGHT + 8.0, 14.0, DARKGRAY);
+
+    // Row 2: tile layout
+    let row_y = row_y + ROW_HEIGHT + 14.0;
+    draw_text(&format!("Tiles per row {}", state.import.tiles_per_row), x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button("-", x + 150.0, row_y, 30.0) {
+        state.import.tiles_per_row = (state.import.tiles_per_row - 1).max(MIN_TILES_PER_ROW);
+    }
+    if dialog_button("+", x + 185.0, row_y, 30.0) {
+        state.import.tiles_per_row = (state.import.tiles_per_row + 1).min(MAX_TILES_PER_ROW);
+    }
+
+    // Row 3: how full-color images meet the palette chosen in the palette window
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("Palette", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(state.import.quantize.label(), x + 150.0, row_y, 130.0) {
+        state.import.quantize = state.import.quantize.next();
+    }
+
+    // Row 4: SVG outlines' size, in cells per SVG unit
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text(&format!("SVG scale {}x", state.import.svg_scale), x + 8.0, row_y + 17.0, 16.0, BLACK);
+    let scale_index = SVG_SCALES.iter().position(|&s| s == state.import.svg_scale).unwrap_or(3);
+    if dialog_button("-", x + 150.0, row_y, 30.0) {
+        state.import.svg_scale = SVG_SCALES[scale_index.saturating_sub(1)];
+    }
+    if dialog_button("+", x + 185.0, row_y, 30.0) {
+        state.import.svg_scale = SVG_SCALES[(scale_index + 1).min(SVG_SCALES.len() - 1)];
+    }
+
+    // Row 5: a PNG alone, or with its numbered siblings as animation frames
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("PNG files", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(if state.import.png_sequence { "Numbered sequence" } else { "Single image" }, x + 150.0, row_y, 130.0) {
+        state.import.png_sequence = !state.import.png_sequence;
+    }
+
+    // Row 6: whether an imported project brings its groups
+    let row_y = row_y + ROW_HEIGHT;
+    draw_text("Project groups", x + 8.0, row_y + 17.0, 16.0, BLACK);
+    if dialog_button(if state.import.project_groups { "Keep" } else { "Skip" }, x + 150.0, row_y, 130.0) {
+        state.import.project_groups = !state.import.project_groups;
+    }
+
+    // Row 7: actions
+    let row_y = row_y + ROW_HEIGHT;
+    let import_clicked = dialog_button("Import", x + 8.0, row_y, 80.0);
+    if can_export(&state.import) && dialog_button("Export", x + 96.0, row_y, 80.0) {
+        run_export(state);
+    }
+    if dialog_button("Close", x + 184.0, row_y, 64.0) {
+        state.show_import_dialog = false;
+    }
+
+    // Status line
+    if let Some(status) = &state.import.status {
+        draw_text(status, x + 8.0, row_y + ROW_HEIGHT + 10.0, 14.0, DARKGRAY);
+    }
+
+    // Typing
+    while let Some(ch) = get_char_pressed() {
+        if !ch.is_control() {
+            state.import.path.push(ch);
+        }
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        state.import.path.pop();
+    }
+    if is_key_pressed(KeyCode::Escape) {
+        state.show_import_dialog = false;
+    } else if import_clicked || focus::enter_pressed() {
+        run_import(state, canvas);
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, DIALOG_HEIGHT).contains(mouse_pos)
+}
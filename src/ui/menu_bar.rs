@@ -0,0 +1,257 @@
+use macroquad::prelude::*;
+use crate::input::actions::{perform, Action};
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::focus;
+
+pub const MENU_BAR_HEIGHT: f32 = 24.0;
+const TITLE_PADDING: f32 = 12.0;
+const ITEM_WIDTH: f32 = 230.0;
+const ITEM_HEIGHT: f32 = 22.0;
+
+/// Top-level menus and their items, all drawn from the shared action registry
+const MENUS: &[(&str, &[Action])] = &[
+    ("File", &[
+        Action::NewDocument,
+        Action::OpenProject,
+        Action::OpenRecent,
+        Action::RestoreBackup,
+        Action::SaveProject,
+        Action::SaveProjectAs,
+        Action::ToggleTextFormat,
+        Action::ToggleSaveHistory,
+        Action::ToggleActionLog,
+        Action::CycleBackupInterval,
+        Action::CycleBackupCount,
+        Action::ExportPng,
+        Action::QuickExport,
+        Action::ExportGif,
+        Action::ExportGroups,
+        Action::ExportSpriteSheet,
+        Action::Import,
+        Action::DocumentInfo,
+    ]),
+    ("Edit", &[
+        Action::Undo,
+        Action::Redo,
+        Action::RecenterDocument,
+        Action::ShiftDocument,
+        Action::ToggleDocumentBounds,
+        Action::TrimToBounds,
+        Action::FindStrays,
+        Action::ToolPaint,
+        Action::ToolErase,
+        Action::ToolSpray,
+        Action::ToolLine,
+        Action::ToolRectangle,
+        Action::ToggleRectangleFill,
+        Action::ToolPen,
+        Action::TogglePenFill,
+        Action::ToolCurve,
+        Action::ToolFill,
+        Action::ToggleFillGlobal,
+        Action::ToolDither,
+        Action::ToolAutotile,
+        Action::SetAutotileTemplate,
+        Action::ToggleCurveCubic,
+        Action::CurveStepsDown,
+        Action::CurveStepsUp,
+        Action::ToolPan,
+        Action::SwapColors,
+        Action::ToggleAlphaLock,
+        Action::TogglePalette,
+        Action::ToggleHarmony,
+        Action::BrushSmaller,
+        Action::BrushLarger,
+        Action::DensityDown,
+        Action::DensityUp,
+        Action::SymmetryVertical,
+        Action::SymmetryHorizontal,
+        Action::SymmetryDiagonal,
+        Action::SymmetryAntiDiagonal,
+        Action::CycleSymmetrySegments,
+        Action::CenterSymmetry,
+    ]),
+    ("View", &[
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ResetView,
+        Action::ReturnToDrawing,
+        Action::GoToCell,
+        Action::RotateClockwise,
+        Action::RotateCounterClockwise,
+        Action::FlipView,
+        Action::CycleCellAspect,
+        Action::CanvasSettings,
+        Action::CycleTheme,
+        Action::TogglePreview,
+        Action::ToggleMirror,
+        Action::ToggleCrosshair,
+        Action::TogglePrecisionCursor,
+        Action::ToggleViewClamp,
+        Action::SwapMouseButtons,
+        Action::InvertWheelZoom,
+        Action::CycleZoomStep,
+        Action::ToggleHud,
+        Action::ToggleHudCompact,
+        Action::CycleHudCorner,
+        Action::ToggleBudget,
+        Action::CycleBudgetSize,
+        Action::CycleBudgetColors,
+        Action::TakeDiffSnapshot,
+        Action::DiffSnapshot,
+        Action::DiffSavedFile,
+        Action::DiffFrames,
+    ]),
+    ("Selection", &[
+        Action::ToolSelect,
+        Action::Copy,
+        Action::Cut,
+        Action::Paste,
+        Action::DeleteSelection,
+        Action::FillSelection,
+        Action::FillSelectionRect,
+        Action::GroupSelection,
+        Action::ExtractSelection,
+        Action::ToggleQuickMask,
+        Action::SaveSelection,
+        Action::ToggleSavedSelections,
+        Action::CycleFeather,
+        Action::PlayFrames,
+        Action::ToggleTimeline,
+        Action::TransformSelection,
+        Action::Deselect,
+    ]),
+    ("Generate", &[
+        Action::GenerateLife,
+        Action::GenerateMaze,
+        Action::GenerateNoise,
+        Action::GenerateVoronoi,
+    ]),
+    // Shared sessions need raw sockets, which the browser build doesn't have
+    #[cfg(not(target_arch = "wasm32"))]
+    ("Session", &[
+        Action::JoinSession,
+        Action::LeaveSession,
+        Action::OpenChat,
+        Action::LockRegion,
+        Action::UnlockRegion,
+    ]),
+    ("Help", &[
+        Action::ToggleHelp,
+        Action::ToggleConsole,
+        Action::About,
+    ]),
+];
+
+/// Draw the menu bar and any open dropdown; clicking an item performs its action
+/// Returns true if the mouse is over the bar or an open menu (a click outside an open menu
+/// only closes it, so it never reaches the canvas)
+pub fn render_menu_bar(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> bool {
+    let mouse_pos = Vec2::from(mouse_position());
+    let clicked = is_mouse_button_pressed(MouseButton::Left);
+    let mut over_ui = false;
+
+    // Bar background
+    draw_rectangle(0.0, 0.0, screen_width(), MENU_BAR_HEIGHT, Color::from_rgba(220, 220, 225, 255));
+    draw_line(0.0, MENU_BAR_HEIGHT, screen_width(), MENU_BAR_HEIGHT, 1.0, DARKGRAY);
+    if mouse_pos.y < MENU_BAR_HEIGHT {
+        over_ui = true;
+    }
+
+    // Titles
+    let mut x = 0.0;
+    let mut dropdown_x = 0.0;
+    for (i, (title, _)) in MENUS.iter().enumerate() {
+        let width = measure_text(title, None, 16, 1.0).width + TITLE_PADDING * 2.0;
+        let rect = Rect::new(x, 0.0, width, MENU_BAR_HEIGHT);
+        let hovered = rect.contains(mouse_pos);
+
+        if (hovered && clicked) || focus::focusable(rect) {
+            state.open_menu = if state.open_menu == Some(i) { None } else { Some(i) };
+        } else if hovered && state.open_menu.is_some() {
+            // Sliding across titles switches menus once one is open
+            state.open_menu = Some(i);
+        }
+
+        if state.open_menu == Some(i) {
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(80, 80, 150, 255));
+            draw_text(title, x + TITLE_PADDING, 17.0, 16.0, WHITE);
+            dropdown_x = x;
+        } else {
+            draw_text(title, x + TITLE_PADDING, 17.0, 16.0, BLACK);
+        }
+        x += width;
+    }
+
+    // Export button, right-aligned: writes the selection or drawing with the export settings
+    let label = "Export PNG";
+    let width = measure_text(label, None, 16, 1.0).width + TITLE_PADDING * 2.0;
+    let export_rect = Rect::new(screen_width() - width, 0.0, width, MENU_BAR_HEIGHT);
+    let export_hovered = export_rect.contains(mouse_pos);
+    if export_hovered {
+        draw_rectangle(export_rect.x, export_rect.y, export_rect.w, export_rect.h, Color::from_rgba(200, 200, 215, 255));
+    }
+    draw_line(export_rect.x, 4.0, export_rect.x, MENU_BAR_HEIGHT - 4.0, 1.0, DARKGRAY);
+    draw_text(label, export_rect.x + TITLE_PADDING, 17.0, 16.0, BLACK);
+    if (export_hovered && clicked && state.open_menu.is_none()) || focus::focusable(export_rect) {
+        perform(Action::QuickExport, state, canvas);
+    }
+
+    // Dropdown
+    let Some(open) = state.open_menu else {
+        return over_ui;
+    };
+    let items = MENUS[open].1;
+    let dropdown = Rect::new(dropdown_x, MENU_BAR_HEIGHT, ITEM_WIDTH, items.len() as f32 * ITEM_HEIGHT);
+    draw_rectangle(dropdown.x, dropdown.y, dropdown.w, dropdown.h, Color::from_rgba(235, 235, 235, 255));
+    draw_rectangle_lines(dropdown.x, dropdown.y, dropdown.w, dropdown.h, 1.0, BLACK);
+
+    let mut chosen = None;
+    for (row, &action) in items.iter().enumerate() {
+        let item = Rect::new(dropdown.x, dropdown.y + row as f32 * ITEM_HEIGHT, ITEM_WIDTH, ITEM_HEIGHT);
+        let hovered = item.contains(mouse_pos);
+        if hovered {
+            draw_rectangle(item.x, item.y, item.w, item.h, Color::from_rgba(200, 200, 215, 255));
+            if clicked {
+                chosen = Some(action);
+            }
+        }
+        if focus::focusable(item) {
+            chosen = Some(action);
+        }
+
+        // Check box for tools and toggles
+        if let Some(checked) = action.checked(state) {
+            draw_rectangle_lines(item.x + 6.0, item.y + 6.0, 10.0, 10.0, 1.0, DARKGRAY);
+            if checked {
+                draw_rectangle(item.x + 8.0, item.y + 8.0, 6.0, 6.0, BLACK);
+            }
+        }
+        draw_text(action.label(), item.x + 22.0, item.y + 16.0, 16.0, BLACK);
+
+        // Shortcut, right-aligned
+        if let Some(binding) = action.binding(&state.keymap) {
+            let shortcut = binding.label();
+            let width = measure_text(&shortcut, None, 14, 1.0).width;
+            draw_text(&shortcut, item.x + item.w - width - 8.0, item.y + 15.0, 14.0, DARKGRAY);
+        }
+    }
+
+    if dropdown.contains(mouse_pos) {
+        over_ui = true;
+    }
+
+    if let Some(action) = chosen {
+        state.open_menu = None;
+        perform(action, state, canvas);
+    } else if clicked && !over_ui {
+        // Click outside closes the menu and is swallowed
+        state.open_menu = None;
+        over_ui = true;
+    } else if is_key_pressed(KeyCode::Escape) {
+        state.open_menu = None;
+    }
+
+    over_ui
+}
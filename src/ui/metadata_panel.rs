@@ -0,0 +1,148 @@
+use macroquad::prelude::*;
+use crate::project::format::ProjectMetadata;
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+
+const DIALOG_WIDTH: f32 = 380.0;
+const DIALOG_HEIGHT: f32 = 200.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+const FIELD_X: f32 = 100.0;
+const FIELD_WIDTH: f32 = 270.0;
+
+/// Editable text fields of the metadata panel
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MetadataField {
+    Title,
+    Author,
+    Description,
+    Tags,
+}
+
+impl MetadataField {
+    const ALL: [MetadataField; 4] = [MetadataField::Title, MetadataField::Author, MetadataField::Description, MetadataField::Tags];
+
+    fn label(self) -> &'static str {
+        match self {
+            MetadataField::Title => "Title",
+            MetadataField::Author => "Author",
+            MetadataField::Description => "Description",
+            MetadataField::Tags => "Tags",
+        }
+    }
+
+    /// The field's current value as editable text (tags comma-separated)
+    fn text(self, metadata: &ProjectMetadata) -> String {
+        match self {
+            MetadataField::Title => metadata.title.clone(),
+            MetadataField::Author => metadata.author.clone(),
+            MetadataField::Description => metadata.description.clone(),
+            MetadataField::Tags => metadata.tags.join(", "),
+        }
+    }
+
+    fn store(self, metadata: &mut ProjectMetadata, text: String) {
+        match self {
+            MetadataField::Title => metadata.title = text,
+            MetadataField::Author => metadata.author = text,
+            MetadataField::Description => metadata.description = text,
+            MetadataField::Tags => {
+                metadata.tags = text.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect();
+            }
+        }
+    }
+}
+
+/// Title, author, description and tags of the document, saved in the project and written
+/// into exported PNGs. Click a field to type; Enter keeps the edit, Escape drops it
+/// Returns true if the mouse is over the dialog
+pub fn render_metadata_panel(state: &mut ApplicationState) -> bool {
+    if !state.show_metadata {
+        return false;
+    }
+
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - DIALOG_HEIGHT) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+    let clicked = is_mouse_button_pressed(MouseButton::Left);
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Document Info", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Rows 1-4: text fields
+    let mut row_y = body_y + 6.0;
+    for field in MetadataField::ALL {
+        draw_text(field.label(), x + 8.0, row_y + 17.0, 16.0, BLACK);
+        let rect = Rect::new(x + FIELD_X, row_y, FIELD_WIDTH, ROW_HEIGHT - 4.0);
+        let editing = matches!(&state.metadata_edit, Some((f, _)) if *f == field);
+        let text = match &state.metadata_edit {
+            Some((f, text)) if *f == field => format!("{}_", text),
+            _ => field.text(&state.metadata),
+        };
+        let background = if editing { WHITE } else { Color::from_rgba(245, 245, 245, 255) };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, background);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, if editing { 2.0 } else { 1.0 }, BLACK);
+        draw_text(&fit_tail(&text, rect.w - 8.0), rect.x + 4.0, rect.y + 17.0, 16.0, BLACK);
+
+        if clicked && rect.contains(mouse_pos) && !editing {
+            commit_edit(state);
+            state.metadata_edit = Some((field, field.text(&state.metadata)));
+        }
+        row_y += ROW_HEIGHT;
+    }
+
+    // Row 5: creation date (set when the document was started)
+    let created = if state.metadata.created.is_empty() { "Unknown" } else { &state.metadata.created };
+    draw_text(&format!("Created {}", created), x + 8.0, row_y + 17.0, 16.0, DARKGRAY);
+    row_y += ROW_HEIGHT;
+
+    // Row 6: actions
+    if dialog_button("Close", x + 8.0, row_y, 64.0) {
+        commit_edit(state);
+        state.show_metadata = false;
+    }
+
+    // Typing
+    if let Some((_, text)) = &mut state.metadata_edit {
+        while let Some(ch) = get_char_pressed() {
+            if !ch.is_control() {
+                text.push(ch);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            text.pop();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            state.metadata_edit = None;
+        } else if is_key_pressed(KeyCode::Enter) {
+            commit_edit(state);
+        }
+    } else if is_key_pressed(KeyCode::Escape) {
+        state.show_metadata = false;
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, DIALOG_HEIGHT).contains(mouse_pos)
+}
+
+/// Store the field being edited, if any, back into the document metadata
+fn commit_edit(state: &mut ApplicationState) {
+    if let Some((field, text)) = state.metadata_edit.take() {
+        field.store(&mut state.metadata, text.trim().to_string());
+    }
+}
+
+/// The longest tail of `text` that fits in `width` pixels, so the end being typed stays visible
+fn fit_tail(text: &str, width: f32) -> String {
+    let mut start = 0;
+    while start < text.len() && measure_text(&text[start..], None, 16, 1.0).width > width {
+        start += text[start..].chars().next().map_or(1, char::len_utf8);
+    }
+    text[start..].to_string()
+}
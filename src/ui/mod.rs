@@ -1,3 +1,49 @@
 pub mod palette;
+pub mod export_dialog;
+pub mod open_recent;
+pub mod menu_bar;
+pub mod help_overlay;
+pub mod groups_gutter;
+pub mod confirm_dialog;
+pub mod stray_navigator;
+pub mod hit_test;
+pub mod icons;
+pub mod fill_options;
+pub mod transform_panel;
+pub mod paste_options;
+pub mod chat;
+pub mod import_dialog;
+pub mod canvas_settings;
+pub mod theme;
+pub mod console;
+pub mod shift_dialog;
+pub mod harmony;
+pub mod metadata_panel;
+pub mod saved_selections;
+pub mod focus;
+pub mod file_picker;
+pub mod timeline;
+pub mod go_to_cell;
 
 pub use palette::render_palette_window;
+pub use export_dialog::render_export_dialog;
+pub use open_recent::render_open_recent;
+pub use file_picker::render_file_picker;
+pub use menu_bar::render_menu_bar;
+pub use help_overlay::render_help_overlay;
+pub use groups_gutter::render_groups_gutter;
+pub use confirm_dialog::render_confirm_dialog;
+pub use stray_navigator::render_stray_navigator;
+pub use fill_options::render_fill_options;
+pub use transform_panel::render_transform_panel;
+pub use paste_options::render_paste_options;
+pub use chat::render_chat_overlay;
+pub use import_dialog::render_import_dialog;
+pub use canvas_settings::render_canvas_settings;
+pub use console::render_console;
+pub use shift_dialog::render_shift_dialog;
+pub use harmony::render_harmony_panel;
+pub use metadata_panel::render_metadata_panel;
+pub use saved_selections::render_saved_selections;
+pub use timeline::render_timeline;
+pub use go_to_cell::render_go_to_cell;
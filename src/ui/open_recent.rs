@@ -0,0 +1,115 @@
+use macroquad::prelude::*;
+use crate::error::report;
+use crate::project::{open_project, restore_backup};
+use crate::project::recent::RecentProject;
+use crate::project::thumbnail::THUMBNAIL_SIZE;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::focus;
+
+const CARD_WIDTH: f32 = 120.0;
+const CARD_HEIGHT: f32 = 140.0;
+const CARD_GAP: f32 = 12.0;
+const COLUMNS: usize = 4;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+
+/// Full-screen "Open recent" picker showing each project's embedded thumbnail (Ctrl+Shift+O)
+/// Also lists backups when restoring one (`restoring_backup_of` set)
+/// Modal while shown: returns true so the canvas ignores the mouse
+pub fn render_open_recent(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> bool {
+    let projects = match &state.open_recent {
+        Some(projects) => projects,
+        None => return false,
+    };
+
+    // Dim the canvas behind the picker
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 120));
+
+    let rows = projects.len().div_ceil(COLUMNS).max(1);
+    let width = COLUMNS as f32 * (CARD_WIDTH + CARD_GAP) + CARD_GAP;
+    let height = TITLE_BAR_HEIGHT + rows as f32 * (CARD_HEIGHT + CARD_GAP) + CARD_GAP;
+    let x = (screen_width() - width) / 2.0;
+    let y = (screen_height() - height) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+    let clicked = is_mouse_button_pressed(MouseButton::Left);
+
+    // Title bar
+    draw_rectangle(x, y, width, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, width, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    let title = match &state.restoring_backup_of {
+        Some(project) => format!("Restore a backup of {} (Esc to close)", project),
+        None => "Open recent (Esc to close)".to_string(),
+    };
+    draw_text(&title, x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, width, height - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, width, height - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    if projects.is_empty() {
+        let hint = if state.restoring_backup_of.is_some() { "No backups of this project yet" } else { "No recent projects yet - save one with Ctrl+S" };
+        draw_text(hint, x + CARD_GAP, body_y + 30.0, 16.0, DARKGRAY);
+    }
+
+    let mut chosen = None;
+    for (i, project) in projects.iter().enumerate() {
+        let card_x = x + CARD_GAP + (i % COLUMNS) as f32 * (CARD_WIDTH + CARD_GAP);
+        let card_y = body_y + CARD_GAP + (i / COLUMNS) as f32 * (CARD_HEIGHT + CARD_GAP);
+        let card = Rect::new(card_x, card_y, CARD_WIDTH, CARD_HEIGHT);
+        let hovered = card.contains(mouse_pos);
+
+        let fill = if hovered { Color::from_rgba(200, 200, 210, 255) } else { Color::from_rgba(210, 210, 210, 255) };
+        draw_rectangle(card.x, card.y, card.w, card.h, fill);
+        draw_rectangle_lines(card.x, card.y, card.w, card.h, 2.0, BLACK);
+        draw_thumbnail(project, card_x + (CARD_WIDTH - THUMBNAIL_SIZE as f32) / 2.0, card_y + 8.0);
+
+        let name = std::path::Path::new(&project.path)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| project.path.clone());
+        let text_size = measure_text(&name, None, 14, 1.0);
+        let text_x = card_x + ((CARD_WIDTH - text_size.width) / 2.0).max(4.0);
+        draw_text(&name, text_x, card_y + CARD_HEIGHT - 14.0, 14.0, BLACK);
+
+        if (hovered && clicked) || focus::focusable(card) {
+            chosen = Some(project.path.clone());
+        }
+    }
+
+    if let Some(path) = chosen {
+        state.status_message = Some(match state.restoring_backup_of.take() {
+            Some(project) => match restore_backup(state, canvas, &path) {
+                Ok(()) => format!("Restored {}; save to overwrite {}", path, project),
+                Err(e) => report("Restore", &e),
+            },
+            None => match open_project(state, canvas, &path) {
+                Ok(()) => format!("Opened {}", path),
+                Err(e) => report("Open", &e),
+            },
+        });
+        state.open_recent = None;
+    } else if is_key_pressed(KeyCode::Escape) {
+        state.open_recent = None;
+        state.restoring_backup_of = None;
+    }
+
+    true
+}
+
+/// Thumbnail centered in a THUMBNAIL_SIZE box over a white backdrop, or a placeholder
+fn draw_thumbnail(project: &RecentProject, x: f32, y: f32) {
+    let size = THUMBNAIL_SIZE as f32;
+    draw_rectangle(x, y, size, size, WHITE);
+    match &project.thumbnail {
+        Some(texture) => {
+            let (w, h) = (texture.width(), texture.height());
+            draw_texture(texture, x + (size - w) / 2.0, y + (size - h) / 2.0, WHITE);
+        }
+        None => {
+            let text_size = measure_text("No preview", None, 14, 1.0);
+            draw_text("No preview", x + (size - text_size.width) / 2.0, y + size / 2.0, 14.0, GRAY);
+        }
+    }
+    draw_rectangle_lines(x, y, size, size, 1.0, DARKGRAY);
+}
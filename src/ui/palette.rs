@@ -1,6 +1,12 @@
 use macroquad::prelude::*;
-use crate::core::color::{GBA_PALETTE, GBA_PALETTE_ROWS, GBA_PALETTE_COLS, generate_gba_extended_palette};
+use crate::core::color::{GBA_PALETTE, generate_gba_extended_palette, generate_hue_shifted_ramp};
 use crate::state::{ApplicationState, PaletteMode};
+use crate::ui::focus;
+
+/// Swatches per row in the custom palette
+const CUSTOM_COLS: usize = 8;
+const MIN_RAMP_STEPS: usize = 2;
+const MAX_RAMP_STEPS: usize = 8;
 
 pub fn render_palette_window(state: &mut ApplicationState) -> bool {
     if !state.show_palette {
@@ -15,6 +21,11 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
     let base_height = match state.palette_mode {
         PaletteMode::Basic => 160.0,
         PaletteMode::Extended => 320.0, // Taller for extended mode
+        PaletteMode::Custom => {
+            // Grows with the number of custom swatch rows
+            let rows = state.custom_palette.len().div_ceil(CUSTOM_COLS).max(1);
+            100.0 + rows as f32 * 24.0
+        }
     };
     let palette_height = base_height;
     let title_bar_height = 25.0;
@@ -68,59 +79,38 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
 
     // Mode toggle buttons
     let button_y = content_y + 5.0;
-    let button_width = 90.0;
+    let button_width = 60.0;
     let button_height = 25.0;
     let button_spacing = 5.0;
-    let basic_button_x = palette_x + 5.0;
-    let extended_button_x = basic_button_x + button_width + button_spacing;
-
-    let basic_button_rect = Rect::new(basic_button_x, button_y, button_width, button_height);
-    let extended_button_rect = Rect::new(extended_button_x, button_y, button_width, button_height);
-
-    // Draw Basic button
-    let basic_color = match state.palette_mode {
-        PaletteMode::Basic => Color::from_rgba(100, 150, 100, 255), // Active green
-        PaletteMode::Extended => Color::from_rgba(180, 180, 180, 255), // Inactive gray
-    };
-    draw_rectangle(basic_button_x, button_y, button_width, button_height, basic_color);
-    draw_rectangle_lines(basic_button_x, button_y, button_width, button_height, 2.0, BLACK);
-    let basic_text = "Basic";
-    let basic_text_size = measure_text(basic_text, None, 16, 1.0);
-    draw_text(
-        basic_text,
-        basic_button_x + (button_width - basic_text_size.width) / 2.0,
-        button_y + (button_height + basic_text_size.height) / 2.0,
-        16.0,
-        BLACK,
-    );
-
-    // Draw Extended button
-    let extended_color = match state.palette_mode {
-        PaletteMode::Extended => Color::from_rgba(100, 150, 100, 255), // Active green
-        PaletteMode::Basic => Color::from_rgba(180, 180, 180, 255), // Inactive gray
-    };
-    draw_rectangle(extended_button_x, button_y, button_width, button_height, extended_color);
-    draw_rectangle_lines(extended_button_x, button_y, button_width, button_height, 2.0, BLACK);
-    let extended_text = "Extended";
-    let extended_text_size = measure_text(extended_text, None, 16, 1.0);
-    draw_text(
-        extended_text,
-        extended_button_x + (button_width - extended_text_size.width) / 2.0,
-        button_y + (button_height + extended_text_size.height) / 2.0,
-        16.0,
-        BLACK,
-    );
-
-    // Handle mode button clicks
-    if !state.palette_dragging {
-        if is_mouse_button_pressed(MouseButton::Left) {
-            if basic_button_rect.contains(mouse_pos) {
-                state.palette_mode = PaletteMode::Basic;
-                state.palette_page = 0; // Reset page when switching modes
-            } else if extended_button_rect.contains(mouse_pos) {
-                state.palette_mode = PaletteMode::Extended;
-                state.palette_page = 0; // Reset page when switching modes
-            }
+    let modes = [
+        (PaletteMode::Basic, "Basic"),
+        (PaletteMode::Extended, "Extended"),
+        (PaletteMode::Custom, "Custom"),
+    ];
+
+    for (i, (mode, label)) in modes.into_iter().enumerate() {
+        let button_x = palette_x + 5.0 + i as f32 * (button_width + button_spacing);
+        let button_color = if state.palette_mode == mode {
+            Color::from_rgba(100, 150, 100, 255) // Active green
+        } else {
+            Color::from_rgba(180, 180, 180, 255) // Inactive gray
+        };
+        draw_rectangle(button_x, button_y, button_width, button_height, button_color);
+        draw_rectangle_lines(button_x, button_y, button_width, button_height, 2.0, BLACK);
+        let text_size = measure_text(label, None, 14, 1.0);
+        draw_text(
+            label,
+            button_x + (button_width - text_size.width) / 2.0,
+            button_y + (button_height + text_size.height) / 2.0,
+            14.0,
+            BLACK,
+        );
+
+        // Handle mode button clicks
+        let button_rect = Rect::new(button_x, button_y, button_width, button_height);
+        if !state.palette_dragging && focus::pressed(button_rect) {
+            state.palette_mode = mode;
+            state.palette_page = 0; // Reset page when switching modes
         }
     }
 
@@ -135,10 +125,9 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
             let start_x = palette_x + padding;
             let start_y = swatch_start_y;
 
-            for row in 0..GBA_PALETTE_ROWS {
-                for col in 0..GBA_PALETTE_COLS {
-                    let rgba = GBA_PALETTE[row][col];
-                    let mq_color = rgba.to_mq_color();
+            for (row, palette_row) in GBA_PALETTE.iter().enumerate() {
+                for (col, &rgba) in palette_row.iter().enumerate() {
+                    let mq_color = Color::from(rgba);
 
                     let x = start_x + col as f32 * (color_size + padding);
                     let y = start_y + row as f32 * (color_size + padding);
@@ -147,8 +136,8 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
                     draw_rectangle(x, y, color_size, color_size, mq_color);
 
                     // Highlight if this is the current color
-                    let border_width = if colors_match(state.current_color, mq_color) { 3.0 } else { 1.5 };
-                    let border_color = if colors_match(state.current_color, mq_color) {
+                    let border_width = if state.current_color == rgba { 3.0 } else { 1.5 };
+                    let border_color = if state.current_color == rgba {
                         Color::from_rgba(255, 255, 0, 255) // Yellow highlight
                     } else {
                         BLACK
@@ -159,8 +148,10 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
                     // Check if clicked (only if not dragging title bar)
                     if !state.palette_dragging {
                         let rect = Rect::new(x, y, color_size, color_size);
-                        if is_mouse_button_pressed(MouseButton::Left) && rect.contains(mouse_pos) {
-                            state.current_color = mq_color;
+                        if focus::pressed(rect) {
+                            state.current_color = rgba;
+                        } else if is_mouse_button_pressed(MouseButton::Right) && rect.contains(mouse_pos) {
+                            state.secondary_color = rgba;
                         }
                     }
                 }
@@ -171,7 +162,7 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
             let extended_palette = generate_gba_extended_palette();
             let total_colors = extended_palette.len(); // 343 colors
             let colors_per_page = 200;
-            let total_pages = (total_colors + colors_per_page - 1) / colors_per_page; // Ceiling division
+            let total_pages = total_colors.div_ceil(colors_per_page);
 
             // Ensure page is within bounds
             if state.palette_page >= total_pages {
@@ -190,11 +181,11 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
             let start_x = palette_x + 5.0;
             let start_y = swatch_start_y;
 
-            for (idx, rgba) in page_colors.iter().enumerate() {
+            for (idx, &rgba) in page_colors.iter().enumerate() {
                 let row = idx / cols;
                 let col = idx % cols;
 
-                let mq_color = rgba.to_mq_color();
+                let mq_color = Color::from(rgba);
                 let x = start_x + col as f32 * (color_size + padding);
                 let y = start_y + row as f32 * (color_size + padding);
 
@@ -202,8 +193,8 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
                 draw_rectangle(x, y, color_size, color_size, mq_color);
 
                 // Highlight if this is the current color
-                let border_width = if colors_match(state.current_color, mq_color) { 2.0 } else { 1.0 };
-                let border_color = if colors_match(state.current_color, mq_color) {
+                let border_width = if state.current_color == rgba { 2.0 } else { 1.0 };
+                let border_color = if state.current_color == rgba {
                     Color::from_rgba(255, 255, 0, 255) // Yellow highlight
                 } else {
                     Color::from_rgba(100, 100, 100, 255) // Gray border
@@ -214,8 +205,10 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
                 // Check if clicked (only if not dragging title bar)
                 if !state.palette_dragging {
                     let rect = Rect::new(x, y, color_size, color_size);
-                    if is_mouse_button_pressed(MouseButton::Left) && rect.contains(mouse_pos) {
-                        state.current_color = mq_color;
+                    if focus::pressed(rect) {
+                        state.current_color = rgba;
+                    } else if is_mouse_button_pressed(MouseButton::Right) && rect.contains(mouse_pos) {
+                        state.secondary_color = rgba;
                     }
                 }
             }
@@ -284,11 +277,71 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
 
             // Handle page button clicks
             if !state.palette_dragging {
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    if prev_button_rect.contains(mouse_pos) && prev_active {
-                        state.palette_page = state.palette_page.saturating_sub(1);
-                    } else if next_button_rect.contains(mouse_pos) && next_active {
-                        state.palette_page = (state.palette_page + 1).min(total_pages - 1);
+                let (prev_pressed, next_pressed) = (focus::pressed(prev_button_rect), focus::pressed(next_button_rect));
+                if prev_pressed && prev_active {
+                    state.palette_page = state.palette_page.saturating_sub(1);
+                } else if next_pressed && next_active {
+                    state.palette_page = (state.palette_page + 1).min(total_pages - 1);
+                }
+            }
+        }
+        PaletteMode::Custom => {
+            // Ramp controls: step count and generate button
+            let controls_y = swatch_start_y;
+            let small = 25.0;
+            let minus_rect = Rect::new(palette_x + 5.0, controls_y, small, small);
+            let plus_rect = Rect::new(palette_x + 60.0, controls_y, small, small);
+            let ramp_rect = Rect::new(palette_x + 90.0, controls_y, 60.0, small);
+            let clear_rect = Rect::new(palette_x + 155.0, controls_y, 40.0, small);
+
+            for (rect, label) in [(minus_rect, "-"), (plus_rect, "+"), (ramp_rect, "Ramp"), (clear_rect, "Clear")] {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(180, 180, 180, 255));
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, BLACK);
+                let text_size = measure_text(label, None, 14, 1.0);
+                draw_text(label, rect.x + (rect.w - text_size.width) / 2.0, rect.y + (rect.h + text_size.height) / 2.0, 14.0, BLACK);
+            }
+            let steps_text = format!("{}", state.ramp_steps);
+            let steps_size = measure_text(&steps_text, None, 16, 1.0);
+            draw_text(&steps_text, palette_x + 45.0 - steps_size.width / 2.0, controls_y + (small + steps_size.height) / 2.0, 16.0, BLACK);
+
+            if !state.palette_dragging {
+                let pressed = [minus_rect, plus_rect, ramp_rect, clear_rect].map(focus::pressed);
+                if pressed[0] {
+                    state.ramp_steps = (state.ramp_steps - 1).max(MIN_RAMP_STEPS);
+                } else if pressed[1] {
+                    state.ramp_steps = (state.ramp_steps + 1).min(MAX_RAMP_STEPS);
+                } else if pressed[2] {
+                    let ramp = generate_hue_shifted_ramp(state.current_color, state.ramp_steps);
+                    state.custom_palette.extend(ramp);
+                } else if pressed[3] {
+                    state.custom_palette.clear();
+                }
+            }
+
+            // Custom swatches
+            let color_size = 20.0;
+            let padding = 4.0;
+            let start_x = palette_x + padding;
+            let start_y = controls_y + small + 5.0;
+
+            for (idx, &rgba) in state.custom_palette.clone().iter().enumerate() {
+                let x = start_x + (idx % CUSTOM_COLS) as f32 * (color_size + padding);
+                let y = start_y + (idx / CUSTOM_COLS) as f32 * (color_size + padding);
+
+                draw_rectangle(x, y, color_size, color_size, Color::from(rgba));
+                let (border_width, border_color) = if state.current_color == rgba {
+                    (3.0, Color::from_rgba(255, 255, 0, 255)) // Yellow highlight
+                } else {
+                    (1.5, BLACK)
+                };
+                draw_rectangle_lines(x, y, color_size, color_size, border_width, border_color);
+
+                if !state.palette_dragging {
+                    let rect = Rect::new(x, y, color_size, color_size);
+                    if focus::pressed(rect) {
+                        state.current_color = rgba;
+                    } else if is_mouse_button_pressed(MouseButton::Right) && rect.contains(mouse_pos) {
+                        state.secondary_color = rgba;
                     }
                 }
             }
@@ -299,10 +352,3 @@ pub fn render_palette_window(state: &mut ApplicationState) -> bool {
     let full_rect = Rect::new(palette_x, palette_y, palette_width, palette_height);
     full_rect.contains(mouse_pos)
 }
-
-fn colors_match(c1: Color, c2: Color) -> bool {
-    (c1.r - c2.r).abs() < 0.01
-        && (c1.g - c2.g).abs() < 0.01
-        && (c1.b - c2.b).abs() < 0.01
-        && (c1.a - c2.a).abs() < 0.01
-}
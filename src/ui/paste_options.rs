@@ -0,0 +1,59 @@
+use macroquad::prelude::*;
+use crate::core::merge::{next_feather, PasteMerge};
+use crate::state::ApplicationState;
+use crate::ui::menu_bar::MENU_BAR_HEIGHT;
+
+const PANEL_WIDTH: f32 = 450.0;
+const PANEL_HEIGHT: f32 = 32.0;
+const BUTTON_WIDTH: f32 = 80.0;
+
+/// Merge mode picker and edge feather button shown while a paste is pending (Tab also cycles
+/// the mode);
+/// registers its rect for hit-testing
+pub fn render_paste_options(state: &mut ApplicationState) {
+    if !state.pending_paste {
+        return;
+    }
+
+    let x = ((screen_width() - PANEL_WIDTH) / 2.0).max(0.0);
+    let y = MENU_BAR_HEIGHT + 6.0;
+    let mouse_pos = Vec2::from(mouse_position());
+    draw_rectangle(x, y, PANEL_WIDTH, PANEL_HEIGHT, Color::from_rgba(230, 230, 230, 240));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, PANEL_HEIGHT, 2.0, BLACK);
+    draw_text("Paste (Tab)", x + 8.0, y + 21.0, 16.0, BLACK);
+
+    let feather = Rect::new(x + 96.0, y + 4.0, BUTTON_WIDTH, PANEL_HEIGHT - 8.0);
+    let feather_color = if state.feather > 0 {
+        Color::from_rgba(150, 190, 255, 255)
+    } else {
+        Color::from_rgba(180, 180, 180, 255)
+    };
+    draw_rectangle(feather.x, feather.y, feather.w, feather.h, feather_color);
+    draw_rectangle_lines(feather.x, feather.y, feather.w, feather.h, 2.0, BLACK);
+    let label = format!("Feather {}", state.feather);
+    let size = measure_text(&label, None, 14, 1.0);
+    draw_text(&label, feather.x + (feather.w - size.width) / 2.0, feather.y + (feather.h + size.height) / 2.0, 14.0, BLACK);
+    if is_mouse_button_pressed(MouseButton::Left) && feather.contains(mouse_pos) {
+        state.feather = next_feather(state.feather);
+    }
+
+    let mut bx = x + PANEL_WIDTH - PasteMerge::ALL.len() as f32 * (BUTTON_WIDTH + 4.0) - 4.0;
+    for merge in PasteMerge::ALL {
+        let button = Rect::new(bx, y + 4.0, BUTTON_WIDTH, PANEL_HEIGHT - 8.0);
+        let color = if state.paste_merge == merge {
+            Color::from_rgba(150, 190, 255, 255)
+        } else {
+            Color::from_rgba(180, 180, 180, 255)
+        };
+        draw_rectangle(button.x, button.y, button.w, button.h, color);
+        draw_rectangle_lines(button.x, button.y, button.w, button.h, 2.0, BLACK);
+        let size = measure_text(merge.label(), None, 14, 1.0);
+        draw_text(merge.label(), button.x + (button.w - size.width) / 2.0, button.y + (button.h + size.height) / 2.0, 14.0, BLACK);
+        if is_mouse_button_pressed(MouseButton::Left) && button.contains(mouse_pos) {
+            state.paste_merge = merge;
+        }
+        bx += BUTTON_WIDTH + 4.0;
+    }
+
+    state.ui_regions.push(Rect::new(x, y, PANEL_WIDTH, PANEL_HEIGHT));
+}
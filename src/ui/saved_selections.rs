@@ -0,0 +1,117 @@
+use macroquad::prelude::*;
+use crate::input::saved_selections::{activate_saved_selection, delete_saved_selection, save_selection};
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+
+const DIALOG_WIDTH: f32 = 320.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+const NAME_WIDTH: f32 = 200.0;
+
+/// Named selections saved with the project: click a name to select its cells again,
+/// Rename to type a new name (Enter keeps it, Escape drops it), X to forget it
+/// Returns true if the mouse is over the dialog
+pub fn render_saved_selections(state: &mut ApplicationState) -> bool {
+    if !state.show_saved_selections {
+        return false;
+    }
+
+    let rows = state.saved_selections.len().max(1) as f32;
+    let height = TITLE_BAR_HEIGHT + 12.0 + (rows + 1.0) * ROW_HEIGHT;
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - height) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Saved Selections", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, height - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, height - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // One row per saved selection
+    let mut row_y = body_y + 6.0;
+    if state.saved_selections.is_empty() {
+        draw_text("Nothing saved yet", x + 8.0, row_y + 17.0, 16.0, DARKGRAY);
+        row_y += ROW_HEIGHT;
+    }
+    let (mut activate, mut delete, mut rename) = (None, None, None);
+    for (i, saved) in state.saved_selections.iter().enumerate() {
+        let renaming = matches!(&state.saved_selection_rename, Some((index, _)) if *index == i);
+        let label = match &state.saved_selection_rename {
+            Some((index, text)) if *index == i => format!("{}_", text),
+            _ => format!("{} ({})", saved.name, saved.cells.len()),
+        };
+        if renaming {
+            draw_rectangle(x + 8.0, row_y, NAME_WIDTH, ROW_HEIGHT - 4.0, WHITE);
+            draw_rectangle_lines(x + 8.0, row_y, NAME_WIDTH, ROW_HEIGHT - 4.0, 2.0, BLACK);
+            draw_text(&label, x + 12.0, row_y + 17.0, 16.0, BLACK);
+        } else if dialog_button(&label, x + 8.0, row_y, NAME_WIDTH) {
+            activate = Some(i);
+        }
+        if dialog_button("Rename", x + 16.0 + NAME_WIDTH, row_y, 56.0) {
+            rename = Some((i, saved.name.clone()));
+        }
+        if dialog_button("X", x + 80.0 + NAME_WIDTH, row_y, 24.0) {
+            delete = Some(i);
+        }
+        row_y += ROW_HEIGHT;
+    }
+
+    // Last row: actions
+    let save_clicked = dialog_button("Save Selection", x + 8.0, row_y, 120.0);
+    if dialog_button("Close", x + 136.0, row_y, 64.0) {
+        commit_rename(state);
+        state.show_saved_selections = false;
+    }
+
+    if let Some(i) = activate {
+        commit_rename(state);
+        activate_saved_selection(state, i);
+    }
+    if let Some(edit) = rename {
+        commit_rename(state);
+        state.saved_selection_rename = Some(edit);
+    }
+    if let Some(i) = delete {
+        delete_saved_selection(state, i);
+    }
+    if save_clicked {
+        commit_rename(state);
+        save_selection(state);
+    }
+
+    // Typing
+    if let Some((_, text)) = &mut state.saved_selection_rename {
+        while let Some(ch) = get_char_pressed() {
+            if !ch.is_control() {
+                text.push(ch);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            text.pop();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            state.saved_selection_rename = None;
+        } else if is_key_pressed(KeyCode::Enter) {
+            commit_rename(state);
+        }
+    } else if is_key_pressed(KeyCode::Escape) {
+        state.show_saved_selections = false;
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, height).contains(mouse_pos)
+}
+
+/// Store the name being typed, if any; a blank name keeps the old one
+fn commit_rename(state: &mut ApplicationState) {
+    if let Some((index, text)) = state.saved_selection_rename.take() {
+        let name = text.trim();
+        if let (Some(saved), false) = (state.saved_selections.get_mut(index), name.is_empty()) {
+            saved.name = name.to_string();
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use macroquad::prelude::*;
+use crate::input::transform::shift_document;
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+use crate::ui::focus;
+
+const DIALOG_WIDTH: f32 = 260.0;
+const DIALOG_HEIGHT: f32 = 122.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+/// Offset change per -/+ click with Shift held
+const LARGE_STEP: i32 = 8;
+
+/// "Shift Everything" window: pick dx/dy with -/+ (Shift for steps of 8), then Shift or Enter
+/// moves the whole document by that many cells. Returns true if the mouse is over the dialog
+pub fn render_shift_dialog(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> bool {
+    let Some((mut dx, mut dy)) = state.shift_canvas else {
+        return false;
+    };
+
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - DIALOG_HEIGHT) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+    let step = if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) { LARGE_STEP } else { 1 };
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Shift Everything", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, DIALOG_HEIGHT - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Rows 1-2: offsets in cells (positive is right / down)
+    let mut row_y = body_y + 6.0;
+    for (label, value) in [("Right", &mut dx), ("Down", &mut dy)] {
+        draw_text(&format!("{} {}", label, value), x + 8.0, row_y + 17.0, 16.0, BLACK);
+        if dialog_button("-", x + 150.0, row_y, 30.0) {
+            *value -= step;
+        }
+        if dialog_button("+", x + 185.0, row_y, 30.0) {
+            *value += step;
+        }
+        row_y += ROW_HEIGHT;
+    }
+    state.shift_canvas = Some((dx, dy));
+
+    // Row 3: actions
+    if dialog_button("Shift", x + 8.0, row_y, 64.0) || focus::enter_pressed() {
+        shift_document(state, canvas, dx, dy);
+        state.shift_canvas = None;
+    }
+    if dialog_button("Close", x + 80.0, row_y, 64.0) || is_key_pressed(KeyCode::Escape) {
+        state.shift_canvas = None;
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, DIALOG_HEIGHT).contains(mouse_pos)
+}
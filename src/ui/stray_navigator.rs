@@ -0,0 +1,130 @@
+use macroquad::prelude::*;
+use crate::core::strays::find_strays;
+use crate::rendering::CanvasRenderer;
+use crate::state::{ApplicationState, CellChange, Confirm};
+use crate::input::dispatcher::apply_changes_and_record;
+use crate::input::groups::remove_cells_from_groups;
+use crate::ui::focus;
+use crate::ui::menu_bar::MENU_BAR_HEIGHT;
+
+const PANEL_WIDTH: f32 = 380.0;
+const PANEL_HEIGHT: f32 = 62.0;
+const BUTTON_HEIGHT: f32 = 24.0;
+
+/// Scan for stray cells and open the navigator on the first one
+pub fn open_stray_navigator(state: &mut ApplicationState) {
+    let cells = find_strays(&state.cells);
+    if cells.is_empty() {
+        state.strays = None;
+        state.status_message = Some("No stray cells found".to_string());
+        return;
+    }
+    state.status_message = Some(format!("Found {} stray cell(s)", cells.len()));
+    state.strays = Some(crate::core::strays::StrayNavigator { cells, index: 0 });
+    jump_to_current(state);
+}
+
+/// Strip below the menu bar: position, Prev / Next to jump the camera, Delete to remove
+/// the shown cell, Delete all (confirmed) and Close. Returns true if the mouse is over it
+pub fn render_stray_navigator(state: &mut ApplicationState, canvas: &mut CanvasRenderer) -> bool {
+    let Some(nav) = &state.strays else {
+        return false;
+    };
+    let Some((cx, cy)) = nav.current() else {
+        state.strays = None;
+        return false;
+    };
+
+    let x = ((screen_width() - PANEL_WIDTH) / 2.0).max(0.0);
+    let y = MENU_BAR_HEIGHT + 50.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    draw_rectangle(x, y, PANEL_WIDTH, PANEL_HEIGHT, Color::from_rgba(230, 230, 230, 240));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, PANEL_HEIGHT, 2.0, BLACK);
+    let title = format!("Stray {}/{} at ({}, {})", nav.index + 1, nav.cells.len(), cx, cy);
+    draw_text(&title, x + 8.0, y + 18.0, 16.0, BLACK);
+
+    // Highlight the cell on the canvas so it's visible even at low zoom
+    let screen = state.camera.world_to_screen(Vec2::new(cx as f32 + 0.5, cy as f32 + 0.5));
+    draw_circle_lines(screen.x, screen.y, 14.0, 2.0, RED);
+
+    let row_y = y + 30.0;
+    let buttons = ["Prev", "Next", "Delete", "Delete all", "Close"];
+    let widths = [56.0, 56.0, 64.0, 90.0, 60.0];
+    let mut bx = x + 8.0;
+    let mut pressed = None;
+    for (label, w) in buttons.iter().zip(widths) {
+        if nav_button(label, bx, row_y, w) {
+            pressed = Some(*label);
+        }
+        bx += w + 6.0;
+    }
+
+    match pressed {
+        Some("Prev") | Some("Next") => {
+            if let Some(nav) = &mut state.strays {
+                nav.step(pressed == Some("Next"));
+            }
+            jump_to_current(state);
+        }
+        Some("Delete") => {
+            delete_cells(state, canvas, vec![(cx, cy)]);
+            jump_to_current(state);
+        }
+        Some("Delete all") => {
+            state.confirm = Some(Confirm {
+                message: format!("Delete all {} stray cell(s)?", state.strays.as_ref().map_or(0, |n| n.cells.len())),
+                confirm_label: "Delete",
+                on_confirm: delete_all_strays,
+            });
+        }
+        Some(_) => state.strays = None,
+        None => {}
+    }
+
+    Rect::new(x, y, PANEL_WIDTH, PANEL_HEIGHT).contains(mouse_pos)
+}
+
+fn delete_all_strays(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    if let Some(nav) = state.strays.take() {
+        let count = nav.cells.len();
+        delete_cells(state, canvas, nav.cells);
+        state.status_message = Some(format!("Deleted {} stray cell(s)", count));
+    }
+}
+
+/// Remove cells as one undoable command and drop them from the navigator
+fn delete_cells(state: &mut ApplicationState, canvas: &mut CanvasRenderer, coords: Vec<(i32, i32)>) {
+    let changes = coords.iter().map(|&coord| CellChange { coord, before: None, after: None }).collect();
+    apply_changes_and_record(state, canvas, "Delete Strays", changes);
+    remove_cells_from_groups(state, &coords);
+    if let Some(nav) = &mut state.strays {
+        for &coord in &coords {
+            nav.remove(coord);
+        }
+    }
+}
+
+/// Center the view on the stray being shown
+fn jump_to_current(state: &mut ApplicationState) {
+    if let Some((x, y)) = state.strays.as_ref().and_then(|nav| nav.current()) {
+        let screen_center = Vec2::new(screen_width(), screen_height()) / 2.0;
+        state.camera.center_on(Vec2::new(x as f32 + 0.5, y as f32 + 0.5), screen_center);
+    }
+}
+
+/// Returns true when clicked or activated from the keyboard
+fn nav_button(label: &str, x: f32, y: f32, w: f32) -> bool {
+    let rect = Rect::new(x, y, w, BUTTON_HEIGHT);
+    let hovered = rect.contains(Vec2::from(mouse_position()));
+    let color = if hovered {
+        Color::from_rgba(200, 200, 210, 255)
+    } else {
+        Color::from_rgba(180, 180, 180, 255)
+    };
+    draw_rectangle(x, y, w, BUTTON_HEIGHT, color);
+    draw_rectangle_lines(x, y, w, BUTTON_HEIGHT, 2.0, BLACK);
+    let text_size = measure_text(label, None, 14, 1.0);
+    draw_text(label, x + (w - text_size.width) / 2.0, y + (BUTTON_HEIGHT + text_size.height) / 2.0, 14.0, BLACK);
+    focus::pressed(rect)
+}
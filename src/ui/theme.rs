@@ -0,0 +1,33 @@
+use crate::core::color::Rgba;
+
+/// Workspace colors behind and around the artwork
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background: Rgba,
+    /// Minor grid line color; major lines draw it more opaque
+    pub grid: Rgba,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self { name: "Light".to_string(), background: Rgba::WHITE, grid: Rgba::new(179, 191, 217, 115) }
+    }
+
+    pub fn paper() -> Self {
+        Self { name: "Paper".to_string(), background: Rgba::rgb(245, 241, 230), grid: Rgba::new(170, 150, 120, 90) }
+    }
+
+    pub fn slate() -> Self {
+        Self { name: "Slate".to_string(), background: Rgba::rgb(196, 202, 212), grid: Rgba::new(90, 100, 120, 110) }
+    }
+
+    /// The built-in theme after this one; custom (preset) themes go back to the first
+    pub fn next(&self) -> Self {
+        let builtins = [Self::light(), Self::paper(), Self::slate()];
+        match builtins.iter().position(|theme| theme.name == self.name) {
+            Some(i) => builtins[(i + 1) % builtins.len()].clone(),
+            None => Self::light(),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use macroquad::prelude::*;
+use crate::input::frame_tags::{cycle_frame_tag_loop, delete_frame_tag, new_frame_tag, nudge_frame_tag, toggle_active_frame_tag};
+use crate::input::frames::{frame_duration_ms, nudge_frame_duration, tagged_frames};
+use crate::input::groups::select_group;
+use crate::input::jobs::{start_gif_export, start_sprite_sheet_export};
+use crate::state::ApplicationState;
+use crate::ui::export_dialog::dialog_button;
+
+const DIALOG_WIDTH: f32 = 408.0;
+const TITLE_BAR_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 28.0;
+const NAME_WIDTH: f32 = 120.0;
+const NUDGE_WIDTH: f32 = 20.0;
+/// Room for an "F12" label between a range end's - and + buttons
+const FRAME_LABEL_WIDTH: f32 = 34.0;
+/// Frames per line of the strip, and the width each takes
+const FRAMES_PER_LINE: usize = 5;
+const FRAME_WIDTH: f32 = 72.0;
+const FRAME_GAP: f32 = 6.0;
+/// Duration steps one Shift+click of a frame's -/+ moves
+const SHIFT_STEPS: i32 = 10;
+
+/// What a click in a tag row asks for
+enum RowEdit {
+    Activate(usize),
+    Nudge { index: usize, end: bool, delta: i32 },
+    CycleLoop(usize),
+    Rename(usize, String),
+    Delete(usize),
+}
+
+/// Animation timeline. The frame strip shows every tagged frame with its duration: click a
+/// frame to select its group, -/+ shorten or lengthen it (Shift for bigger steps).
+/// Below, named frame ranges ("walk", "idle") with their loop modes: click a name to play and
+/// export just that tag (again for every frame), -/+ move the first and last frame, the loop
+/// button cycles Loop / Ping-pong / Once. Rename types a new name (Enter keeps it, Escape drops it)
+/// Returns true if the mouse is over the dialog
+pub fn render_timeline(state: &mut ApplicationState) -> bool {
+    if !state.show_timeline {
+        return false;
+    }
+
+    let frames: Vec<(u32, u32, u32, bool)> = tagged_frames(&state.groups)
+        .iter()
+        .map(|g| (g.id, g.frame.unwrap_or_default(), frame_duration_ms(g, state.frame_fps), g.duration_ms.is_some()))
+        .collect();
+    let frame_lines = frames.len().div_ceil(FRAMES_PER_LINE).max(1) as f32;
+    let rows = state.frame_tags.len().max(1) as f32;
+    let height = TITLE_BAR_HEIGHT + 18.0 + (frame_lines * 2.0 + rows + 1.0) * ROW_HEIGHT;
+    let x = (screen_width() - DIALOG_WIDTH) / 2.0;
+    let y = (screen_height() - height) / 2.0;
+    let mouse_pos = Vec2::from(mouse_position());
+
+    // Title bar
+    draw_rectangle(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, Color::from_rgba(80, 80, 150, 255));
+    draw_rectangle_lines(x, y, DIALOG_WIDTH, TITLE_BAR_HEIGHT, 2.0, BLACK);
+    draw_text("Timeline", x + 8.0, y + 17.0, 16.0, WHITE);
+
+    // Body
+    let body_y = y + TITLE_BAR_HEIGHT;
+    draw_rectangle(x, body_y, DIALOG_WIDTH, height - TITLE_BAR_HEIGHT, Color::from_rgba(230, 230, 230, 255));
+    draw_rectangle_lines(x, body_y, DIALOG_WIDTH, height - TITLE_BAR_HEIGHT, 2.0, BLACK);
+
+    // Frame strip: number (selects the group) over its duration with -/+
+    let mut row_y = body_y + 6.0;
+    if frames.is_empty() {
+        draw_text("No frames yet - right-click a group to tag it", x + 8.0, row_y + 17.0, 16.0, DARKGRAY);
+    }
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    let (mut select, mut nudge) = (None, None);
+    for (i, &(id, frame, duration, custom)) in frames.iter().enumerate() {
+        let frame_x = x + 8.0 + (i % FRAMES_PER_LINE) as f32 * (FRAME_WIDTH + FRAME_GAP);
+        let frame_y = row_y + (i / FRAMES_PER_LINE) as f32 * ROW_HEIGHT * 2.0;
+        if dialog_button(&format!("F{}", frame), frame_x, frame_y, FRAME_WIDTH) {
+            select = Some(id);
+        }
+        let steps = if shift { SHIFT_STEPS } else { 1 };
+        if dialog_button("-", frame_x, frame_y + ROW_HEIGHT, NUDGE_WIDTH) {
+            nudge = Some((id, -steps));
+        }
+        // Frames following the playback rate show their duration greyed
+        let label = format!("{}ms", duration);
+        let width = measure_text(&label, None, 14, 1.0).width;
+        let label_x = frame_x + (FRAME_WIDTH - width) / 2.0;
+        draw_text(&label, label_x, frame_y + ROW_HEIGHT + 17.0, 14.0, if custom { BLACK } else { DARKGRAY });
+        if dialog_button("+", frame_x + FRAME_WIDTH - NUDGE_WIDTH, frame_y + ROW_HEIGHT, NUDGE_WIDTH) {
+            nudge = Some((id, steps));
+        }
+    }
+    row_y += frame_lines * ROW_HEIGHT * 2.0;
+    draw_line(x + 8.0, row_y + 2.0, x + DIALOG_WIDTH - 8.0, row_y + 2.0, 1.0, GRAY);
+    row_y += 6.0;
+
+    // One row per tag: name, first frame, last frame, loop mode, rename, delete
+    if state.frame_tags.is_empty() {
+        draw_text("No tags yet - every frame plays in a loop", x + 8.0, row_y + 17.0, 16.0, DARKGRAY);
+        row_y += ROW_HEIGHT;
+    }
+    let mut edit = None;
+    for (i, tag) in state.frame_tags.iter().enumerate() {
+        match &state.frame_tag_rename {
+            Some((index, text)) if *index == i => {
+                draw_rectangle(x + 8.0, row_y, NAME_WIDTH, ROW_HEIGHT - 4.0, WHITE);
+                draw_rectangle_lines(x + 8.0, row_y, NAME_WIDTH, ROW_HEIGHT - 4.0, 2.0, BLACK);
+                draw_text(&format!("{}_", text), x + 12.0, row_y + 17.0, 16.0, BLACK);
+            }
+            _ => {
+                let label = if state.active_frame_tag == Some(i) { format!("> {}", tag.name) } else { tag.name.clone() };
+                if dialog_button(&label, x + 8.0, row_y, NAME_WIDTH) {
+                    edit = Some(RowEdit::Activate(i));
+                }
+            }
+        }
+
+        let mut nudge_x = x + 16.0 + NAME_WIDTH;
+        for (end, frame) in [(false, tag.start), (true, tag.end)] {
+            if dialog_button("-", nudge_x, row_y, NUDGE_WIDTH) {
+                edit = Some(RowEdit::Nudge { index: i, end, delta: -1 });
+            }
+            let label = format!("F{}", frame);
+            let width = measure_text(&label, None, 16, 1.0).width;
+            draw_text(&label, nudge_x + NUDGE_WIDTH + (FRAME_LABEL_WIDTH - width) / 2.0, row_y + 17.0, 16.0, BLACK);
+            if dialog_button("+", nudge_x + NUDGE_WIDTH + FRAME_LABEL_WIDTH, row_y, NUDGE_WIDTH) {
+                edit = Some(RowEdit::Nudge { index: i, end, delta: 1 });
+            }
+            nudge_x += NUDGE_WIDTH * 2.0 + FRAME_LABEL_WIDTH + 8.0;
+        }
+
+        if dialog_button(tag.loop_mode.label(), nudge_x, row_y, 76.0) {
+            edit = Some(RowEdit::CycleLoop(i));
+        }
+        if dialog_button("Rename", nudge_x + 84.0, row_y, 56.0) {
+            edit = Some(RowEdit::Rename(i, tag.name.clone()));
+        }
+        if dialog_button("X", nudge_x + 148.0, row_y, 24.0) {
+            edit = Some(RowEdit::Delete(i));
+        }
+        row_y += ROW_HEIGHT;
+    }
+
+    // Last row: actions
+    let new_clicked = dialog_button("New Tag", x + 8.0, row_y, 80.0);
+    let gif_clicked = dialog_button("Export GIF", x + 96.0, row_y, 96.0);
+    let sheet_clicked = dialog_button("Export Sheet", x + 200.0, row_y, 104.0);
+    if dialog_button("Close", x + 312.0, row_y, 64.0) {
+        commit_rename(state);
+        state.show_timeline = false;
+    }
+
+    if let Some(id) = select {
+        select_group(state, id);
+    }
+    if let Some((id, steps)) = nudge {
+        nudge_frame_duration(state, id, steps);
+    }
+    if let Some(edit) = edit {
+        if !matches!(edit, RowEdit::Delete(_)) {
+            commit_rename(state);
+        }
+        match edit {
+            RowEdit::Activate(i) => toggle_active_frame_tag(state, i),
+            RowEdit::Nudge { index, end, delta } => nudge_frame_tag(state, index, end, delta),
+            RowEdit::CycleLoop(i) => cycle_frame_tag_loop(state, i),
+            RowEdit::Rename(i, name) => state.frame_tag_rename = Some((i, name)),
+            RowEdit::Delete(i) => delete_frame_tag(state, i),
+        }
+    }
+    if new_clicked {
+        commit_rename(state);
+        new_frame_tag(state);
+    }
+    if gif_clicked {
+        start_gif_export(state);
+    }
+    if sheet_clicked {
+        start_sprite_sheet_export(state);
+    }
+
+    // Typing
+    if let Some((_, text)) = &mut state.frame_tag_rename {
+        while let Some(ch) = get_char_pressed() {
+            if !ch.is_control() {
+                text.push(ch);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            text.pop();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            state.frame_tag_rename = None;
+        } else if is_key_pressed(KeyCode::Enter) {
+            commit_rename(state);
+        }
+    } else if is_key_pressed(KeyCode::Escape) {
+        state.show_timeline = false;
+    }
+
+    Rect::new(x, y, DIALOG_WIDTH, height).contains(mouse_pos)
+}
+
+/// Store the name being typed, if any; a blank name keeps the old one
+fn commit_rename(state: &mut ApplicationState) {
+    if let Some((index, text)) = state.frame_tag_rename.take() {
+        let name = text.trim();
+        if let (Some(tag), false) = (state.frame_tags.get_mut(index), name.is_empty()) {
+            tag.name = name.to_string();
+        }
+    }
+}
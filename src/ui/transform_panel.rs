@@ -0,0 +1,108 @@
+use macroquad::prelude::*;
+use crate::core::transform::MAX_SHEAR;
+use crate::input::transform::{apply_selection_transform, cancel_selection_transform};
+use crate::rendering::CanvasRenderer;
+use crate::state::ApplicationState;
+use crate::ui::focus;
+
+const PANEL_WIDTH: f32 = 420.0;
+const PANEL_HEIGHT: f32 = 126.0;
+const ROW_HEIGHT: f32 = 28.0;
+const BUTTON_HEIGHT: f32 = 24.0;
+
+/// Angle nudges offered as buttons
+const ANGLE_STEPS: &[(&str, f32)] = &[("-15", -15.0), ("-1", -1.0), ("+1", 1.0), ("+15", 15.0)];
+
+/// Shear nudges offered as buttons
+const SHEAR_STEPS: &[(&str, f32)] = &[("-.25", -0.25), ("-.05", -0.05), ("+.05", 0.05), ("+.25", 0.25)];
+
+/// Controls for the pending selection transform: angle and shear nudges and the scale set by
+/// the corner handles (the canvas gizmo drags all of them), Apply / Cancel
+/// Enter applies, Esc cancels; registers its rect for hit-testing
+pub fn render_transform_panel(state: &mut ApplicationState, canvas: &mut CanvasRenderer) {
+    let Some(transform) = state.selection_transform else {
+        return;
+    };
+    // Dragging the gizmo with the select tool applies on release, without the panel
+    if state.transform_drag.is_some_and(|drag| drag.apply_on_release) {
+        return;
+    }
+
+    let x = ((screen_width() - PANEL_WIDTH) / 2.0).max(0.0);
+    let y = screen_height() - PANEL_HEIGHT - 10.0;
+    draw_rectangle(x, y, PANEL_WIDTH, PANEL_HEIGHT, Color::from_rgba(230, 230, 230, 240));
+    draw_rectangle_lines(x, y, PANEL_WIDTH, PANEL_HEIGHT, 2.0, BLACK);
+    draw_text("Transform selection (Enter applies, Esc cancels)", x + 8.0, y + 18.0, 16.0, BLACK);
+
+    // Rotation
+    let row_y = y + 36.0;
+    draw_text(&format!("Rotate {:.0}°", transform.angle), x + 8.0, row_y + 17.0, 16.0, BLACK);
+    let mut bx = x + 100.0;
+    for &(label, step) in ANGLE_STEPS {
+        if panel_button(label, bx, row_y, 36.0) {
+            if let Some(t) = &mut state.selection_transform {
+                t.angle = (t.angle + step + 180.0).rem_euclid(360.0) - 180.0;
+            }
+        }
+        bx += 40.0;
+    }
+
+    // Shear, one row per axis
+    for axis in 0..2 {
+        let row_y = row_y + ROW_HEIGHT * (axis + 1) as f32;
+        let value = if axis == 0 { transform.shear_x } else { transform.shear_y };
+        let name = if axis == 0 { "Shear X" } else { "Shear Y" };
+        draw_text(&format!("{} {:+.2}", name, value), x + 8.0, row_y + 17.0, 16.0, BLACK);
+        let mut bx = x + 100.0;
+        for &(label, step) in SHEAR_STEPS {
+            if panel_button(label, bx, row_y, 36.0) {
+                if let Some(t) = &mut state.selection_transform {
+                    let shear = if axis == 0 { &mut t.shear_x } else { &mut t.shear_y };
+                    *shear = (*shear + step).clamp(-MAX_SHEAR, MAX_SHEAR);
+                }
+            }
+            bx += 40.0;
+        }
+    }
+
+    // Scale comes from the gizmo; the panel only reports and resets it
+    draw_text(
+        &format!("Scale {:.0}% x {:.0}%", transform.scale_x * 100.0, transform.scale_y * 100.0),
+        x + 276.0,
+        row_y + ROW_HEIGHT + 17.0,
+        16.0,
+        BLACK,
+    );
+    if panel_button("Reset scale", x + PANEL_WIDTH - 150.0, row_y + ROW_HEIGHT * 2.0, 142.0) {
+        if let Some(t) = &mut state.selection_transform {
+            t.scale_x = 1.0;
+            t.scale_y = 1.0;
+        }
+    }
+
+    let apply = panel_button("Apply", x + PANEL_WIDTH - 150.0, row_y, 66.0) || focus::enter_pressed();
+    let cancel = panel_button("Cancel", x + PANEL_WIDTH - 78.0, row_y, 70.0) || is_key_pressed(KeyCode::Escape);
+    if apply {
+        apply_selection_transform(state, canvas);
+    } else if cancel {
+        cancel_selection_transform(state);
+    }
+
+    state.ui_regions.push(Rect::new(x, y, PANEL_WIDTH, PANEL_HEIGHT));
+}
+
+/// Returns true when clicked or activated from the keyboard
+fn panel_button(label: &str, x: f32, y: f32, w: f32) -> bool {
+    let rect = Rect::new(x, y, w, BUTTON_HEIGHT);
+    let hovered = rect.contains(Vec2::from(mouse_position()));
+    let color = if hovered {
+        Color::from_rgba(200, 200, 210, 255)
+    } else {
+        Color::from_rgba(180, 180, 180, 255)
+    };
+    draw_rectangle(x, y, w, BUTTON_HEIGHT, color);
+    draw_rectangle_lines(x, y, w, BUTTON_HEIGHT, 2.0, BLACK);
+    let text_size = measure_text(label, None, 14, 1.0);
+    draw_text(label, x + (w - text_size.width) / 2.0, y + (BUTTON_HEIGHT + text_size.height) / 2.0, 14.0, BLACK);
+    focus::pressed(rect)
+}
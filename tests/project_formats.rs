@@ -0,0 +1,87 @@
+//! Round trips and upgrades for the project file formats in `project`
+
+use tiny_neo_space::core::cell::{Cell, CellAspect, CellGrid, GridSettings};
+use tiny_neo_space::core::color::Rgba;
+use tiny_neo_space::core::frame_tag::{FrameTag, LoopMode};
+use tiny_neo_space::project::format::{ProjectFile, ProjectGroup, ProjectMetadata, ProjectSelection, FORMAT_VERSION};
+use tiny_neo_space::project::migrate::parse_project;
+use tiny_neo_space::project::text_format::encode_text_project;
+
+/// A document using every text entry, with names that need escaping
+fn sample_project() -> ProjectFile {
+    let mut cells = CellGrid::new();
+    for (x, y) in [(0, 0), (1, 0), (-3, 2), (5, -7)] {
+        cells.insert((x, y), Cell::with_color(Rgba::new((x * 20) as u8, (y * 30) as u8, 90, 255)));
+    }
+    let metadata = ProjectMetadata {
+        title: "Forest \\ tiles".to_string(),
+        author: "Someone".to_string(),
+        description: "Two lines\nof description".to_string(),
+        tags: vec!["tiles".to_string(), "green grass".to_string()],
+        created: "2026-10-17".to_string(),
+    };
+    let mut file = ProjectFile::from_document(&cells, CellAspect::SQUARE, GridSettings::DEFAULT, &[Rgba::new(148, 176, 194, 255)], &[], &[], &metadata);
+    file.groups = vec![
+        ProjectGroup { name: "Walk 1".to_string(), cells: vec![(0, 0)], frame: Some(1), duration_ms: Some(250) },
+        ProjectGroup { name: "Walk\n2".to_string(), cells: vec![(1, 0)], frame: Some(2), duration_ms: None },
+        ProjectGroup { name: "Trees".to_string(), cells: vec![(-3, 2), (5, -7)], frame: None, duration_ms: None },
+    ];
+    file.saved_selections = vec![ProjectSelection { name: "Roof \\n".to_string(), cells: vec![(0, 0), (1, 0)] }];
+    file.frame_tags = vec![FrameTag { name: "Walk cycle".to_string(), start: 1, end: 2, loop_mode: LoopMode::PingPong }];
+    file
+}
+
+#[test]
+fn text_format_round_trips_byte_for_byte() {
+    let text = encode_text_project(&sample_project());
+    let parsed = parse_project(&text).unwrap();
+    assert_eq!(encode_text_project(&parsed), text);
+
+    assert_eq!(parsed.metadata.title, "Forest \\ tiles");
+    assert_eq!(parsed.metadata.description, "Two lines\nof description");
+    assert_eq!(parsed.groups[1].name, "Walk\n2");
+    assert_eq!(parsed.groups[0].duration_ms, Some(250));
+    assert_eq!(parsed.saved_selections[0].name, "Roof \\n");
+    assert_eq!(parsed.frame_tags[0].loop_mode, LoopMode::PingPong);
+}
+
+#[test]
+fn version_9_text_projects_still_open() {
+    let text = "tiny-neo-space-project 9\naspect 1 1\ncell-pixels 24\nmajor-grid 16\ngroup 1 Walk\nmember 0 0\ncell 0 0 #333c57ff\n";
+    let file = parse_project(text).unwrap();
+    assert_eq!(file.version, FORMAT_VERSION);
+    assert_eq!(file.groups[0].frame, Some(1));
+    assert_eq!(file.groups[0].duration_ms, None);
+    assert_eq!(file.cells.len(), 1);
+}
+
+#[test]
+fn malformed_text_lines_are_reported_by_number() {
+    let header = "tiny-neo-space-project 10\n";
+    let cases = [
+        ("aspect 1\n", "Line 2: expected 'aspect <w> <h>'"),
+        ("\ncell 0 x #333c57ff\n", "Line 3: 'x' is not a number"),
+        ("member 0 0\n", "Line 2: 'member' before any 'group' or 'selection'"),
+        ("aspect 1 1\nsparkle 4\n", "Line 3: unknown entry 'sparkle'"),
+        ("group 1@soon Walk\n", "Line 2: invalid frame duration 'soon'"),
+    ];
+    for (body, expected) in cases {
+        let error = parse_project(&format!("{}{}", header, body)).err();
+        assert_eq!(error.as_deref(), Some(expected), "for {:?}", body);
+    }
+}
+
+#[test]
+fn version_1_projects_migrate_to_the_current_format() {
+    let file = parse_project(include_str!("fixtures/v1.tnsp")).unwrap();
+    assert_eq!(file.version, FORMAT_VERSION);
+    assert_eq!(file.thumbnail, None);
+    assert!(file.history.is_empty() && file.groups.is_empty() && file.saved_selections.is_empty() && file.frame_tags.is_empty());
+
+    let document = file.into_document().unwrap();
+    assert_eq!((document.cell_aspect.w, document.cell_aspect.h), (2, 1));
+    assert_eq!((document.grid.cell_pixels, document.grid.major_every), (24, 16));
+    assert_eq!(document.cells.get(&(3, -2)).map(|cell| cell.color), Some(Rgba::new(0, 255, 0, 128)));
+    assert_eq!(document.custom_palette, vec![Rgba::new(148, 176, 194, 255)]);
+    assert_eq!(document.metadata, ProjectMetadata::default());
+}
@@ -0,0 +1,95 @@
+//! Property tests for the undo/redo invariants of `core::history`
+
+use proptest::prelude::*;
+use tiny_neo_space::core::cell::{Cell, CellGrid};
+use tiny_neo_space::core::color::Rgba;
+use tiny_neo_space::core::history::{CellChange, Command, History};
+
+/// One edit: paint a cell with a color, or erase it (None)
+type Edit = ((i32, i32), Option<u8>);
+
+/// Coordinates are kept in a small window so commands overlap and touch cells repeatedly
+fn edit() -> impl Strategy<Value = Edit> {
+    ((-4i32..4, -4i32..4), proptest::option::of(any::<u8>()))
+}
+
+fn grid() -> impl Strategy<Value = CellGrid> {
+    proptest::collection::hash_map((-4i32..4, -4i32..4), any::<u8>(), 0..32).prop_map(|cells| {
+        cells
+            .into_iter()
+            .map(|(coord, shade)| (coord, Cell::with_color(Rgba::new(shade, shade, shade, 255))))
+            .collect()
+    })
+}
+
+/// Apply edits to `cells` the way the tools do, recording before/after for each cell touched
+fn record(cells: &mut CellGrid, edits: &[Edit]) -> Command {
+    let changes = edits
+        .iter()
+        .map(|&(coord, shade)| {
+            let after = shade.map(|s| Cell::with_color(Rgba::new(s, 0, 255 - s, 255)));
+            let before = match after {
+                Some(cell) => cells.insert(coord, cell),
+                None => cells.remove(&coord),
+            };
+            CellChange { coord, before, after }
+        })
+        .collect();
+    Command { changes }
+}
+
+proptest! {
+    #[test]
+    fn undo_all_restores_original(
+        original in grid(),
+        commands in proptest::collection::vec(proptest::collection::vec(edit(), 1..16), 0..12),
+    ) {
+        let mut cells = original.clone();
+        let mut history = History::new(usize::MAX);
+        for edits in &commands {
+            let cmd = record(&mut cells, edits);
+            history.push(cmd);
+        }
+
+        while history.undo(&mut cells).is_some() {}
+        prop_assert_eq!(cells, original);
+    }
+
+    #[test]
+    fn redo_after_undo_reproduces_state(
+        original in grid(),
+        commands in proptest::collection::vec(proptest::collection::vec(edit(), 1..16), 1..12),
+        undo_count in 0usize..12,
+    ) {
+        let mut cells = original;
+        let mut history = History::new(usize::MAX);
+        for edits in &commands {
+            let cmd = record(&mut cells, edits);
+            history.push(cmd);
+        }
+        let edited = cells.clone();
+
+        // Undo part (or all) of the history, then redo everything that was undone
+        let mut undone = 0;
+        while undone < undo_count && history.undo(&mut cells).is_some() {
+            undone += 1;
+        }
+        while history.redo(&mut cells).is_some() {}
+        prop_assert_eq!(cells, edited);
+    }
+
+    #[test]
+    fn each_command_round_trips(
+        original in grid(),
+        edits in proptest::collection::vec(edit(), 1..32),
+    ) {
+        let mut cells = original.clone();
+        let cmd = record(&mut cells, &edits);
+        let edited = cells.clone();
+
+        cmd.revert(&mut cells);
+        prop_assert_eq!(&cells, &original);
+        cmd.apply(&mut cells);
+        prop_assert_eq!(cells, edited);
+    }
+}